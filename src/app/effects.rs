@@ -4,7 +4,9 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::time::Duration;
 
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -14,6 +16,7 @@ use super::event_loop::set_mouse_motion_tracking;
 
 use crate::app::{App, Message, Model, ToastLevel};
 use crate::config::shell_split_tokens;
+use crate::editor::ExCommand;
 use crate::watcher::FileWatcher;
 use base64::Engine;
 
@@ -28,6 +31,7 @@ impl App {
         msg: &Message,
     ) {
         match msg {
+            Message::ToggleWatch if model.from_stdin => {}
             Message::ToggleWatch => {
                 if model.watch_enabled {
                     match Self::make_file_watcher(&model.file_path) {
@@ -53,6 +57,9 @@ impl App {
                     model.show_toast(ToastLevel::Info, "Watch disabled");
                 }
             }
+            Message::ForceReload | Message::FileChanged if model.from_stdin => {
+                model.show_toast(ToastLevel::Warning, "Cannot reload stdin input");
+            }
             Message::ForceReload | Message::FileChanged => {
                 if model.editor_mode {
                     // Don't reload while editing — check for conflict instead
@@ -60,7 +67,10 @@ impl App {
                         && model.editor_disk_hash != Some(new_hash)
                     {
                         model.editor_disk_conflict = true;
-                        model.show_toast(ToastLevel::Warning, "File changed on disk while editing");
+                        model.show_toast(
+                            ToastLevel::Warning,
+                            "File changed on disk while editing; Ctrl+S merges",
+                        );
                     }
                 } else if let Err(err) = model.reload_from_disk() {
                     model.show_toast(ToastLevel::Error, format!("Reload failed: {err}"));
@@ -84,7 +94,24 @@ impl App {
                 Self::follow_link_picker_index(model, *index);
             }
             Message::EndSelection(_) => {
-                Self::copy_selection(model);
+                Self::copy_selection(model, Model::selected_text);
+                model.clear_selection();
+            }
+            Message::EditorCopy | Message::EditorCut => {
+                if let Some(text) = model.editor_clipboard.clone() {
+                    match copy_to_clipboard(&text) {
+                        Ok(()) => model.show_toast(
+                            ToastLevel::Info,
+                            format!("Copied {} line(s)", text.lines().count().max(1)),
+                        ),
+                        Err(err) => {
+                            model.show_toast(ToastLevel::Error, format!("Copy failed: {err}"));
+                        }
+                    }
+                }
+            }
+            Message::EndSourceSelection(_) => {
+                Self::copy_selection(model, Model::selected_source);
                 model.clear_selection();
             }
             Message::TocSelect | Message::TocClick(_) | Message::TocExpand if model.browse_mode => {
@@ -93,6 +120,35 @@ impl App {
             Message::TocCollapse if model.browse_mode => {
                 Self::browse_navigate_parent(model);
             }
+            Message::OpenFileFinder => {
+                if let Some(finder) = model.file_finder.as_mut() {
+                    finder.start_indexing(&model.browse_filters);
+                }
+            }
+            Message::FileFinderSelect => {
+                Self::open_file_finder_selection(model);
+            }
+            Message::CloseFileFinder => {
+                // Drop the live preview and go back to the file shown before.
+                if let Some(finder) = model.file_finder.take()
+                    && model.file_path != finder.original
+                    && let Err(err) = model.load_file(&finder.original)
+                {
+                    model.show_toast(ToastLevel::Error, format!("Open failed: {err}"));
+                }
+            }
+            Message::ProjectSearchSubmit
+            | Message::ToggleSearchRegex
+            | Message::ToggleSearchWholeWord => {
+                model.run_project_search();
+            }
+            Message::ProjectSearchOpen => {
+                if let Err(err) = model.open_project_search_hit() {
+                    model.show_toast(ToastLevel::Error, format!("Open failed: {err}"));
+                } else {
+                    Self::sync_browse_state_to_loaded_file(model);
+                }
+            }
             Message::EnterEditMode => {
                 if !model.can_edit() {
                     // Guard already handled in update(); skip side effects.
@@ -103,12 +159,22 @@ impl App {
                 }
             }
             Message::ExitEditMode => {
-                // Reload from disk to restore proper wrapping (code fences, etc.)
-                let _ = model.reload_from_disk();
+                Self::finish_exit_edit_mode(model);
             }
             Message::EditorSave => {
                 Self::save_editor_buffer(model);
             }
+            Message::EditorVimCommand(command) => match command {
+                ExCommand::Write => Self::save_editor_buffer(model),
+                ExCommand::WriteQuit if model.editor_mode => Self::save_editor_buffer(model),
+                ExCommand::Quit | ExCommand::ForceQuit | ExCommand::WriteQuit => {
+                    Self::finish_exit_edit_mode(model);
+                }
+                ExCommand::Unknown(_) => {}
+            },
+            Message::EditorSwapDiscard => {
+                Self::remove_editor_swap(model);
+            }
             Message::EnterBrowseMode => {
                 let dir = model
                     .file_path
@@ -306,6 +372,24 @@ impl App {
         }
     }
 
+    fn open_file_finder_selection(model: &mut Model) {
+        let Some(path) = model
+            .file_finder
+            .as_ref()
+            .and_then(crate::app::FileFinder::selected_path)
+        else {
+            return;
+        };
+        model.file_finder = None;
+        if model.file_path != path
+            && let Err(err) = model.load_file(&path)
+        {
+            model.show_toast(ToastLevel::Error, format!("Open failed: {err}"));
+            return;
+        }
+        Self::sync_browse_state_to_loaded_file(model);
+    }
+
     fn browse_navigate_parent(model: &mut Model) {
         let parent = model
             .browse_dir
@@ -384,20 +468,46 @@ impl App {
             .unwrap_or_else(|_| model.document.source().to_string());
 
         let mut buf = crate::editor::EditorBuffer::from_text(&source);
+        model.editor_merge = None;
+        model.editor_swap_recovery = crate::editor::take_leftover_swap(&model.file_path, &source)
+            .map(|swap| crate::editor::SwapRecovery::new(&source, swap));
 
-        // Approximate editor scroll from viewport position
+        // Open the editor at the source line behind the top of the viewport,
+        // approximating from the scroll ratio for documents without a mapping.
         let vp_offset = model.viewport.offset();
         let rendered_total = model.document.line_count().max(1);
         let source_lines = buf.line_count();
-        let target_line = if rendered_total > 1 && vp_offset > 0 {
-            (vp_offset * source_lines.saturating_sub(1)) / rendered_total.saturating_sub(1)
-        } else {
-            0
-        };
+        let target_line = model.file_line_for_rendered(vp_offset).map_or_else(
+            || {
+                if rendered_total > 1 && vp_offset > 0 {
+                    (vp_offset * source_lines.saturating_sub(1)) / rendered_total.saturating_sub(1)
+                } else {
+                    0
+                }
+            },
+            |line| line.min(source_lines.saturating_sub(1)),
+        );
         buf.move_to(target_line, 0);
         model.editor_scroll_offset = target_line;
 
         model.editor_buffer = Some(buf);
+        model.editor_base = Some(source);
+        model.editor_find = None;
+        model.editor_vim = model.editor_vim_keys.then(crate::editor::VimState::default);
+        model.editor_highlight = Some(crate::ui::EditorHighlighter::new(&model.file_path));
+        if model.editor_preview {
+            model.refresh_editor_preview();
+        }
+    }
+
+    /// Side effects of leaving (or trying to leave) the built-in editor.
+    fn finish_exit_edit_mode(model: &mut Model) {
+        // Reload from disk to restore proper wrapping (code fences, etc.)
+        let _ = model.reload_from_disk();
+        // Leaving with a recovery prompt open keeps the swap file for later.
+        if !model.editor_mode && model.editor_swap_recovery.take().is_none() {
+            Self::remove_editor_swap(model);
+        }
     }
 
     fn launch_external_editor(model: &mut Model, editor_cmd: &str) {
@@ -416,6 +526,7 @@ impl App {
         // Suspend TUI: disable mouse, leave alternate screen, disable raw mode
         let _ = set_mouse_motion_tracking(false);
         let _ = execute!(stdout(), DisableMouseCapture);
+        let _ = execute!(stdout(), DisableBracketedPaste);
         let _ = execute!(stdout(), LeaveAlternateScreen);
         let _ = disable_raw_mode();
 
@@ -429,6 +540,7 @@ impl App {
         let _ = enable_raw_mode();
         let _ = execute!(stdout(), EnterAlternateScreen);
         let _ = execute!(stdout(), EnableMouseCapture);
+        let _ = execute!(stdout(), EnableBracketedPaste);
         let _ = set_mouse_motion_tracking(true);
         model.needs_full_redraw = true;
 
@@ -474,17 +586,13 @@ impl App {
             return;
         }
 
-        // Check if the file changed on disk since we started editing
-        if !model.save_confirmed
-            && let Some(current_hash) = model.file_disk_hash()
+        // If the file changed on disk since we started editing, merge
+        // instead of overwriting the other writer's changes.
+        if let Some(current_hash) = model.file_disk_hash()
             && model.editor_disk_hash != Some(current_hash)
         {
             model.editor_disk_conflict = true;
-            model.save_confirmed = true;
-            model.show_toast(
-                ToastLevel::Warning,
-                "File changed on disk! Press Ctrl+S again to overwrite",
-            );
+            Self::open_editor_merge(model);
             return;
         }
 
@@ -494,14 +602,15 @@ impl App {
             .map(crate::editor::EditorBuffer::text)
             .unwrap_or_default();
         let path = model.file_path.clone();
-        match std::fs::write(&path, &text) {
+        match crate::editor::write_atomic(&path, text.as_bytes()) {
             Ok(()) => {
                 if let Some(buf) = &mut model.editor_buffer {
                     buf.mark_clean();
                 }
+                Self::remove_editor_swap(model);
                 model.editor_disk_hash = model.file_disk_hash();
+                model.editor_base = Some(text);
                 model.editor_disk_conflict = false;
-                model.save_confirmed = false;
 
                 // Reload from disk so the document stays in sync with what
                 // was saved, including proper code-fence wrapping for
@@ -515,6 +624,10 @@ impl App {
                     // Exit edit mode now that the buffer is saved
                     model.editor_mode = false;
                     model.editor_buffer = None;
+                    model.editor_find = None;
+                    model.editor_highlight = None;
+                    model.editor_base = None;
+                    model.editor_vim = None;
                     model.editor_scroll_offset = 0;
                     model.editor_disk_hash = None;
                     model.exit_confirmed = false;
@@ -532,8 +645,59 @@ impl App {
         }
     }
 
-    fn copy_selection(model: &mut Model) {
-        let Some((text, lines)) = model.selected_text() else {
+    /// Open the merge view for the buffer and the changed file on disk.
+    fn open_editor_merge(model: &mut Model) {
+        let disk_text = match std::fs::read_to_string(&model.file_path) {
+            Ok(text) => text,
+            Err(err) => {
+                model.show_toast(ToastLevel::Error, format!("Merge failed: {err}"));
+                return;
+            }
+        };
+        let Some(buf) = &model.editor_buffer else {
+            return;
+        };
+        let base = model.editor_base.as_deref().unwrap_or_default();
+        let merge = crate::editor::MergeState::new(base, &buf.text(), disk_text);
+        let conflicts = merge.conflict_count();
+        model.editor_merge = Some(merge);
+        model.show_toast(
+            ToastLevel::Warning,
+            format!("File changed on disk: {conflicts} conflict(s) to resolve"),
+        );
+    }
+
+    /// Write unsaved editor changes to the swap file, or remove it once the
+    /// buffer is clean again.
+    pub(super) fn write_editor_swap(model: &mut Model) {
+        let Some(buf) = &model.editor_buffer else {
+            return;
+        };
+        let result = if buf.is_dirty() {
+            crate::editor::write_swap(&model.file_path, &buf.text())
+        } else {
+            crate::editor::remove_swap(&model.file_path)
+        };
+        if let Err(err) = result {
+            model.show_toast(ToastLevel::Warning, format!("Swap file failed: {err}"));
+            crate::perf::log_event(
+                "swap.error",
+                format!("failed path={} err={err}", model.file_path.display()),
+            );
+        }
+    }
+
+    pub(super) fn remove_editor_swap(model: &Model) {
+        if let Err(err) = crate::editor::remove_swap(&model.file_path) {
+            crate::perf::log_event(
+                "swap.error",
+                format!("remove path={} err={err}", model.file_path.display()),
+            );
+        }
+    }
+
+    fn copy_selection(model: &mut Model, selected: fn(&Model) -> Option<(String, usize)>) {
+        let Some((text, lines)) = selected(model) else {
             return;
         };
         if text.is_empty() {
@@ -3,7 +3,9 @@ use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use crossterm::event;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
 use crossterm::execute;
 use ratatui::DefaultTerminal;
 
@@ -78,6 +80,72 @@ impl BrowseDebouncer {
     }
 }
 
+pub(super) struct PreviewDebouncer {
+    delay_ms: u64,
+    pending: Option<u64>,
+}
+
+impl PreviewDebouncer {
+    pub(super) const fn new(delay_ms: u64) -> Self {
+        Self {
+            delay_ms,
+            pending: None,
+        }
+    }
+
+    pub(super) const fn queue(&mut self, now_ms: u64) {
+        self.pending = Some(now_ms);
+    }
+
+    pub(super) const fn take_ready(&mut self, now_ms: u64) -> bool {
+        let Some(queued_at) = self.pending else {
+            return false;
+        };
+        if now_ms.saturating_sub(queued_at) >= self.delay_ms {
+            self.pending = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(super) const fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+/// Fires once per interval while edits keep arriving, so a steady stream
+/// of keystrokes still gets written out.
+pub(super) struct SwapThrottle {
+    interval_ms: u64,
+    due: Option<u64>,
+}
+
+impl SwapThrottle {
+    pub(super) const fn new(interval_ms: u64) -> Self {
+        Self {
+            interval_ms,
+            due: None,
+        }
+    }
+
+    pub(super) const fn queue(&mut self, now_ms: u64) {
+        if self.due.is_none() {
+            self.due = Some(now_ms.saturating_add(self.interval_ms));
+        }
+    }
+
+    pub(super) const fn take_ready(&mut self, now_ms: u64) -> bool {
+        match self.due {
+            Some(due) if now_ms >= due => {
+                self.due = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 impl App {
     /// Run the main event loop.
     ///
@@ -100,7 +168,7 @@ impl App {
 
         // Determine the file to load (may be overridden in browse mode)
         let initial_file = if self.browse_mode {
-            Self::find_first_viewable_file(&self.file_path)
+            Self::find_first_viewable_file(&self.file_path, &self.browse_filters)
         } else {
             Some(self.file_path.clone())
         };
@@ -110,6 +178,8 @@ impl App {
         let mut terminal = ratatui::try_init()
             .context("Failed to initialize terminal — markless requires an interactive terminal")?;
         let size = terminal.size()?;
+        // Pastes arrive as one event instead of a burst of key presses.
+        let _ = execute!(stdout(), EnableBracketedPaste);
         drop(init_scope);
 
         // Load the document
@@ -132,7 +202,12 @@ impl App {
                 layout_width
             ),
         );
-        let (document, effective_file) = if let Some(ref file) = initial_file {
+        let from_stdin = self.stdin_content.is_some();
+        let (document, effective_file) = if let Some(bytes) = self.stdin_content.take() {
+            let doc =
+                crate::document::prepare_document_from_bytes(&self.file_path, bytes, layout_width);
+            (doc, self.file_path.clone())
+        } else if let Some(ref file) = initial_file {
             let raw_bytes = std::fs::read(file)?;
             let doc = crate::document::prepare_document_from_bytes(file, raw_bytes, layout_width);
             (doc, file.clone())
@@ -145,12 +220,20 @@ impl App {
         // Create initial model
         let mut model =
             Model::new(effective_file, document, (size.width, size.height)).with_picker(picker);
-        model.watch_enabled = self.watch_enabled;
+        model.watch_enabled = self.watch_enabled && !from_stdin;
+        model.from_stdin = from_stdin;
         model.toc_visible = toc_visible;
         model.image_mode = self.image_mode;
         model.images_enabled = self.images_enabled;
         model.wrap_width = self.wrap_width;
+        model.image_limits = self.image_limits;
+        model.mermaid_options = self.mermaid_options;
+        model.browse_filters.clone_from(&self.browse_filters);
         model.external_editor.clone_from(&self.editor);
+        model.editor_vim_keys = self.vim_keys;
+        if let Some(keymap) = &self.keymap {
+            model.keymap.clone_from(keymap);
+        }
         model
             .config_global_path
             .clone_from(&self.config_global_path);
@@ -185,15 +268,26 @@ impl App {
         // Main loop
         let result = Self::event_loop(&mut terminal, &mut model);
 
+        // A clean exit leaves no swap file behind, unless it is still
+        // waiting to be recovered. After an error the swap file is the only
+        // copy of unsaved edits, so it stays.
+        if result.is_ok() && model.editor_mode && model.editor_swap_recovery.is_none() {
+            Self::remove_editor_swap(&model);
+        }
+
         // Restore terminal
         let _ = execute!(stdout(), DisableMouseCapture);
+        let _ = execute!(stdout(), DisableBracketedPaste);
         ratatui::restore();
 
         result
     }
 
-    /// Find the first viewable file in a directory.
-    fn find_first_viewable_file(dir: &std::path::Path) -> Option<std::path::PathBuf> {
+    /// Find the first viewable file in a directory that browse mode lists.
+    fn find_first_viewable_file(
+        dir: &std::path::Path,
+        filters: &crate::config::BrowseFilters,
+    ) -> Option<std::path::PathBuf> {
         let Ok(entries) = std::fs::read_dir(dir) else {
             return None;
         };
@@ -201,7 +295,7 @@ impl App {
             .filter_map(std::result::Result::ok)
             .filter(|e| {
                 e.file_type().ok().is_some_and(|ft| ft.is_file())
-                    && !e.file_name().to_string_lossy().starts_with('.')
+                    && !filters.hides(&e.file_name().to_string_lossy())
             })
             .map(|e| e.path())
             .collect();
@@ -231,13 +325,50 @@ impl App {
                     debouncer.queue(sel, now_ms);
                 }
             }
-            Message::TocSelect | Message::TocClick(_) | Message::TocExpand => {
+            Message::TocSelect
+            | Message::TocClick(_)
+            | Message::TocExpand
+            | Message::FileFinderSelect
+            | Message::CloseFileFinder => {
                 debouncer.cancel();
             }
+            // The file finder previews its highlighted file the same way.
+            Message::OpenFileFinder
+            | Message::FileFinderInput(_)
+            | Message::FileFinderDown(_)
+            | Message::FileFinderUp(_) => {
+                if let Some(finder) = &model.file_finder {
+                    debouncer.queue(finder.selected, now_ms);
+                }
+            }
             _ => {}
         }
     }
 
+    /// Re-parse the live preview once typing pauses.
+    const fn update_preview_debouncer(
+        model: &Model,
+        msg: &Message,
+        now_ms: u64,
+        debouncer: &mut PreviewDebouncer,
+    ) {
+        if model.editor_mode && model.editor_preview && msg.edits_editor_text() {
+            debouncer.queue(now_ms);
+        }
+    }
+
+    /// Schedule a swap file write after editor changes.
+    const fn update_swap_throttle(
+        model: &Model,
+        msg: &Message,
+        now_ms: u64,
+        throttle: &mut SwapThrottle,
+    ) {
+        if model.editor_mode && msg.edits_editor_text() {
+            throttle.queue(now_ms);
+        }
+    }
+
     fn event_loop(terminal: &mut DefaultTerminal, model: &mut Model) -> Result<()> {
         let start = Instant::now();
         let mut resize_debouncer = ResizeDebouncer::new(100);
@@ -259,6 +390,8 @@ impl App {
         };
         let mut watched_path = model.file_path.clone();
         let mut browse_debouncer = BrowseDebouncer::new(400);
+        let mut preview_debouncer = PreviewDebouncer::new(200);
+        let mut swap_throttle = SwapThrottle::new(2000);
         let mut frame_idx: u64 = 0;
         let mut needs_render = true;
         let mut mouse_capture_enabled = false;
@@ -315,15 +448,26 @@ impl App {
             // Auto-load file in browse mode after navigation settles
             if let Some(sel) = browse_debouncer.take_ready(now_ms)
                 && model.browse_mode
-                && let Some(entry) = model.browse_entries.get(sel).cloned()
-                && !entry.is_dir
+                && let Some(path) = model.browse_preview_path(sel)
+                && path != model.file_path
             {
-                if let Err(err) = model.load_file(&entry.path) {
+                if let Err(err) = model.load_file(&path) {
                     model.show_toast(ToastLevel::Error, format!("Open failed: {err}"));
                 }
                 needs_render = true;
             }
 
+            // Re-render the live preview after editing settles
+            if preview_debouncer.take_ready(now_ms) && model.editor_mode && model.editor_preview {
+                model.refresh_editor_preview();
+                needs_render = true;
+            }
+
+            // Keep the swap file current while there are unsaved changes
+            if swap_throttle.take_ready(now_ms) && model.editor_mode {
+                Self::write_editor_swap(model);
+            }
+
             if model.watch_enabled
                 && file_watcher
                     .as_mut()
@@ -334,12 +478,31 @@ impl App {
                 needs_render = true;
             }
 
+            // Pick up results streamed from a project-wide search
+            if model.poll_project_search() {
+                needs_render = true;
+            }
+            if model.poll_file_finder() {
+                needs_render = true;
+            }
+
             model.set_resize_pending(resize_debouncer.is_pending());
 
             // Handle events
             let poll_ms = if needs_render {
                 0
-            } else if resize_debouncer.is_pending() || browse_debouncer.is_pending() {
+            } else if resize_debouncer.is_pending()
+                || browse_debouncer.is_pending()
+                || preview_debouncer.is_pending()
+                || model
+                    .project_search
+                    .as_ref()
+                    .is_some_and(crate::app::ProjectSearchState::is_running)
+                || model
+                    .file_finder
+                    .as_ref()
+                    .is_some_and(crate::app::FileFinder::is_indexing)
+            {
                 10
             } else {
                 250
@@ -363,6 +526,13 @@ impl App {
                         event_ms,
                         &mut browse_debouncer,
                     );
+                    Self::update_preview_debouncer(
+                        model,
+                        &side_msg,
+                        event_ms,
+                        &mut preview_debouncer,
+                    );
+                    Self::update_swap_throttle(model, &side_msg, event_ms, &mut swap_throttle);
                     needs_render = true;
                 }
 
@@ -383,6 +553,13 @@ impl App {
                             drain_ms,
                             &mut browse_debouncer,
                         );
+                        Self::update_preview_debouncer(
+                            model,
+                            &side_msg,
+                            drain_ms,
+                            &mut preview_debouncer,
+                        );
+                        Self::update_swap_throttle(model, &side_msg, drain_ms, &mut swap_throttle);
                         needs_render = true;
                     }
                 }
@@ -6,7 +6,11 @@ use ratatui::layout::Rect;
 use unicode_width::UnicodeWidthStr;
 
 use crate::app::{App, Message, Model};
-use crate::editor::Direction;
+use crate::editor::{
+    Direction, ExCommand, FindField, FindState, Motion, Resolution, SwapRecovery, VimKey, VimMode,
+    VimState,
+};
+use crate::input::{Action, KeyChord, KeyContext, KeyLookup};
 
 use super::event_loop::ResizeDebouncer;
 
@@ -20,6 +24,25 @@ impl App {
         match event {
             Event::Key(key) if key.kind == KeyEventKind::Press => Self::handle_key(*key, model),
             Event::Mouse(mouse) => Self::handle_mouse(*mouse, model),
+            Event::Paste(_)
+                if model.editor_swap_recovery.is_some() || model.editor_merge.is_some() =>
+            {
+                None
+            }
+            Event::Paste(text) if model.editor_mode => {
+                Some(model.editor_find.as_ref().map_or_else(
+                    || Message::EditorPasteText(text.clone()),
+                    |find| {
+                        let field = match find.focus {
+                            FindField::Query => &find.query,
+                            FindField::Replacement => &find.replacement,
+                        };
+                        let pasted: String =
+                            text.chars().filter(|c| !matches!(c, '\n' | '\r')).collect();
+                        Message::EditorFindInput(format!("{field}{pasted}"))
+                    },
+                ))
+            }
             Event::Resize(w, h) => {
                 crate::perf::log_event("event.resize.queue", format!("width={w} height={h}"));
                 resize_debouncer.queue(*w, *h, now_ms);
@@ -38,6 +61,38 @@ impl App {
             };
         }
 
+        if model.file_finder.is_some() {
+            return match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Message::FileFinderDown(1)),
+                MouseEventKind::ScrollUp => Some(Message::FileFinderUp(1)),
+                _ => None,
+            };
+        }
+
+        if model.heading_palette.is_some() {
+            return match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Message::HeadingPaletteDown(1)),
+                MouseEventKind::ScrollUp => Some(Message::HeadingPaletteUp(1)),
+                _ => None,
+            };
+        }
+
+        if model.project_search.is_some() {
+            return match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Message::ProjectSearchDown(1)),
+                MouseEventKind::ScrollUp => Some(Message::ProjectSearchUp(1)),
+                _ => None,
+            };
+        }
+
+        if model.search_results.is_some() {
+            return match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Message::SearchResultsDown(1)),
+                MouseEventKind::ScrollUp => Some(Message::SearchResultsUp(1)),
+                _ => None,
+            };
+        }
+
         if model.link_picker_active() {
             let area = Rect::new(
                 0,
@@ -71,15 +126,47 @@ impl App {
             }
         }
 
+        if model.editor_swap_recovery.is_some() {
+            return match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Message::EditorSwapScrollDown(3)),
+                MouseEventKind::ScrollUp => Some(Message::EditorSwapScrollUp(3)),
+                _ => None,
+            };
+        }
+
+        if model.editor_merge.is_some() {
+            return match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Message::EditorMergeScrollDown(3)),
+                MouseEventKind::ScrollUp => Some(Message::EditorMergeScrollUp(3)),
+                _ => None,
+            };
+        }
+
         // Editor mode: handle scroll wheel and mouse click
         if model.editor_mode {
+            // The live preview pane scrolls the rendered document.
+            let total_width = model.viewport.width();
+            let editor_width = if model.editor_preview {
+                crate::ui::split_editor_columns(Rect::new(0, 0, total_width, 1))
+                    .first()
+                    .map_or(total_width, |r| r.width)
+            } else {
+                total_width
+            };
+            if mouse.column >= editor_width {
+                return match mouse.kind {
+                    MouseEventKind::ScrollDown => Some(Message::ScrollDown(3)),
+                    MouseEventKind::ScrollUp => Some(Message::ScrollUp(3)),
+                    _ => None,
+                };
+            }
             return match mouse.kind {
                 MouseEventKind::ScrollDown => Some(Message::EditorScrollDown(3)),
                 MouseEventKind::ScrollUp => Some(Message::EditorScrollUp(3)),
                 MouseEventKind::Down(MouseButton::Left) => {
                     let buf = model.editor_buffer.as_ref()?;
                     let toast_active = model.active_toast().is_some();
-                    let footer_rows = 1 + u16::from(toast_active);
+                    let footer_rows = 1 + u16::from(toast_active) + model.editor_find_rows();
                     let editor_area_height = model
                         .viewport
                         .height()
@@ -114,26 +201,27 @@ impl App {
                     return Some(Message::UpdateSelection(line));
                 }
             }
-            MouseEventKind::Up(MouseButton::Left) => {
-                if model.selection.is_some() {
-                    if let Some(line) = doc_line_for_row(model, doc_area, mouse.row, true) {
-                        if model.selection_dragging() {
-                            return Some(Message::EndSelection(line));
-                        }
-                        let content_col = mouse
-                            .column
-                            .saturating_sub(doc_area.x + crate::ui::DOCUMENT_LEFT_PADDING)
-                            as usize;
-                        if Self::link_at_column(model, line, content_col).is_some() {
-                            return Some(Message::FollowLinkAtLine(line, Some(content_col)));
-                        }
-                        if image_at_line(model, line) {
-                            return Some(Message::FollowLinkAtLine(line, None));
+            MouseEventKind::Up(MouseButton::Left) if model.selection.is_some() => {
+                if let Some(line) = doc_line_for_row(model, doc_area, mouse.row, true) {
+                    if model.selection_dragging() {
+                        if mouse.modifiers.contains(KeyModifiers::ALT) {
+                            return Some(Message::EndSourceSelection(line));
                         }
-                        return Some(Message::ClearSelection);
+                        return Some(Message::EndSelection(line));
+                    }
+                    let content_col = mouse
+                        .column
+                        .saturating_sub(doc_area.x + crate::ui::DOCUMENT_LEFT_PADDING)
+                        as usize;
+                    if Self::link_at_column(model, line, content_col).is_some() {
+                        return Some(Message::FollowLinkAtLine(line, Some(content_col)));
+                    }
+                    if image_at_line(model, line) {
+                        return Some(Message::FollowLinkAtLine(line, None));
                     }
                     return Some(Message::ClearSelection);
                 }
+                return Some(Message::ClearSelection);
             }
             _ => {}
         }
@@ -262,9 +350,29 @@ impl App {
             };
         }
 
-        // Ctrl+C / Ctrl+Q quit from any mode
-        if key.modifiers.contains(KeyModifiers::CONTROL)
-            && matches!(key.code, KeyCode::Char('c' | 'q'))
+        // Ctrl+C / Ctrl+X copy and cut the editor selection; without one,
+        // Ctrl+C still quits.
+        if model.editor_mode
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && model
+                .editor_buffer
+                .as_ref()
+                .is_some_and(|buf| buf.selection().is_some())
+        {
+            match key.code {
+                KeyCode::Char('c') => return Some(Message::EditorCopy),
+                KeyCode::Char('x') => return Some(Message::EditorCut),
+                _ => {}
+            }
+        }
+
+        // Ctrl/Alt keys bound to quit (Ctrl+C / Ctrl+Q by default) work
+        // from any mode.
+        let chord = KeyChord::from(key);
+        if chord
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            && model.keymap.lookup(KeyContext::View, &[chord]) == KeyLookup::Action(Action::Quit)
         {
             return Some(Message::Quit);
         }
@@ -279,125 +387,219 @@ impl App {
         }
 
         if model.editor_mode {
+            if let Some(recovery) = &model.editor_swap_recovery {
+                return Self::handle_swap_recovery_key(key, recovery);
+            }
+            if model.editor_merge.is_some() {
+                return Self::handle_merge_key(key);
+            }
+            if let Some(find) = &model.editor_find {
+                return Self::handle_editor_find_key(key, find);
+            }
+            if let Some(vim) = &model.editor_vim {
+                return Self::handle_vim_key(key, vim);
+            }
             return Self::handle_editor_key(key);
         }
 
-        if let Some(active_query) = model.search_query.as_ref() {
+        if let Some(finder) = model.file_finder.as_ref() {
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
             return match key.code {
-                KeyCode::Esc => Some(Message::ClearSearch),
-                KeyCode::Enter => Some(Message::NextMatch),
+                KeyCode::Esc => Some(Message::CloseFileFinder),
+                KeyCode::Enter => Some(Message::FileFinderSelect),
+                KeyCode::Down => Some(Message::FileFinderDown(1)),
+                KeyCode::Up => Some(Message::FileFinderUp(1)),
+                KeyCode::Char('n') if ctrl => Some(Message::FileFinderDown(1)),
+                KeyCode::Char('p') if ctrl => Some(Message::FileFinderUp(1)),
+                KeyCode::PageDown => Some(Message::FileFinderDown(10)),
+                KeyCode::PageUp => Some(Message::FileFinderUp(10)),
                 KeyCode::Backspace => {
-                    let mut next = active_query.clone();
+                    let mut next = finder.query.clone();
                     next.pop();
-                    Some(Message::SearchInput(next))
+                    Some(Message::FileFinderInput(next))
+                }
+                KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                    let mut next = finder.query.clone();
+                    next.push(c);
+                    Some(Message::FileFinderInput(next))
+                }
+                _ => None,
+            };
+        }
+
+        if let Some(palette) = model.heading_palette.as_ref() {
+            return match key.code {
+                KeyCode::Esc => Some(Message::CloseHeadingPalette),
+                KeyCode::Enter => Some(Message::HeadingPaletteSelect),
+                KeyCode::Down => Some(Message::HeadingPaletteDown(1)),
+                KeyCode::Up => Some(Message::HeadingPaletteUp(1)),
+                KeyCode::PageDown => Some(Message::HeadingPaletteDown(10)),
+                KeyCode::PageUp => Some(Message::HeadingPaletteUp(10)),
+                KeyCode::Backspace => {
+                    let mut next = palette.query.clone();
+                    next.pop();
+                    Some(Message::HeadingPaletteInput(next))
                 }
                 KeyCode::Char(c)
                     if !key.modifiers.contains(KeyModifiers::CONTROL)
                         && !key.modifiers.contains(KeyModifiers::ALT) =>
                 {
-                    let mut next = active_query.clone();
+                    let mut next = palette.query.clone();
                     next.push(c);
-                    Some(Message::SearchInput(next))
+                    Some(Message::HeadingPaletteInput(next))
                 }
                 _ => None,
             };
         }
 
-        // Global keys — always apply regardless of TOC focus
-        match key.code {
-            KeyCode::Char(' ') | KeyCode::PageDown => {
-                if model.viewport.can_scroll_down() {
-                    return Some(Message::PageDown);
-                }
-            }
-            KeyCode::Char('b') | KeyCode::PageUp => {
-                if model.viewport.can_scroll_up() {
-                    return Some(Message::PageUp);
+        if let Some(state) = model.project_search.as_ref() {
+            let alt = key.modifiers.contains(KeyModifiers::ALT);
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            return match key.code {
+                KeyCode::Esc => Some(Message::CloseProjectSearch),
+                // Enter searches for a new query, then opens the selected hit.
+                KeyCode::Enter if state.searched.as_deref() == Some(state.query.as_str()) => {
+                    (state.hit_count() > 0).then_some(Message::ProjectSearchOpen)
                 }
-            }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                if model.viewport.can_scroll_down() {
-                    return Some(Message::HalfPageDown);
+                KeyCode::Enter => Some(Message::ProjectSearchSubmit),
+                KeyCode::Down => Some(Message::ProjectSearchDown(1)),
+                KeyCode::Up => Some(Message::ProjectSearchUp(1)),
+                KeyCode::PageDown => Some(Message::ProjectSearchDown(10)),
+                KeyCode::PageUp => Some(Message::ProjectSearchUp(10)),
+                KeyCode::Char('r') if alt => Some(Message::ToggleSearchRegex),
+                KeyCode::Char('w') if alt => Some(Message::ToggleSearchWholeWord),
+                KeyCode::Backspace => {
+                    let mut next = state.query.clone();
+                    next.pop();
+                    Some(Message::ProjectSearchInput(next))
                 }
-            }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                if model.viewport.can_scroll_up() {
-                    return Some(Message::HalfPageUp);
+                KeyCode::Char(c) if !ctrl && !alt => {
+                    let mut next = state.query.clone();
+                    next.push(c);
+                    Some(Message::ProjectSearchInput(next))
                 }
-            }
-            KeyCode::Char('g') | KeyCode::Home => return Some(Message::GoToTop),
-            KeyCode::Char('G') | KeyCode::End => return Some(Message::GoToBottom),
-            KeyCode::Char('/') => return Some(Message::StartSearch),
-            KeyCode::Char('w') => return Some(Message::ToggleWatch),
-            KeyCode::Char('R' | 'r') => return Some(Message::ForceReload),
-            KeyCode::Char('o') => return Some(Message::OpenVisibleLinks),
-            _ => {}
+                _ => None,
+            };
         }
 
-        // Handle TOC-focused navigation
-        if model.toc_focused && model.toc_visible {
+        if let Some(results) = model.search_results.as_ref() {
             return match key.code {
-                KeyCode::Char('j') | KeyCode::Down => Some(Message::TocDown),
-                KeyCode::Char('k') | KeyCode::Up => Some(Message::TocUp),
-                KeyCode::Enter => Some(Message::TocSelect),
-                KeyCode::Char('h') | KeyCode::Left => Some(Message::TocCollapse),
-                KeyCode::Backspace if model.browse_mode => Some(Message::TocCollapse),
-                KeyCode::Char('l') | KeyCode::Right => Some(Message::TocExpand),
-                KeyCode::Tab | KeyCode::Esc => Some(Message::SwitchFocus),
-                KeyCode::Char('?') | KeyCode::F(1) => Some(Message::ToggleHelp),
-                KeyCode::Char('t') => Some(Message::ToggleToc),
-                KeyCode::Char('B') => Some(Message::EnterBrowseMode),
-                KeyCode::Char('F') => Some(Message::EnterFileMode),
-                KeyCode::Char('e') => Some(Message::EnterEditMode),
-                KeyCode::Char('q') => Some(Message::Quit),
+                KeyCode::Esc => Some(Message::CloseSearchResults),
+                KeyCode::Enter => Some(Message::SearchResultsSelect),
+                KeyCode::Down => Some(Message::SearchResultsDown(1)),
+                KeyCode::Up => Some(Message::SearchResultsUp(1)),
+                KeyCode::PageDown => Some(Message::SearchResultsDown(10)),
+                KeyCode::PageUp => Some(Message::SearchResultsUp(10)),
+                KeyCode::Backspace => {
+                    let mut next = results.filter.clone();
+                    next.pop();
+                    Some(Message::SearchResultsFilter(next))
+                }
+                KeyCode::Char(c)
+                    if !key.modifiers.contains(KeyModifiers::CONTROL)
+                        && !key.modifiers.contains(KeyModifiers::ALT) =>
+                {
+                    let mut next = results.filter.clone();
+                    next.push(c);
+                    Some(Message::SearchResultsFilter(next))
+                }
                 _ => None,
             };
         }
 
-        // Normal key handling
-        match key.code {
-            // Navigation
-            KeyCode::Char('j') | KeyCode::Down => {
-                if model.viewport.can_scroll_down() {
-                    Some(Message::ScrollDown(1))
-                } else {
-                    None
+        if let Some(active_query) = model.search_query.as_ref() {
+            return match key.code {
+                KeyCode::Tab => Some(Message::OpenSearchResults),
+                KeyCode::Esc => Some(Message::ClearSearch),
+                KeyCode::Enter => Some(Message::NextMatch),
+                KeyCode::Backspace => {
+                    let mut next = active_query.clone();
+                    next.pop();
+                    Some(Message::SearchInput(next))
                 }
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if model.viewport.can_scroll_up() {
-                    Some(Message::ScrollUp(1))
-                } else {
-                    None
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    Some(Message::ToggleSearchRegex)
                 }
-            }
-            // TOC
-            KeyCode::Char('t') => Some(Message::ToggleToc),
-            KeyCode::Char('T') => Some(Message::ToggleTocFocus),
-            KeyCode::Tab if model.toc_visible => Some(Message::SwitchFocus),
-
-            // Browse mode
-            KeyCode::Char('B') => Some(Message::EnterBrowseMode),
-            KeyCode::Char('F') => Some(Message::EnterFileMode),
-
-            // Editor
-            KeyCode::Char('e') => Some(Message::EnterEditMode),
-
-            // File
-            KeyCode::Char('?') | KeyCode::F(1) => Some(Message::ToggleHelp),
-
-            // Search
-            KeyCode::Esc => Some(Message::ClearSearch),
+                KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    Some(Message::ToggleSearchWholeWord)
+                }
+                KeyCode::Char(c)
+                    if !key.modifiers.contains(KeyModifiers::CONTROL)
+                        && !key.modifiers.contains(KeyModifiers::ALT) =>
+                {
+                    let mut next = active_query.clone();
+                    next.push(c);
+                    Some(Message::SearchInput(next))
+                }
+                _ => None,
+            };
+        }
 
-            // Quit
-            KeyCode::Char('q') => Some(Message::Quit),
+        let context = if model.toc_focused && model.toc_visible {
+            KeyContext::Toc
+        } else {
+            KeyContext::View
+        };
+        let mut keys = model.pending_keys.clone();
+        keys.push(chord);
+        match model.keymap.lookup(context, &keys) {
+            KeyLookup::Prefix => Some(Message::PendingKeys(keys)),
+            KeyLookup::Action(action) => Self::action_message(action, model)
+                .or_else(|| (keys.len() > 1).then(|| Message::PendingKeys(Vec::new()))),
+            // A key that doesn't continue the pending sequence starts afresh.
+            KeyLookup::Unbound if keys.len() > 1 => match model.keymap.lookup(context, &[chord]) {
+                KeyLookup::Prefix => Some(Message::PendingKeys(vec![chord])),
+                KeyLookup::Action(action) => Self::action_message(action, model),
+                KeyLookup::Unbound => None,
+            }
+            .or(Some(Message::PendingKeys(Vec::new()))),
+            KeyLookup::Unbound => None,
+        }
+    }
 
-            _ => None,
+    /// The message for a bound view-mode action, or `None` when it doesn't
+    /// apply right now (e.g. scrolling past the end).
+    fn action_message(action: Action, model: &Model) -> Option<Message> {
+        let can_down = model.viewport.can_scroll_down();
+        let can_up = model.viewport.can_scroll_up();
+        match action {
+            Action::ScrollDown => can_down.then_some(Message::ScrollDown(1)),
+            Action::ScrollUp => can_up.then_some(Message::ScrollUp(1)),
+            Action::PageDown => can_down.then_some(Message::PageDown),
+            Action::PageUp => can_up.then_some(Message::PageUp),
+            Action::HalfPageDown => can_down.then_some(Message::HalfPageDown),
+            Action::HalfPageUp => can_up.then_some(Message::HalfPageUp),
+            Action::GoToTop => Some(Message::GoToTop),
+            Action::GoToBottom => Some(Message::GoToBottom),
+            Action::StartSearch => Some(Message::StartSearch),
+            Action::ClearSearch => Some(Message::ClearSearch),
+            Action::ProjectSearch => model.browse_mode.then_some(Message::OpenProjectSearch),
+            Action::ToggleToc => Some(Message::ToggleToc),
+            Action::ToggleTocFocus => Some(Message::ToggleTocFocus),
+            Action::SwitchFocus => model.toc_visible.then_some(Message::SwitchFocus),
+            Action::TocDown => Some(Message::TocDown),
+            Action::TocUp => Some(Message::TocUp),
+            Action::TocSelect => Some(Message::TocSelect),
+            Action::TocCollapse => Some(Message::TocCollapse),
+            Action::TocExpand => Some(Message::TocExpand),
+            Action::HeadingPalette => Some(Message::OpenHeadingPalette),
+            Action::EnterBrowseMode => Some(Message::EnterBrowseMode),
+            Action::EnterFileMode => Some(Message::EnterFileMode),
+            Action::TocParent => model.browse_mode.then_some(Message::TocCollapse),
+            Action::FileFinder => model.browse_mode.then_some(Message::OpenFileFinder),
+            Action::EnterEditMode => Some(Message::EnterEditMode),
+            Action::ToggleWatch => Some(Message::ToggleWatch),
+            Action::ForceReload => Some(Message::ForceReload),
+            Action::OpenVisibleLinks => Some(Message::OpenVisibleLinks),
+            Action::ToggleFrontMatter => Some(Message::ToggleFrontMatter),
+            Action::ToggleHelp => Some(Message::ToggleHelp),
+            Action::Quit => Some(Message::Quit),
         }
     }
 
     const fn handle_editor_key(key: event::KeyEvent) -> Option<Message> {
         let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
 
         match key.code {
             // Exit edit mode
@@ -406,6 +608,42 @@ impl App {
             // Save
             KeyCode::Char('s') if ctrl => Some(Message::EditorSave),
 
+            // History
+            KeyCode::Char('z') if ctrl => Some(Message::EditorUndo),
+            KeyCode::Char('y') if ctrl => Some(Message::EditorRedo),
+
+            // Find / replace
+            KeyCode::Char('f') if ctrl => Some(Message::EditorFindOpen),
+            KeyCode::Char('r') if ctrl => Some(Message::EditorReplaceOpen),
+
+            // Live preview
+            KeyCode::Char('p') if ctrl => Some(Message::EditorTogglePreview),
+
+            // Markdown helpers
+            KeyCode::Char('t') if ctrl => Some(Message::EditorFormatTable),
+
+            // Clipboard
+            KeyCode::Char('a') if ctrl => Some(Message::EditorSelectAll),
+            KeyCode::Char('v') if ctrl => Some(Message::EditorPaste),
+
+            // Selection with Shift
+            KeyCode::Left if ctrl && shift => Some(Message::EditorSelect(Motion::WordLeft)),
+            KeyCode::Right if ctrl && shift => Some(Message::EditorSelect(Motion::WordRight)),
+            KeyCode::Home if ctrl && shift => Some(Message::EditorSelect(Motion::BufferStart)),
+            KeyCode::End if ctrl && shift => Some(Message::EditorSelect(Motion::BufferEnd)),
+            KeyCode::Left if shift => {
+                Some(Message::EditorSelect(Motion::Direction(Direction::Left)))
+            }
+            KeyCode::Right if shift => {
+                Some(Message::EditorSelect(Motion::Direction(Direction::Right)))
+            }
+            KeyCode::Up if shift => Some(Message::EditorSelect(Motion::Direction(Direction::Up))),
+            KeyCode::Down if shift => {
+                Some(Message::EditorSelect(Motion::Direction(Direction::Down)))
+            }
+            KeyCode::Home if shift => Some(Message::EditorSelect(Motion::Home)),
+            KeyCode::End if shift => Some(Message::EditorSelect(Motion::End)),
+
             // Navigation with Ctrl
             KeyCode::Left if ctrl => Some(Message::EditorMoveWordLeft),
             KeyCode::Right if ctrl => Some(Message::EditorMoveWordRight),
@@ -426,13 +664,143 @@ impl App {
             KeyCode::Enter => Some(Message::EditorSplitLine),
             KeyCode::Backspace => Some(Message::EditorDeleteBack),
             KeyCode::Delete => Some(Message::EditorDeleteForward),
-            KeyCode::Tab => Some(Message::EditorInsertChar('\t')),
+            KeyCode::Tab => Some(Message::EditorIndent),
+            KeyCode::BackTab => Some(Message::EditorOutdent),
             KeyCode::Char(c) if !ctrl => Some(Message::EditorInsertChar(c)),
 
             _ => None,
         }
     }
 
+    /// Keys for the editor with Vim keybindings. Insert mode keeps the
+    /// regular editor keys apart from Esc, and Ctrl combinations other than
+    /// Ctrl+R (redo) keep their editor meaning in every mode.
+    fn handle_vim_key(key: event::KeyEvent, vim: &VimState) -> Option<Message> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if let Some(line) = vim.command_line() {
+            return match key.code {
+                KeyCode::Enter => Some(Message::EditorVimCommand(ExCommand::parse(line))),
+                KeyCode::Esc => Some(Message::EditorVimKey(VimKey::Esc)),
+                KeyCode::Backspace => Some(Message::EditorVimKey(VimKey::Backspace)),
+                KeyCode::Char(c) if !ctrl => Some(Message::EditorVimKey(VimKey::Char(c))),
+                _ => None,
+            };
+        }
+        if vim.mode() == VimMode::Insert {
+            return if key.code == KeyCode::Esc {
+                Some(Message::EditorVimKey(VimKey::Esc))
+            } else {
+                Self::handle_editor_key(key)
+            };
+        }
+
+        let key_char = |c| Some(Message::EditorVimKey(VimKey::Char(c)));
+        match key.code {
+            KeyCode::Char('r') if ctrl => Some(Message::EditorVimKey(VimKey::Ctrl('r'))),
+            _ if ctrl => Self::handle_editor_key(key),
+            KeyCode::Char(c) => key_char(c),
+            KeyCode::Esc => Some(Message::EditorVimKey(VimKey::Esc)),
+            KeyCode::Backspace => Some(Message::EditorVimKey(VimKey::Backspace)),
+            KeyCode::Left => key_char('h'),
+            KeyCode::Right => key_char('l'),
+            KeyCode::Up => key_char('k'),
+            KeyCode::Down | KeyCode::Enter => key_char('j'),
+            KeyCode::Home => key_char('0'),
+            KeyCode::End => key_char('$'),
+            KeyCode::Delete => key_char('x'),
+            KeyCode::PageUp | KeyCode::PageDown => Self::handle_editor_key(key),
+            _ => None,
+        }
+    }
+
+    /// Keys for the prompt offering a leftover swap file.
+    const fn handle_swap_recovery_key(
+        key: event::KeyEvent,
+        recovery: &SwapRecovery,
+    ) -> Option<Message> {
+        match key.code {
+            KeyCode::Char('r') => Some(Message::EditorSwapRecover),
+            KeyCode::Char('d') => Some(Message::EditorSwapToggleDiff),
+            KeyCode::Char('x') => Some(Message::EditorSwapDiscard),
+            KeyCode::Esc if recovery.showing_diff => Some(Message::EditorSwapToggleDiff),
+            KeyCode::Esc => Some(Message::ExitEditMode),
+            KeyCode::Char('j') | KeyCode::Down => Some(Message::EditorSwapScrollDown(1)),
+            KeyCode::Char('k') | KeyCode::Up => Some(Message::EditorSwapScrollUp(1)),
+            KeyCode::Char(' ') | KeyCode::PageDown => Some(Message::EditorSwapScrollDown(10)),
+            KeyCode::Char('b') | KeyCode::PageUp => Some(Message::EditorSwapScrollUp(10)),
+            _ => None,
+        }
+    }
+
+    /// Keys for the merge view opened when saving over a changed file.
+    const fn handle_merge_key(key: event::KeyEvent) -> Option<Message> {
+        match key.code {
+            KeyCode::Char('n') | KeyCode::Tab => Some(Message::EditorMergeNext),
+            KeyCode::Char('p') | KeyCode::BackTab => Some(Message::EditorMergePrev),
+            KeyCode::Char('o') => Some(Message::EditorMergeResolve(Resolution::Ours)),
+            KeyCode::Char('t') => Some(Message::EditorMergeResolve(Resolution::Theirs)),
+            KeyCode::Char('b') => Some(Message::EditorMergeResolve(Resolution::Both)),
+            KeyCode::Enter => Some(Message::EditorMergeApply),
+            KeyCode::Char('d') => Some(Message::EditorMergeToggleDiff),
+            KeyCode::Esc => Some(Message::EditorMergeCancel),
+            KeyCode::Char('j') | KeyCode::Down => Some(Message::EditorMergeScrollDown(1)),
+            KeyCode::Char('k') | KeyCode::Up => Some(Message::EditorMergeScrollUp(1)),
+            KeyCode::Char(' ') | KeyCode::PageDown => Some(Message::EditorMergeScrollDown(10)),
+            KeyCode::PageUp => Some(Message::EditorMergeScrollUp(10)),
+            _ => None,
+        }
+    }
+
+    /// Keys while the editor's find bar has focus.
+    fn handle_editor_find_key(key: event::KeyEvent, find: &FindState) -> Option<Message> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let field = match find.focus {
+            FindField::Query => &find.query,
+            FindField::Replacement => &find.replacement,
+        };
+
+        match key.code {
+            KeyCode::Esc => Some(Message::EditorFindClose),
+
+            // Save and history keep working while searching
+            KeyCode::Char('s' | 'z' | 'y' | 'p') if ctrl => Self::handle_editor_key(key),
+            KeyCode::Char('f') if ctrl => Some(Message::EditorFindOpen),
+            KeyCode::Char('r') if ctrl => Some(Message::EditorReplaceOpen),
+
+            // Options and replace-all
+            KeyCode::Char('c') if alt => Some(Message::EditorFindToggleCase),
+            KeyCode::Char('r') if alt => Some(Message::EditorFindToggleRegex),
+            KeyCode::Char('a') if alt && find.replacing => Some(Message::EditorReplaceAll),
+
+            // Match navigation
+            KeyCode::Enter if !shift && find.focus == FindField::Replacement => {
+                Some(Message::EditorReplaceOne)
+            }
+            KeyCode::Enter | KeyCode::F(3) | KeyCode::Up | KeyCode::Down => {
+                if shift || key.code == KeyCode::Up {
+                    Some(Message::EditorFindPrev)
+                } else {
+                    Some(Message::EditorFindNext)
+                }
+            }
+            KeyCode::Tab | KeyCode::BackTab => Some(Message::EditorFindSwitchField),
+
+            // Field editing
+            KeyCode::Backspace => {
+                let mut next = field.clone();
+                next.pop();
+                Some(Message::EditorFindInput(next))
+            }
+            KeyCode::Char(c) if !ctrl && !alt => {
+                Some(Message::EditorFindInput(format!("{field}{c}")))
+            }
+
+            _ => None,
+        }
+    }
+
     pub(super) fn view(model: &mut Model, frame: &mut Frame) {
         crate::ui::render(model, frame);
     }
@@ -12,12 +12,21 @@ mod input;
 mod model;
 mod update;
 
-pub use model::{Model, ToastLevel};
+pub use model::{
+    FileFinder, FileFinderItem, HeadingPalette, HeadingPaletteItem, Model, ProjectSearchState,
+    SearchResultItem, SearchResults, ToastLevel,
+};
 pub use update::{Message, update};
 
 use std::path::PathBuf;
 
-use crate::config::ImageMode;
+use crate::config::{BrowseFilters, ImageLimits, ImageMode, MermaidOptions};
+use crate::input::Keymap;
+
+/// File name shown for documents read from stdin.
+///
+/// It has no extension so the content is treated as markdown.
+pub const STDIN_FILE_NAME: &str = "<stdin>";
 
 /// Main application struct that owns the terminal and runs the event loop.
 pub struct App {
@@ -30,7 +39,13 @@ pub struct App {
     config_local_path: Option<PathBuf>,
     browse_mode: bool,
     wrap_width: Option<u16>,
+    image_limits: ImageLimits,
+    mermaid_options: MermaidOptions,
+    browse_filters: BrowseFilters,
     editor: Option<String>,
+    vim_keys: bool,
+    keymap: Option<Keymap>,
+    stdin_content: Option<Vec<u8>>,
 }
 
 impl App {
@@ -46,7 +61,22 @@ impl App {
             config_local_path: None,
             browse_mode: false,
             wrap_width: None,
+            image_limits: ImageLimits {
+                max_width: None,
+                max_height: None,
+            },
+            mermaid_options: MermaidOptions {
+                enabled: None,
+                width_percent: None,
+            },
+            browse_filters: BrowseFilters {
+                show_hidden: None,
+                exclude: Vec::new(),
+            },
             editor: None,
+            vim_keys: false,
+            keymap: None,
+            stdin_content: None,
         }
     }
 
@@ -92,6 +122,27 @@ impl App {
         self
     }
 
+    /// Limit the size of inline images.
+    #[must_use]
+    pub const fn with_image_limits(mut self, limits: ImageLimits) -> Self {
+        self.image_limits = limits;
+        self
+    }
+
+    /// Set mermaid diagram rendering options.
+    #[must_use]
+    pub const fn with_mermaid_options(mut self, options: MermaidOptions) -> Self {
+        self.mermaid_options = options;
+        self
+    }
+
+    /// Choose which entries browse mode lists.
+    #[must_use]
+    pub fn with_browse_filters(mut self, filters: BrowseFilters) -> Self {
+        self.browse_filters = filters;
+        self
+    }
+
     /// Set the external editor command.
     #[must_use]
     pub fn with_editor(mut self, editor: Option<String>) -> Self {
@@ -99,6 +150,29 @@ impl App {
         self
     }
 
+    /// Use Vim-style modal keybindings in the built-in editor.
+    #[must_use]
+    pub const fn with_vim_keys(mut self, enabled: bool) -> Self {
+        self.vim_keys = enabled;
+        self
+    }
+
+    /// Use `keymap` for view-mode keys instead of the defaults.
+    #[must_use]
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Show `content` (already read from stdin) instead of reading `file_path`.
+    ///
+    /// Watching and editing are unavailable for such documents.
+    #[must_use]
+    pub fn with_stdin_content(mut self, content: Option<Vec<u8>>) -> Self {
+        self.stdin_content = content;
+        self
+    }
+
     /// Set config paths to show in help.
     #[must_use]
     pub fn with_config_paths(
@@ -7,12 +7,18 @@ use anyhow::Result;
 use image::DynamicImage;
 use ratatui_image::picker::{Picker, ProtocolType};
 
-use crate::config::ImageMode;
+use crate::config::{BrowseFilters, ImageLimits, ImageMode, MermaidOptions};
 use ratatui_image::protocol::StatefulProtocol;
 
 use crate::document::Document;
-use crate::editor::EditorBuffer;
+use crate::editor::{EditorBuffer, FindState, MergeState, SwapRecovery, VimState};
 use crate::image::ImageLoader;
+use crate::input::{KeyChord, Keymap};
+use crate::search::{
+    FileHits, FileIndex, FileIndexEvent, LineMatch, ProjectHit, ProjectSearch, ProjectSearchEvent,
+    SearchMode,
+};
+use crate::ui::EditorHighlighter;
 use crate::ui::viewport::Viewport;
 
 /// Hash a byte slice for content comparison.
@@ -30,6 +36,10 @@ pub(super) fn hash_bytes(bytes: &[u8]) -> u64 {
 /// good balance between readability and not overwhelming the terminal.
 const MERMAID_WIDTH_PERCENT: u32 = 60;
 
+/// Glyph color for rasterized display math on light / dark terminals.
+const MATH_COLOR_LIGHT_BG: &str = "#1f1f1f";
+const MATH_COLOR_DARK_BG: &str = "#e4e4e4";
+
 use super::update::{closest_heading_to_line, refresh_search_matches};
 
 /// The complete application state.
@@ -74,6 +84,226 @@ pub struct LineSelection {
     pub state: SelectionState,
 }
 
+/// State of the search results overlay.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchResults {
+    /// Extra text the listed matches must contain.
+    pub filter: String,
+    /// Selected row among the filtered results.
+    pub selected: usize,
+}
+
+/// One row of the search results overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResultItem {
+    /// Index into the search matches.
+    pub index: usize,
+    /// Rendered line of the match.
+    pub line: usize,
+    /// Text of the heading the match falls under.
+    pub heading: Option<String>,
+    /// The matched line, shortened to start near the first match.
+    pub snippet: String,
+    /// Byte ranges of the matches within `snippet`.
+    pub ranges: Vec<std::ops::Range<usize>>,
+}
+
+/// State of the heading palette.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeadingPalette {
+    /// Text typed to narrow the headings.
+    pub query: String,
+    /// Selected row among the ranked headings.
+    pub selected: usize,
+}
+
+/// One row of the heading palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingPaletteItem {
+    /// Index into the document's headings.
+    pub index: usize,
+    /// Heading level (1-6), or 0 for the front matter title.
+    pub level: u8,
+    pub text: String,
+    /// Texts of the enclosing headings, outermost first.
+    pub breadcrumb: Vec<String>,
+    /// Byte offsets of the characters in `text` the query matched.
+    pub positions: Vec<usize>,
+}
+
+/// State of the file finder overlay.
+#[derive(Debug, Default)]
+pub struct FileFinder {
+    /// Directory the listed paths are relative to.
+    pub root: PathBuf,
+    /// Text typed to narrow the files.
+    pub query: String,
+    /// Selected row among the ranked files.
+    pub selected: usize,
+    /// File shown before the finder opened, restored on cancel.
+    pub original: PathBuf,
+    /// Every file indexed so far, relative to `root`.
+    files: Vec<PathBuf>,
+    /// Files matching the query, best match first.
+    ranked: Vec<FileFinderItem>,
+    index: Option<FileIndex>,
+}
+
+/// One row of the file finder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFinderItem {
+    /// Path relative to the finder root.
+    pub path: String,
+    /// Byte offsets of the characters in `path` the query matched.
+    pub positions: Vec<usize>,
+}
+
+impl FileFinder {
+    /// A finder over the files under `root`, with nothing indexed yet.
+    pub fn new(root: &Path, original: PathBuf) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            original,
+            ..Self::default()
+        }
+    }
+
+    /// Start indexing the files under `root` in the background.
+    pub fn start_indexing(&mut self, filters: &crate::config::BrowseFilters) {
+        self.index = Some(FileIndex::start(&self.root, filters));
+    }
+
+    /// Whether files are still being indexed.
+    pub const fn is_indexing(&self) -> bool {
+        self.index.is_some()
+    }
+
+    /// Number of files indexed so far.
+    pub const fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Files matching the query, best match first.
+    pub fn items(&self) -> &[FileFinderItem] {
+        &self.ranked
+    }
+
+    /// Full path of the selected file.
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        self.ranked
+            .get(self.selected)
+            .map(|item| self.root.join(&item.path))
+    }
+
+    /// Change the query and rank the files against it.
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.selected = 0;
+        self.rank();
+    }
+
+    /// Take in files from the background index. Returns whether anything
+    /// arrived.
+    pub fn poll(&mut self) -> bool {
+        let Some(index) = self.index.as_ref() else {
+            return false;
+        };
+        let events = index.poll();
+        if events.is_empty() {
+            return false;
+        }
+        for event in events {
+            match event {
+                FileIndexEvent::Files(batch) => self.files.extend(batch),
+                FileIndexEvent::Done => self.index = None,
+            }
+        }
+        self.rank();
+        true
+    }
+
+    /// Rank the files by how well their paths match the query; ties go to
+    /// the shorter path.
+    fn rank(&mut self) {
+        let mut scored: Vec<(i32, FileFinderItem)> = self
+            .files
+            .iter()
+            .filter_map(|path| {
+                let path = path.to_string_lossy().into_owned();
+                let matched = crate::search::fuzzy_match(&self.query, &path)?;
+                Some((
+                    matched.score,
+                    FileFinderItem {
+                        path,
+                        positions: matched.positions,
+                    },
+                ))
+            })
+            .collect();
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then(a.path.len().cmp(&b.path.len()))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        self.ranked = scored.into_iter().map(|(_, item)| item).collect();
+        self.selected = self.selected.min(self.ranked.len().saturating_sub(1));
+    }
+}
+
+/// State of the project-wide search overlay.
+#[derive(Debug, Default)]
+pub struct ProjectSearchState {
+    /// Query being typed.
+    pub query: String,
+    /// Query the listed results are for.
+    pub searched: Option<String>,
+    /// Files with hits, in path order.
+    pub files: Vec<FileHits>,
+    /// Selected hit, counted across all files.
+    pub selected: usize,
+    /// Number of files searched, once the search has finished.
+    pub files_searched: Option<usize>,
+    /// Whether the search stopped at the hit limit.
+    pub truncated: bool,
+    task: Option<ProjectSearch>,
+}
+
+impl ProjectSearchState {
+    /// An overlay with `query` typed in and nothing searched yet.
+    pub fn new(query: String) -> Self {
+        Self {
+            query,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the background search is still running.
+    pub const fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Total number of hits received.
+    pub fn hit_count(&self) -> usize {
+        self.files.iter().map(|file| file.hits.len()).sum()
+    }
+
+    /// The file and hit at flat index `index`.
+    pub fn hit(&self, index: usize) -> Option<(&FileHits, &ProjectHit)> {
+        let mut index = index;
+        for file in &self.files {
+            if let Some(hit) = file.hits.get(index) {
+                return Some((file, hit));
+            }
+            index -= file.hits.len();
+        }
+        None
+    }
+}
+
+/// Bytes of context kept before the first match in a snippet.
+const SNIPPET_LEAD: usize = 24;
+
 pub struct Model {
     /// The loaded markdown document
     pub document: Document,
@@ -106,12 +336,22 @@ pub struct Model {
     toast: Option<Toast>,
     /// Current search query
     pub search_query: Option<String>,
-    /// Rendered line indices that match the current search query
-    pub(super) search_matches: Vec<usize>,
+    /// Rendered lines that match the current search query
+    pub(super) search_matches: Vec<LineMatch>,
     /// Current selected match index inside `search_matches`
     pub(super) search_match_index: Option<usize>,
     /// Allow searching short (<3 char) queries after explicit Enter.
     pub(super) search_allow_short: bool,
+    /// Regex and whole-word toggles, kept between searches.
+    pub search_mode: SearchMode,
+    /// Search results overlay, when open.
+    pub search_results: Option<SearchResults>,
+    /// Project-wide search overlay (browse mode), when open.
+    pub project_search: Option<ProjectSearchState>,
+    /// Fuzzy heading palette, when open.
+    pub heading_palette: Option<HeadingPalette>,
+    /// Fuzzy file finder (browse mode), when open.
+    pub file_finder: Option<FileFinder>,
     /// Whether the app should quit
     pub should_quit: bool,
     /// Focus: true = TOC, false = document
@@ -139,6 +379,12 @@ pub struct Model {
     pub images_enabled: bool,
     /// Optional maximum content wrap width in columns
     pub wrap_width: Option<u16>,
+    /// Configured inline image size limits
+    pub image_limits: ImageLimits,
+    /// Configured mermaid rendering options
+    pub mermaid_options: MermaidOptions,
+    /// Which entries browse mode lists
+    pub browse_filters: BrowseFilters,
     /// Whether directory browse mode is active
     pub browse_mode: bool,
     /// Current directory being browsed
@@ -151,12 +397,32 @@ pub struct Model {
     pub editor_buffer: Option<EditorBuffer>,
     /// Scroll offset for the editor viewport (line index of first visible line)
     pub editor_scroll_offset: usize,
+    /// Text last copied or cut in the editor, pasted back with Ctrl+V
+    pub editor_clipboard: Option<String>,
+    /// Find/replace bar state while it is open in the editor
+    pub editor_find: Option<FindState>,
+    /// Cached syntax highlighting state for the editor buffer
+    pub editor_highlight: Option<EditorHighlighter>,
+    /// Whether the rendered document is shown beside the editor
+    pub editor_preview: bool,
+    /// Leftover swap file offered for recovery when edit mode was entered
+    pub editor_swap_recovery: Option<SwapRecovery>,
     /// Hash of the file on disk when edit mode was entered (for conflict detection)
     pub editor_disk_hash: Option<u64>,
+    /// File text the buffer started from, used as the base of a three-way merge
+    pub editor_base: Option<String>,
     /// Whether the file on disk has changed since edit mode was entered
     pub editor_disk_conflict: bool,
-    /// Set after first save attempt when disk conflict detected; allows second save to force
-    pub save_confirmed: bool,
+    /// Merge view shown when saving over a file that changed on disk
+    pub editor_merge: Option<MergeState>,
+    /// Whether the editor uses Vim-style modal keybindings
+    pub editor_vim_keys: bool,
+    /// Vim mode, pending keys and register while editing with Vim keys
+    pub editor_vim: Option<VimState>,
+    /// View-mode key bindings
+    pub keymap: Keymap,
+    /// Keys typed so far of a multi-key binding such as `gg`
+    pub pending_keys: Vec<KeyChord>,
     /// Set after first quit attempt with unsaved editor changes; allows second quit to proceed
     pub quit_confirmed: bool,
     /// Set after first Esc press with unsaved editor changes; allows second Esc to discard
@@ -166,6 +432,10 @@ pub struct Model {
     /// Signals that ratatui's internal buffer is stale and `terminal.clear()` must
     /// be called before the next draw (e.g. after returning from an external process).
     pub needs_full_redraw: bool,
+    /// Whether the document was read from stdin (nothing on disk to watch, reload, or edit)
+    pub from_stdin: bool,
+    /// Whether the front matter panel is collapsed to its header line
+    pub front_matter_collapsed: bool,
 }
 
 impl std::fmt::Debug for Model {
@@ -212,6 +482,11 @@ impl Model {
             search_matches: Vec::new(),
             search_match_index: None,
             search_allow_short: false,
+            search_mode: SearchMode::default(),
+            search_results: None,
+            project_search: None,
+            heading_palette: None,
+            file_finder: None,
             should_quit: false,
             toc_focused: false,
             image_protocols: HashMap::new(),
@@ -225,19 +500,34 @@ impl Model {
             selection: None,
             images_enabled: true,
             wrap_width: None,
+            image_limits: ImageLimits::default(),
+            mermaid_options: MermaidOptions::default(),
+            browse_filters: BrowseFilters::default(),
             browse_mode: false,
             browse_dir: base_dir,
             browse_entries: Vec::new(),
             editor_mode: false,
             editor_buffer: None,
             editor_scroll_offset: 0,
+            editor_clipboard: None,
+            editor_find: None,
+            editor_highlight: None,
+            editor_preview: false,
+            editor_swap_recovery: None,
             editor_disk_hash: None,
+            editor_base: None,
             editor_disk_conflict: false,
-            save_confirmed: false,
+            editor_merge: None,
+            editor_vim_keys: false,
+            editor_vim: None,
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
             quit_confirmed: false,
             exit_confirmed: false,
             external_editor: None,
             needs_full_redraw: false,
+            from_stdin: false,
+            front_matter_collapsed: false,
         }
     }
 
@@ -250,10 +540,11 @@ impl Model {
 
     /// Whether mermaid diagrams should be rendered as images.
     ///
-    /// True only when images are enabled and the terminal supports a real
-    /// graphics protocol (Kitty, Sixel, iTerm2) — not half-block fallback.
+    /// True only when images are enabled, mermaid rendering isn't turned off
+    /// in the config, and the terminal supports a real graphics protocol
+    /// (Kitty, Sixel, iTerm2) — not half-block fallback.
     pub fn should_render_mermaid_as_images(&self) -> bool {
-        if !self.images_enabled {
+        if !self.images_enabled || self.mermaid_options.enabled == Some(false) {
             return false;
         }
         let Some(picker) = &self.picker else {
@@ -286,6 +577,10 @@ impl Model {
         #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
         // target_width_cols is always positive and within u16 range (65% of a u16).
         let target_width_cols = (f32::from(current_width) * 0.65) as u16;
+        let target_width_cols = self
+            .image_limits
+            .max_width
+            .map_or(target_width_cols, |max| target_width_cols.min(max.max(1)));
         let target_width_px = u32::from(target_width_cols) * u32::from(font_size.0);
 
         // Load images within 2 viewport heights of current position
@@ -335,7 +630,11 @@ impl Model {
                     if let Some(img) = self.original_images.get(&src) {
                         Some(img.clone())
                     } else if src.starts_with("mermaid://") {
-                        let mermaid_width_px = target_width_px * MERMAID_WIDTH_PERCENT / 100;
+                        let percent = self
+                            .mermaid_options
+                            .width_percent
+                            .unwrap_or(MERMAID_WIDTH_PERCENT);
+                        let mermaid_width_px = target_width_px * percent / 100;
                         self.document
                             .mermaid_sources()
                             .get(&src)
@@ -352,6 +651,34 @@ impl Model {
                             .inspect(|img| {
                                 self.original_images.insert(src.clone(), img.clone());
                             })
+                    } else if src.starts_with("math://") {
+                        let color = if crate::highlight::is_light_background() {
+                            MATH_COLOR_LIGHT_BG
+                        } else {
+                            MATH_COLOR_DARK_BG
+                        };
+                        self.document
+                            .math_sources()
+                            .get(&src)
+                            .and_then(|tex| {
+                                let svg = crate::document::tex_to_svg(
+                                    tex,
+                                    f64::from(font_size.1),
+                                    target_width_px,
+                                    color,
+                                );
+                                crate::mermaid::rasterize_svg(&svg, target_width_px)
+                                    .inspect_err(|e| {
+                                        crate::perf::log_event(
+                                            "math.render.error",
+                                            format!("src={src} err={e}"),
+                                        );
+                                    })
+                                    .ok()
+                            })
+                            .inspect(|img| {
+                                self.original_images.insert(src.clone(), img.clone());
+                            })
                     } else if let Some(img) = loader.load_sync(&src) {
                         self.original_images.insert(src.clone(), img.clone());
                         Some(img)
@@ -360,15 +687,23 @@ impl Model {
                     };
 
                 if let Some(img) = original {
-                    // Scale to fit target width, preserving aspect ratio.
-                    let scale = f64::from(target_width_px) / f64::from(img.width());
+                    // Scale to fit target width (and the configured maximum
+                    // height), preserving aspect ratio.
+                    let mut scale = f64::from(target_width_px) / f64::from(img.width());
+                    if let Some(max_rows) = self.image_limits.max_height {
+                        let max_height_px = u32::from(max_rows.max(1)) * u32::from(font_size.1);
+                        scale = scale.min(f64::from(max_height_px) / f64::from(img.height()));
+                    }
                     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                    // Scaled image height is always positive and well within u32 range.
-                    let scaled_height_px = (f64::from(img.height()) * scale) as u32;
+                    // Scaled image sizes are always positive and well within u32 range.
+                    let (scaled_width_px, scaled_height_px) = (
+                        (f64::from(img.width()) * scale) as u32,
+                        (f64::from(img.height()) * scale) as u32,
+                    );
 
                     let mut scaled = img.resize(
-                        target_width_px,
-                        scaled_height_px,
+                        scaled_width_px.max(1),
+                        scaled_height_px.max(1),
                         if use_halfblocks {
                             image::imageops::FilterType::CatmullRom
                         } else {
@@ -456,9 +791,238 @@ impl Model {
             .map(|idx| (idx + 1, self.search_matches.len()))
     }
 
+    /// Rows of the search results overlay, narrowed by its filter.
+    pub fn search_result_items(&self) -> Vec<SearchResultItem> {
+        let filter = self
+            .search_results
+            .as_ref()
+            .map(|results| results.filter.trim().to_lowercase())
+            .unwrap_or_default();
+        let headings = self.document.headings();
+        self.search_matches
+            .iter()
+            .enumerate()
+            .filter_map(|(index, m)| {
+                let text = self.search_line_text(m.line)?;
+                let heading = closest_heading_to_line(headings, m.line)
+                    // The closest heading may follow the match; use the one before it.
+                    .and_then(|idx| {
+                        if headings[idx].line > m.line {
+                            idx.checked_sub(1)
+                        } else {
+                            Some(idx)
+                        }
+                    })
+                    .map(|idx| headings[idx].text.clone());
+                if !filter.is_empty()
+                    && !text.to_lowercase().contains(&filter)
+                    && !heading
+                        .as_deref()
+                        .is_some_and(|h| h.to_lowercase().contains(&filter))
+                {
+                    return None;
+                }
+                let (snippet, ranges) = search_snippet(&text, &m.ranges);
+                Some(SearchResultItem {
+                    index,
+                    line: m.line,
+                    heading,
+                    snippet,
+                    ranges,
+                })
+            })
+            .collect()
+    }
+
+    fn search_line_text(&self, line: usize) -> Option<String> {
+        if self.document.is_hex_mode() {
+            self.document.hex_line_content(line)
+        } else {
+            self.document
+                .line_at(line)
+                .map(|line| line.content().to_string())
+        }
+    }
+
+    /// Take in files indexed for the file finder. Returns whether anything
+    /// arrived.
+    pub fn poll_file_finder(&mut self) -> bool {
+        self.file_finder.as_mut().is_some_and(FileFinder::poll)
+    }
+
+    /// File the debounced browse preview should load: the file finder's
+    /// highlighted file while it is open, otherwise browse entry `idx`
+    /// unless that is a directory.
+    pub fn browse_preview_path(&self, idx: usize) -> Option<PathBuf> {
+        if let Some(finder) = self.file_finder.as_ref() {
+            return finder.selected_path();
+        }
+        self.browse_entries
+            .get(idx)
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.path.clone())
+    }
+
+    /// Headings matching the palette query, best match first; ties keep
+    /// document order.
+    pub fn heading_palette_items(&self) -> Vec<HeadingPaletteItem> {
+        let query = self
+            .heading_palette
+            .as_ref()
+            .map_or("", |palette| palette.query.as_str());
+        let headings = self.document.headings();
+        // Enclosing headings of each heading, as indices.
+        let mut parents: Vec<Vec<usize>> = Vec::with_capacity(headings.len());
+        let mut stack: Vec<usize> = Vec::new();
+        for (idx, heading) in headings.iter().enumerate() {
+            while stack
+                .last()
+                .is_some_and(|&top| headings[top].level >= heading.level)
+            {
+                stack.pop();
+            }
+            parents.push(stack.clone());
+            stack.push(idx);
+        }
+
+        let mut items: Vec<(i32, HeadingPaletteItem)> = headings
+            .iter()
+            .enumerate()
+            .filter_map(|(index, heading)| {
+                let matched = crate::search::fuzzy_match(query, &heading.text)?;
+                let breadcrumb = parents[index]
+                    .iter()
+                    .map(|&parent| headings[parent].text.clone())
+                    .collect();
+                Some((
+                    matched.score,
+                    HeadingPaletteItem {
+                        index,
+                        level: heading.level,
+                        text: heading.text.clone(),
+                        breadcrumb,
+                        positions: matched.positions,
+                    },
+                ))
+            })
+            .collect();
+        items.sort_by_key(|(score, item)| (std::cmp::Reverse(*score), item.index));
+        items.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Clear the project search results and stop any running search, and
+    /// record the query as the one to search for. The search itself is
+    /// started by [`Self::run_project_search`].
+    pub fn reset_project_search(&mut self) {
+        let Some(state) = self.project_search.as_mut() else {
+            return;
+        };
+        state.task = None;
+        state.files.clear();
+        state.selected = 0;
+        state.files_searched = None;
+        state.truncated = false;
+        state.searched = (!state.query.trim().is_empty()).then(|| state.query.clone());
+    }
+
+    /// Start searching every file under `browse_dir` for the query
+    /// [`Self::reset_project_search`] recorded.
+    pub fn run_project_search(&mut self) {
+        let Some(state) = self.project_search.as_mut() else {
+            return;
+        };
+        let Some(query) = state.searched.as_deref() else {
+            return;
+        };
+        match ProjectSearch::start(
+            &self.browse_dir,
+            query,
+            self.search_mode,
+            &self.browse_filters,
+        ) {
+            Ok(task) => state.task = Some(task),
+            Err(err) => {
+                state.searched = None;
+                let summary = crate::search::regex_error_summary(&err);
+                self.show_toast(ToastLevel::Error, format!("Invalid regex: {summary}"));
+            }
+        }
+    }
+
+    /// Take in results from the running project search. Returns whether
+    /// anything arrived.
+    pub fn poll_project_search(&mut self) -> bool {
+        let Some(state) = self.project_search.as_mut() else {
+            return false;
+        };
+        let Some(task) = state.task.as_ref() else {
+            return false;
+        };
+        let events = task.poll();
+        let changed = !events.is_empty();
+        for event in events {
+            match event {
+                ProjectSearchEvent::File(file) => state.files.push(file),
+                ProjectSearchEvent::Done { files, truncated } => {
+                    state.files_searched = Some(files);
+                    state.truncated = truncated;
+                    state.task = None;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Open the file of the selected project search hit and jump to it,
+    /// with the query as the current search.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be loaded.
+    pub fn open_project_search_hit(&mut self) -> Result<()> {
+        let Some(state) = self.project_search.as_ref() else {
+            return Ok(());
+        };
+        let Some((file, hit)) = state.hit(state.selected) else {
+            return Ok(());
+        };
+        let (path, file_line) = (file.path.clone(), hit.line);
+        let query = state
+            .searched
+            .clone()
+            .unwrap_or_else(|| state.query.clone());
+        self.load_file(&path)?;
+        self.project_search = None;
+
+        self.search_query = Some(query);
+        self.search_allow_short = true;
+        refresh_search_matches(self, false, true);
+        let line = self.rendered_line_for_file_line(file_line).unwrap_or(0);
+        // Prefer the first match at or after the hit's rendered line.
+        let index = self
+            .search_matches
+            .iter()
+            .position(|m| m.line >= line)
+            .or_else(|| self.search_matches.len().checked_sub(1));
+        self.search_match_index = index;
+        let target = index.map_or(line, |idx| self.search_matches[idx].line);
+        self.viewport.go_to_line(target);
+        Ok(())
+    }
+
+    /// Byte ranges of search matches on rendered line `line`.
+    pub fn search_ranges_for_line(&self, line: usize) -> &[std::ops::Range<usize>] {
+        self.search_matches
+            .binary_search_by_key(&line, |m| m.line)
+            .map_or(&[], |idx| &self.search_matches[idx].ranges)
+    }
+
     pub(super) fn layout_width(&self) -> u16 {
-        let terminal_width =
-            crate::ui::document_content_width(self.viewport.width(), self.toc_visible);
+        let terminal_width = if self.editor_mode && self.editor_preview {
+            crate::ui::preview_content_width(self.viewport.width())
+        } else {
+            crate::ui::document_content_width(self.viewport.width(), self.toc_visible)
+        };
         match self.wrap_width {
             Some(w) if w > 0 => terminal_width.min(w),
             _ => terminal_width,
@@ -508,6 +1072,7 @@ impl Model {
             width,
             &self.image_layout_heights,
             mermaid,
+            self.front_matter_collapsed,
         ) {
             self.document = document;
             self.viewport.set_total_lines(self.document.line_count());
@@ -542,8 +1107,8 @@ impl Model {
         for entry in std::fs::read_dir(&dir)? {
             let entry = entry?;
             let name = entry.file_name().to_string_lossy().to_string();
-            // Skip hidden files/dirs
-            if name.starts_with('.') {
+            // Skip hidden and excluded files/dirs
+            if self.browse_filters.hides(&name) {
                 continue;
             }
             let path = entry.path();
@@ -559,8 +1124,8 @@ impl Model {
             }
         }
 
-        dirs.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-        files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        dirs.sort_by_key(|e| e.name.to_lowercase());
+        files.sort_by_key(|e| e.name.to_lowercase());
 
         self.browse_entries.extend(dirs);
         self.browse_entries.extend(files);
@@ -595,6 +1160,7 @@ impl Model {
                 self.layout_width(),
                 &self.image_layout_heights,
                 self.should_render_mermaid_as_images(),
+                self.front_matter_collapsed,
             )
         } else {
             Ok(Document::from_plain_text(&content))
@@ -611,6 +1177,7 @@ impl Model {
         let raw_bytes = std::fs::read(path)?;
         let document = self.document_from_bytes(path, raw_bytes)?;
         self.file_path = path.to_path_buf();
+        self.from_stdin = false;
         self.base_dir = path
             .parent()
             .map_or_else(|| PathBuf::from("."), std::path::Path::to_path_buf);
@@ -654,9 +1221,12 @@ impl Model {
     ///
     /// Returns `true` only for files whose extension (or filename) is in
     /// the text-editable whitelist or is recognized by syntect, AND whose
-    /// content is not binary (hex mode).  All other files are rejected.
+    /// content is not binary (hex mode).  All other files are rejected, as
+    /// are documents read from stdin.
     pub fn can_edit(&self) -> bool {
-        crate::document::is_editable_file(&self.file_path) && !self.document.is_hex_mode()
+        !self.from_stdin
+            && crate::document::is_editable_file(&self.file_path)
+            && !self.document.is_hex_mode()
     }
 
     /// Whether the editor has unsaved changes.
@@ -668,6 +1238,18 @@ impl Model {
                 .is_some_and(crate::editor::EditorBuffer::is_dirty)
     }
 
+    /// Whether the editor should apply markdown list and table helpers.
+    pub fn editing_markdown(&self) -> bool {
+        !crate::document::is_fence_wrapped(&self.file_path)
+    }
+
+    /// Rows taken by the editor's find bar (one, or two with replace).
+    pub fn editor_find_rows(&self) -> u16 {
+        self.editor_find
+            .as_ref()
+            .map_or(0, |find| 1 + u16::from(find.replacing))
+    }
+
     /// Hash the contents of a file on disk, returning `None` if the file can't be read.
     pub fn file_disk_hash(&self) -> Option<u64> {
         let bytes = std::fs::read(&self.file_path).ok()?;
@@ -708,6 +1290,58 @@ impl Model {
         let raw_bytes = std::fs::read(&self.file_path)?;
         let path = self.file_path.clone();
         let document = self.document_from_bytes(&path, raw_bytes)?;
+        self.replace_document(document);
+        Ok(())
+    }
+
+    /// Re-render the live preview from the editor buffer and scroll it to
+    /// the cursor.
+    pub(super) fn refresh_editor_preview(&mut self) {
+        let Some(buf) = &self.editor_buffer else {
+            return;
+        };
+        let path = self.file_path.clone();
+        match self.document_from_bytes(&path, buf.text().into_bytes()) {
+            Ok(document) => {
+                self.replace_document(document);
+                self.sync_preview_to_cursor();
+            }
+            Err(err) => crate::perf::log_event("preview.error", format!("err={err}")),
+        }
+    }
+
+    /// Turn the live preview off and lay the document out for the full view
+    /// again. While the editor is still open the buffer is re-wrapped; when
+    /// `editor_base` is set (leaving edit mode) the file text it holds,
+    /// which matches disk, is shown instead.
+    pub(super) fn close_editor_preview(&mut self) {
+        self.editor_preview = false;
+        let Some(base) = self.editor_base.as_ref().filter(|_| !self.editor_mode) else {
+            self.reflow_layout();
+            return;
+        };
+        let path = self.file_path.clone();
+        match self.document_from_bytes(&path, base.clone().into_bytes()) {
+            Ok(document) => self.replace_document(document),
+            Err(_) => self.reflow_layout(),
+        }
+    }
+
+    /// Scroll the preview so the rendered editor cursor line sits on the
+    /// same screen row as the cursor.
+    pub(super) fn sync_preview_to_cursor(&mut self) {
+        let Some(cursor_line) = self.editor_buffer.as_ref().map(|buf| buf.cursor().line) else {
+            return;
+        };
+        if let Some(target) = self.rendered_line_for_file_line(cursor_line) {
+            let row = cursor_line.saturating_sub(self.editor_scroll_offset);
+            self.viewport.go_to_line(target.saturating_sub(row));
+        }
+    }
+
+    /// Swap in a freshly parsed document, dropping stale image caches and
+    /// clamping scroll, search, and selection state.
+    fn replace_document(&mut self, document: Document) {
         self.document = document;
 
         // Drop cached image entries that are no longer present in the document.
@@ -732,7 +1366,6 @@ impl Model {
         if self.toc_visible && !self.toc_focused {
             self.sync_toc_to_viewport();
         }
-        Ok(())
     }
 
     pub fn selection_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
@@ -770,6 +1403,34 @@ impl Model {
         Some((lines.join("\n"), count))
     }
 
+    /// Original markdown for the selected lines, as `(text, line_count)`.
+    pub fn selected_source(&self) -> Option<(String, usize)> {
+        let range = self.document.source_range_for(self.selection_range()?)?;
+        let offset = self.fence_offset();
+        let source: Vec<&str> = self.document.source().lines().collect();
+        // Leave out the fence lines `prepare_content` wrapped the file in.
+        let start = range.start.max(offset);
+        let end = range.end.min(source.len().saturating_sub(offset));
+        let lines = source.get(start..end).filter(|lines| !lines.is_empty())?;
+        Some((lines.join("\n"), lines.len()))
+    }
+
+    /// Line of the file on disk (0-based) that rendered line `index` shows.
+    pub fn file_line_for_rendered(&self, index: usize) -> Option<usize> {
+        let line = self.document.source_line_at(index)?;
+        Some(line.saturating_sub(self.fence_offset()))
+    }
+
+    /// First rendered line showing `file_line` (0-based) of the file on disk.
+    pub fn rendered_line_for_file_line(&self, file_line: usize) -> Option<usize> {
+        self.document
+            .rendered_line_for_source(file_line + self.fence_offset())
+    }
+
+    fn fence_offset(&self) -> usize {
+        usize::from(crate::document::is_fence_wrapped(&self.file_path))
+    }
+
     pub fn selection_dragging(&self) -> bool {
         self.selection
             .as_ref()
@@ -889,6 +1550,11 @@ impl Default for Model {
             search_matches: Vec::new(),
             search_match_index: None,
             search_allow_short: false,
+            search_mode: SearchMode::default(),
+            search_results: None,
+            project_search: None,
+            heading_palette: None,
+            file_finder: None,
             should_quit: false,
             toc_focused: false,
             image_protocols: HashMap::new(),
@@ -902,19 +1568,66 @@ impl Default for Model {
             selection: None,
             images_enabled: true,
             wrap_width: None,
+            image_limits: ImageLimits::default(),
+            mermaid_options: MermaidOptions::default(),
+            browse_filters: BrowseFilters::default(),
             browse_mode: false,
             browse_dir: PathBuf::from("."),
             browse_entries: Vec::new(),
             editor_mode: false,
             editor_buffer: None,
             editor_scroll_offset: 0,
+            editor_clipboard: None,
+            editor_find: None,
+            editor_highlight: None,
+            editor_preview: false,
+            editor_swap_recovery: None,
             editor_disk_hash: None,
+            editor_base: None,
             editor_disk_conflict: false,
-            save_confirmed: false,
+            editor_merge: None,
+            editor_vim_keys: false,
+            editor_vim: None,
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
             quit_confirmed: false,
             exit_confirmed: false,
             external_editor: None,
             needs_full_redraw: false,
+            from_stdin: false,
+            front_matter_collapsed: false,
         }
     }
 }
+
+/// Trim `text` to start shortly before the first match, shifting the match
+/// ranges to suit.
+fn search_snippet(
+    text: &str,
+    ranges: &[std::ops::Range<usize>],
+) -> (String, Vec<std::ops::Range<usize>>) {
+    let first = ranges.first().map_or(0, |range| range.start);
+    let mut start = first.saturating_sub(SNIPPET_LEAD);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let skipped = text[start..].len() - text[start..].trim_start().len();
+    let start = if start + skipped <= first {
+        start + skipped
+    } else {
+        start
+    };
+    let (prefix, cut) = if start > 0 && !text[..start].trim().is_empty() {
+        ("…", start)
+    } else {
+        ("", start)
+    };
+    let snippet = format!("{prefix}{}", &text[cut..]);
+    let shift = |pos: usize| pos - cut + prefix.len();
+    let ranges = ranges
+        .iter()
+        .filter(|range| range.start >= cut)
+        .map(|range| shift(range.start)..shift(range.end))
+        .collect();
+    (snippet, ranges)
+}
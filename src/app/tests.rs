@@ -1,3 +1,4 @@
+use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
@@ -7,9 +8,12 @@ use ratatui::backend::TestBackend;
 use ratatui::layout::Rect;
 use tempfile::tempdir;
 
+use crate::config::{BrowseFilters, KeyBinding};
 use crate::document::Document;
+use crate::editor::VimMode;
+use crate::input::{KeyChord, Keymap};
 
-use super::event_loop::{BrowseDebouncer, ResizeDebouncer};
+use super::event_loop::{BrowseDebouncer, PreviewDebouncer, ResizeDebouncer, SwapThrottle};
 use super::{App, Message, Model, ToastLevel, update};
 
 /// Enter edit mode: runs pure update then side effects (which reads
@@ -235,6 +239,70 @@ fn test_help_toggle_works_when_toc_focused() {
     assert_eq!(msg, Some(Message::ToggleHelp));
 }
 
+#[test]
+fn test_custom_key_sequence_binding() {
+    let mut model = create_test_model();
+    model.keymap = Keymap::with_bindings(&[KeyBinding {
+        context: "view".to_string(),
+        keys: "gg".to_string(),
+        action: "go_to_bottom".to_string(),
+    }])
+    .unwrap();
+    let g = event::KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+
+    let msg = App::handle_key(g, &model).unwrap();
+    assert_eq!(msg, Message::PendingKeys(vec![KeyChord::from(g)]));
+    let model = update(model, msg);
+    assert_eq!(App::handle_key(g, &model), Some(Message::GoToBottom));
+
+    // A key that doesn't continue the sequence runs its own binding.
+    let q = event::KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    assert_eq!(App::handle_key(q, &model), Some(Message::Quit));
+    let model = update(model, Message::Quit);
+    assert!(model.pending_keys.is_empty());
+}
+
+#[test]
+fn test_pending_keys_abandoned_on_unbound_key() {
+    let mut model = create_test_model();
+    model.pending_keys = KeyChord::parse_sequence("g").unwrap();
+    let x = event::KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(x, &model),
+        Some(Message::PendingKeys(Vec::new()))
+    );
+}
+
+#[test]
+fn test_toggle_front_matter_collapses_panel() {
+    let md = "---\ntitle: Plan\nstatus: draft\n---\n\n# Intro\n";
+    let doc = Document::parse(md).unwrap();
+    let model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    let expanded_lines = model.document.line_count();
+
+    let key = event::KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(key, &model),
+        Some(Message::ToggleFrontMatter)
+    );
+
+    let model = update(model, Message::ToggleFrontMatter);
+    assert!(model.front_matter_collapsed);
+    assert_eq!(model.document.line_count(), expanded_lines - 2);
+    assert_eq!(model.viewport.total_lines(), model.document.line_count());
+
+    let model = update(model, Message::ToggleFrontMatter);
+    assert!(!model.front_matter_collapsed);
+    assert_eq!(model.document.line_count(), expanded_lines);
+}
+
+#[test]
+fn test_toggle_front_matter_without_front_matter_is_noop() {
+    let model = create_test_model();
+    let model = update(model, Message::ToggleFrontMatter);
+    assert!(!model.front_matter_collapsed);
+}
+
 #[test]
 fn test_toast_lifecycle() {
     let mut model = create_test_model();
@@ -338,6 +406,31 @@ fn test_ctrl_q_quits_in_search_mode() {
     assert_eq!(msg, Some(Message::Quit));
 }
 
+#[test]
+fn test_quit_keys_follow_the_keymap() {
+    let mut model = create_test_model();
+    model.keymap = Keymap::with_bindings(&[
+        KeyBinding {
+            context: "view".to_string(),
+            keys: "Ctrl-q".to_string(),
+            action: "toggle_toc".to_string(),
+        },
+        KeyBinding {
+            context: "view".to_string(),
+            keys: "Alt-x".to_string(),
+            action: "quit".to_string(),
+        },
+    ])
+    .unwrap();
+    let ctrl_q = event::KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+    let alt_x = event::KeyEvent::new(KeyCode::Char('x'), KeyModifiers::ALT);
+    assert_eq!(App::handle_key(ctrl_q, &model), Some(Message::ToggleToc));
+
+    let model = enter_edit_mode(model);
+    assert_ne!(App::handle_key(ctrl_q, &model), Some(Message::Quit));
+    assert_eq!(App::handle_key(alt_x, &model), Some(Message::Quit));
+}
+
 #[test]
 fn test_resize_updates_viewport() {
     let model = create_test_model();
@@ -552,6 +645,509 @@ fn test_search_mode_enter_moves_to_next_match() {
     assert_eq!(msg, Some(Message::NextMatch));
 }
 
+#[test]
+fn test_search_regex_and_whole_word_toggles() {
+    let doc = Document::parse("cat 42\n\ncatalog\n\ndog 7").unwrap();
+    let model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    let model = update(model, Message::StartSearch);
+    let model = update(model, Message::SearchInput("cat".to_string()));
+    assert_eq!(model.search_match_count(), 2);
+
+    let model = update(model, Message::ToggleSearchWholeWord);
+    assert_eq!(model.search_match_count(), 1);
+    let model = update(model, Message::ToggleSearchWholeWord);
+    assert_eq!(model.search_match_count(), 2);
+
+    let model = update(model, Message::SearchInput(r"\d+".to_string()));
+    assert_eq!(model.search_match_count(), 0);
+    let model = update(model, Message::ToggleSearchRegex);
+    assert_eq!(model.search_match_count(), 2);
+    assert_eq!(model.current_search_match(), Some((1, 2)));
+    assert_eq!(
+        model.search_ranges_for_line(0),
+        std::slice::from_ref(&(4..6))
+    );
+}
+
+#[test]
+fn test_search_smartcase() {
+    let doc = Document::parse("Alpha\n\nalpha").unwrap();
+    let model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    let model = update(model, Message::StartSearch);
+    let model = update(model, Message::SearchInput("alpha".to_string()));
+    assert_eq!(model.search_match_count(), 2);
+    let model = update(model, Message::SearchInput("Alpha".to_string()));
+    assert_eq!(model.search_match_count(), 1);
+    let model = update(model, Message::SearchInput(r"Alpha\c".to_string()));
+    assert_eq!(model.search_match_count(), 2);
+}
+
+#[test]
+fn test_invalid_search_regex_shows_toast() {
+    let mut model = create_test_model();
+    model.search_mode.regex = true;
+    let model = update(model, Message::StartSearch);
+    let model = update(model, Message::SearchInput("(unclosed".to_string()));
+    assert_eq!(model.search_match_count(), 0);
+    let (msg, level) = model.active_toast().expect("toast should be set");
+    assert_eq!(level, ToastLevel::Error);
+    assert!(msg.starts_with("Invalid regex: "), "{msg}");
+}
+
+#[test]
+fn test_search_mode_alt_keys_toggle_modes() {
+    let mut model = create_test_model();
+    model = update(model, Message::StartSearch);
+
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('r'), KeyModifiers::ALT),
+        &model,
+    );
+    assert_eq!(msg, Some(Message::ToggleSearchRegex));
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('w'), KeyModifiers::ALT),
+        &model,
+    );
+    assert_eq!(msg, Some(Message::ToggleSearchWholeWord));
+}
+
+fn search_results_model() -> Model {
+    let doc = Document::parse(
+        "# Intro\n\nThe alpha release.\n\n## Setup\n\nInstall alpha tools.\n\n## Usage\n\nRun alpha daily.",
+    )
+    .unwrap();
+    let model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    let model = update(model, Message::StartSearch);
+    update(model, Message::SearchInput("alpha".to_string()))
+}
+
+#[test]
+fn test_search_results_list_matches_with_headings() {
+    let model = update(search_results_model(), Message::OpenSearchResults);
+    let items = model.search_result_items();
+    let headings: Vec<_> = items.iter().map(|item| item.heading.as_deref()).collect();
+    assert_eq!(headings, [Some("Intro"), Some("Setup"), Some("Usage")]);
+    assert_eq!(items[1].snippet, "Install alpha tools.");
+    assert_eq!(items[1].ranges, std::slice::from_ref(&(8..13)));
+    assert_eq!(model.search_results.as_ref().unwrap().selected, 0);
+}
+
+#[test]
+fn test_search_results_filter_and_jump() {
+    let model = update(search_results_model(), Message::OpenSearchResults);
+    let model = update(model, Message::SearchResultsFilter("usage".to_string()));
+    let items = model.search_result_items();
+    assert_eq!(items.len(), 1);
+    let target = items[0].line;
+
+    let model = update(model, Message::SearchResultsSelect);
+    assert!(model.search_results.is_none());
+    assert_eq!(model.current_search_match(), Some((3, 3)));
+    assert!(model.viewport.offset() <= target);
+    assert!(model.search_query.is_some());
+}
+
+#[test]
+fn test_search_results_selection_is_clamped() {
+    let model = update(search_results_model(), Message::OpenSearchResults);
+    let model = update(model, Message::SearchResultsDown(10));
+    assert_eq!(model.search_results.as_ref().unwrap().selected, 2);
+    let model = update(model, Message::SearchResultsUp(1));
+    assert_eq!(model.search_results.as_ref().unwrap().selected, 1);
+    let model = update(model, Message::CloseSearchResults);
+    assert!(model.search_results.is_none());
+    assert_eq!(model.search_match_count(), 3);
+}
+
+#[test]
+fn test_search_results_keys() {
+    let model = search_results_model();
+    let key = |code| event::KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(key(KeyCode::Tab), &model),
+        Some(Message::OpenSearchResults)
+    );
+
+    let model = update(model, Message::OpenSearchResults);
+    assert_eq!(
+        App::handle_key(key(KeyCode::Down), &model),
+        Some(Message::SearchResultsDown(1))
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Char('s')), &model),
+        Some(Message::SearchResultsFilter("s".to_string()))
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Enter), &model),
+        Some(Message::SearchResultsSelect)
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Esc), &model),
+        Some(Message::CloseSearchResults)
+    );
+}
+
+fn wait_for_project_search(model: &mut Model) {
+    let deadline = std::time::Instant::now() + Duration::from_secs(10);
+    while model
+        .project_search
+        .as_ref()
+        .is_some_and(crate::app::ProjectSearchState::is_running)
+    {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "project search timed out"
+        );
+        model.poll_project_search();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_project_search_opens_hit_in_browse_mode() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("a.md"), "# A\n\nNothing here.").unwrap();
+    std::fs::create_dir_all(dir.path().join("guide")).unwrap();
+    let target = dir.path().join("guide/b.md");
+    let body = "Filler line.\n\n".repeat(40);
+    std::fs::write(&target, format!("# B\n\n{body}The needle is here.\n")).unwrap();
+
+    let mut model = Model::new(
+        dir.path().join("a.md"),
+        Document::parse("# A\n\nNothing here.").unwrap(),
+        (80, 24),
+    );
+    model.browse_mode = true;
+    model.load_directory(dir.path()).unwrap();
+    let mut watcher = None;
+
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT),
+        &model,
+    );
+    assert_eq!(msg, Some(Message::OpenProjectSearch));
+    model = update(model, Message::OpenProjectSearch);
+    model = update(model, Message::ProjectSearchInput("needle".to_string()));
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        &model,
+    );
+    assert_eq!(msg, Some(Message::ProjectSearchSubmit));
+    model = update(model, Message::ProjectSearchSubmit);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::ProjectSearchSubmit);
+    wait_for_project_search(&mut model);
+
+    let state = model.project_search.as_ref().unwrap();
+    assert_eq!(state.files_searched, Some(2));
+    assert_eq!(state.hit_count(), 1);
+    assert!(state.files[0].path.ends_with("guide/b.md"));
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        &model,
+    );
+    assert_eq!(msg, Some(Message::ProjectSearchOpen));
+
+    model = update(model, Message::ProjectSearchOpen);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::ProjectSearchOpen);
+    assert!(model.project_search.is_none());
+    assert!(model.file_path.ends_with("guide/b.md"));
+    assert!(model.browse_dir.ends_with("guide"));
+    assert_eq!(model.search_query.as_deref(), Some("needle"));
+    assert_eq!(model.current_search_match(), Some((1, 1)));
+    assert!(model.viewport.offset() > 0);
+}
+
+#[test]
+fn test_project_search_toggle_reruns_in_effects() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("a.md"), "needles\n").unwrap();
+    let mut model = create_test_model();
+    model.browse_dir = dir.path().to_path_buf();
+    let mut watcher = None;
+    model = update(model, Message::OpenProjectSearch);
+    model = update(model, Message::ProjectSearchInput("needle".to_string()));
+    model = update(model, Message::ProjectSearchSubmit);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::ProjectSearchSubmit);
+    wait_for_project_search(&mut model);
+    assert_eq!(model.project_search.as_ref().unwrap().hit_count(), 1);
+
+    model = update(model, Message::ToggleSearchWholeWord);
+    let state = model.project_search.as_ref().unwrap();
+    assert_eq!(state.hit_count(), 0);
+    assert!(!state.is_running());
+    assert_eq!(state.files_searched, None);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::ToggleSearchWholeWord);
+    wait_for_project_search(&mut model);
+    let state = model.project_search.as_ref().unwrap();
+    assert_eq!(state.files_searched, Some(1));
+    assert_eq!(state.hit_count(), 0);
+}
+
+#[test]
+fn test_project_search_needs_browse_mode() {
+    let model = create_test_model();
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT),
+        &model,
+    );
+    assert_eq!(msg, None);
+}
+
+#[test]
+fn test_project_search_invalid_regex_shows_toast() {
+    let dir = tempdir().unwrap();
+    let mut model = create_test_model();
+    model.browse_dir = dir.path().to_path_buf();
+    model.search_mode.regex = true;
+    model = update(model, Message::OpenProjectSearch);
+    model = update(model, Message::ProjectSearchInput("[".to_string()));
+    model = update(model, Message::ProjectSearchSubmit);
+    // update() only records the query; the search starts in effects.
+    let state = model.project_search.as_ref().unwrap();
+    assert_eq!(state.searched.as_deref(), Some("["));
+    assert!(!state.is_running());
+    assert!(model.active_toast().is_none());
+    App::handle_message_side_effects(&mut model, &mut None, &Message::ProjectSearchSubmit);
+
+    let state = model.project_search.as_ref().unwrap();
+    assert!(!state.is_running());
+    assert!(state.searched.is_none());
+    let (msg, _) = model.active_toast().expect("toast should be set");
+    assert!(msg.starts_with("Invalid regex"), "{msg}");
+}
+
+fn heading_palette_model() -> Model {
+    let mut md = String::from("# Guide\n\n");
+    for section in ["Install", "Configure", "Usage"] {
+        write!(md, "## {section}\n\n").unwrap();
+        md.push_str(&"Body text.\n\n".repeat(10));
+        write!(md, "### {section} options\n\n").unwrap();
+        md.push_str(&"More text.\n\n".repeat(10));
+    }
+    let doc = Document::parse(&md).unwrap();
+    Model::new(PathBuf::from("test.md"), doc, (80, 24))
+}
+
+#[test]
+fn test_heading_palette_ranks_and_shows_breadcrumb() {
+    let model = update(heading_palette_model(), Message::OpenHeadingPalette);
+    assert_eq!(model.heading_palette_items().len(), 7);
+
+    let model = update(model, Message::HeadingPaletteInput("conf".to_string()));
+    let items = model.heading_palette_items();
+    let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+    assert_eq!(texts, ["Configure", "Configure options"]);
+    assert_eq!(items[0].level, 2);
+    assert_eq!(items[0].breadcrumb, ["Guide"]);
+    assert_eq!(items[1].breadcrumb, ["Guide", "Configure"]);
+    assert_eq!(items[0].positions, [0, 1, 2, 3]);
+
+    let model = update(model, Message::HeadingPaletteInput("uo".to_string()));
+    let texts: Vec<String> = model
+        .heading_palette_items()
+        .into_iter()
+        .map(|item| item.text)
+        .collect();
+    assert_eq!(texts, ["Usage options", "Configure options"]);
+}
+
+#[test]
+fn test_heading_palette_select_jumps_like_toc() {
+    let model = update(heading_palette_model(), Message::OpenHeadingPalette);
+    let model = update(model, Message::HeadingPaletteInput("usage".to_string()));
+    let model = update(model, Message::HeadingPaletteDown(5));
+    assert_eq!(model.heading_palette.as_ref().unwrap().selected, 1);
+    let model = update(model, Message::HeadingPaletteUp(1));
+    let target = model.heading_palette_items()[0].index;
+
+    let expected = {
+        let mut toc = heading_palette_model();
+        toc.toc_selected = Some(target);
+        update(toc, Message::TocSelect)
+    };
+    let model = update(model, Message::HeadingPaletteSelect);
+    assert!(model.heading_palette.is_none());
+    assert_eq!(model.viewport.offset(), expected.viewport.offset());
+    assert!(model.viewport.offset() > 0);
+    assert_eq!(model.toc_selected, Some(target));
+}
+
+#[test]
+fn test_heading_palette_keys() {
+    let model = heading_palette_model();
+    let msg = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('H'), KeyModifiers::SHIFT),
+        &model,
+    );
+    assert_eq!(msg, Some(Message::OpenHeadingPalette));
+
+    let model = update(model, Message::OpenHeadingPalette);
+    let key = |code| event::KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(key(KeyCode::Char('j')), &model),
+        Some(Message::HeadingPaletteInput("j".to_string()))
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Down), &model),
+        Some(Message::HeadingPaletteDown(1))
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Enter), &model),
+        Some(Message::HeadingPaletteSelect)
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Esc), &model),
+        Some(Message::CloseHeadingPalette)
+    );
+}
+
+fn file_finder_model(dir: &std::path::Path) -> Model {
+    std::fs::write(dir.join(".gitignore"), "build/\n").unwrap();
+    for path in [
+        "README.md",
+        "docs/guide.md",
+        "docs/reference/api.md",
+        "build/guide.md",
+        ".hidden/guide.md",
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, format!("# {}\n", path.display())).unwrap();
+    }
+    let mut model = Model::new(
+        dir.join("README.md"),
+        Document::parse("# Readme").unwrap(),
+        (80, 24),
+    );
+    model.browse_mode = true;
+    model.load_directory(dir).unwrap();
+    model
+}
+
+/// Open the file finder the way the event loop does, starting its index.
+fn open_file_finder(model: Model) -> Model {
+    let mut model = update(model, Message::OpenFileFinder);
+    App::handle_message_side_effects(&mut model, &mut None, &Message::OpenFileFinder);
+    model
+}
+
+fn wait_for_file_index(model: &mut Model) {
+    let deadline = std::time::Instant::now() + Duration::from_secs(10);
+    while model
+        .file_finder
+        .as_ref()
+        .is_some_and(crate::app::FileFinder::is_indexing)
+    {
+        assert!(std::time::Instant::now() < deadline, "file index timed out");
+        model.poll_file_finder();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_file_finder_indexes_and_ranks_paths() {
+    let dir = tempdir().unwrap();
+    let mut model = open_file_finder(file_finder_model(dir.path()));
+    wait_for_file_index(&mut model);
+
+    let finder = model.file_finder.as_ref().unwrap();
+    let paths: Vec<&str> = finder
+        .items()
+        .iter()
+        .map(|item| item.path.as_str())
+        .collect();
+    assert_eq!(
+        paths,
+        ["README.md", "docs/guide.md", "docs/reference/api.md"]
+    );
+
+    let model = update(model, Message::FileFinderInput("gd".to_string()));
+    let finder = model.file_finder.as_ref().unwrap();
+    assert_eq!(finder.items()[0].path, "docs/guide.md");
+    assert_eq!(finder.items()[0].positions, [5, 8]);
+    let model = update(model, Message::FileFinderInput("api".to_string()));
+    let model = update(model, Message::FileFinderDown(5));
+    let finder = model.file_finder.as_ref().unwrap();
+    assert_eq!(finder.items().len(), 1);
+    assert_eq!(finder.selected, 0);
+    assert_eq!(
+        model.browse_preview_path(0),
+        Some(dir.path().join("docs/reference/api.md"))
+    );
+}
+
+#[test]
+fn test_file_finder_select_opens_file_and_syncs_browse() {
+    let dir = tempdir().unwrap();
+    let mut model = open_file_finder(file_finder_model(dir.path()));
+    let mut watcher = None;
+    wait_for_file_index(&mut model);
+    model = update(model, Message::FileFinderInput("api".to_string()));
+
+    model = update(model, Message::FileFinderSelect);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::FileFinderSelect);
+    assert!(model.file_finder.is_none());
+    assert!(model.file_path.ends_with("docs/reference/api.md"));
+    assert!(model.browse_dir.ends_with("docs/reference"));
+}
+
+#[test]
+fn test_file_finder_cancel_restores_original_file() {
+    let dir = tempdir().unwrap();
+    let mut model = open_file_finder(file_finder_model(dir.path()));
+    let mut watcher = None;
+    wait_for_file_index(&mut model);
+    model = update(model, Message::FileFinderInput("guide".to_string()));
+
+    // What the debounced preview does while the finder is open.
+    let preview = model.browse_preview_path(0).unwrap();
+    model.load_file(&preview).unwrap();
+    assert!(model.file_path.ends_with("docs/guide.md"));
+
+    model = update(model, Message::CloseFileFinder);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::CloseFileFinder);
+    assert!(model.file_finder.is_none());
+    assert_eq!(model.file_path, dir.path().join("README.md"));
+    assert!(model.browse_dir.ends_with(dir.path().file_name().unwrap()));
+}
+
+#[test]
+fn test_file_finder_keys() {
+    let dir = tempdir().unwrap();
+    let ctrl = |c| event::KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    assert_eq!(App::handle_key(ctrl('p'), &create_test_model()), None);
+
+    let model = file_finder_model(dir.path());
+    assert_eq!(
+        App::handle_key(ctrl('p'), &model),
+        Some(Message::OpenFileFinder)
+    );
+    let model = update(model, Message::OpenFileFinder);
+    // update() only opens the overlay; indexing starts in effects.
+    assert!(!model.file_finder.as_ref().unwrap().is_indexing());
+    let key = |code| event::KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(key(KeyCode::Char('j')), &model),
+        Some(Message::FileFinderInput("j".to_string()))
+    );
+    assert_eq!(
+        App::handle_key(ctrl('n'), &model),
+        Some(Message::FileFinderDown(1))
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Up), &model),
+        Some(Message::FileFinderUp(1))
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Enter), &model),
+        Some(Message::FileFinderSelect)
+    );
+    assert_eq!(
+        App::handle_key(key(KeyCode::Esc), &model),
+        Some(Message::CloseFileFinder)
+    );
+}
+
 #[test]
 fn test_toc_focus_space_pages_document() {
     let mut model = create_long_test_model();
@@ -877,6 +1473,31 @@ fn test_follow_file_url_with_anchor_loads_file_and_jumps() {
     assert!(model.viewport.offset() >= target_line.saturating_sub(1));
 }
 
+#[test]
+fn test_follow_cross_file_link_lands_on_duplicate_heading() {
+    let dir = tempdir().unwrap();
+    let current_path = dir.path().join("current.md");
+    let target_path = dir.path().join("other.md");
+    std::fs::write(
+        &target_path,
+        "# Guide\n\n## Setup\n\nfirst\n\n## Setup\n\nsecond\n",
+    )
+    .unwrap();
+    let current_md = "[Second setup](other.md#setup-1)";
+    std::fs::write(&current_path, current_md).unwrap();
+
+    let doc = Document::parse_with_layout(current_md, 80).unwrap();
+    let mut model = Model::new(current_path, doc, (80, 4));
+    let mut watcher = None;
+
+    model = update(model, Message::OpenVisibleLinks);
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::OpenVisibleLinks);
+
+    assert_eq!(model.file_path, target_path);
+    let second = model.document.headings()[2].line;
+    assert_eq!(model.viewport.offset(), second);
+}
+
 #[test]
 fn test_follow_missing_local_link_keeps_current_file() {
     let dir = tempdir().unwrap();
@@ -1171,9 +1792,31 @@ fn test_browse_debouncer_cancel_clears_pending() {
 }
 
 #[test]
-fn test_resize_debouncer_uses_latest_size() {
-    let mut debouncer = ResizeDebouncer::new(100);
-    debouncer.queue(120, 40, 0);
+fn test_preview_debouncer_restarts_on_each_edit() {
+    let mut debouncer = PreviewDebouncer::new(200);
+    debouncer.queue(0);
+    debouncer.queue(150);
+
+    assert!(!debouncer.take_ready(250));
+    assert!(debouncer.take_ready(350));
+    assert!(!debouncer.is_pending());
+}
+
+#[test]
+fn test_swap_throttle_fires_during_steady_edits() {
+    let mut throttle = SwapThrottle::new(2000);
+    throttle.queue(0);
+    throttle.queue(1500);
+
+    assert!(!throttle.take_ready(1999));
+    assert!(throttle.take_ready(2000));
+    assert!(!throttle.take_ready(5000));
+}
+
+#[test]
+fn test_resize_debouncer_uses_latest_size() {
+    let mut debouncer = ResizeDebouncer::new(100);
+    debouncer.queue(120, 40, 0);
     debouncer.queue(140, 50, 20);
 
     assert!(debouncer.take_ready(80).is_none());
@@ -1309,6 +1952,30 @@ fn test_load_directory_skips_hidden_files() {
     assert!(names.contains(&"visible.md"));
 }
 
+#[test]
+fn test_load_directory_applies_browse_filters() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join(".notes.md"), "# Notes").unwrap();
+    std::fs::write(dir.path().join("draft.md"), "# Draft").unwrap();
+    std::fs::write(dir.path().join("visible.md"), "# Hi").unwrap();
+
+    let mut model = create_test_model();
+    model.browse_filters = BrowseFilters {
+        show_hidden: Some(true),
+        exclude: vec!["draft*".to_string()],
+    };
+    model.load_directory(dir.path()).unwrap();
+
+    let names: Vec<&str> = model
+        .browse_entries
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    assert!(names.contains(&".notes.md"));
+    assert!(!names.contains(&"draft.md"));
+    assert!(names.contains(&"visible.md"));
+}
+
 #[test]
 fn test_toc_entry_count_uses_headings_in_file_mode() {
     let model = create_test_model();
@@ -2186,6 +2853,360 @@ fn test_editor_split_line() {
     assert_eq!(buf.line_count(), line_count_before + 1);
 }
 
+#[test]
+fn test_editor_undo_redo_keys() {
+    let model = create_test_model();
+    let mut model = enter_edit_mode(model);
+    let original = model.editor_buffer.as_ref().unwrap().text();
+    model = update(model, Message::EditorInsertChar('A'));
+    model = update(model, Message::EditorInsertChar('B'));
+
+    let undo = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL),
+        &model,
+    );
+    assert_eq!(undo, Some(Message::EditorUndo));
+    model = update(model, Message::EditorUndo);
+    let buf = model.editor_buffer.as_ref().unwrap();
+    assert_eq!(buf.text(), original);
+    assert!(!buf.is_dirty());
+    assert!(!model.editor_is_dirty());
+
+    let redo = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL),
+        &model,
+    );
+    assert_eq!(redo, Some(Message::EditorRedo));
+    model = update(model, Message::EditorRedo);
+    assert!(
+        model
+            .editor_buffer
+            .as_ref()
+            .unwrap()
+            .text()
+            .starts_with("AB")
+    );
+}
+
+#[test]
+fn test_editor_copy_cut_paste() {
+    let doc = Document::parse("hello world").unwrap();
+    let mut model = enter_edit_mode(Model::new(PathBuf::from("test.md"), doc, (80, 24)));
+    model = update(
+        model,
+        Message::EditorSelect(crate::editor::Motion::WordRight),
+    );
+
+    let ctrl = |c| event::KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    assert_eq!(
+        App::handle_key(ctrl('c'), &model),
+        Some(Message::EditorCopy)
+    );
+    assert_eq!(App::handle_key(ctrl('x'), &model), Some(Message::EditorCut));
+
+    model = update(model, Message::EditorCut);
+    assert_eq!(model.editor_clipboard.as_deref(), Some("hello "));
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "world");
+    // With no selection left, Ctrl+C quits as usual.
+    assert_eq!(App::handle_key(ctrl('c'), &model), Some(Message::Quit));
+
+    model = update(model, Message::EditorMoveToEnd);
+    model = update(model, Message::EditorPaste);
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "worldhello ");
+}
+
+#[test]
+fn test_bracketed_paste_is_one_edit() {
+    let model = enter_edit_mode(create_test_model());
+    let mut resize = ResizeDebouncer::new(0);
+    let msg = App::handle_event(
+        &event::Event::Paste("a\r\nb\rc".to_string()),
+        &model,
+        0,
+        &mut resize,
+    );
+    assert_eq!(msg, Some(Message::EditorPasteText("a\r\nb\rc".to_string())));
+
+    let model = update(model, msg.unwrap());
+    let buf = model.editor_buffer.as_ref().unwrap();
+    assert!(buf.text().starts_with("a\nb\nc# Test"));
+    let mut model = model;
+    model = update(model, Message::EditorUndo);
+    assert_eq!(
+        model.editor_buffer.as_ref().unwrap().text(),
+        "# Test\n\nHello world"
+    );
+}
+
+#[test]
+fn test_editor_markdown_list_keys() {
+    let doc = Document::parse("- one").unwrap();
+    let mut model = enter_edit_mode(Model::new(PathBuf::from("test.md"), doc, (80, 24)));
+    model = update(model, Message::EditorMoveEnd);
+
+    let key = |code| event::KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(key(KeyCode::Tab), &model),
+        Some(Message::EditorIndent)
+    );
+    assert_eq!(
+        App::handle_key(
+            event::KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL),
+            &model
+        ),
+        Some(Message::EditorFormatTable)
+    );
+
+    model = update(model, Message::EditorSplitLine);
+    model = update(model, Message::EditorIndent);
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "- one\n  - ");
+    model = update(model, Message::EditorOutdent);
+    model = update(model, Message::EditorSplitLine);
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "- one\n");
+
+    model = update(model, Message::EditorFormatTable);
+    assert_eq!(
+        model.active_toast(),
+        Some(("No table under the cursor", ToastLevel::Info))
+    );
+}
+
+/// Send each character of `keys` through key handling, update and side
+/// effects; `\n` stands for Enter and `\x1b` for Esc.
+fn press_editor_keys(mut model: Model, keys: &str) -> Model {
+    let mut watcher = None;
+    for c in keys.chars() {
+        let code = match c {
+            '\n' => KeyCode::Enter,
+            '\x1b' => KeyCode::Esc,
+            c => KeyCode::Char(c),
+        };
+        let Some(msg) = App::handle_key(event::KeyEvent::new(code, KeyModifiers::NONE), &model)
+        else {
+            continue;
+        };
+        model = update(model, msg.clone());
+        App::handle_message_side_effects(&mut model, &mut watcher, &msg);
+    }
+    model
+}
+
+#[test]
+fn test_editor_vim_keys_edit_save_and_quit() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().canonicalize().unwrap().join("test.md");
+    std::fs::write(&file_path, "one two\nthree\n").unwrap();
+
+    let doc = Document::parse("one two\nthree\n").unwrap();
+    let mut model = Model::new(file_path.clone(), doc, (80, 24));
+    model.editor_vim_keys = true;
+    let model = enter_edit_mode(model);
+    assert_eq!(
+        model.editor_vim.as_ref().map(crate::editor::VimState::mode),
+        Some(VimMode::Normal)
+    );
+
+    // Normal-mode keys edit through the Vim state, insert mode types text.
+    let model = press_editor_keys(model, "dwjAfour\x1b");
+    assert_eq!(
+        model.editor_buffer.as_ref().unwrap().text(),
+        "two\nthreefour\n"
+    );
+    assert_eq!(
+        model.editor_vim.as_ref().map(crate::editor::VimState::mode),
+        Some(VimMode::Normal)
+    );
+
+    // `:q` refuses to drop unsaved changes.
+    let model = press_editor_keys(model, ":q\n");
+    assert!(model.editor_mode);
+    assert_eq!(
+        model.active_toast(),
+        Some((
+            "Unsaved changes! Use :w to save or :q! to discard",
+            ToastLevel::Warning
+        ))
+    );
+
+    let model = press_editor_keys(model, ":wq\n");
+    assert!(!model.editor_mode);
+    assert!(model.editor_vim.is_none());
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "two\nthreefour\n"
+    );
+}
+
+#[test]
+fn test_editor_vim_force_quit_discards_changes() {
+    let doc = Document::parse("one").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model.editor_vim_keys = true;
+    let model = press_editor_keys(enter_edit_mode(model), "x:e\n");
+    assert_eq!(
+        model.active_toast(),
+        Some(("Not an editor command: e", ToastLevel::Error))
+    );
+    let model = press_editor_keys(model, ":q!\n");
+    assert!(!model.editor_mode);
+    assert!(model.editor_buffer.is_none());
+}
+
+#[test]
+fn test_editor_find_selects_matches_incrementally() {
+    let doc = Document::parse("foo bar\nFoo baz foo").unwrap();
+    let mut model = enter_edit_mode(Model::new(PathBuf::from("test.md"), doc, (80, 24)));
+    let ctrl_f = event::KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    assert_eq!(
+        App::handle_key(ctrl_f, &model),
+        Some(Message::EditorFindOpen)
+    );
+    model = update(model, Message::EditorFindOpen);
+
+    let key = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE),
+        &model,
+    );
+    assert_eq!(key, Some(Message::EditorFindInput("f".to_string())));
+    model = update(model, Message::EditorFindInput("foo".to_string()));
+    let find = model.editor_find.as_ref().unwrap();
+    assert_eq!(find.counter(), Some((1, 3)));
+
+    model = update(model, Message::EditorFindNext);
+    let buf = model.editor_buffer.as_ref().unwrap();
+    assert_eq!(buf.selected_text().as_deref(), Some("Foo"));
+    assert_eq!(buf.cursor().line, 1);
+
+    model = update(model, Message::EditorFindToggleCase);
+    assert_eq!(model.editor_find.as_ref().unwrap().matches().len(), 2);
+
+    let esc = App::handle_key(
+        event::KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+        &model,
+    );
+    assert_eq!(esc, Some(Message::EditorFindClose));
+    model = update(model, Message::EditorFindClose);
+    assert!(model.editor_find.is_none());
+    assert!(model.editor_mode);
+}
+
+#[test]
+fn test_editor_replace_one_and_all() {
+    let doc = Document::parse("a1 a2 a3").unwrap();
+    let mut model = enter_edit_mode(Model::new(PathBuf::from("test.md"), doc, (80, 24)));
+    model = update(model, Message::EditorReplaceOpen);
+    model = update(model, Message::EditorFindToggleRegex);
+    model = update(model, Message::EditorFindInput(r"a(\d)".to_string()));
+    model = update(model, Message::EditorFindSwitchField);
+    model = update(model, Message::EditorFindInput("b$1".to_string()));
+    let enter = App::handle_key(
+        event::KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        &model,
+    );
+    assert_eq!(enter, Some(Message::EditorReplaceOne));
+
+    model = update(model, Message::EditorReplaceOne);
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "b1 a2 a3");
+    assert_eq!(model.editor_find.as_ref().unwrap().counter(), Some((1, 2)));
+
+    model = update(model, Message::EditorReplaceAll);
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "b1 b2 b3");
+    assert!(model.editor_find.as_ref().unwrap().matches().is_empty());
+
+    model = update(model, Message::EditorUndo);
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "b1 a2 a3");
+    assert_eq!(model.editor_find.as_ref().unwrap().matches().len(), 2);
+}
+
+#[test]
+fn test_editor_preview_renders_buffer_and_follows_cursor() {
+    let mut md = String::from("# Top\n\n");
+    for i in 1..=40 {
+        write!(md, "Para {i}\n\n").unwrap();
+    }
+    let doc = Document::parse(&md).unwrap();
+    let mut model = enter_edit_mode(Model::new(PathBuf::from("test.md"), doc, (80, 24)));
+    let toggle = App::handle_key(
+        event::KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL),
+        &model,
+    );
+    assert_eq!(toggle, Some(Message::EditorTogglePreview));
+    model = update(model, Message::EditorTogglePreview);
+    assert!(model.editor_preview);
+    // The preview wraps to the right-hand column.
+    assert!(model.layout_width() < 40);
+
+    // Edits reach the preview when it is refreshed (debounced in the loop).
+    model = update(model, Message::EditorInsertChar('X'));
+    assert!(Message::EditorInsertChar('X').edits_editor_text());
+    model.refresh_editor_preview();
+    assert_eq!(model.document.line_at(0).unwrap().content(), "X# Top");
+
+    // Moving the cursor scrolls the preview to the matching rendered line.
+    model = update(model, Message::EditorMoveTo(60, 0));
+    let buf = model.editor_buffer.as_ref().unwrap();
+    let row = buf.cursor().line - model.editor_scroll_offset;
+    let rendered = model.rendered_line_for_file_line(60).unwrap();
+    assert_eq!(model.viewport.offset(), rendered - row);
+    assert!(
+        model
+            .document
+            .line_at(rendered)
+            .unwrap()
+            .content()
+            .contains("Para 30")
+    );
+}
+
+#[test]
+fn test_exit_edit_mode_with_preview_keeps_view_position() {
+    let mut md = String::from("# Top\n\n");
+    for i in 1..=40 {
+        write!(
+            md,
+            "Para {i} has enough words in it to wrap in the narrow preview column.\n\n"
+        )
+        .unwrap();
+    }
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("doc.md");
+    std::fs::write(&path, &md).unwrap();
+    let open = |preview: bool| {
+        let doc = Document::parse_with_layout(&md, 78).unwrap();
+        let mut model = enter_edit_mode(Model::new(path.clone(), doc, (80, 24)));
+        if preview {
+            model = update(model, Message::EditorTogglePreview);
+        }
+        model = update(model, Message::EditorMoveTo(60, 0));
+        // Unsaved edits in the preview are thrown away on exit.
+        model = update(model, Message::EditorInsertChar('X'));
+        model.refresh_editor_preview();
+        model.exit_confirmed = true;
+        update(model, Message::ExitEditMode)
+    };
+
+    let expected = open(false);
+    let model = open(true);
+    assert!(!model.editor_preview);
+    assert!(expected.viewport.offset() > 0);
+    assert_eq!(model.viewport.offset(), expected.viewport.offset());
+    assert_eq!(model.document.line_count(), expected.document.line_count());
+}
+
+#[test]
+fn test_editor_preview_toggle_off_reflows_document() {
+    let mut md = String::from("# Top\n\n");
+    md.push_str(&"words in a long paragraph ".repeat(20));
+    let doc = Document::parse_with_layout(&md, 78).unwrap();
+    let model = enter_edit_mode(Model::new(PathBuf::from("test.md"), doc, (80, 24)));
+    let full_lines = model.document.line_count();
+    let model = update(model, Message::EditorTogglePreview);
+    assert!(model.document.line_count() > full_lines);
+    let model = update(model, Message::EditorTogglePreview);
+    assert!(!model.editor_preview);
+    assert_eq!(model.document.line_count(), full_lines);
+}
+
 #[test]
 fn test_editor_cursor_movement() {
     use crate::editor::Direction;
@@ -2482,6 +3503,80 @@ fn test_editor_save_no_changes_shows_toast() {
     assert!(toast.unwrap().0.contains("No changes"));
 }
 
+#[test]
+fn test_editor_swap_file_follows_unsaved_changes() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().canonicalize().unwrap().join("test.md");
+    std::fs::write(&file_path, "# Hello").unwrap();
+    let swap = crate::editor::swap_path(&file_path);
+
+    let doc = Document::parse("# Hello").unwrap();
+    let mut model = enter_edit_mode(Model::new(file_path.clone(), doc, (80, 24)));
+    assert!(model.editor_swap_recovery.is_none());
+
+    model = update(model, Message::EditorInsertChar('X'));
+    App::write_editor_swap(&mut model);
+    assert_eq!(std::fs::read_to_string(&swap).unwrap(), "X# Hello");
+
+    let mut watcher = None;
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "X# Hello");
+    assert!(!swap.exists());
+}
+
+#[test]
+fn test_editor_recovers_leftover_swap_file() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().canonicalize().unwrap().join("test.md");
+    std::fs::write(&file_path, "one\ntwo").unwrap();
+    crate::editor::write_swap(&file_path, "one\n2").unwrap();
+
+    let doc = Document::parse("one\ntwo").unwrap();
+    let mut model = enter_edit_mode(Model::new(file_path, doc, (80, 24)));
+    let recovery = model.editor_swap_recovery.as_ref().unwrap();
+    assert!(
+        recovery
+            .diff
+            .contains(&crate::editor::DiffLine::Added("2".to_string()))
+    );
+
+    let key = |c| event::KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    // Typing is blocked until the prompt is answered.
+    assert_eq!(
+        App::handle_key(key('d'), &model),
+        Some(Message::EditorSwapToggleDiff)
+    );
+    assert_eq!(
+        App::handle_key(key('r'), &model),
+        Some(Message::EditorSwapRecover)
+    );
+
+    model = update(model, Message::EditorSwapRecover);
+    assert!(model.editor_swap_recovery.is_none());
+    let buf = model.editor_buffer.as_ref().unwrap();
+    assert_eq!(buf.text(), "one\n2");
+    assert!(buf.is_dirty());
+}
+
+#[test]
+fn test_editor_discards_leftover_swap_file() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().canonicalize().unwrap().join("test.md");
+    std::fs::write(&file_path, "saved").unwrap();
+    crate::editor::write_swap(&file_path, "unsaved").unwrap();
+
+    let doc = Document::parse("saved").unwrap();
+    let mut model = enter_edit_mode(Model::new(file_path.clone(), doc, (80, 24)));
+    assert!(model.editor_swap_recovery.is_some());
+
+    model = update(model, Message::EditorSwapDiscard);
+    let mut watcher = None;
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSwapDiscard);
+    assert!(model.editor_swap_recovery.is_none());
+    assert!(!crate::editor::swap_path(&file_path).exists());
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "saved");
+}
+
 // --- Dirty buffer protection tests ---
 
 #[test]
@@ -2721,6 +3816,101 @@ fn test_exit_edit_mode_restores_scroll_position() {
     );
 }
 
+fn create_sections_model() -> Model {
+    let mut md = String::new();
+    for i in 0..30 {
+        write!(md, "## Section {i}\n\nText for section {i}.\n\n").unwrap();
+    }
+    let doc = Document::parse(&md).unwrap();
+    Model::new(PathBuf::from("test.md"), doc, (80, 24))
+}
+
+#[test]
+fn test_enter_edit_mode_opens_at_source_line_in_view() {
+    let mut model = create_sections_model();
+    let target = model.document.headings()[20].line;
+    model.viewport.go_to_line(target);
+
+    let model = enter_edit_mode(model);
+    let buf = model.editor_buffer.as_ref().unwrap();
+    let cursor_line = buf.cursor().line;
+    assert_eq!(buf.line_at(cursor_line).as_deref(), Some("## Section 20"));
+    assert_eq!(model.editor_scroll_offset, cursor_line);
+}
+
+#[test]
+fn test_exit_edit_mode_returns_to_rendered_source_line() {
+    let model = create_sections_model();
+    let mut model = enter_edit_mode(model);
+    let source_line = model
+        .editor_buffer
+        .as_ref()
+        .unwrap()
+        .text()
+        .lines()
+        .position(|line| line == "## Section 12")
+        .unwrap();
+    model.editor_scroll_offset = source_line;
+
+    let model = update(model, Message::ExitEditMode);
+    assert_eq!(model.viewport.offset(), model.document.headings()[12].line);
+}
+
+#[test]
+fn test_selected_source_copies_original_markdown() {
+    let md =
+        "Intro with **bold** and [a link](https://x.test).\n\n| a | b |\n|---|---|\n| 1 | 2 |\n";
+    let doc = Document::parse(md).unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+
+    model = update(model, Message::StartSelection(0));
+    model = update(model, Message::EndSourceSelection(0));
+    let (text, count) = model.selected_source().unwrap();
+    assert_eq!(text, "Intro with **bold** and [a link](https://x.test).");
+    assert_eq!(count, 1);
+
+    let last_row = model.document.line_count() - 2;
+    model = update(model, Message::StartSelection(2));
+    model = update(model, Message::EndSourceSelection(last_row));
+    let (text, _) = model.selected_source().unwrap();
+    assert_eq!(text, "| a | b |\n|---|---|\n| 1 | 2 |");
+}
+
+#[test]
+fn test_selected_source_leaves_out_code_file_fences() {
+    let path = PathBuf::from("main.rs");
+    let content = crate::document::prepare_content(&path, "fn main() {}\nlet x = 1;".to_string());
+    let doc = Document::parse(&content).unwrap();
+    let mut model = Model::new(path, doc, (80, 24));
+
+    // Select the whole frame, borders included.
+    model = update(model, Message::StartSelection(0));
+    model = update(model, Message::EndSourceSelection(3));
+    let (text, count) = model.selected_source().unwrap();
+    assert_eq!(text, "fn main() {}\nlet x = 1;");
+    assert_eq!(count, 2);
+    assert_eq!(model.file_line_for_rendered(2), Some(1));
+    assert_eq!(model.rendered_line_for_file_line(1), Some(2));
+}
+
+#[test]
+fn test_alt_drag_release_copies_source() {
+    let mut model = create_test_model();
+    model.toc_visible = true;
+    model = update(model, Message::StartSelection(0));
+    model = update(model, Message::UpdateSelection(2));
+
+    let chunks = crate::ui::split_main_columns(Rect::new(0, 0, 80, 24));
+    let mouse = MouseEvent {
+        kind: MouseEventKind::Up(MouseButton::Left),
+        column: chunks[1].x + crate::ui::DOCUMENT_LEFT_PADDING,
+        row: 2,
+        modifiers: KeyModifiers::ALT,
+    };
+    let msg = App::handle_mouse(mouse, &model);
+    assert_eq!(msg, Some(Message::EndSourceSelection(2)));
+}
+
 #[test]
 fn test_discard_edit_then_reenter_shows_original() {
     let model = create_test_model();
@@ -2829,7 +4019,7 @@ fn test_file_changed_during_edit_skips_reload() {
 }
 
 #[test]
-fn test_save_with_disk_conflict_warns_first() {
+fn test_save_with_disk_conflict_opens_merge() {
     let dir = tempdir().unwrap();
     let file_path = dir.path().canonicalize().unwrap().join("test.md");
     std::fs::write(&file_path, "# Original").unwrap();
@@ -2847,28 +4037,26 @@ fn test_save_with_disk_conflict_warns_first() {
     // Change file on disk behind our back
     std::fs::write(&file_path, "# Changed externally").unwrap();
 
-    // First save should detect conflict and warn
+    // Saving opens the merge view instead of overwriting
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
+    let merge = model.editor_merge.as_ref().expect("merge view should open");
+    assert_eq!(merge.conflict_count(), 1);
     App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
 
-    assert!(
-        model.save_confirmed,
-        "first save should set save_confirmed on conflict"
-    );
-    // File on disk should still be the external change
     let on_disk = std::fs::read_to_string(&file_path).unwrap();
     assert_eq!(
         on_disk, "# Changed externally",
-        "file should not be overwritten on first save"
+        "file should never be overwritten while it conflicts"
     );
 }
 
 #[test]
-fn test_save_with_disk_conflict_second_save_forces() {
+fn test_save_with_disk_conflict_merges_separate_changes() {
     let dir = tempdir().unwrap();
     let file_path = dir.path().canonicalize().unwrap().join("test.md");
-    std::fs::write(&file_path, "# Original").unwrap();
+    std::fs::write(&file_path, "# Title\n\nbody\n").unwrap();
 
-    let doc = Document::parse("# Original").unwrap();
+    let doc = Document::parse("# Title\n\nbody\n").unwrap();
     let mut model = Model::new(file_path.clone(), doc, (80, 24));
     model.file_path = file_path.clone();
 
@@ -2876,27 +4064,67 @@ fn test_save_with_disk_conflict_second_save_forces() {
 
     let mut model = update(model, Message::EnterEditMode);
     App::handle_message_side_effects(&mut model, &mut watcher, &Message::EnterEditMode);
+    model = update(model, Message::EditorMoveToStart);
     model = update(model, Message::EditorInsertChar('X'));
 
-    // Change file on disk behind our back
-    std::fs::write(&file_path, "# Changed externally").unwrap();
+    // Another writer changes a different line
+    std::fs::write(&file_path, "# Title\n\nbody from disk\n").unwrap();
 
-    // First save: warns
     App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
-    assert!(model.save_confirmed);
+    let merge = model.editor_merge.as_ref().expect("merge view should open");
+    assert_eq!(merge.conflict_count(), 0);
+    assert_eq!(merge.auto_merged, 2);
 
-    // Second save: forces overwrite
-    App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
+    model = update(model, Message::EditorMergeApply);
+    assert!(model.editor_merge.is_none());
+    assert!(!model.editor_disk_conflict);
+    assert_eq!(
+        model.editor_buffer.as_ref().unwrap().text(),
+        "X# Title\n\nbody from disk\n"
+    );
 
+    // The merged buffer now saves without a conflict
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
     let on_disk = std::fs::read_to_string(&file_path).unwrap();
-    assert!(
-        on_disk.starts_with('X'),
-        "second save should force overwrite"
+    assert_eq!(on_disk, "X# Title\n\nbody from disk\n");
+}
+
+#[test]
+fn test_merge_view_requires_every_conflict_resolved() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().canonicalize().unwrap().join("test.md");
+    std::fs::write(&file_path, "line").unwrap();
+
+    let doc = Document::parse("line").unwrap();
+    let mut model = enter_edit_mode(Model::new(file_path.clone(), doc, (80, 24)));
+    model = update(model, Message::EditorMoveEnd);
+    model = update(model, Message::EditorInsertChar('!'));
+    std::fs::write(&file_path, "line?").unwrap();
+
+    let mut watcher = None;
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::EditorSave);
+
+    let key = |c| event::KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    assert_eq!(
+        App::handle_key(key('t'), &model),
+        Some(Message::EditorMergeResolve(
+            crate::editor::Resolution::Theirs
+        ))
     );
+
+    model = update(model, Message::EditorMergeApply);
     assert!(
-        !model.save_confirmed,
-        "save_confirmed should reset after successful save"
+        model.editor_merge.is_some(),
+        "unresolved conflicts block apply"
     );
+
+    model = update(
+        model,
+        Message::EditorMergeResolve(crate::editor::Resolution::Both),
+    );
+    model = update(model, Message::EditorMergeApply);
+    assert!(model.editor_merge.is_none());
+    assert_eq!(model.editor_buffer.as_ref().unwrap().text(), "line!\nline?");
 }
 
 #[test]
@@ -2923,7 +4151,7 @@ fn test_save_without_conflict_succeeds_immediately() {
         on_disk.starts_with('X'),
         "save should succeed immediately without conflict"
     );
-    assert!(!model.save_confirmed);
+    assert!(model.editor_merge.is_none());
 }
 
 #[test]
@@ -2974,6 +4202,60 @@ fn test_toggle_watch_watches_model_file_path_not_app_path() {
     );
 }
 
+fn create_stdin_model() -> Model {
+    let doc = Document::parse("# Piped\n\nfrom stdin").unwrap();
+    let mut model = Model::new(
+        std::env::temp_dir().join(super::STDIN_FILE_NAME),
+        doc,
+        (80, 24),
+    );
+    model.from_stdin = true;
+    model
+}
+
+#[test]
+fn test_stdin_document_cannot_be_edited() {
+    let model = create_stdin_model();
+    assert!(!model.can_edit());
+
+    let model = enter_edit_mode(model);
+    assert!(!model.editor_mode);
+    let toast = model.active_toast().map(|(msg, _)| msg.to_string());
+    assert_eq!(toast.as_deref(), Some("Cannot edit stdin input"));
+}
+
+#[test]
+fn test_stdin_document_toggle_watch_stays_disabled() {
+    let model = create_stdin_model();
+    let mut model = update(model, Message::ToggleWatch);
+    let mut watcher: Option<crate::watcher::FileWatcher> = None;
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::ToggleWatch);
+
+    assert!(!model.watch_enabled);
+    assert!(watcher.is_none());
+    let (msg, level) = model.active_toast().expect("toast should be set");
+    assert_eq!(msg, "Watch unavailable for stdin input");
+    assert_eq!(level, ToastLevel::Warning);
+}
+
+#[test]
+fn test_stdin_document_reload_keeps_content() {
+    let mut model = create_stdin_model();
+    let line_count = model.document.line_count();
+    let mut watcher = None;
+    App::handle_message_side_effects(&mut model, &mut watcher, &Message::ForceReload);
+
+    assert_eq!(model.document.line_count(), line_count);
+    let toast = model.active_toast().map(|(msg, _)| msg.to_string());
+    assert_eq!(toast.as_deref(), Some("Cannot reload stdin input"));
+}
+
+#[test]
+fn test_stdin_document_base_dir_is_parent_of_synthetic_path() {
+    let model = create_stdin_model();
+    assert_eq!(model.base_dir, std::env::temp_dir());
+}
+
 // --- Editor mouse click tests ---
 
 #[test]
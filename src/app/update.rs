@@ -1,6 +1,7 @@
 use crate::app::Model;
 use crate::app::model::{LineSelection, SelectionState};
-use crate::editor::Direction;
+use crate::editor::{Direction, ExCommand, FindField, FindState, Motion, Resolution, VimKey};
+use crate::input::KeyChord;
 
 /// All possible events and actions in the application.
 ///
@@ -69,6 +70,10 @@ pub enum Message {
     /// Force reload file
     ForceReload,
 
+    // Front matter
+    /// Collapse or expand the front matter panel
+    ToggleFrontMatter,
+
     // Search
     /// Start search mode
     StartSearch,
@@ -80,8 +85,62 @@ pub enum Message {
     NextMatch,
     /// Go to previous match
     PrevMatch,
+    /// Toggle regex matching for the search query
+    ToggleSearchRegex,
+    /// Toggle whole-word matching for the search query
+    ToggleSearchWholeWord,
     /// Clear search
     ClearSearch,
+    /// Open the overlay listing every search match
+    OpenSearchResults,
+    /// Set the search results filter text
+    SearchResultsFilter(String),
+    /// Move the search results selection down
+    SearchResultsDown(usize),
+    /// Move the search results selection up
+    SearchResultsUp(usize),
+    /// Jump to the selected search result
+    SearchResultsSelect,
+    /// Close the search results overlay
+    CloseSearchResults,
+    /// Open the project-wide search overlay (browse mode)
+    OpenProjectSearch,
+    /// Set the project search query
+    ProjectSearchInput(String),
+    /// Search every file under the browse directory for the query
+    ProjectSearchSubmit,
+    /// Move the project search selection down
+    ProjectSearchDown(usize),
+    /// Move the project search selection up
+    ProjectSearchUp(usize),
+    /// Open the file of the selected project search hit
+    ProjectSearchOpen,
+    /// Close the project search overlay
+    CloseProjectSearch,
+    /// Open the fuzzy heading palette
+    OpenHeadingPalette,
+    /// Set the heading palette query
+    HeadingPaletteInput(String),
+    /// Move the heading palette selection down
+    HeadingPaletteDown(usize),
+    /// Move the heading palette selection up
+    HeadingPaletteUp(usize),
+    /// Jump to the selected heading
+    HeadingPaletteSelect,
+    /// Close the heading palette
+    CloseHeadingPalette,
+    /// Open the fuzzy file finder (browse mode)
+    OpenFileFinder,
+    /// Set the file finder query
+    FileFinderInput(String),
+    /// Move the file finder selection down
+    FileFinderDown(usize),
+    /// Move the file finder selection up
+    FileFinderUp(usize),
+    /// Open the selected file
+    FileFinderSelect,
+    /// Close the file finder, going back to the file shown before
+    CloseFileFinder,
     /// Open visible-link picker (or follow directly when single link)
     OpenVisibleLinks,
     /// Follow link on an exact rendered line, optionally at a specific column
@@ -98,6 +157,8 @@ pub enum Message {
     UpdateSelection(usize),
     /// Finish a line selection (mouse up)
     EndSelection(usize),
+    /// Finish a line selection and copy its markdown source (Alt + mouse up)
+    EndSourceSelection(usize),
     /// Clear current selection
     ClearSelection,
 
@@ -118,8 +179,52 @@ pub enum Message {
     EditorDeleteBack,
     /// Delete character at cursor (Delete)
     EditorDeleteForward,
-    /// Split line at cursor (Enter)
+    /// Split line at cursor, continuing markdown lists (Enter)
     EditorSplitLine,
+    /// Nest the list item under the cursor, or insert a tab (Tab)
+    EditorIndent,
+    /// Move the list item under the cursor out one level (Shift+Tab)
+    EditorOutdent,
+    /// Re-align the pipe table under the cursor (Ctrl+T)
+    EditorFormatTable,
+    /// Undo the last edit (Ctrl+Z)
+    EditorUndo,
+    /// Redo the last undone edit (Ctrl+Y)
+    EditorRedo,
+    /// Move the cursor while extending the selection (Shift+movement)
+    EditorSelect(Motion),
+    /// Select the whole buffer (Ctrl+A)
+    EditorSelectAll,
+    /// Copy the selection to the clipboard (Ctrl+C)
+    EditorCopy,
+    /// Cut the selection to the clipboard (Ctrl+X)
+    EditorCut,
+    /// Paste the last copied or cut text (Ctrl+V)
+    EditorPaste,
+    /// Insert pasted text as a single edit (bracketed paste)
+    EditorPasteText(String),
+    /// Open the find bar (Ctrl+F)
+    EditorFindOpen,
+    /// Open the find bar with the replace row (Ctrl+R)
+    EditorReplaceOpen,
+    /// Set the text of the focused find bar field
+    EditorFindInput(String),
+    /// Select the next match (Enter / Down)
+    EditorFindNext,
+    /// Select the previous match (Up)
+    EditorFindPrev,
+    /// Move focus between the find and replace fields (Tab)
+    EditorFindSwitchField,
+    /// Toggle case-sensitive matching (Alt+C)
+    EditorFindToggleCase,
+    /// Toggle regex matching (Alt+R)
+    EditorFindToggleRegex,
+    /// Replace the current match and select the next one (Enter in replace field)
+    EditorReplaceOne,
+    /// Replace every match as one undo step (Alt+A)
+    EditorReplaceAll,
+    /// Close the find bar (Esc)
+    EditorFindClose,
     /// Move cursor in a direction
     EditorMoveCursor(Direction),
     /// Move cursor to beginning of line (Home)
@@ -134,6 +239,38 @@ pub enum Message {
     EditorMoveToStart,
     /// Move cursor to end of buffer (Ctrl+End)
     EditorMoveToEnd,
+    /// Show or hide the live preview beside the editor (Ctrl+P)
+    EditorTogglePreview,
+    /// Load the leftover swap file into the buffer (r)
+    EditorSwapRecover,
+    /// Show or hide the diff between the file and the swap file (d)
+    EditorSwapToggleDiff,
+    /// Delete the leftover swap file and keep the file as is (x)
+    EditorSwapDiscard,
+    /// Scroll the swap file diff up by n lines
+    EditorSwapScrollUp(usize),
+    /// Scroll the swap file diff down by n lines
+    EditorSwapScrollDown(usize),
+    /// Select the next conflict in the merge view (n / Tab)
+    EditorMergeNext,
+    /// Select the previous conflict in the merge view (p / Shift+Tab)
+    EditorMergePrev,
+    /// Resolve the selected conflict (o / t / b)
+    EditorMergeResolve(Resolution),
+    /// Load the merged text into the buffer (Enter)
+    EditorMergeApply,
+    /// Show or hide the diff between the file on disk and the buffer (d)
+    EditorMergeToggleDiff,
+    /// Scroll the merge view up by n lines
+    EditorMergeScrollUp(usize),
+    /// Scroll the merge view down by n lines
+    EditorMergeScrollDown(usize),
+    /// Close the merge view without changing the buffer (Esc)
+    EditorMergeCancel,
+    /// A key for the Vim keybindings outside insert mode, or Esc in insert mode
+    EditorVimKey(VimKey),
+    /// Run the command typed on the Vim `:` line (Enter)
+    EditorVimCommand(ExCommand),
     /// Save editor buffer to file
     EditorSave,
     /// Scroll editor viewport up by n lines
@@ -143,6 +280,10 @@ pub enum Message {
     /// Scroll editor viewport down by n lines
     EditorScrollDown(usize),
 
+    // Input
+    /// Keys typed so far of a multi-key binding (empty to abandon one)
+    PendingKeys(Vec<KeyChord>),
+
     // Window
     /// Terminal resized
     Resize(u16, u16),
@@ -154,6 +295,32 @@ pub enum Message {
     Quit,
 }
 
+impl Message {
+    /// Whether this message can change the editor buffer's text.
+    pub const fn edits_editor_text(&self) -> bool {
+        matches!(
+            self,
+            Self::EditorInsertChar(_)
+                | Self::EditorDeleteBack
+                | Self::EditorDeleteForward
+                | Self::EditorSplitLine
+                | Self::EditorIndent
+                | Self::EditorOutdent
+                | Self::EditorFormatTable
+                | Self::EditorUndo
+                | Self::EditorRedo
+                | Self::EditorCut
+                | Self::EditorPaste
+                | Self::EditorPasteText(_)
+                | Self::EditorReplaceOne
+                | Self::EditorReplaceAll
+                | Self::EditorSwapRecover
+                | Self::EditorMergeApply
+                | Self::EditorVimKey(_)
+        )
+    }
+}
+
 /// Pure function that updates the model based on a message.
 ///
 /// This is the core of TEA - all state transitions happen here.
@@ -170,7 +337,13 @@ pub fn update(mut model: Model, msg: Message) -> Model {
             | Message::TocCollapse
             | Message::TocExpand
             | Message::HoverLink(_)
+            | Message::PendingKeys(_)
     );
+    let edits_editor_text = msg.edits_editor_text();
+    let editor_position = model
+        .editor_buffer
+        .as_ref()
+        .map(|buf| (buf.cursor(), model.editor_scroll_offset));
     // Reset confirmation flags on any action other than the confirmed one.
     // EditorSave preserves quit/exit flags so Ctrl+S can complete a pending quit/exit.
     if !matches!(msg, Message::Quit | Message::EditorSave) {
@@ -179,8 +352,9 @@ pub fn update(mut model: Model, msg: Message) -> Model {
     if !matches!(msg, Message::ExitEditMode | Message::EditorSave) {
         model.exit_confirmed = false;
     }
-    if !matches!(msg, Message::EditorSave) {
-        model.save_confirmed = false;
+    // Any other key ends a multi-key sequence; mouse hovering doesn't.
+    if !matches!(msg, Message::PendingKeys(_) | Message::HoverLink(_)) {
+        model.pending_keys.clear();
     }
 
     match msg {
@@ -300,7 +474,20 @@ pub fn update(mut model: Model, msg: Message) -> Model {
 
         // File watching
         Message::ToggleWatch => {
-            model.watch_enabled = !model.watch_enabled;
+            if model.from_stdin {
+                model.show_toast(
+                    crate::app::ToastLevel::Warning,
+                    "Watch unavailable for stdin input",
+                );
+            } else {
+                model.watch_enabled = !model.watch_enabled;
+            }
+        }
+        Message::ToggleFrontMatter => {
+            if model.document.front_matter().is_some() {
+                model.front_matter_collapsed = !model.front_matter_collapsed;
+                model.reflow_layout();
+            }
         }
         Message::ToggleHelp => {
             model.help_visible = !model.help_visible;
@@ -323,6 +510,9 @@ pub fn update(mut model: Model, msg: Message) -> Model {
         | Message::FileChanged
         | Message::ForceReload
         | Message::Redraw
+        | Message::ProjectSearchOpen
+        | Message::FileFinderSelect
+        | Message::CloseFileFinder
         | Message::EditorSave => {}
 
         // Search
@@ -349,7 +539,7 @@ pub fn update(mut model: Model, msg: Message) -> Model {
                     None => 0,
                 };
                 model.search_match_index = Some(next);
-                if let Some(line) = model.search_matches.get(next).copied() {
+                if let Some(line) = model.search_matches.get(next).map(|m| m.line) {
                     model.viewport.go_to_line(line);
                 }
             }
@@ -365,16 +555,170 @@ pub fn update(mut model: Model, msg: Message) -> Model {
                     Some(idx) => idx - 1,
                 };
                 model.search_match_index = Some(prev);
-                if let Some(line) = model.search_matches.get(prev).copied() {
+                if let Some(line) = model.search_matches.get(prev).map(|m| m.line) {
                     model.viewport.go_to_line(line);
                 }
             }
         }
+        Message::ToggleSearchRegex | Message::ToggleSearchWholeWord => {
+            if matches!(msg, Message::ToggleSearchRegex) {
+                model.search_mode.regex = !model.search_mode.regex;
+            } else {
+                model.search_mode.whole_word = !model.search_mode.whole_word;
+            }
+            // The search re-runs in effects.
+            if model
+                .project_search
+                .as_ref()
+                .is_some_and(|state| state.searched.is_some())
+            {
+                model.reset_project_search();
+            }
+            let allow_short = model.search_allow_short;
+            refresh_search_matches(&mut model, true, allow_short);
+        }
         Message::ClearSearch => {
             model.search_query = None;
             model.search_matches.clear();
             model.search_match_index = None;
             model.search_allow_short = false;
+            model.search_results = None;
+        }
+        Message::OpenSearchResults => {
+            if model.search_matches.is_empty() {
+                model.search_allow_short = true;
+                refresh_search_matches(&mut model, false, true);
+            }
+            model.search_results = Some(crate::app::SearchResults {
+                filter: String::new(),
+                selected: model.search_match_index.unwrap_or(0),
+            });
+        }
+        Message::SearchResultsFilter(filter) => {
+            if let Some(results) = model.search_results.as_mut() {
+                results.filter = filter;
+                results.selected = 0;
+            }
+        }
+        Message::SearchResultsDown(n) => {
+            let count = model.search_result_items().len();
+            if let Some(results) = model.search_results.as_mut() {
+                results.selected = results
+                    .selected
+                    .saturating_add(n)
+                    .min(count.saturating_sub(1));
+            }
+        }
+        Message::SearchResultsUp(n) => {
+            if let Some(results) = model.search_results.as_mut() {
+                results.selected = results.selected.saturating_sub(n);
+            }
+        }
+        Message::SearchResultsSelect => {
+            let selected = model.search_results.as_ref().map(|r| r.selected);
+            let item = selected.and_then(|idx| model.search_result_items().into_iter().nth(idx));
+            if let Some(item) = item {
+                model.search_results = None;
+                model.search_match_index = Some(item.index);
+                model.viewport.go_to_line(item.line);
+            }
+        }
+        Message::CloseSearchResults => {
+            model.search_results = None;
+        }
+        Message::OpenProjectSearch => {
+            let query = model.search_query.clone().unwrap_or_default();
+            model.project_search = Some(crate::app::ProjectSearchState::new(query));
+        }
+        Message::ProjectSearchInput(query) => {
+            if let Some(state) = model.project_search.as_mut() {
+                state.query = query;
+            }
+        }
+        Message::ProjectSearchSubmit => {
+            // The search itself starts in effects.
+            model.reset_project_search();
+        }
+        Message::ProjectSearchDown(n) => {
+            if let Some(state) = model.project_search.as_mut() {
+                state.selected = state
+                    .selected
+                    .saturating_add(n)
+                    .min(state.hit_count().saturating_sub(1));
+            }
+        }
+        Message::ProjectSearchUp(n) => {
+            if let Some(state) = model.project_search.as_mut() {
+                state.selected = state.selected.saturating_sub(n);
+            }
+        }
+        Message::CloseProjectSearch => {
+            model.project_search = None;
+        }
+        Message::OpenHeadingPalette => {
+            model.heading_palette = Some(crate::app::HeadingPalette::default());
+        }
+        Message::HeadingPaletteInput(query) => {
+            if let Some(palette) = model.heading_palette.as_mut() {
+                palette.query = query;
+                palette.selected = 0;
+            }
+        }
+        Message::HeadingPaletteDown(n) => {
+            let count = model.heading_palette_items().len();
+            if let Some(palette) = model.heading_palette.as_mut() {
+                palette.selected = palette
+                    .selected
+                    .saturating_add(n)
+                    .min(count.saturating_sub(1));
+            }
+        }
+        Message::HeadingPaletteUp(n) => {
+            if let Some(palette) = model.heading_palette.as_mut() {
+                palette.selected = palette.selected.saturating_sub(n);
+            }
+        }
+        Message::HeadingPaletteSelect => {
+            let selected = model.heading_palette.as_ref().map(|p| p.selected);
+            let item = selected.and_then(|idx| model.heading_palette_items().into_iter().nth(idx));
+            if let Some(item) = item {
+                model.heading_palette = None;
+                // Same as selecting the heading in the TOC.
+                if !model.browse_mode {
+                    model.toc_selected = Some(item.index);
+                }
+                if let Some(heading) = model.document.headings().get(item.index) {
+                    model.viewport.go_to_line(heading.line);
+                }
+            }
+        }
+        Message::CloseHeadingPalette => {
+            model.heading_palette = None;
+        }
+        Message::OpenFileFinder => {
+            // Indexing starts in effects.
+            model.file_finder = Some(crate::app::FileFinder::new(
+                &model.browse_dir,
+                model.file_path.clone(),
+            ));
+        }
+        Message::FileFinderInput(query) => {
+            if let Some(finder) = model.file_finder.as_mut() {
+                finder.set_query(query);
+            }
+        }
+        Message::FileFinderDown(n) => {
+            if let Some(finder) = model.file_finder.as_mut() {
+                finder.selected = finder
+                    .selected
+                    .saturating_add(n)
+                    .min(finder.items().len().saturating_sub(1));
+            }
+        }
+        Message::FileFinderUp(n) => {
+            if let Some(finder) = model.file_finder.as_mut() {
+                finder.selected = finder.selected.saturating_sub(n);
+            }
         }
         Message::OpenVisibleLinks
         | Message::FollowLinkAtLine(_, _)
@@ -404,7 +748,7 @@ pub fn update(mut model: Model, msg: Message) -> Model {
                 });
             }
         }
-        Message::EndSelection(line) => {
+        Message::EndSelection(line) | Message::EndSourceSelection(line) => {
             if let Some(selection) = model.selection {
                 model.selection = Some(LineSelection {
                     anchor: selection.anchor,
@@ -420,7 +764,9 @@ pub fn update(mut model: Model, msg: Message) -> Model {
         // Editor
         Message::EnterEditMode => {
             if !model.can_edit() {
-                let reason = if model.document.is_hex_mode() {
+                let reason = if model.from_stdin {
+                    "Cannot edit stdin input".to_string()
+                } else if model.document.is_hex_mode() {
                     "Cannot edit binary files".to_string()
                 } else {
                     let ext = model
@@ -459,18 +805,35 @@ pub fn update(mut model: Model, msg: Message) -> Model {
                 });
 
                 model.editor_mode = false;
+                // The preview laid out the buffer in a narrow column; show
+                // the file as last loaded or saved at full width instead.
+                if model.editor_preview {
+                    model.close_editor_preview();
+                }
                 model.editor_buffer = None;
+                model.editor_find = None;
+                model.editor_highlight = None;
                 model.editor_scroll_offset = 0;
                 model.editor_disk_hash = None;
+                model.editor_base = None;
                 model.editor_disk_conflict = false;
-                model.save_confirmed = false;
+                model.editor_merge = None;
+                model.editor_vim = None;
 
-                // Map source scroll position to rendered line position
+                // Map the top source line back to where it renders. The
+                // document now matches the file on disk: saves reload it,
+                // the preview was replaced above, and unsaved edits are
+                // discarded here.
                 if let Some((src_offset, src_total)) = scroll_ratio
                     && src_offset > 0
                 {
-                    let rendered_total = model.document.line_count().saturating_sub(1).max(1);
-                    let target = (src_offset * rendered_total) / src_total;
+                    let target = model
+                        .rendered_line_for_file_line(src_offset)
+                        .unwrap_or_else(|| {
+                            let rendered_total =
+                                model.document.line_count().saturating_sub(1).max(1);
+                            (src_offset * rendered_total) / src_total
+                        });
                     model.viewport.go_to_line(target);
                 }
                 // Document reload from disk happens in effects (ExitEditMode)
@@ -495,11 +858,318 @@ pub fn update(mut model: Model, msg: Message) -> Model {
             }
         }
         Message::EditorSplitLine => {
+            let markdown = model.editing_markdown();
+            if let Some(buf) = &mut model.editor_buffer {
+                if markdown {
+                    buf.markdown_newline();
+                } else {
+                    buf.newline_with_indent();
+                }
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorIndent => {
+            let markdown = model.editing_markdown();
+            if let Some(buf) = &mut model.editor_buffer {
+                if !(markdown && buf.selection().is_none() && buf.indent_list_item()) {
+                    buf.insert_char('\t');
+                }
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorOutdent => {
+            let markdown = model.editing_markdown();
+            if let Some(buf) = &mut model.editor_buffer
+                && markdown
+            {
+                buf.outdent_list_item();
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorFormatTable => {
+            let markdown = model.editing_markdown();
+            if let Some(buf) = &mut model.editor_buffer {
+                if markdown && buf.format_table() {
+                    editor_ensure_cursor_visible(&mut model);
+                } else {
+                    model.show_toast(crate::app::ToastLevel::Info, "No table under the cursor");
+                }
+            }
+        }
+        Message::EditorUndo => {
+            if let Some(buf) = &mut model.editor_buffer {
+                buf.undo();
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorRedo => {
+            if let Some(buf) = &mut model.editor_buffer {
+                buf.redo();
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorSelect(motion) => {
             if let Some(buf) = &mut model.editor_buffer {
-                buf.split_line();
+                buf.select(motion);
                 editor_ensure_cursor_visible(&mut model);
             }
         }
+        Message::EditorSelectAll => {
+            if let Some(buf) = &mut model.editor_buffer {
+                buf.select_all();
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorCopy | Message::EditorCut => {
+            // The system clipboard is written in effects.
+            if let Some(buf) = &mut model.editor_buffer
+                && let Some(text) = buf.selected_text()
+            {
+                model.editor_clipboard = Some(text);
+                if matches!(msg, Message::EditorCut) {
+                    buf.delete_selection();
+                    editor_ensure_cursor_visible(&mut model);
+                }
+            }
+        }
+        Message::EditorPaste => {
+            if let Some(buf) = &mut model.editor_buffer
+                && let Some(text) = &model.editor_clipboard
+            {
+                buf.insert_str(text);
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorPasteText(text) => {
+            if let Some(buf) = &mut model.editor_buffer {
+                buf.insert_str(&normalize_pasted_newlines(&text));
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorFindOpen | Message::EditorReplaceOpen => {
+            let replacing = matches!(msg, Message::EditorReplaceOpen);
+            if let Some(buf) = &model.editor_buffer {
+                let find = model.editor_find.get_or_insert_with(|| {
+                    let mut find = FindState::new(replacing, buf.cursor_byte_offset());
+                    // Seed the query with a single-line selection.
+                    if let Some(text) = buf.selected_text().filter(|t| !t.contains('\n')) {
+                        find.query = text;
+                    }
+                    find
+                });
+                find.replacing |= replacing;
+                find.focus = if replacing && !find.query.is_empty() {
+                    FindField::Replacement
+                } else {
+                    FindField::Query
+                };
+                refresh_editor_find(&mut model, true);
+            }
+        }
+        Message::EditorFindInput(text) => {
+            if let Some(find) = &mut model.editor_find {
+                let is_query = find.focus == FindField::Query;
+                *find.active_input_mut() = text;
+                if is_query {
+                    refresh_editor_find(&mut model, true);
+                }
+            }
+        }
+        Message::EditorFindNext | Message::EditorFindPrev => {
+            if let Some(find) = &mut model.editor_find {
+                find.step(matches!(msg, Message::EditorFindNext));
+                select_current_find_match(&mut model);
+            }
+        }
+        Message::EditorFindSwitchField => {
+            if let Some(find) = &mut model.editor_find {
+                find.replacing = true;
+                find.focus = match find.focus {
+                    FindField::Query => FindField::Replacement,
+                    FindField::Replacement => FindField::Query,
+                };
+            }
+        }
+        Message::EditorFindToggleCase | Message::EditorFindToggleRegex => {
+            if let Some(find) = &mut model.editor_find {
+                if matches!(msg, Message::EditorFindToggleCase) {
+                    find.options.case_sensitive = !find.options.case_sensitive;
+                } else {
+                    find.options.regex = !find.options.regex;
+                }
+                refresh_editor_find(&mut model, true);
+            }
+        }
+        Message::EditorReplaceOne => {
+            if let (Some(find), Some(buf)) = (&mut model.editor_find, &mut model.editor_buffer)
+                && let Some(range) = find.current_match().cloned()
+            {
+                let text = buf.text();
+                if let Ok(pattern) = crate::search::text_pattern(&find.query, find.options) {
+                    let replacement = crate::search::expand_replacement(
+                        &text,
+                        &range,
+                        &pattern,
+                        &find.replacement,
+                        find.options,
+                    );
+                    find.set_origin(range.start + replacement.len());
+                    buf.replace_ranges(&[(range, replacement)]);
+                    refresh_editor_find(&mut model, true);
+                }
+            }
+        }
+        Message::EditorReplaceAll => {
+            if let (Some(find), Some(buf)) = (&mut model.editor_find, &mut model.editor_buffer)
+                && let Ok(pattern) = crate::search::text_pattern(&find.query, find.options)
+            {
+                let text = buf.text();
+                let replacements: Vec<_> = find
+                    .matches()
+                    .iter()
+                    .map(|range| {
+                        let replacement = crate::search::expand_replacement(
+                            &text,
+                            range,
+                            &pattern,
+                            &find.replacement,
+                            find.options,
+                        );
+                        (range.clone(), replacement)
+                    })
+                    .collect();
+                if !replacements.is_empty() {
+                    let count = replacements.len();
+                    buf.replace_ranges(&replacements);
+                    editor_ensure_cursor_visible(&mut model);
+                    refresh_editor_find(&mut model, false);
+                    model.show_toast(
+                        crate::app::ToastLevel::Info,
+                        format!("Replaced {count} match(es)"),
+                    );
+                }
+            }
+        }
+        Message::EditorFindClose => {
+            model.editor_find = None;
+        }
+        Message::EditorTogglePreview => {
+            if model.editor_mode {
+                if model.editor_preview {
+                    model.close_editor_preview();
+                } else {
+                    model.editor_preview = true;
+                    model.refresh_editor_preview();
+                }
+            }
+        }
+        Message::EditorSwapRecover => {
+            if let Some(recovery) = model.editor_swap_recovery.take()
+                && let Some(buf) = &mut model.editor_buffer
+            {
+                let line = buf.cursor().line;
+                let end = buf.text().len();
+                buf.replace_ranges_and_move(&[(0..end, recovery.text)], 0);
+                buf.move_to(line, 0);
+                editor_ensure_cursor_visible(&mut model);
+                model.show_toast(crate::app::ToastLevel::Info, "Recovered unsaved changes");
+            }
+        }
+        Message::EditorSwapToggleDiff => {
+            if let Some(recovery) = &mut model.editor_swap_recovery {
+                recovery.showing_diff = !recovery.showing_diff;
+                recovery.scroll = 0;
+            }
+        }
+        Message::EditorSwapDiscard => {
+            // The file itself is removed in effects.
+            if model.editor_swap_recovery.take().is_some() {
+                model.show_toast(crate::app::ToastLevel::Info, "Discarded unsaved changes");
+            }
+        }
+        Message::EditorSwapScrollUp(n) => {
+            if let Some(recovery) = &mut model.editor_swap_recovery {
+                recovery.scroll = recovery.scroll.saturating_sub(n);
+            }
+        }
+        Message::EditorSwapScrollDown(n) => {
+            if let Some(recovery) = &mut model.editor_swap_recovery {
+                let max = recovery.diff.len().saturating_sub(1);
+                recovery.scroll = recovery.scroll.saturating_add(n).min(max);
+            }
+        }
+        Message::EditorMergeNext | Message::EditorMergePrev => {
+            if let Some(merge) = &mut model.editor_merge {
+                merge.step(matches!(msg, Message::EditorMergeNext));
+            }
+        }
+        Message::EditorMergeResolve(resolution) => {
+            if let Some(merge) = &mut model.editor_merge {
+                merge.resolve(resolution);
+            }
+        }
+        Message::EditorMergeApply => apply_editor_merge(&mut model),
+        Message::EditorMergeToggleDiff => {
+            if let Some(merge) = &mut model.editor_merge {
+                merge.showing_diff = !merge.showing_diff;
+                merge.scroll = 0;
+            }
+        }
+        Message::EditorMergeScrollUp(n) => {
+            if let Some(merge) = &mut model.editor_merge {
+                merge.scroll = merge.scroll.saturating_sub(n);
+            }
+        }
+        Message::EditorMergeScrollDown(n) => {
+            if let Some(merge) = &mut model.editor_merge {
+                let rows = if merge.showing_diff {
+                    merge.diff.len()
+                } else {
+                    merge.rows().len()
+                };
+                merge.scroll = merge.scroll.saturating_add(n).min(rows.saturating_sub(1));
+            }
+        }
+        Message::EditorMergeCancel => {
+            if model.editor_merge.take().is_some() {
+                model.show_toast(
+                    crate::app::ToastLevel::Warning,
+                    "Merge cancelled; the file on disk still differs",
+                );
+            }
+        }
+        Message::EditorVimKey(key) => {
+            if let (Some(vim), Some(buf)) = (&mut model.editor_vim, &mut model.editor_buffer) {
+                vim.handle_key(key, buf);
+                editor_ensure_cursor_visible(&mut model);
+            }
+        }
+        Message::EditorVimCommand(ref command) => {
+            if let Some(vim) = &mut model.editor_vim {
+                vim.close_command_line();
+            }
+            // Writing happens in effects; with `:wq` the save then leaves
+            // the editor like a save after an exit warning.
+            match command {
+                ExCommand::Write => {}
+                ExCommand::Quit | ExCommand::WriteQuit if !model.editor_is_dirty() => {
+                    return update(model, Message::ExitEditMode);
+                }
+                ExCommand::Quit => model.show_toast(
+                    crate::app::ToastLevel::Warning,
+                    "Unsaved changes! Use :w to save or :q! to discard",
+                ),
+                ExCommand::ForceQuit => {
+                    model.exit_confirmed = true;
+                    return update(model, Message::ExitEditMode);
+                }
+                ExCommand::WriteQuit => model.exit_confirmed = true,
+                ExCommand::Unknown(name) => model.show_toast(
+                    crate::app::ToastLevel::Error,
+                    format!("Not an editor command: {name}"),
+                ),
+            }
+        }
         Message::EditorMoveCursor(dir) => {
             if let Some(buf) = &mut model.editor_buffer {
                 buf.move_cursor(dir);
@@ -573,6 +1243,8 @@ pub fn update(mut model: Model, msg: Message) -> Model {
                 model.toc_selected = Some(0);
             }
         }
+        // Input
+        Message::PendingKeys(keys) => model.pending_keys = keys,
         // Window
         Message::Resize(width, height) => {
             model.viewport.resize(width, height.saturating_sub(1));
@@ -591,6 +1263,16 @@ pub fn update(mut model: Model, msg: Message) -> Model {
             }
         }
     }
+    if edits_editor_text {
+        refresh_editor_find(&mut model, false);
+    }
+    // The preview follows the cursor; it is re-parsed after edits settle.
+    if model.editor_preview
+        && let Some(buf) = &model.editor_buffer
+        && editor_position != Some((buf.cursor(), model.editor_scroll_offset))
+    {
+        model.sync_preview_to_cursor();
+    }
     if should_sync_toc && model.toc_visible && !model.browse_mode {
         model.sync_toc_to_viewport();
     }
@@ -621,13 +1303,60 @@ pub(super) fn closest_heading_to_line(
     }
 }
 
+/// Replace the buffer with the resolved merge, which becomes the new base
+/// for conflict detection. While conflicts remain the merge stays open and
+/// a warning says how many are left.
+fn apply_editor_merge(model: &mut Model) {
+    let Some(merge) = &model.editor_merge else {
+        return;
+    };
+    let Some(text) = merge.result() else {
+        let left = merge.unresolved_count();
+        model.show_toast(
+            crate::app::ToastLevel::Warning,
+            format!("{left} conflict(s) left to resolve"),
+        );
+        return;
+    };
+    let Some(merge) = model.editor_merge.take() else {
+        return;
+    };
+    if let Some(buf) = &mut model.editor_buffer {
+        let matches_disk = text == merge.disk_text;
+        let line = buf.cursor().line;
+        if buf.text() != text {
+            let end = buf.text().len();
+            buf.replace_ranges_and_move(&[(0..end, text)], 0);
+            buf.move_to(line, 0);
+        }
+        if matches_disk {
+            buf.mark_clean();
+        }
+    }
+    model.editor_disk_hash = Some(crate::app::model::hash_bytes(merge.disk_text.as_bytes()));
+    model.editor_base = Some(merge.disk_text);
+    model.editor_disk_conflict = false;
+    editor_ensure_cursor_visible(model);
+    let message = if model.editor_is_dirty() {
+        "Merged changes from disk; Ctrl+S to save"
+    } else {
+        "Buffer now matches the file on disk"
+    };
+    model.show_toast(crate::app::ToastLevel::Info, message);
+}
+
 /// Ensure the editor cursor line is visible in the viewport.
 fn editor_ensure_cursor_visible(model: &mut Model) {
     let Some(buf) = &model.editor_buffer else {
         return;
     };
     let cursor_line = buf.cursor().line;
-    let visible_height = usize::from(model.viewport.height().saturating_sub(1));
+    let visible_height = usize::from(
+        model
+            .viewport
+            .height()
+            .saturating_sub(1 + model.editor_find_rows()),
+    );
     if visible_height == 0 {
         model.editor_scroll_offset = cursor_line;
         return;
@@ -640,6 +1369,38 @@ fn editor_ensure_cursor_visible(model: &mut Model) {
     }
 }
 
+/// Recompute find matches after the query, options, or text changed,
+/// optionally selecting the current match.
+fn refresh_editor_find(model: &mut Model, select: bool) {
+    let (Some(find), Some(buf)) = (&mut model.editor_find, &model.editor_buffer) else {
+        return;
+    };
+    find.refresh(&buf.text());
+    if select {
+        select_current_find_match(model);
+    }
+}
+
+/// Select the current find match in the buffer and scroll to it.
+fn select_current_find_match(model: &mut Model) {
+    let (Some(find), Some(buf)) = (&model.editor_find, &mut model.editor_buffer) else {
+        return;
+    };
+    if let Some(range) = find.current_match() {
+        buf.select_range(range.clone());
+        editor_ensure_cursor_visible(model);
+    }
+}
+
+/// Terminals send pasted line breaks as `\r`; the buffer uses `\n`.
+fn normalize_pasted_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Recompute search matches for the current query and mode.
+///
+/// `jump_to_first` is set when the user changed the search, which also
+/// reports an invalid regex as a toast.
 pub(super) fn refresh_search_matches(model: &mut Model, jump_to_first: bool, allow_short: bool) {
     let Some(query) = model.search_query.as_deref() else {
         model.search_matches.clear();
@@ -661,7 +1422,21 @@ pub(super) fn refresh_search_matches(model: &mut Model, jump_to_first: bool, all
         return;
     }
 
-    model.search_matches = crate::search::find_matches(&model.document, query);
+    match crate::search::find_matches(&model.document, query, model.search_mode) {
+        Ok(matches) => model.search_matches = matches,
+        Err(err) => {
+            model.search_matches.clear();
+            model.search_match_index = None;
+            if jump_to_first {
+                let summary = crate::search::regex_error_summary(&err);
+                model.show_toast(
+                    crate::app::ToastLevel::Error,
+                    format!("Invalid regex: {summary}"),
+                );
+            }
+            return;
+        }
+    }
     if model.search_matches.is_empty() {
         model.search_match_index = None;
         return;
@@ -669,7 +1444,7 @@ pub(super) fn refresh_search_matches(model: &mut Model, jump_to_first: bool, all
 
     if jump_to_first || model.search_match_index.is_none() {
         model.search_match_index = Some(0);
-        if let Some(line) = model.search_matches.first().copied() {
+        if let Some(line) = model.search_matches.first().map(|m| m.line) {
             model.viewport.go_to_line(line);
         }
     } else if let Some(idx) = model.search_match_index {
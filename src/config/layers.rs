@@ -0,0 +1,255 @@
+//! Merging settings from several config files and the command line.
+//!
+//! Each source becomes a [`ConfigLayer`] holding its settings as a TOML
+//! table. [`LayeredConfig`] merges the layers in order, later ones
+//! overriding earlier ones key by key, and remembers which layer set each
+//! value so `markless config check` can explain the result.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::{ConfigFlags, flags_from_table, flags_to_table, read_config_table};
+
+/// Where a setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// A config file.
+    File(PathBuf),
+    /// A command-line flag.
+    CommandLine,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::CommandLine => f.write_str("command line"),
+        }
+    }
+}
+
+/// The settings from one source.
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    source: ConfigSource,
+    table: toml::Table,
+}
+
+impl ConfigLayer {
+    /// Load the config file at `path`, or `None` if there isn't one.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or holds invalid settings.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let Some(table) = read_config_table(path)? else {
+            return Ok(None);
+        };
+        // Check the file on its own so errors name it.
+        flags_from_table(table.clone())
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        Ok(Some(Self {
+            source: ConfigSource::File(path.to_path_buf()),
+            table,
+        }))
+    }
+
+    /// A layer setting everything in `flags` that differs from the default.
+    pub fn from_flags(source: ConfigSource, flags: &ConfigFlags) -> Self {
+        Self {
+            source,
+            table: flags_to_table(flags),
+        }
+    }
+}
+
+/// Settings merged from several layers.
+#[derive(Debug, Clone, Default)]
+pub struct LayeredConfig {
+    sources: Vec<ConfigSource>,
+    table: toml::Table,
+    /// Dotted key of every set value and the index of the layer that set it.
+    origins: HashMap<String, usize>,
+}
+
+impl LayeredConfig {
+    /// Merge `layer` over the layers added so far.
+    pub fn push(&mut self, layer: ConfigLayer) {
+        let index = self.sources.len();
+        merge_table(&mut self.table, layer.table, "", index, &mut self.origins);
+        self.sources.push(layer.source);
+    }
+
+    /// The sources merged so far, lowest precedence first.
+    pub fn sources(&self) -> &[ConfigSource] {
+        &self.sources
+    }
+
+    /// The merged settings.
+    ///
+    /// # Errors
+    /// Returns an error if the merged values don't form valid settings.
+    pub fn flags(&self) -> Result<ConfigFlags> {
+        flags_from_table(self.table.clone()).context("Invalid config")
+    }
+
+    /// Every value set by some layer, as `(dotted key, TOML value, source)`
+    /// in config order.
+    pub fn values(&self) -> Vec<(String, String, &ConfigSource)> {
+        let mut values = Vec::new();
+        self.collect_values(&self.table, "", &mut values);
+        values
+    }
+
+    fn collect_values<'a>(
+        &'a self,
+        table: &toml::Table,
+        prefix: &str,
+        values: &mut Vec<(String, String, &'a ConfigSource)>,
+    ) {
+        for (key, value) in table {
+            let path = dotted_key(prefix, key);
+            if let toml::Value::Table(inner) = value {
+                self.collect_values(inner, &path, values);
+            } else if let Some(&index) = self.origins.get(&path) {
+                values.push((path, value.to_string(), &self.sources[index]));
+            }
+        }
+    }
+}
+
+/// Merge `layer` into `merged`, recursing into tables so a layer only
+/// overrides the keys it sets.
+fn merge_table(
+    merged: &mut toml::Table,
+    layer: toml::Table,
+    prefix: &str,
+    index: usize,
+    origins: &mut HashMap<String, usize>,
+) {
+    for (key, value) in layer {
+        let path = dotted_key(prefix, &key);
+        match value {
+            toml::Value::Table(inner) => {
+                let entry = merged
+                    .entry(key)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                if !entry.is_table() {
+                    *entry = toml::Value::Table(toml::Table::new());
+                }
+                if let toml::Value::Table(merged_inner) = entry {
+                    merge_table(merged_inner, inner, &path, index, origins);
+                }
+            }
+            value => {
+                merged.insert(key, value);
+                origins.insert(path, index);
+            }
+        }
+    }
+}
+
+/// Append `key` to a dotted key path, quoting it unless it is a bare key.
+fn dotted_key(prefix: &str, key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let key = if bare {
+        key.to_string()
+    } else {
+        format!("{key:?}")
+    };
+    if prefix.is_empty() {
+        key
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_later_layers_override_per_key() {
+        let dir = tempdir().unwrap();
+        let global = dir.path().join("config.toml");
+        let local = dir.path().join(".marklessrc.toml");
+        fs::write(
+            &global,
+            "watch = true\nwrap_width = 80\n[keys]\ngg = \"go_to_top\"\n[images]\nmax_width = 60\n",
+        )
+        .unwrap();
+        fs::write(&local, "watch = false\n[images]\nmax_height = 20\n").unwrap();
+
+        let mut config = LayeredConfig::default();
+        config.push(ConfigLayer::load(&global).unwrap().unwrap());
+        config.push(ConfigLayer::load(&local).unwrap().unwrap());
+        let cli = ConfigFlags {
+            wrap_width: Some(100),
+            ..ConfigFlags::default()
+        };
+        config.push(ConfigLayer::from_flags(ConfigSource::CommandLine, &cli));
+
+        let flags = config.flags().unwrap();
+        assert!(!flags.watch);
+        assert_eq!(flags.wrap_width, Some(100));
+        assert_eq!(flags.images.max_width, Some(60));
+        assert_eq!(flags.images.max_height, Some(20));
+        assert_eq!(flags.keys.len(), 1);
+
+        let values = config.values();
+        let origin = |key: &str| {
+            values
+                .iter()
+                .find(|(k, _, _)| k == key)
+                .map(|(_, v, source)| (v.clone(), (*source).clone()))
+                .unwrap()
+        };
+        assert_eq!(
+            origin("watch"),
+            ("false".to_string(), ConfigSource::File(local.clone()))
+        );
+        assert_eq!(
+            origin("wrap_width"),
+            ("100".to_string(), ConfigSource::CommandLine)
+        );
+        assert_eq!(
+            origin("keys.gg"),
+            ("\"go_to_top\"".to_string(), ConfigSource::File(global))
+        );
+        assert_eq!(
+            origin("images.max_height"),
+            ("20".to_string(), ConfigSource::File(local))
+        );
+        assert_eq!(config.sources().len(), 3);
+    }
+
+    #[test]
+    fn test_load_reports_invalid_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc.toml");
+        fs::write(&path, "wrap_width = \"wide\"\n").unwrap();
+        let err = format!("{:#}", ConfigLayer::load(&path).unwrap_err());
+        assert!(err.contains(".marklessrc.toml"), "{err}");
+
+        fs::write(&path, "colour = \"red\"\n").unwrap();
+        assert!(ConfigLayer::load(&path).is_err());
+        assert!(
+            ConfigLayer::load(&dir.path().join("missing.toml"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_dotted_key_quotes_symbols() {
+        assert_eq!(dotted_key("keys", "?"), "keys.\"?\"");
+        assert_eq!(dotted_key("", "wrap_width"), "wrap_width");
+    }
+}
@@ -0,0 +1,1381 @@
+mod layers;
+
+pub use layers::{ConfigLayer, ConfigSource, LayeredConfig};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    Auto,
+    Light,
+    Dark,
+}
+
+/// Forced image rendering mode.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageMode {
+    /// Kitty graphics protocol
+    Kitty,
+    /// Sixel graphics
+    Sixel,
+    /// iTerm2 inline images
+    #[value(name = "iterm2")]
+    ITerm2,
+    /// Unicode half-blocks (universal fallback)
+    Halfblock,
+}
+
+impl std::fmt::Display for ImageMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Kitty => write!(f, "Kitty"),
+            Self::Sixel => write!(f, "Sixel"),
+            Self::ITerm2 => write!(f, "iTerm2"),
+            Self::Halfblock => write!(f, "Halfblock"),
+        }
+    }
+}
+
+/// Settings from the command line and config files.
+///
+/// The top-level fields mirror the command-line flags; the TOML config
+/// spells them the same way (`no_toc = true`). Unset fields are left out
+/// when saving.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFlags {
+    #[serde(skip_serializing_if = "is_false")]
+    pub watch: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub no_toc: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub toc: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub no_images: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub perf: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub force_half_cell: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_mode: Option<ImageMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeMode>,
+    /// Color theme: a built-in name, a file in the themes directory, or a
+    /// path to a theme file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme_name: Option<String>,
+    /// Syntax highlighting theme for code, by name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub render_debug_log: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_width: Option<u16>,
+    /// External editor command (e.g. "hx", "vim", "emacsclient -t").
+    /// `Some("")` means explicitly cleared via `--no-editor`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// Vim-style modal keybindings in the built-in editor.
+    /// `Some(false)` means explicitly turned off via `--no-vim`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vim: Option<bool>,
+    /// View-mode key bindings from `[keys]` sections, in file order.
+    /// Converted by hand since a binding's context is its sub-table.
+    #[serde(skip)]
+    pub keys: Vec<KeyBinding>,
+    /// `[colors]`: foreground color by document element (`h1`, `code`, …).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, String>,
+    /// `[images]`: inline image size limits.
+    #[serde(skip_serializing_if = "ImageLimits::is_empty")]
+    pub images: ImageLimits,
+    /// `[mermaid]`: diagram rendering options.
+    #[serde(skip_serializing_if = "MermaidOptions::is_empty")]
+    pub mermaid: MermaidOptions,
+    /// `[browse]`: which entries browse mode lists.
+    #[serde(skip_serializing_if = "BrowseFilters::is_empty")]
+    pub browse: BrowseFilters,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Size limits for inline images, in terminal cells.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageLimits {
+    /// Widest an image is drawn, in columns (otherwise 65% of the view).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<u16>,
+    /// Tallest an image is drawn, in rows; taller images are scaled down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u16>,
+}
+
+impl ImageLimits {
+    pub const fn is_empty(&self) -> bool {
+        self.max_width.is_none() && self.max_height.is_none()
+    }
+
+    #[must_use]
+    pub const fn or(self, fallback: Self) -> Self {
+        Self {
+            max_width: if self.max_width.is_some() {
+                self.max_width
+            } else {
+                fallback.max_width
+            },
+            max_height: if self.max_height.is_some() {
+                self.max_height
+            } else {
+                fallback.max_height
+            },
+        }
+    }
+}
+
+/// Mermaid diagram rendering options.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MermaidOptions {
+    /// Render diagrams as images when the terminal supports graphics
+    /// (default); `false` shows their source as a code block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Diagram width as a percentage of the regular image width.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width_percent: Option<u32>,
+}
+
+impl MermaidOptions {
+    pub const fn is_empty(&self) -> bool {
+        self.enabled.is_none() && self.width_percent.is_none()
+    }
+
+    #[must_use]
+    pub const fn or(self, fallback: Self) -> Self {
+        Self {
+            enabled: if self.enabled.is_some() {
+                self.enabled
+            } else {
+                fallback.enabled
+            },
+            width_percent: if self.width_percent.is_some() {
+                self.width_percent
+            } else {
+                fallback.width_percent
+            },
+        }
+    }
+}
+
+/// Filters for the browse-mode file listing.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrowseFilters {
+    /// List dot files and directories.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_hidden: Option<bool>,
+    /// Names to leave out; `*` matches any run of characters and `?` one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl BrowseFilters {
+    pub const fn is_empty(&self) -> bool {
+        self.show_hidden.is_none() && self.exclude.is_empty()
+    }
+
+    /// Whether an entry called `name` is left out of the listing.
+    pub fn hides(&self, name: &str) -> bool {
+        (name.starts_with('.') && self.show_hidden != Some(true))
+            || self
+                .exclude
+                .iter()
+                .any(|pattern| glob_matches(pattern, name))
+    }
+}
+
+/// Match `name` against a pattern where `*` is any run of characters and
+/// `?` any single character.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((after, tried)) => {
+                    p = after;
+                    n = tried + 1;
+                    star = Some((after, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// One `keys = "action"` line from a `[keys]` or `[keys.<context>]`
+/// section. Validated when the keymap is built at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// Key context name (`view` for a plain `[keys]` section).
+    pub context: String,
+    /// Key sequence as written, e.g. `gg` or `Ctrl-d`.
+    pub keys: String,
+    /// Action name, e.g. `go_to_top`.
+    pub action: String,
+}
+
+/// Directory holding the global config, if the platform has one.
+fn global_config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(appdata) = std::env::var_os("APPDATA") {
+            return Some(PathBuf::from(appdata).join("markless"));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = std::env::var_os("HOME") {
+            return Some(
+                PathBuf::from(home)
+                    .join("Library")
+                    .join("Application Support")
+                    .join("markless"),
+            );
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME") {
+            return Some(PathBuf::from(xdg).join("markless"));
+        }
+        if let Some(home) = std::env::var_os("HOME") {
+            return Some(PathBuf::from(home).join(".config").join("markless"));
+        }
+    }
+
+    None
+}
+
+/// Name of the per-directory TOML config files.
+pub const LOCAL_CONFIG_FILE: &str = ".marklessrc.toml";
+
+/// The global TOML config, written by `--save`.
+pub fn global_config_path() -> PathBuf {
+    global_config_dir().map_or_else(
+        || PathBuf::from(LOCAL_CONFIG_FILE),
+        |dir| dir.join("config.toml"),
+    )
+}
+
+/// The global config in the legacy flag-per-line format, read when there
+/// is no `config.toml`.
+pub fn legacy_global_config_path() -> PathBuf {
+    global_config_dir().map_or_else(|| PathBuf::from(".marklessrc"), |dir| dir.join("config"))
+}
+
+/// Directory searched for `<name>.toml` theme files and `.tmTheme` code
+/// themes.
+pub fn themes_dir() -> Option<PathBuf> {
+    global_config_dir().map(|dir| dir.join("themes"))
+}
+
+/// Directory of extra `.sublime-syntax` grammars.
+pub fn syntaxes_dir() -> Option<PathBuf> {
+    global_config_dir().map(|dir| dir.join("syntaxes"))
+}
+
+/// Directory for caches that can be rebuilt at any time.
+pub fn cache_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            return Some(PathBuf::from(local).join("markless").join("cache"));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = std::env::var_os("HOME") {
+            return Some(
+                PathBuf::from(home)
+                    .join("Library")
+                    .join("Caches")
+                    .join("markless"),
+            );
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        if let Some(xdg) = std::env::var_os("XDG_CACHE_HOME") {
+            return Some(PathBuf::from(xdg).join("markless"));
+        }
+        if let Some(home) = std::env::var_os("HOME") {
+            return Some(PathBuf::from(home).join(".cache").join("markless"));
+        }
+    }
+
+    None
+}
+
+/// The legacy-format override in the current directory.
+pub fn local_override_path() -> PathBuf {
+    PathBuf::from(".marklessrc")
+}
+
+/// The global config in use: `config.toml`, or the legacy file if only
+/// that exists.
+pub fn active_global_config_path() -> PathBuf {
+    let path = global_config_path();
+    let legacy = legacy_global_config_path();
+    if !path.exists() && legacy.is_file() {
+        legacy
+    } else {
+        path
+    }
+}
+
+/// Every config file that applies to documents in `dir`, lowest precedence
+/// first.
+///
+/// That is the global config, the legacy `.marklessrc` in the current
+/// directory, then `.marklessrc.toml` files from the outermost ancestor of
+/// `dir` down to `dir` itself. Only the global path may not exist.
+pub fn config_file_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![active_global_config_path()];
+    let legacy_local = local_override_path();
+    if legacy_local.is_file() {
+        paths.push(legacy_local);
+    }
+    for path in local_config_paths(dir) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// `.marklessrc.toml` files in `dir` and its ancestors, outermost first so
+/// that later (closer) files override earlier ones.
+pub fn local_config_paths(dir: &Path) -> Vec<PathBuf> {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let mut paths: Vec<PathBuf> = dir
+        .ancestors()
+        .map(|ancestor| ancestor.join(LOCAL_CONFIG_FILE))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.reverse();
+    paths
+}
+
+/// Split a string into tokens, respecting double-quoted segments.
+///
+/// Unquoted segments are split on whitespace. Double-quoted segments
+/// preserve interior spaces. Single quotes are not special.
+///
+/// # Examples
+///
+/// ```
+/// # use markless::config::shell_split_tokens;
+/// assert_eq!(shell_split_tokens(r#"--editor "emacsclient -t""#),
+///            vec!["--editor", "emacsclient -t"]);
+/// ```
+pub fn shell_split_tokens(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for ch in input.chars() {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if ch.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(ch);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Load configuration flags from a file at the given path.
+///
+/// `.toml` files are TOML. Other files may be TOML too, or use the legacy
+/// format of command-line flags followed by `[keys]` sections.
+///
+/// # Errors
+/// Returns an error if the config file exists but cannot be read or parsed.
+pub fn load_config_flags(path: &Path) -> Result<ConfigFlags> {
+    let Some(table) = read_config_table(path)? else {
+        return Ok(ConfigFlags::default());
+    };
+    flags_from_table(table).with_context(|| format!("Invalid config {}", path.display()))
+}
+
+/// Read a config file as a TOML table, converting the legacy format.
+/// Returns `None` if the file doesn't exist.
+fn read_config_table(path: &Path) -> Result<Option<toml::Table>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config {}", path.display()))?;
+    let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+    match content.parse::<toml::Table>() {
+        Ok(table) => Ok(Some(table)),
+        Err(err) if is_toml => {
+            Err(err).with_context(|| format!("Invalid config {}", path.display()))
+        }
+        Err(_) => Ok(Some(flags_to_table(&parse_legacy_config(&content, path)?))),
+    }
+}
+
+/// Settings from a parsed TOML config.
+///
+/// # Errors
+/// Returns an error for unknown keys or values of the wrong type.
+pub fn flags_from_table(mut table: toml::Table) -> Result<ConfigFlags> {
+    let keys = match table.remove("keys") {
+        Some(toml::Value::Table(keys)) => key_bindings_from_table(keys)?,
+        Some(_) => anyhow::bail!("`keys` must be a table"),
+        None => Vec::new(),
+    };
+    let mut flags: ConfigFlags = table.try_into()?;
+    flags.keys = keys;
+    Ok(flags)
+}
+
+/// The TOML form of `flags`, leaving out unset values.
+///
+/// # Panics
+/// Never in practice: every field serializes to a TOML value.
+pub fn flags_to_table(flags: &ConfigFlags) -> toml::Table {
+    let mut table = toml::Table::try_from(flags).expect("config flags always serialize to a table");
+    let mut keys = toml::Table::new();
+    for binding in &flags.keys {
+        let action = toml::Value::String(binding.action.clone());
+        if binding.context == "view" {
+            keys.insert(binding.keys.clone(), action);
+        } else if let toml::Value::Table(section) = keys
+            .entry(binding.context.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        {
+            section.insert(binding.keys.clone(), action);
+        }
+    }
+    if !keys.is_empty() {
+        table.insert("keys".to_string(), toml::Value::Table(keys));
+    }
+    table
+}
+
+/// `[keys]` holds view bindings directly and other contexts as sub-tables
+/// (`[keys.toc]`).
+fn key_bindings_from_table(keys: toml::Table) -> Result<Vec<KeyBinding>> {
+    let mut bindings = Vec::new();
+    for (name, value) in keys {
+        match value {
+            toml::Value::String(action) => bindings.push(KeyBinding {
+                context: "view".to_string(),
+                keys: name,
+                action,
+            }),
+            toml::Value::Table(section) => {
+                for (keys, action) in section {
+                    let toml::Value::String(action) = action else {
+                        anyhow::bail!("keys.{name}.{keys}: action must be a string");
+                    };
+                    bindings.push(KeyBinding {
+                        context: name.clone(),
+                        keys,
+                        action,
+                    });
+                }
+            }
+            _ => anyhow::bail!("keys.{name}: action must be a string"),
+        }
+    }
+    Ok(bindings)
+}
+
+/// Parse the legacy format: flags as on the command line, then optional
+/// `[keys]` / `[keys.<context>]` sections of `keys = "action"` lines.
+fn parse_legacy_config(content: &str, path: &Path) -> Result<ConfigFlags> {
+    let mut tokens = Vec::new();
+    let mut keys = Vec::new();
+    // Flags come first; a `[keys]` header switches to binding lines.
+    let mut key_context: Option<String> = None;
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            key_context = match section.trim() {
+                "keys" => Some("view".to_string()),
+                other => match other.strip_prefix("keys.") {
+                    Some(name) => Some(name.to_string()),
+                    None => anyhow::bail!(
+                        "Unknown section [{other}] in {} line {}",
+                        path.display(),
+                        idx + 1
+                    ),
+                },
+            };
+            continue;
+        }
+        match &key_context {
+            Some(section) => {
+                let (keys_text, action) = parse_key_binding_line(line).with_context(|| {
+                    format!(
+                        "Invalid key binding in {} line {}: {line}",
+                        path.display(),
+                        idx + 1
+                    )
+                })?;
+                keys.push(KeyBinding {
+                    context: section.clone(),
+                    keys: keys_text,
+                    action,
+                });
+            }
+            None => tokens.extend(shell_split_tokens(line)),
+        }
+    }
+    let mut flags = parse_flag_tokens(&tokens);
+    flags.keys = keys;
+    Ok(flags)
+}
+
+/// Split a `keys = "action"` line. Either side may be double-quoted, which
+/// is needed for keys like `"="` or `"#"`.
+fn parse_key_binding_line(line: &str) -> Option<(String, String)> {
+    let (keys, rest) = if let Some(quoted) = line.strip_prefix('"') {
+        let (keys, rest) = quoted.split_once('"')?;
+        (keys, rest.trim_start().strip_prefix('=')?)
+    } else {
+        let (keys, rest) = line.split_once('=')?;
+        (keys.trim_end(), rest)
+    };
+    let action = rest.trim();
+    let action = action
+        .strip_prefix('"')
+        .and_then(|a| a.strip_suffix('"'))
+        .unwrap_or(action);
+    if keys.is_empty() || action.is_empty() {
+        return None;
+    }
+    Some((keys.to_string(), action.to_string()))
+}
+
+/// Save configuration flags to a file at the given path, as TOML.
+///
+/// # Errors
+/// Returns an error if the config directory cannot be created or the file cannot be written.
+pub fn save_config_flags(path: &Path, flags: &ConfigFlags) -> Result<()> {
+    let body = toml::to_string(&flags_to_table(flags)).context("Failed to serialize config")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create config dir {}", parent.display()))?;
+    }
+    fs::write(
+        path,
+        format!("# markless defaults (saved with --save)\n{body}"),
+    )
+    .with_context(|| format!("Failed to write config {}", path.display()))
+}
+
+/// Remove the config file at the given path if it exists.
+///
+/// # Errors
+/// Returns an error if the file exists but cannot be removed.
+pub fn clear_config_flags(path: &Path) -> Result<()> {
+    if path.exists() {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub fn parse_flag_tokens(tokens: &[String]) -> ConfigFlags {
+    let mut flags = ConfigFlags::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        if token == "--watch" {
+            flags.watch = true;
+        } else if token == "--no-toc" {
+            flags.no_toc = true;
+        } else if token == "--toc" {
+            flags.toc = true;
+        } else if token == "--no-images" {
+            flags.no_images = true;
+        } else if token == "--perf" {
+            flags.perf = true;
+        } else if token == "--force-half-cell" {
+            flags.force_half_cell = true;
+            flags.image_mode = Some(ImageMode::Halfblock);
+        } else if token == "--image-mode" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.image_mode = parse_image_mode(next);
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--image-mode=") {
+            flags.image_mode = parse_image_mode(value);
+        } else if token == "--theme" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.theme = parse_theme(next);
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--theme=") {
+            flags.theme = parse_theme(value);
+        } else if token == "--theme-name" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.theme_name = Some(next.clone());
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--theme-name=") {
+            flags.theme_name = Some(value.to_string());
+        } else if token == "--code-theme" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.code_theme = Some(next.clone());
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--code-theme=") {
+            flags.code_theme = Some(value.to_string());
+        } else if token == "--render-debug-log" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.render_debug_log = Some(PathBuf::from(next));
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--render-debug-log=") {
+            flags.render_debug_log = Some(PathBuf::from(value));
+        } else if token == "--wrap-width" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.wrap_width = next.parse::<u16>().ok();
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--wrap-width=") {
+            flags.wrap_width = value.parse::<u16>().ok();
+        } else if token == "--editor" {
+            if let Some(next) = tokens.get(i + 1) {
+                flags.editor = Some(next.clone());
+                i += 1;
+            }
+        } else if let Some(value) = token.strip_prefix("--editor=") {
+            flags.editor = Some(value.to_string());
+        } else if token == "--no-editor" {
+            flags.editor = Some(String::new());
+        } else if token == "--vim" {
+            flags.vim = Some(true);
+        } else if token == "--no-vim" {
+            flags.vim = Some(false);
+        }
+        i += 1;
+    }
+    flags
+}
+
+fn parse_image_mode(s: &str) -> Option<ImageMode> {
+    match s {
+        "kitty" => Some(ImageMode::Kitty),
+        "sixel" => Some(ImageMode::Sixel),
+        "iterm2" => Some(ImageMode::ITerm2),
+        "halfblock" => Some(ImageMode::Halfblock),
+        _ => None,
+    }
+}
+
+fn parse_theme(s: &str) -> Option<ThemeMode> {
+    match s {
+        "auto" => Some(ThemeMode::Auto),
+        "light" => Some(ThemeMode::Light),
+        "dark" => Some(ThemeMode::Dark),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Merge `layers` the way startup does, later layers winning.
+    fn merge(layers: &[&ConfigFlags]) -> ConfigFlags {
+        let mut config = LayeredConfig::default();
+        for flags in layers {
+            config.push(ConfigLayer::from_flags(ConfigSource::CommandLine, flags));
+        }
+        config.flags().unwrap()
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_extracts_known_flags() {
+        let args = vec![
+            "markless".to_string(),
+            "--watch".to_string(),
+            "--toc".to_string(),
+            "--no-images".to_string(),
+            "--theme".to_string(),
+            "dark".to_string(),
+            "--render-debug-log=render.log".to_string(),
+            "--force-half-cell".to_string(),
+            "README.md".to_string(),
+        ];
+        let flags = parse_flag_tokens(&args);
+        assert!(flags.watch);
+        assert!(flags.toc);
+        assert!(flags.no_images);
+        assert_eq!(flags.theme, Some(ThemeMode::Dark));
+        assert_eq!(flags.render_debug_log, Some(PathBuf::from("render.log")));
+        assert!(flags.force_half_cell);
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_image_mode_kitty() {
+        let args = vec!["--image-mode".to_string(), "kitty".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.image_mode, Some(ImageMode::Kitty));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_image_mode_sixel() {
+        let args = vec!["--image-mode=sixel".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.image_mode, Some(ImageMode::Sixel));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_image_mode_iterm2() {
+        let args = vec!["--image-mode".to_string(), "iterm2".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.image_mode, Some(ImageMode::ITerm2));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_image_mode_halfblock() {
+        let args = vec!["--image-mode=halfblock".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.image_mode, Some(ImageMode::Halfblock));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_image_mode_invalid_ignored() {
+        let args = vec!["--image-mode".to_string(), "invalid".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.image_mode, None);
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_force_half_cell_sets_image_mode() {
+        let args = vec!["--force-half-cell".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.image_mode, Some(ImageMode::Halfblock));
+    }
+
+    #[test]
+    fn test_config_merge_image_mode_cli_overrides_file() {
+        let file = ConfigFlags {
+            image_mode: Some(ImageMode::Kitty),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags {
+            image_mode: Some(ImageMode::Sixel),
+            ..ConfigFlags::default()
+        };
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.image_mode, Some(ImageMode::Sixel));
+    }
+
+    #[test]
+    fn test_config_merge_image_mode_file_preserved_when_cli_none() {
+        let file = ConfigFlags {
+            image_mode: Some(ImageMode::ITerm2),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags::default();
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.image_mode, Some(ImageMode::ITerm2));
+    }
+
+    #[test]
+    fn test_save_load_image_mode_kitty() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            image_mode: Some(ImageMode::Kitty),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.image_mode, Some(ImageMode::Kitty));
+    }
+
+    #[test]
+    fn test_save_load_image_mode_sixel() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            image_mode: Some(ImageMode::Sixel),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.image_mode, Some(ImageMode::Sixel));
+    }
+
+    #[test]
+    fn test_save_load_image_mode_iterm2() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            image_mode: Some(ImageMode::ITerm2),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.image_mode, Some(ImageMode::ITerm2));
+    }
+
+    #[test]
+    fn test_save_load_image_mode_halfblock() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            image_mode: Some(ImageMode::Halfblock),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.image_mode, Some(ImageMode::Halfblock));
+    }
+
+    #[test]
+    fn test_config_merge_merges_cli_over_file_for_options() {
+        let file = ConfigFlags {
+            watch: true,
+            theme: Some(ThemeMode::Light),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags {
+            toc: true,
+            theme: Some(ThemeMode::Dark),
+            ..ConfigFlags::default()
+        };
+        let merged = merge(&[&file, &cli]);
+        assert!(merged.watch);
+        assert!(merged.toc);
+        assert_eq!(merged.theme, Some(ThemeMode::Dark));
+    }
+
+    #[test]
+    fn test_save_load_and_clear_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            watch: true,
+            toc: true,
+            no_images: true,
+            perf: true,
+            force_half_cell: true,
+            theme: Some(ThemeMode::Dark),
+            render_debug_log: Some(PathBuf::from("render.log")),
+            ..ConfigFlags::default()
+        };
+
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.watch, true);
+        assert_eq!(loaded.toc, true);
+        assert_eq!(loaded.no_images, true);
+        assert_eq!(loaded.perf, true);
+        assert_eq!(loaded.force_half_cell, true);
+        assert_eq!(loaded.theme, Some(ThemeMode::Dark));
+        assert_eq!(loaded.render_debug_log, Some(PathBuf::from("render.log")));
+
+        clear_config_flags(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_wrap_width_space() {
+        let args = vec!["--wrap-width".to_string(), "60".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.wrap_width, Some(60));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_wrap_width_equals() {
+        let args = vec!["--wrap-width=100".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.wrap_width, Some(100));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_wrap_width_invalid_ignored() {
+        let args = vec!["--wrap-width".to_string(), "notanumber".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.wrap_width, None);
+    }
+
+    #[test]
+    fn test_config_merge_wrap_width_cli_overrides_file() {
+        let file = ConfigFlags {
+            wrap_width: Some(80),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags {
+            wrap_width: Some(120),
+            ..ConfigFlags::default()
+        };
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.wrap_width, Some(120));
+    }
+
+    #[test]
+    fn test_config_merge_wrap_width_file_preserved_when_cli_none() {
+        let file = ConfigFlags {
+            wrap_width: Some(60),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags::default();
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.wrap_width, Some(60));
+    }
+
+    #[test]
+    fn test_save_load_wrap_width() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            wrap_width: Some(72),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.wrap_width, Some(72));
+    }
+
+    // --- shell_split_tokens tests ---
+
+    #[test]
+    fn test_shell_split_tokens_simple_words() {
+        let tokens = shell_split_tokens("hello world");
+        assert_eq!(tokens, vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_shell_split_tokens_double_quoted_preserves_spaces() {
+        let tokens = shell_split_tokens(r#""emacsclient -t""#);
+        assert_eq!(tokens, vec!["emacsclient -t"]);
+    }
+
+    #[test]
+    fn test_shell_split_tokens_mixed_quoted_and_unquoted() {
+        let tokens = shell_split_tokens(r#"--editor "emacsclient -t" --watch"#);
+        assert_eq!(tokens, vec!["--editor", "emacsclient -t", "--watch"]);
+    }
+
+    #[test]
+    fn test_shell_split_tokens_empty_input() {
+        let tokens = shell_split_tokens("");
+        assert!(tokens.is_empty());
+    }
+
+    #[test]
+    fn test_shell_split_tokens_whitespace_only() {
+        let tokens = shell_split_tokens("   ");
+        assert!(tokens.is_empty());
+    }
+
+    #[test]
+    fn test_shell_split_tokens_adjacent_quotes() {
+        let tokens = shell_split_tokens(r#""hello""world""#);
+        assert_eq!(tokens, vec!["helloworld"]);
+    }
+
+    #[test]
+    fn test_shell_split_tokens_single_token() {
+        let tokens = shell_split_tokens("hx");
+        assert_eq!(tokens, vec!["hx"]);
+    }
+
+    // --- editor flag parsing tests ---
+
+    #[test]
+    fn test_parse_flag_tokens_editor_space() {
+        let args = vec!["--editor".to_string(), "hx".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.editor, Some("hx".to_string()));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_editor_equals() {
+        let args = vec!["--editor=vim".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.editor, Some("vim".to_string()));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_no_editor() {
+        let args = vec!["--no-editor".to_string()];
+        let flags = parse_flag_tokens(&args);
+        assert_eq!(flags.editor, Some(String::new()));
+    }
+
+    #[test]
+    fn test_config_merge_editor_cli_overrides_file() {
+        let file = ConfigFlags {
+            editor: Some("hx".to_string()),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags {
+            editor: Some("vim".to_string()),
+            ..ConfigFlags::default()
+        };
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.editor, Some("vim".to_string()));
+    }
+
+    #[test]
+    fn test_config_merge_editor_file_preserved_when_cli_none() {
+        let file = ConfigFlags {
+            editor: Some("hx".to_string()),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags::default();
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.editor, Some("hx".to_string()));
+    }
+
+    #[test]
+    fn test_config_merge_no_editor_clears_file_setting() {
+        let file = ConfigFlags {
+            editor: Some("hx".to_string()),
+            ..ConfigFlags::default()
+        };
+        let cli = ConfigFlags {
+            editor: Some(String::new()),
+            ..ConfigFlags::default()
+        };
+        let merged = merge(&[&file, &cli]);
+        assert_eq!(merged.editor, Some(String::new()));
+    }
+
+    #[test]
+    fn test_save_load_editor_single_word() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            editor: Some("hx".to_string()),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.editor, Some("hx".to_string()));
+    }
+
+    #[test]
+    fn test_save_load_editor_multi_word() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            editor: Some("emacsclient -t".to_string()),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.editor, Some("emacsclient -t".to_string()));
+    }
+
+    #[test]
+    fn test_save_load_no_editor_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let flags = ConfigFlags {
+            editor: Some(String::new()),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.editor, Some(String::new()));
+    }
+
+    #[test]
+    fn test_save_load_vim_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        assert_eq!(parse_flag_tokens(&["--vim".to_string()]).vim, Some(true));
+        let flags = ConfigFlags {
+            vim: Some(true),
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert_eq!(loaded.vim, Some(true));
+    }
+
+    #[test]
+    fn test_no_vim_overrides_earlier_vim() {
+        let global = parse_flag_tokens(&["--vim".to_string()]);
+        let local = parse_flag_tokens(&["--no-vim".to_string()]);
+        assert_eq!(local.vim, Some(false));
+        assert_eq!(merge(&[&global, &local]).vim, Some(false));
+        assert_eq!(merge(&[&global, &ConfigFlags::default()]).vim, Some(true));
+        assert_eq!(merge(&[&local, &global]).vim, Some(true));
+    }
+
+    #[test]
+    fn test_load_config_key_sections() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        fs::write(
+            &path,
+            "--watch\n[keys]\ngg = \"go_to_top\"\n\"?\" = toggle_help\n\n[keys.toc]\n# comment\nSpace = \"toc_select\"\n",
+        )
+        .unwrap();
+        let loaded = load_config_flags(&path).unwrap();
+        assert!(loaded.watch);
+        let keys: Vec<_> = loaded
+            .keys
+            .iter()
+            .map(|b| (b.context.as_str(), b.keys.as_str(), b.action.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("view", "gg", "go_to_top"),
+                ("view", "?", "toggle_help"),
+                ("toc", "Space", "toc_select"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_config_rejects_malformed_key_line() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        fs::write(&path, "[keys]\ngg go_to_top\n").unwrap();
+        let err = format!("{:#}", load_config_flags(&path).unwrap_err());
+        assert!(err.contains("line 2"), "{err}");
+
+        fs::write(&path, "[colours]\n").unwrap();
+        assert!(load_config_flags(&path).is_err());
+    }
+
+    #[test]
+    fn test_save_load_key_bindings_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc");
+        let binding = |context: &str, keys: &str, action: &str| KeyBinding {
+            context: context.to_string(),
+            keys: keys.to_string(),
+            action: action.to_string(),
+        };
+        let flags = ConfigFlags {
+            toc: true,
+            keys: vec![
+                binding("view", "Ctrl-w j", "scroll_down"),
+                binding("view", "=", "page_down"),
+                binding("toc", "x", "none"),
+            ],
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        assert_eq!(load_config_flags(&path).unwrap(), flags);
+    }
+
+    #[test]
+    fn test_merge_local_key_binding_wins() {
+        let binding = |action: &str| KeyBinding {
+            context: "view".to_string(),
+            keys: "x".to_string(),
+            action: action.to_string(),
+        };
+        let global = ConfigFlags {
+            keys: vec![binding("quit")],
+            ..ConfigFlags::default()
+        };
+        let local = ConfigFlags {
+            keys: vec![binding("toggle_help")],
+            ..ConfigFlags::default()
+        };
+        let merged = merge(&[&global, &local]);
+        assert_eq!(merged.keys, vec![binding("toggle_help")]);
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_theme_name() {
+        let tokens = vec!["--theme-name".to_string(), "nord".to_string()];
+        assert_eq!(
+            parse_flag_tokens(&tokens).theme_name.as_deref(),
+            Some("nord")
+        );
+        let tokens = vec![
+            "--theme-name=solarized".to_string(),
+            "--theme=dark".to_string(),
+        ];
+        let flags = parse_flag_tokens(&tokens);
+        assert_eq!(flags.theme_name.as_deref(), Some("solarized"));
+        assert_eq!(flags.theme, Some(ThemeMode::Dark));
+    }
+
+    #[test]
+    fn test_parse_flag_tokens_code_theme() {
+        let tokens = vec!["--code-theme".to_string(), "Dracula".to_string()];
+        assert_eq!(
+            parse_flag_tokens(&tokens).code_theme.as_deref(),
+            Some("Dracula")
+        );
+        let tokens = vec!["--code-theme=base16-ocean.dark".to_string()];
+        assert_eq!(
+            parse_flag_tokens(&tokens).code_theme.as_deref(),
+            Some("base16-ocean.dark")
+        );
+    }
+
+    #[test]
+    fn test_save_writes_toml_sections() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let flags = ConfigFlags {
+            watch: true,
+            wrap_width: Some(72),
+            colors: BTreeMap::from([("h1".to_string(), "#ff8800".to_string())]),
+            images: ImageLimits {
+                max_width: Some(60),
+                max_height: None,
+            },
+            mermaid: MermaidOptions {
+                enabled: Some(false),
+                width_percent: None,
+            },
+            browse: BrowseFilters {
+                show_hidden: Some(true),
+                exclude: vec!["target".to_string()],
+            },
+            ..ConfigFlags::default()
+        };
+        save_config_flags(&path, &flags).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("watch = true"), "{content}");
+        assert!(content.contains("[colors]"), "{content}");
+        assert!(content.contains("[browse]"), "{content}");
+        assert!(!content.contains("max_height"), "{content}");
+        assert_eq!(load_config_flags(&path).unwrap(), flags);
+    }
+
+    #[test]
+    fn test_legacy_config_still_loads() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config");
+        fs::write(&path, "--watch\n--wrap-width 80\n[keys]\ngg = go_to_top\n").unwrap();
+        let flags = load_config_flags(&path).unwrap();
+        assert!(flags.watch);
+        assert_eq!(flags.wrap_width, Some(80));
+        assert_eq!(flags.keys.len(), 1);
+    }
+
+    #[test]
+    fn test_toml_config_rejects_invalid_toml() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".marklessrc.toml");
+        fs::write(&path, "--watch\n").unwrap();
+        assert!(load_config_flags(&path).is_err());
+    }
+
+    #[test]
+    fn test_local_config_paths_outermost_first() {
+        let dir = tempdir().unwrap();
+        let nested = dir.path().join("docs/guide");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join(LOCAL_CONFIG_FILE), "").unwrap();
+        fs::write(nested.join(LOCAL_CONFIG_FILE), "").unwrap();
+        let paths = local_config_paths(&nested);
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                root.join(LOCAL_CONFIG_FILE),
+                root.join("docs/guide").join(LOCAL_CONFIG_FILE),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_browse_filters_hide_dotfiles_and_excluded_names() {
+        let filters = BrowseFilters {
+            show_hidden: None,
+            exclude: vec!["*.lock".to_string(), "node_?odules".to_string()],
+        };
+        assert!(filters.hides(".git"));
+        assert!(filters.hides("Cargo.lock"));
+        assert!(filters.hides("node_modules"));
+        assert!(!filters.hides("README.md"));
+
+        let filters = BrowseFilters {
+            show_hidden: Some(true),
+            exclude: Vec::new(),
+        };
+        assert!(!filters.hides(".github"));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("a*b*c", "aXXbYYc"));
+        assert!(glob_matches("draft-?.md", "draft-1.md"));
+        assert!(!glob_matches("draft-?.md", "draft-10.md"));
+        assert!(!glob_matches("*.md", "notes.txt"));
+    }
+
+    #[test]
+    fn test_shell_split_tokens_unclosed_quote_treats_rest_as_one_token() {
+        let tokens = shell_split_tokens(r#"hello "world foo"#);
+        assert_eq!(tokens, vec!["hello", "world foo"]);
+    }
+
+    #[test]
+    fn test_shell_split_tokens_empty_string_guard() {
+        let tokens = shell_split_tokens("");
+        assert!(tokens.is_empty());
+        // Simulates what launch_external_editor does: first token would be None
+        assert!(tokens.first().is_none());
+    }
+
+    #[test]
+    fn test_shell_split_tokens_whitespace_only_guard() {
+        let tokens = shell_split_tokens("   ");
+        assert!(tokens.is_empty());
+        assert!(tokens.first().is_none());
+    }
+}
@@ -0,0 +1,331 @@
+//! Front matter metadata.
+//!
+//! Parses the YAML (`---`) or TOML (`+++`) block at the start of a document
+//! into a flat list of key/value pairs for display. This is deliberately a
+//! shallow reader: TOML tables become `table.key` keys, a nested YAML map is
+//! joined into its parent's value as `key: value, ...`, and lists are joined
+//! with commas, which is all the metadata panel needs.
+
+/// Syntax of a front matter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// `---` delimited YAML
+    Yaml,
+    /// `+++` delimited TOML
+    Toml,
+}
+
+impl FrontMatterFormat {
+    /// Short display name.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Yaml => "YAML",
+            Self::Toml => "TOML",
+        }
+    }
+
+    /// Delimiter line that opens and closes the block.
+    pub const fn delimiter(self) -> &'static str {
+        match self {
+            Self::Yaml => "---",
+            Self::Toml => "+++",
+        }
+    }
+}
+
+/// Structured front matter extracted from a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontMatter {
+    format: FrontMatterFormat,
+    entries: Vec<(String, String)>,
+}
+
+impl FrontMatter {
+    /// Parse a raw front matter block, including its delimiter lines.
+    ///
+    /// Returns `None` if the block does not start with a known delimiter or
+    /// has no entries, as when a document merely opens with a `---` rule.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut lines = raw.lines();
+        let format = match lines.next()?.trim_end() {
+            "---" => FrontMatterFormat::Yaml,
+            "+++" => FrontMatterFormat::Toml,
+            _ => return None,
+        };
+        let delimiter = format.delimiter();
+        let body: Vec<&str> = lines.take_while(|l| l.trim_end() != delimiter).collect();
+        let entries = match format {
+            FrontMatterFormat::Yaml => parse_yaml(&body),
+            FrontMatterFormat::Toml => parse_toml(&body),
+        };
+        (!entries.is_empty()).then_some(Self { format, entries })
+    }
+
+    /// The block syntax.
+    pub const fn format(&self) -> FrontMatterFormat {
+        self.format
+    }
+
+    /// All key/value pairs in source order.
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    /// Look up a value by key (case-insensitive).
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The document title, if one is set and non-empty.
+    pub fn title(&self) -> Option<&str> {
+        self.get("title").filter(|t| !t.is_empty())
+    }
+}
+
+/// The front matter delimiter comrak should look for in `source`, or `None`
+/// when the document does not open with a block [`FrontMatter::parse`]
+/// accepts, so that a leading `---` rule renders as markdown.
+pub fn delimiter_for(source: &str) -> Option<&'static str> {
+    FrontMatter::parse(source).map(|front_matter| front_matter.format().delimiter())
+}
+
+fn parse_yaml(lines: &[&str]) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    // Values collected from indented lines below a `key:` with no inline value.
+    let mut nested: Vec<String> = Vec::new();
+    let mut block_scalar = false;
+
+    let flush = |entries: &mut Vec<(String, String)>, nested: &mut Vec<String>, block: bool| {
+        if nested.is_empty() {
+            return;
+        }
+        if let Some((_, value)) = entries.last_mut() {
+            *value = nested.join(if block { " " } else { ", " });
+        }
+        nested.clear();
+    };
+
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indented = line.starts_with([' ', '\t']);
+        if indented || trimmed.starts_with("- ") || trimmed == "-" {
+            if entries.is_empty() {
+                continue;
+            }
+            if block_scalar {
+                nested.push(trimmed.to_string());
+            } else if let Some(item) = trimmed.strip_prefix('-') {
+                nested.push(unquote(item.trim()).to_string());
+            } else if let Some((k, v)) = trimmed.split_once(':') {
+                let v = yaml_value(v.trim());
+                if v.is_empty() {
+                    nested.push(k.trim().to_string());
+                } else {
+                    nested.push(format!("{}: {v}", k.trim()));
+                }
+            } else {
+                nested.push(trimmed.to_string());
+            }
+            continue;
+        }
+
+        flush(&mut entries, &mut nested, block_scalar);
+        block_scalar = false;
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        block_scalar = matches!(value, "|" | ">" | "|-" | ">-" | "|+" | ">+");
+        let value = if block_scalar {
+            String::new()
+        } else {
+            yaml_value(value)
+        };
+        entries.push((unquote(key.trim()).to_string(), value));
+    }
+    flush(&mut entries, &mut nested, block_scalar);
+    entries
+}
+
+fn yaml_value(value: &str) -> String {
+    let value = strip_trailing_comment(value);
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return join_inline_list(inner);
+    }
+    unquote(value).to_string()
+}
+
+fn parse_toml(lines: &[&str]) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut table = String::new();
+    // A multi-line array being accumulated: (key, text so far).
+    let mut pending: Option<(String, String)> = None;
+
+    for line in lines {
+        let trimmed = line.trim();
+        if let Some((key, mut text)) = pending.take() {
+            text.push(' ');
+            text.push_str(strip_trailing_comment(trimmed));
+            if text.trim_end().ends_with(']') {
+                entries.push((key, toml_value(&text)));
+            } else {
+                pending = Some((key, text));
+            }
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            table = name.trim_matches(['[', ']']).trim().to_string();
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let key = unquote(key.trim());
+        let key = if table.is_empty() {
+            key.to_string()
+        } else {
+            format!("{table}.{key}")
+        };
+        let value = strip_trailing_comment(value.trim());
+        if value.starts_with('[') && !value.ends_with(']') {
+            pending = Some((key, value.to_string()));
+            continue;
+        }
+        entries.push((key, toml_value(value)));
+    }
+    if let Some((key, text)) = pending {
+        entries.push((key, toml_value(&text)));
+    }
+    entries
+}
+
+fn toml_value(value: &str) -> String {
+    let value = value.trim();
+    if let Some(inner) = value
+        .strip_prefix('[')
+        .map(|v| v.strip_suffix(']').unwrap_or(v))
+    {
+        return join_inline_list(inner);
+    }
+    unquote(value).to_string()
+}
+
+fn join_inline_list(inner: &str) -> String {
+    inner
+        .split(',')
+        .map(|item| unquote(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+/// Drop a ` # comment` suffix that is not inside quotes.
+fn strip_trailing_comment(value: &str) -> &str {
+    let mut in_quote: Option<char> = None;
+    let mut prev_space = true;
+    for (idx, ch) in value.char_indices() {
+        match (in_quote, ch) {
+            (None, '"' | '\'') => in_quote = Some(ch),
+            (Some(q), c) if c == q => in_quote = None,
+            (None, '#') if prev_space => return value[..idx].trim_end(),
+            _ => {}
+        }
+        prev_space = ch.is_whitespace();
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_scalars() {
+        let fm = FrontMatter::parse("---\ntitle: \"My Doc\"\nauthor: Jane\ndraft: false\n---\n")
+            .unwrap();
+        assert_eq!(fm.format(), FrontMatterFormat::Yaml);
+        assert_eq!(fm.title(), Some("My Doc"));
+        assert_eq!(fm.get("author"), Some("Jane"));
+        assert_eq!(fm.get("draft"), Some("false"));
+    }
+
+    #[test]
+    fn test_parse_yaml_lists() {
+        let fm = FrontMatter::parse("---\ntags: [rust, 'cli']\naliases:\n  - one\n  - two\n---\n")
+            .unwrap();
+        assert_eq!(fm.get("tags"), Some("rust, cli"));
+        assert_eq!(fm.get("aliases"), Some("one, two"));
+    }
+
+    #[test]
+    fn test_parse_yaml_nested_map_and_block_scalar() {
+        let raw = "---\nauthor:\n  name: Jane\n  email: j@x.io\nsummary: >\n  first line\n  second line\n---\n";
+        let fm = FrontMatter::parse(raw).unwrap();
+        assert_eq!(fm.get("author"), Some("name: Jane, email: j@x.io"));
+        assert_eq!(fm.get("summary"), Some("first line second line"));
+    }
+
+    #[test]
+    fn test_parse_yaml_ignores_comments() {
+        let fm = FrontMatter::parse("---\n# note\nstatus: accepted # ADR\n---\n").unwrap();
+        assert_eq!(
+            fm.entries(),
+            &[("status".to_string(), "accepted".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let raw = "+++\ntitle = 'Hugo Post'\ndate = 2024-01-02\ntags = [\"a\", \"b\"]\n\n[params]\nauthor = \"Jane\"\n+++\n";
+        let fm = FrontMatter::parse(raw).unwrap();
+        assert_eq!(fm.format(), FrontMatterFormat::Toml);
+        assert_eq!(fm.title(), Some("Hugo Post"));
+        assert_eq!(fm.get("date"), Some("2024-01-02"));
+        assert_eq!(fm.get("tags"), Some("a, b"));
+        assert_eq!(fm.get("params.author"), Some("Jane"));
+    }
+
+    #[test]
+    fn test_parse_toml_multiline_array() {
+        let raw = "+++\nkeywords = [\n  \"x\",\n  \"y\",\n]\n+++\n";
+        let fm = FrontMatter::parse(raw).unwrap();
+        assert_eq!(fm.get("keywords"), Some("x, y"));
+    }
+
+    #[test]
+    fn test_empty_title_is_none() {
+        let fm = FrontMatter::parse("---\ntitle: \"\"\n---\n").unwrap();
+        assert_eq!(fm.title(), None);
+    }
+
+    #[test]
+    fn test_unknown_delimiter_is_rejected() {
+        assert!(FrontMatter::parse("===\na: b\n===\n").is_none());
+    }
+
+    #[test]
+    fn test_delimiter_for_source() {
+        assert_eq!(delimiter_for("+++\ntitle = 'x'\n+++\n"), Some("+++"));
+        assert_eq!(delimiter_for("---\ntitle: x\n---\n"), Some("---"));
+        assert_eq!(delimiter_for("# Heading"), None);
+        assert_eq!(delimiter_for("---\n\nJust text\n\n---\n"), None);
+    }
+}
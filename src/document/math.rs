@@ -0,0 +1,1297 @@
+//! LaTeX math rendering.
+//!
+//! Parses the subset of TeX that shows up in everyday documents (Greek
+//! letters, operators, fractions, roots, sub/superscripts, accents) into a
+//! small atom tree. The tree is either flattened to Unicode for inline use
+//! and terminal fallback, or laid out as SVG so display math can be
+//! rasterized like mermaid diagrams.
+
+use std::fmt::Write;
+
+use super::parser::map_script_chars;
+
+/// Render TeX as a single line of Unicode text.
+pub fn tex_to_unicode(tex: &str) -> String {
+    tex_to_unicode_lines(tex).join(" ")
+}
+
+/// Render TeX as Unicode, one string per `\\`-separated row.
+pub fn tex_to_unicode_lines(tex: &str) -> Vec<String> {
+    parse_rows(tex)
+        .iter()
+        .map(|row| {
+            let mut out = String::new();
+            write_unicode(row, false, &mut out);
+            out.trim().to_string()
+        })
+        .filter(|row| !row.is_empty())
+        .collect()
+}
+
+/// Lay out TeX as an SVG document.
+///
+/// `font_px` is the em size in pixels. The canvas is at least
+/// `min_width_px` wide with the formula centered, so the image can be
+/// scaled to the viewport width without magnifying the glyphs.
+pub fn tex_to_svg(tex: &str, font_px: f64, min_width_px: u32, color: &str) -> String {
+    let rows: Vec<Layout> = parse_rows(tex)
+        .iter()
+        .map(|row| layout_list(row, font_px, false))
+        .collect();
+    let pad = font_px * 0.5;
+    let row_gap = font_px * 0.3;
+    let content_width = rows.iter().map(|r| r.width).fold(0.0, f64::max);
+    let width = 2.0f64
+        .mul_add(pad, content_width)
+        .max(f64::from(min_width_px));
+    let content_height: f64 = rows.iter().map(|r| r.ascent + r.descent).sum::<f64>()
+        + rows.iter().skip(1).map(|_| row_gap).sum::<f64>();
+    let height = 2.0f64.mul_add(pad, content_height);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" viewBox=\"0 0 {width:.2} {height:.2}\">"
+    );
+    let _ = write!(
+        svg,
+        "<g fill=\"{color}\" stroke=\"{color}\" font-family=\"{FONT_FAMILY}\">"
+    );
+    let mut y = pad;
+    for row in &rows {
+        let x = (width - row.width) / 2.0;
+        let baseline = y + row.ascent;
+        for item in &row.items {
+            item.write_svg(&mut svg, x, baseline);
+        }
+        y = baseline + row.descent + row_gap;
+    }
+    svg.push_str("</g></svg>");
+    svg
+}
+
+const FONT_FAMILY: &str =
+    "'STIX Two Math', 'Latin Modern Math', 'Cambria Math', 'DejaVu Serif', serif";
+
+/// One parsed unit of a formula.
+#[derive(Debug, Clone)]
+enum Atom {
+    /// A literal character (variables, digits, punctuation)
+    Char(char),
+    /// An ordinary symbol produced by a command (`\alpha`, `\infty`)
+    Ord(String),
+    /// An upright operator name (`\sin`, `\log`)
+    Op(String),
+    /// Literal text from `\text{...}`
+    Text(String),
+    /// A large operator (`\sum`, `\int`)
+    Large(String),
+    /// A binary operator (`+`, `\times`)
+    Bin(String),
+    /// A relation (`=`, `\leq`, `\to`)
+    Rel(String),
+    /// Horizontal space in ems (may be negative)
+    Space(f64),
+    Group(Vec<Self>),
+    Frac {
+        num: Vec<Self>,
+        den: Vec<Self>,
+        bar: bool,
+    },
+    Sqrt {
+        index: Vec<Self>,
+        body: Vec<Self>,
+    },
+    /// Base with optional (empty = absent) sub- and superscript
+    Scripts {
+        base: Box<Self>,
+        sub: Vec<Self>,
+        sup: Vec<Self>,
+    },
+    Accent {
+        mark: Accent,
+        body: Box<Self>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Accent {
+    Hat,
+    Bar,
+    Vec,
+    Dot,
+    Ddot,
+    Tilde,
+}
+
+impl Accent {
+    const fn combining(self) -> char {
+        match self {
+            Self::Hat => '\u{0302}',
+            Self::Bar => '\u{0305}',
+            Self::Vec => '\u{20D7}',
+            Self::Dot => '\u{0307}',
+            Self::Ddot => '\u{0308}',
+            Self::Tilde => '\u{0303}',
+        }
+    }
+
+    const fn glyph(self) -> Option<&'static str> {
+        match self {
+            Self::Hat => Some("ˆ"),
+            Self::Dot => Some("˙"),
+            Self::Ddot => Some("¨"),
+            Self::Tilde => Some("˜"),
+            Self::Bar | Self::Vec => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// Deepest nesting of groups, arguments and scripts that is rendered.
+/// Deeper formulas are shown as their TeX source instead, so hostile input
+/// cannot exhaust the stack.
+const MAX_DEPTH: usize = 64;
+
+fn parse_rows(tex: &str) -> Vec<Vec<Atom>> {
+    let mut parser = Parser::new(tex, 0);
+    let rows = parser.parse_rows();
+    if parser.too_deep
+        || rows
+            .iter()
+            .flatten()
+            .any(|atom| nesting_exceeds(atom, MAX_DEPTH))
+    {
+        return vec![vec![Atom::Text(tex.trim().to_string())]];
+    }
+    rows
+}
+
+/// Whether `atom` nests more than `limit` levels deep.
+fn nesting_exceeds(atom: &Atom, limit: usize) -> bool {
+    let Some(left) = limit.checked_sub(1) else {
+        return true;
+    };
+    let any = |atoms: &[Atom]| atoms.iter().any(|atom| nesting_exceeds(atom, left));
+    match atom {
+        Atom::Group(inner) => any(inner),
+        Atom::Frac { num, den, .. } => any(num) || any(den),
+        Atom::Sqrt { index, body } => any(index) || any(body),
+        Atom::Scripts { base, sub, sup } => nesting_exceeds(base, left) || any(sub) || any(sup),
+        Atom::Accent { body, .. } => nesting_exceeds(body, left),
+        _ => false,
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Set when a top-level list stopped at `\\`
+    row_break: bool,
+    /// Current nesting of lists and atoms.
+    depth: usize,
+    /// Set once nesting passed [`MAX_DEPTH`]; the rest of the input is skipped.
+    too_deep: bool,
+}
+
+impl Parser {
+    fn new(tex: &str, depth: usize) -> Self {
+        Self {
+            chars: tex.chars().collect(),
+            pos: 0,
+            row_break: false,
+            depth,
+            too_deep: false,
+        }
+    }
+
+    fn parse_rows(&mut self) -> Vec<Vec<Atom>> {
+        let mut rows = Vec::new();
+        loop {
+            let row = self.parse_list(true);
+            if !row.is_empty() {
+                rows.push(row);
+            }
+            if !self.row_break {
+                break;
+            }
+            self.row_break = false;
+        }
+        rows
+    }
+
+    /// Enter one more level of nesting, or give up on the rest of the input
+    /// when that would pass [`MAX_DEPTH`].
+    const fn descend(&mut self) -> bool {
+        if self.depth >= MAX_DEPTH {
+            self.too_deep = true;
+            self.pos = self.chars.len();
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse atoms until the end of input, a closing brace (consumed), or
+    /// (at top level) a `\\` row break.
+    fn parse_list(&mut self, top: bool) -> Vec<Atom> {
+        if !self.descend() {
+            return Vec::new();
+        }
+        let atoms = self.parse_list_items(top);
+        self.depth -= 1;
+        atoms
+    }
+
+    fn parse_list_items(&mut self, top: bool) -> Vec<Atom> {
+        let mut atoms: Vec<Atom> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(ch) = self.peek() else {
+                return atoms;
+            };
+            match ch {
+                '}' => {
+                    self.pos += 1;
+                    if !top {
+                        return atoms;
+                    }
+                }
+                '^' | '_' => {
+                    self.pos += 1;
+                    let arg = self.parse_arg();
+                    let base = atoms.pop().unwrap_or(Atom::Group(Vec::new()));
+                    atoms.push(attach_script(base, arg, ch == '^'));
+                }
+                '\\' if self.chars.get(self.pos + 1) == Some(&'\\') => {
+                    self.pos += 2;
+                    if top {
+                        self.row_break = true;
+                        return atoms;
+                    }
+                    atoms.push(Atom::Space(0.5));
+                }
+                '&' => self.pos += 1,
+                _ => {
+                    if let Some(atom) = self.parse_atom() {
+                        atoms.push(atom);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parse the argument of a command or script: a braced group, a single
+    /// command, or a single character.
+    fn parse_arg(&mut self) -> Vec<Atom> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                self.parse_list(false)
+            }
+            Some(_) => self.parse_atom().into_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Read a braced argument verbatim (for `\text{...}` and friends).
+    fn parse_raw_arg(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return self
+                .peek()
+                .map(|c| {
+                    self.pos += 1;
+                    c.to_string()
+                })
+                .unwrap_or_default();
+        }
+        self.pos += 1;
+        let mut depth = 1;
+        let mut text = String::new();
+        while let Some(ch) = self.peek() {
+            self.pos += 1;
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            text.push(ch);
+        }
+        text
+    }
+
+    fn parse_atom(&mut self) -> Option<Atom> {
+        if !self.descend() {
+            return None;
+        }
+        let atom = self.parse_atom_inner();
+        self.depth -= 1;
+        atom
+    }
+
+    fn parse_atom_inner(&mut self) -> Option<Atom> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        Some(match ch {
+            '{' => Atom::Group(self.parse_list(false)),
+            '\\' => return self.parse_command(),
+            '+' | '-' => Atom::Bin(ch.to_string()),
+            '*' => Atom::Bin("∗".to_string()),
+            '=' | '<' | '>' => Atom::Rel(ch.to_string()),
+            '\'' => Atom::Ord("′".to_string()),
+            '~' => Atom::Space(0.3),
+            _ => Atom::Char(ch),
+        })
+    }
+
+    fn parse_command(&mut self) -> Option<Atom> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            // Control symbol: a single non-letter character.
+            let ch = self.peek()?;
+            self.pos += 1;
+            return Some(match ch {
+                ',' => Atom::Space(0.17),
+                ':' | '>' => Atom::Space(0.22),
+                ';' => Atom::Space(0.28),
+                '!' => Atom::Space(-0.17),
+                ' ' => Atom::Space(0.3),
+                '|' => Atom::Ord("‖".to_string()),
+                _ => Atom::Char(ch),
+            });
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+
+        if let Some((symbol, class)) = symbol(&name) {
+            let symbol = symbol.to_string();
+            return Some(match class {
+                Class::Ord => Atom::Ord(symbol),
+                Class::Bin => Atom::Bin(symbol),
+                Class::Rel => Atom::Rel(symbol),
+                Class::Large => Atom::Large(symbol),
+            });
+        }
+        if is_operator_name(&name) {
+            return Some(Atom::Op(name));
+        }
+        if let Some(mark) = accent(&name) {
+            let body = self.parse_arg();
+            return Some(Atom::Accent {
+                mark,
+                body: Box::new(Atom::Group(body)),
+            });
+        }
+
+        Some(match name.as_str() {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => Atom::Frac {
+                num: self.parse_arg(),
+                den: self.parse_arg(),
+                bar: true,
+            },
+            "binom" | "dbinom" | "tbinom" => Atom::Group(vec![
+                Atom::Char('('),
+                Atom::Frac {
+                    num: self.parse_arg(),
+                    den: self.parse_arg(),
+                    bar: false,
+                },
+                Atom::Char(')'),
+            ]),
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let close = self.chars[self.pos..]
+                        .iter()
+                        .position(|&c| c == ']')
+                        .map_or(self.chars.len(), |i| self.pos + i);
+                    let inner: String = self.chars[self.pos..close].iter().collect();
+                    self.pos = (close + 1).min(self.chars.len());
+                    let mut index = Self::new(&inner, self.depth);
+                    let rows = index.parse_rows();
+                    if index.too_deep {
+                        self.too_deep = true;
+                        self.pos = self.chars.len();
+                    }
+                    rows.into_iter().flatten().collect()
+                } else {
+                    Vec::new()
+                };
+                Atom::Sqrt {
+                    index,
+                    body: self.parse_arg(),
+                }
+            }
+            "text" | "textrm" | "textit" | "textbf" | "textsf" | "texttt" | "mbox" => {
+                Atom::Text(self.parse_raw_arg())
+            }
+            "operatorname" => Atom::Op(self.parse_raw_arg()),
+            "mathbb" => Atom::Ord(self.parse_raw_arg().chars().map(double_struck).collect()),
+            "mathrm" | "mathbf" | "mathit" | "mathsf" | "mathtt" | "mathcal" | "mathfrak"
+            | "mathscr" | "boldsymbol" | "bm" => Atom::Group(self.parse_arg()),
+            "left" | "right" | "middle" => {
+                self.skip_whitespace();
+                if self.peek() == Some('.') {
+                    self.pos += 1;
+                }
+                return None;
+            }
+            "begin" | "end" => {
+                self.parse_raw_arg();
+                return None;
+            }
+            "quad" => Atom::Space(1.0),
+            "qquad" => Atom::Space(2.0),
+            "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" | "biggl"
+            | "biggr" | "Biggl" | "Biggr" | "displaystyle" | "textstyle" | "scriptstyle"
+            | "limits" | "nolimits" | "nonumber" | "notag" => return None,
+            _ => Atom::Text(format!("\\{name}")),
+        })
+    }
+}
+
+fn attach_script(base: Atom, arg: Vec<Atom>, superscript: bool) -> Atom {
+    match base {
+        Atom::Scripts {
+            base,
+            mut sub,
+            mut sup,
+        } if (superscript && sup.is_empty()) || (!superscript && sub.is_empty()) => {
+            if superscript {
+                sup = arg;
+            } else {
+                sub = arg;
+            }
+            Atom::Scripts { base, sub, sup }
+        }
+        base => {
+            let (sub, sup) = if superscript {
+                (Vec::new(), arg)
+            } else {
+                (arg, Vec::new())
+            };
+            Atom::Scripts {
+                base: Box::new(base),
+                sub,
+                sup,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Class {
+    Ord,
+    Bin,
+    Rel,
+    Large,
+}
+
+fn symbol(name: &str) -> Option<(&'static str, Class)> {
+    let ord = |s| Some((s, Class::Ord));
+    let bin = |s| Some((s, Class::Bin));
+    let rel = |s| Some((s, Class::Rel));
+    let large = |s| Some((s, Class::Large));
+    match name {
+        // Greek
+        "alpha" => ord("α"),
+        "beta" => ord("β"),
+        "gamma" => ord("γ"),
+        "delta" => ord("δ"),
+        "epsilon" => ord("ϵ"),
+        "varepsilon" => ord("ε"),
+        "zeta" => ord("ζ"),
+        "eta" => ord("η"),
+        "theta" => ord("θ"),
+        "vartheta" => ord("ϑ"),
+        "iota" => ord("ι"),
+        "kappa" => ord("κ"),
+        "lambda" => ord("λ"),
+        "mu" => ord("μ"),
+        "nu" => ord("ν"),
+        "xi" => ord("ξ"),
+        "pi" => ord("π"),
+        "varpi" => ord("ϖ"),
+        "rho" => ord("ρ"),
+        "varrho" => ord("ϱ"),
+        "sigma" => ord("σ"),
+        "varsigma" => ord("ς"),
+        "tau" => ord("τ"),
+        "upsilon" => ord("υ"),
+        "phi" => ord("ϕ"),
+        "varphi" => ord("φ"),
+        "chi" => ord("χ"),
+        "psi" => ord("ψ"),
+        "omega" => ord("ω"),
+        "Gamma" => ord("Γ"),
+        "Delta" => ord("Δ"),
+        "Theta" => ord("Θ"),
+        "Lambda" => ord("Λ"),
+        "Xi" => ord("Ξ"),
+        "Pi" => ord("Π"),
+        "Sigma" => ord("Σ"),
+        "Upsilon" => ord("Υ"),
+        "Phi" => ord("Φ"),
+        "Psi" => ord("Ψ"),
+        "Omega" => ord("Ω"),
+        // Ordinary symbols
+        "infty" => ord("∞"),
+        "partial" => ord("∂"),
+        "nabla" => ord("∇"),
+        "hbar" => ord("ℏ"),
+        "ell" => ord("ℓ"),
+        "Re" => ord("ℜ"),
+        "Im" => ord("ℑ"),
+        "aleph" => ord("ℵ"),
+        "emptyset" | "varnothing" => ord("∅"),
+        "forall" => ord("∀"),
+        "exists" => ord("∃"),
+        "neg" | "lnot" => ord("¬"),
+        "angle" => ord("∠"),
+        "prime" => ord("′"),
+        "degree" => ord("°"),
+        "top" => ord("⊤"),
+        "bot" => ord("⊥"),
+        "ldots" | "dots" => ord("…"),
+        "cdots" => ord("⋯"),
+        "vdots" => ord("⋮"),
+        "ddots" => ord("⋱"),
+        "langle" => ord("⟨"),
+        "rangle" => ord("⟩"),
+        "lfloor" => ord("⌊"),
+        "rfloor" => ord("⌋"),
+        "lceil" => ord("⌈"),
+        "rceil" => ord("⌉"),
+        "vert" | "lvert" | "rvert" => ord("|"),
+        "Vert" | "lVert" | "rVert" => ord("‖"),
+        "lbrace" => ord("{"),
+        "rbrace" => ord("}"),
+        // Binary operators
+        "cdot" => bin("⋅"),
+        "times" => bin("×"),
+        "div" => bin("÷"),
+        "pm" => bin("±"),
+        "mp" => bin("∓"),
+        "ast" => bin("∗"),
+        "star" => bin("⋆"),
+        "circ" => bin("∘"),
+        "bullet" => bin("∙"),
+        "oplus" => bin("⊕"),
+        "ominus" => bin("⊖"),
+        "otimes" => bin("⊗"),
+        "odot" => bin("⊙"),
+        "cup" => bin("∪"),
+        "cap" => bin("∩"),
+        "setminus" => bin("∖"),
+        "wedge" | "land" => bin("∧"),
+        "vee" | "lor" => bin("∨"),
+        // Relations and arrows
+        "leq" | "le" => rel("≤"),
+        "geq" | "ge" => rel("≥"),
+        "neq" | "ne" => rel("≠"),
+        "approx" => rel("≈"),
+        "equiv" => rel("≡"),
+        "sim" => rel("∼"),
+        "simeq" => rel("≃"),
+        "cong" => rel("≅"),
+        "propto" => rel("∝"),
+        "ll" => rel("≪"),
+        "gg" => rel("≫"),
+        "in" => rel("∈"),
+        "notin" => rel("∉"),
+        "ni" => rel("∋"),
+        "subset" => rel("⊂"),
+        "subseteq" => rel("⊆"),
+        "supset" => rel("⊃"),
+        "supseteq" => rel("⊇"),
+        "perp" => rel("⊥"),
+        "parallel" => rel("∥"),
+        "mid" => rel("∣"),
+        "coloneqq" => rel("≔"),
+        "to" | "rightarrow" => rel("→"),
+        "gets" | "leftarrow" => rel("←"),
+        "leftrightarrow" => rel("↔"),
+        "longrightarrow" => rel("⟶"),
+        "longleftarrow" => rel("⟵"),
+        "Rightarrow" => rel("⇒"),
+        "Leftarrow" => rel("⇐"),
+        "Leftrightarrow" => rel("⇔"),
+        "implies" => rel("⟹"),
+        "impliedby" => rel("⟸"),
+        "iff" => rel("⟺"),
+        "mapsto" => rel("↦"),
+        "uparrow" => rel("↑"),
+        "downarrow" => rel("↓"),
+        // Large operators
+        "sum" => large("∑"),
+        "prod" => large("∏"),
+        "coprod" => large("∐"),
+        "int" => large("∫"),
+        "iint" => large("∬"),
+        "iiint" => large("∭"),
+        "oint" => large("∮"),
+        "bigcup" => large("⋃"),
+        "bigcap" => large("⋂"),
+        "bigoplus" => large("⨁"),
+        "bigotimes" => large("⨂"),
+        _ => None,
+    }
+}
+
+fn is_operator_name(name: &str) -> bool {
+    matches!(
+        name,
+        "sin"
+            | "cos"
+            | "tan"
+            | "cot"
+            | "sec"
+            | "csc"
+            | "arcsin"
+            | "arccos"
+            | "arctan"
+            | "sinh"
+            | "cosh"
+            | "tanh"
+            | "log"
+            | "ln"
+            | "lg"
+            | "exp"
+            | "lim"
+            | "liminf"
+            | "limsup"
+            | "max"
+            | "min"
+            | "sup"
+            | "inf"
+            | "det"
+            | "dim"
+            | "ker"
+            | "deg"
+            | "gcd"
+            | "arg"
+            | "hom"
+            | "Pr"
+            | "mod"
+            | "bmod"
+    )
+}
+
+fn accent(name: &str) -> Option<Accent> {
+    match name {
+        "hat" | "widehat" => Some(Accent::Hat),
+        "bar" | "overline" => Some(Accent::Bar),
+        "vec" | "overrightarrow" => Some(Accent::Vec),
+        "dot" => Some(Accent::Dot),
+        "ddot" => Some(Accent::Ddot),
+        "tilde" | "widetilde" => Some(Accent::Tilde),
+        _ => None,
+    }
+}
+
+const fn double_struck(ch: char) -> char {
+    match ch {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        _ => ch,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unicode output
+// ---------------------------------------------------------------------------
+
+/// Whether `atom` is an operator that wants a space before a following word.
+fn is_operator(atom: &Atom) -> bool {
+    match atom {
+        Atom::Op(_) | Atom::Large(_) => true,
+        Atom::Scripts { base, .. } => is_operator(base),
+        _ => false,
+    }
+}
+
+/// A `+`/`-` with nothing to its left is a sign, not a binary operator.
+fn is_unary(prev: Option<&Atom>) -> bool {
+    prev.is_none_or(|p| {
+        matches!(
+            p,
+            Atom::Bin(_) | Atom::Rel(_) | Atom::Large(_) | Atom::Char('(' | '[' | '{' | ',')
+        )
+    })
+}
+
+/// Display form of a binary operator (`-` becomes a real minus sign).
+fn display_op(op: &str) -> &str {
+    if op == "-" { "−" } else { op }
+}
+
+fn write_unicode(atoms: &[Atom], script: bool, out: &mut String) {
+    let mut prev: Option<&Atom> = None;
+    for atom in atoms {
+        let piece = atom_unicode(atom, prev, script);
+        if prev.is_some_and(is_operator) && piece.chars().next().is_some_and(char::is_alphanumeric)
+        {
+            out.push(' ');
+        }
+        out.push_str(&piece);
+        prev = Some(atom);
+    }
+}
+
+fn atom_unicode(atom: &Atom, prev: Option<&Atom>, script: bool) -> String {
+    let padded = |op: &str, out: &mut String| {
+        if script {
+            out.push_str(op);
+        } else {
+            out.push(' ');
+            out.push_str(display_op(op));
+            out.push(' ');
+        }
+    };
+    let mut out = String::new();
+    match atom {
+        Atom::Char(c) => out.push(*c),
+        Atom::Ord(s) | Atom::Op(s) | Atom::Text(s) | Atom::Large(s) => out.push_str(s),
+        Atom::Bin(op) if is_unary(prev) => {
+            out.push_str(if script { op } else { display_op(op) });
+        }
+        Atom::Bin(op) | Atom::Rel(op) => padded(op, &mut out),
+        Atom::Space(width) => {
+            if *width >= 1.5 {
+                out.push_str("  ");
+            } else if *width >= 0.15 {
+                out.push(' ');
+            }
+        }
+        Atom::Group(inner) => write_unicode(inner, script, &mut out),
+        Atom::Frac { num, den, bar } => {
+            let num = list_unicode(num, script);
+            let den = list_unicode(den, script);
+            if *bar {
+                let _ = write!(out, "{}⁄{}", parenthesize(&num), parenthesize(&den));
+            } else {
+                let _ = write!(out, "{num} {den}");
+            }
+        }
+        Atom::Sqrt { index, body } => {
+            if !index.is_empty() {
+                out.push_str(&script_unicode(&list_unicode(index, true), true));
+            }
+            let _ = write!(out, "√{}", parenthesize(&list_unicode(body, script)));
+        }
+        Atom::Scripts { base, sub, sup } => {
+            out.push_str(&atom_unicode(base, prev, script));
+            if !sub.is_empty() {
+                out.push_str(&script_unicode(&list_unicode(sub, true), false));
+            }
+            if !sup.is_empty() {
+                out.push_str(&script_unicode(&list_unicode(sup, true), true));
+            }
+        }
+        Atom::Accent { mark, body } => {
+            let body = atom_unicode(body, prev, script);
+            if *mark == Accent::Bar {
+                for ch in body.chars() {
+                    out.push(ch);
+                    out.push(mark.combining());
+                }
+            } else {
+                out.push_str(&body);
+                out.push(mark.combining());
+            }
+        }
+    }
+    out
+}
+
+fn list_unicode(atoms: &[Atom], script: bool) -> String {
+    let mut out = String::new();
+    write_unicode(atoms, script, &mut out);
+    out.trim().to_string()
+}
+
+/// Convert script text with the Unicode sub/superscript tables, falling
+/// back to `^x` / `_(xy)` notation for characters without a script form.
+fn script_unicode(text: &str, superscript: bool) -> String {
+    if superscript && text.chars().all(|c| c == '′') {
+        return text.to_string();
+    }
+    if let Some(mapped) = map_script_chars(text, superscript) {
+        return mapped;
+    }
+    let marker = if superscript { '^' } else { '_' };
+    if text.chars().count() == 1 {
+        format!("{marker}{text}")
+    } else {
+        format!("{marker}({text})")
+    }
+}
+
+/// Wrap `text` in parentheses unless it can sit beside `⁄` or `√` as is.
+fn parenthesize(text: &str) -> String {
+    if text.chars().count() <= 1
+        || text
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '√'))
+    {
+        text.to_string()
+    } else {
+        format!("({text})")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SVG layout
+// ---------------------------------------------------------------------------
+
+/// A laid-out box. Coordinates are relative to the left end of the
+/// baseline, with y growing downward as in SVG.
+#[derive(Debug, Clone, Default)]
+struct Layout {
+    width: f64,
+    ascent: f64,
+    descent: f64,
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Text {
+        x: f64,
+        y: f64,
+        size: f64,
+        text: String,
+        italic: bool,
+    },
+    Line {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        thickness: f64,
+    },
+}
+
+impl Item {
+    fn write_svg(&self, svg: &mut String, dx: f64, dy: f64) {
+        match self {
+            Self::Text {
+                x,
+                y,
+                size,
+                text,
+                italic,
+            } => {
+                let style = if *italic {
+                    " font-style=\"italic\""
+                } else {
+                    ""
+                };
+                let _ = write!(
+                    svg,
+                    "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{size:.2}\" stroke=\"none\"{style}>{}</text>",
+                    x + dx,
+                    y + dy,
+                    escape_xml(text)
+                );
+            }
+            Self::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                thickness,
+            } => {
+                let _ = write!(
+                    svg,
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke-width=\"{thickness:.2}\" stroke-linecap=\"round\"/>",
+                    x1 + dx,
+                    y1 + dy,
+                    x2 + dx,
+                    y2 + dy
+                );
+            }
+        }
+    }
+
+    fn shifted(self, dx: f64, dy: f64) -> Self {
+        match self {
+            Self::Text {
+                x,
+                y,
+                size,
+                text,
+                italic,
+            } => Self::Text {
+                x: x + dx,
+                y: y + dy,
+                size,
+                text,
+                italic,
+            },
+            Self::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                thickness,
+            } => Self::Line {
+                x1: x1 + dx,
+                y1: y1 + dy,
+                x2: x2 + dx,
+                y2: y2 + dy,
+                thickness,
+            },
+        }
+    }
+}
+
+impl Layout {
+    fn text(text: &str, size: f64, italic: bool) -> Self {
+        Self {
+            width: text_width(text) * size,
+            ascent: 0.72 * size,
+            descent: 0.22 * size,
+            items: vec![Item::Text {
+                x: 0.0,
+                y: 0.0,
+                size,
+                text: text.to_string(),
+                italic,
+            }],
+        }
+    }
+
+    /// Draw `child` with its baseline origin at `(dx, dy)`.
+    fn place(&mut self, child: Self, dx: f64, dy: f64) {
+        self.ascent = self.ascent.max(child.ascent - dy);
+        self.descent = self.descent.max(child.descent + dy);
+        self.items
+            .extend(child.items.into_iter().map(|item| item.shifted(dx, dy)));
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), thickness: f64) {
+        self.items.push(Item::Line {
+            x1: from.0,
+            y1: from.1,
+            x2: to.0,
+            y2: to.1,
+            thickness,
+        });
+    }
+}
+
+/// Rough advance width of `text` in ems.
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | 't' | 'f' | 'r' | '(' | ')' | '[' | ']' | '|' | ',' | '.' | ';'
+            | ':' | '!' | '′' => 0.32,
+            'm' | 'w' | 'M' | 'W' | '+' | '=' | '<' | '>' => 0.85,
+            ' ' => 0.3,
+            'α'..='ω' => 0.56,
+            c if c.is_ascii_uppercase() => 0.68,
+            c if c.is_ascii() => 0.52,
+            _ => 0.75,
+        })
+        .sum()
+}
+
+fn layout_list(atoms: &[Atom], size: f64, script: bool) -> Layout {
+    let mut out = Layout {
+        ascent: 0.72 * size,
+        descent: 0.22 * size,
+        ..Layout::default()
+    };
+    let mut prev: Option<&Atom> = None;
+    for atom in atoms {
+        let pad = match atom {
+            Atom::Rel(_) => 0.28,
+            Atom::Bin(_) if !is_unary(prev) => 0.22,
+            _ if prev.is_some_and(is_operator) => 0.17,
+            _ => 0.0,
+        } * if script { 0.5 } else { 1.0 }
+            * size;
+        let child = layout_atom(atom, size, script);
+        let width = child.width;
+        let x = out.width + pad;
+        out.place(child, x, 0.0);
+        out.width = x + width;
+        if matches!(atom, Atom::Rel(_)) || (matches!(atom, Atom::Bin(_)) && !is_unary(prev)) {
+            out.width += pad;
+        }
+        prev = Some(atom);
+    }
+    out
+}
+
+fn layout_atom(atom: &Atom, size: f64, script: bool) -> Layout {
+    match atom {
+        Atom::Char(c) => Layout::text(&c.to_string(), size, c.is_ascii_alphabetic()),
+        Atom::Ord(s) | Atom::Op(s) | Atom::Text(s) => Layout::text(s, size, false),
+        Atom::Large(s) => {
+            let big = if script { size } else { size * 1.4 };
+            let mut layout = Layout::default();
+            // Center the enlarged glyph on the math axis.
+            layout.place(Layout::text(s, big, false), 0.0, 0.2 * (big - size));
+            layout.width = text_width(s) * big;
+            layout
+        }
+        Atom::Bin(op) | Atom::Rel(op) => Layout::text(display_op(op), size, false),
+        Atom::Space(width) => Layout {
+            width: width * size,
+            ..Layout::default()
+        },
+        Atom::Group(inner) => layout_list(inner, size, script),
+        Atom::Frac { num, den, bar } => layout_frac(num, den, *bar, size, script),
+        Atom::Sqrt { index, body } => layout_sqrt(index, body, size, script),
+        Atom::Scripts { base, sub, sup } => {
+            let base = layout_atom(base, size, script);
+            let small = size * 0.7;
+            let sub = (!sub.is_empty()).then(|| layout_list(sub, small, true));
+            let sup = (!sup.is_empty()).then(|| layout_list(sup, small, true));
+            let x = base.width;
+            let mut width = base.width;
+            let base_ascent = base.ascent;
+            let base_descent = base.descent;
+            let mut out = Layout::default();
+            out.place(base, 0.0, 0.0);
+            if let Some(sup) = sup {
+                let raise = (0.42 * size).max(0.3f64.mul_add(-size, base_ascent));
+                width = width.max(x + sup.width);
+                out.place(sup, x, -raise);
+            }
+            if let Some(sub) = sub {
+                let lower = (0.2 * size).max(0.05f64.mul_add(-size, base_descent));
+                width = width.max(x + sub.width);
+                out.place(sub, x, lower);
+            }
+            out.width = 0.05f64.mul_add(size, width);
+            out
+        }
+        Atom::Accent { mark, body } => {
+            let body = layout_atom(body, size, script);
+            let mut out = Layout {
+                width: body.width,
+                ..Layout::default()
+            };
+            let top = -0.08f64.mul_add(size, body.ascent);
+            let body_ascent = body.ascent;
+            out.place(body, 0.0, 0.0);
+            let thickness = (0.05 * size).max(1.0);
+            if let Some(glyph) = mark.glyph() {
+                let glyph_size = size * 0.9;
+                let x = text_width(glyph).mul_add(-glyph_size, out.width) / 2.0;
+                out.place(
+                    Layout::text(glyph, glyph_size, false),
+                    x,
+                    -0.5f64.mul_add(-size, body_ascent),
+                );
+            } else {
+                let (x1, x2) = (0.05 * size, 0.05f64.mul_add(-size, out.width));
+                out.line((x1, top), (x2, top), thickness);
+                if *mark == Accent::Vec {
+                    let head = 0.12 * size;
+                    out.line((x2 - head, top - head), (x2, top), thickness);
+                    out.line((x2 - head, top + head), (x2, top), thickness);
+                }
+            }
+            out.ascent = out.ascent.max(0.15f64.mul_add(size, -top));
+            out
+        }
+    }
+}
+
+fn layout_frac(num: &[Atom], den: &[Atom], bar: bool, size: f64, script: bool) -> Layout {
+    let inner = if script { size * 0.85 } else { size };
+    let num = layout_list(num, inner, script);
+    let den = layout_list(den, inner, script);
+    let axis = 0.3 * size;
+    let gap = 0.15 * size;
+    let thickness = (0.06 * size).max(1.0);
+    let width = 0.2f64.mul_add(size, num.width.max(den.width));
+    let mut out = Layout {
+        width,
+        ..Layout::default()
+    };
+    let num_y = -(axis + gap + num.descent);
+    let den_y = -axis + gap + den.ascent;
+    let (num_x, den_x) = ((width - num.width) / 2.0, (width - den.width) / 2.0);
+    out.place(num, num_x, num_y);
+    out.place(den, den_x, den_y);
+    if bar {
+        out.line((0.0, -axis), (width, -axis), thickness);
+    }
+    out
+}
+
+fn layout_sqrt(index: &[Atom], body: &[Atom], size: f64, script: bool) -> Layout {
+    let body = layout_list(body, size, script);
+    let index = (!index.is_empty()).then(|| layout_list(index, size * 0.6, true));
+    let offset = index
+        .as_ref()
+        .map_or(0.0, |index| 0.2f64.mul_add(-size, index.width).max(0.0));
+    let thickness = (0.05 * size).max(1.0);
+    let top = -0.12f64.mul_add(size, body.ascent);
+    let bottom = body.descent;
+    let radical = 0.6 * size;
+    let mut out = Layout {
+        width: 0.1f64.mul_add(size, offset + radical + body.width),
+        ..Layout::default()
+    };
+    let x = offset;
+    out.line(
+        (x, -0.3 * size),
+        (0.15f64.mul_add(size, x), -0.38 * size),
+        thickness,
+    );
+    out.line(
+        (0.15f64.mul_add(size, x), -0.38 * size),
+        (0.3f64.mul_add(size, x), bottom),
+        thickness,
+    );
+    out.line(
+        (0.3f64.mul_add(size, x), bottom),
+        (0.55f64.mul_add(size, x), top),
+        thickness,
+    );
+    out.line((0.55f64.mul_add(size, x), top), (out.width, top), thickness);
+    out.place(body, x + radical, 0.0);
+    if let Some(index) = index {
+        out.place(index, 0.0, -0.5 * size);
+    }
+    out.ascent = out.ascent.max(-top + thickness);
+    out.descent = out.descent.max(bottom + thickness);
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greek_and_operators() {
+        assert_eq!(tex_to_unicode(r"\alpha + \beta \leq \pi"), "α + β ≤ π");
+        assert_eq!(tex_to_unicode(r"a \cdot b \times c"), "a ⋅ b × c");
+        assert_eq!(tex_to_unicode(r"x \to \infty"), "x → ∞");
+    }
+
+    #[test]
+    fn test_scripts_use_unicode_tables() {
+        assert_eq!(tex_to_unicode("x^2 + y_1"), "x² + y₁");
+        assert_eq!(tex_to_unicode("e^{i\\pi} = -1"), "e^(iπ) = −1");
+        assert_eq!(tex_to_unicode("a_{n+1}"), "aₙ₊₁");
+        assert_eq!(tex_to_unicode("x_i^2"), "xᵢ²");
+    }
+
+    #[test]
+    fn test_unmappable_script_falls_back() {
+        assert_eq!(tex_to_unicode("x^q"), "x^q");
+        assert_eq!(tex_to_unicode("x_{qq}"), "x_(qq)");
+    }
+
+    #[test]
+    fn test_fractions_and_roots() {
+        assert_eq!(tex_to_unicode(r"\frac{1}{2}"), "1⁄2");
+        assert_eq!(tex_to_unicode(r"\frac{a+b}{c}"), "(a + b)⁄c");
+        assert_eq!(tex_to_unicode(r"\sqrt{x}"), "√x");
+        assert_eq!(tex_to_unicode(r"\sqrt[3]{x+1}"), "³√(x + 1)");
+    }
+
+    #[test]
+    fn test_operator_names_and_text() {
+        assert_eq!(tex_to_unicode(r"\sin x"), "sin x");
+        assert_eq!(tex_to_unicode(r"\sum_{i=1}^n i"), "∑ᵢ₌₁ⁿ i");
+        assert_eq!(tex_to_unicode(r"f(x) \text{ if } x"), "f(x) if x");
+    }
+
+    #[test]
+    fn test_delimiters_and_environments_are_dropped() {
+        assert_eq!(tex_to_unicode(r"\left( x \right."), "(x");
+        assert_eq!(
+            tex_to_unicode_lines(r"\begin{aligned} a &= b \\ c &= d \end{aligned}"),
+            vec!["a = b", "c = d"]
+        );
+    }
+
+    #[test]
+    fn test_accents_and_blackboard() {
+        assert_eq!(tex_to_unicode(r"\hat{x}"), "x\u{0302}");
+        assert_eq!(tex_to_unicode(r"\mathbb{R}^n"), "ℝⁿ");
+    }
+
+    #[test]
+    fn test_deep_nesting_falls_back_to_source() {
+        let braces = format!("{}x{}", "{".repeat(5000), "}".repeat(5000));
+        assert_eq!(tex_to_unicode(&braces), braces);
+        let fracs = format!("{}x{}", r"\frac{".repeat(3000), "}{y}".repeat(3000));
+        assert_eq!(tex_to_unicode(&fracs), fracs);
+        let roots = format!("{}x{}", r"\sqrt[".repeat(3000), "]".repeat(3000));
+        assert_eq!(tex_to_unicode(&roots), roots);
+        let scripts = format!("x{}", "^a".repeat(5000));
+        assert_eq!(tex_to_unicode(&scripts), scripts);
+        let accents = format!("{}x", r"\hat".repeat(5000));
+        assert!(tex_to_svg(&accents, 16.0, 100, "#000").contains(r"\hat\hat"));
+        assert_eq!(tex_to_unicode("{{{x}}}^{2}"), "x²");
+    }
+
+    #[test]
+    fn test_unknown_command_is_kept() {
+        assert_eq!(tex_to_unicode(r"\foo"), r"\foo");
+    }
+
+    #[test]
+    fn test_svg_contains_glyphs_and_rules() {
+        let svg = tex_to_svg(r"\frac{a}{\sqrt{b}} < c", 20.0, 400, "#000");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("width=\"400\""));
+        assert!(svg.contains(">a</text>"));
+        assert!(svg.contains("&lt;"));
+        assert!(svg.matches("<line").count() >= 5, "{svg}");
+    }
+
+    #[test]
+    fn test_svg_rasterizes() {
+        let svg = tex_to_svg(r"\hat{x} = \vec{v}_1^2 + \sqrt[3]{y}", 16.0, 200, "#fff");
+        let image = crate::mermaid::rasterize_svg(&svg, 200).unwrap();
+        assert_eq!(image.width(), 200);
+        assert!(image.height() > 16);
+    }
+
+    #[test]
+    fn test_svg_grows_past_min_width() {
+        let svg = tex_to_svg("a+b+c+d+e+f+g+h", 40.0, 10, "#000");
+        assert!(!svg.contains("width=\"10\""));
+    }
+}
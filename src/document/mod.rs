@@ -2,16 +2,22 @@
 //!
 //! This module handles:
 //! - Parsing markdown with comrak
-//! - Extracting document structure (headings, links, images)
+//! - Extracting document structure (headings, links, images, front matter)
+//! - Converting LaTeX math to Unicode or SVG
 //! - Rendering to styled lines for display
 
+mod front_matter;
+mod math;
 mod parser;
+mod slug;
 mod types;
 
-pub use parser::{parse, parse_with_image_heights, parse_with_layout};
+pub use front_matter::{FrontMatter, FrontMatterFormat};
+pub use math::{tex_to_svg, tex_to_unicode};
+pub use parser::{display_width, parse, parse_with_image_heights, parse_with_layout};
 pub use types::{
-    Document, HeadingRef, ImageRef, InlineColor, InlineSpan, InlineStyle, LineType, LinkRef,
-    RenderedLine,
+    AlertKind, Document, HeadingRef, ImageRef, InlineColor, InlineSpan, InlineStyle, LineType,
+    LinkRef, RenderedLine,
 };
 
 /// Image file extensions that should be rendered inline.
@@ -38,6 +44,15 @@ pub fn prepare_content(file_path: &std::path::Path, content: String) -> String {
     format!("```{language}\n{content}\n```")
 }
 
+/// Returns true if [`prepare_content`] wraps the file in a code fence.
+///
+/// The opening fence shifts the parsed source one line below the file on
+/// disk, which callers mapping rendered lines back to the file must undo.
+pub fn is_fence_wrapped(file_path: &std::path::Path) -> bool {
+    !is_image_file(file_path)
+        && (is_csv_file(file_path) || crate::highlight::language_for_file(file_path).is_some())
+}
+
 /// Returns true if the file extension is `.csv`.
 fn is_csv_file(path: &std::path::Path) -> bool {
     path.extension()
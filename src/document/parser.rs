@@ -2,15 +2,19 @@
 
 use std::collections::HashMap;
 use std::hash::BuildHasher;
+use std::ops::Range;
 
 use anyhow::Result;
 use comrak::nodes::{AstNode, NodeValue, TableAlignment};
 use comrak::{Arena, Options, parse_document};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use super::front_matter::{FrontMatter, delimiter_for};
+use super::math::{tex_to_unicode, tex_to_unicode_lines};
+use super::slug::{Slugger, split_custom_id};
 use super::types::{
-    CodeBlockRef, Document, HeadingRef, ImageRef, InlineSpan, InlineStyle, LineType, LinkRef,
-    ParsedDocument, RenderedLine,
+    AlertKind, CodeBlockRef, Document, HeadingRef, ImageRef, InlineSpan, InlineStyle, LineType,
+    LinkRef, ParsedDocument, RenderedLine,
 };
 
 /// Parse markdown source into a Document.
@@ -60,10 +64,17 @@ impl Document {
     /// # Errors
     /// Returns an error if markdown parsing fails.
     pub fn parse_with_mermaid_images(source: &str, width: u16) -> Result<Self> {
-        Ok(parse_with_all_options(source, width, &HashMap::new(), true))
+        Ok(parse_with_all_options(
+            source,
+            width,
+            &HashMap::new(),
+            true,
+            false,
+        ))
     }
 
-    /// Parse with all options: layout width, image heights, and mermaid-as-images flag.
+    /// Parse with all options: layout width, image heights, mermaid-as-images
+    /// flag, and whether the front matter panel is collapsed to one line.
     ///
     /// # Errors
     /// Returns an error if markdown parsing fails.
@@ -72,12 +83,14 @@ impl Document {
         width: u16,
         image_heights: &HashMap<String, usize>,
         mermaid_as_images: bool,
+        front_matter_collapsed: bool,
     ) -> Result<Self> {
         Ok(parse_with_all_options(
             source,
             width,
             image_heights,
             mermaid_as_images,
+            front_matter_collapsed,
         ))
     }
 }
@@ -110,7 +123,13 @@ pub fn parse_with_layout<S: BuildHasher>(
     width: u16,
     image_heights: &HashMap<String, usize, S>,
 ) -> Result<Document> {
-    Ok(parse_with_all_options(source, width, image_heights, false))
+    Ok(parse_with_all_options(
+        source,
+        width,
+        image_heights,
+        false,
+        false,
+    ))
 }
 
 /// Parse markdown with all options including mermaid-as-images flag.
@@ -119,9 +138,10 @@ fn parse_with_all_options<S: BuildHasher>(
     width: u16,
     image_heights: &HashMap<String, usize, S>,
     mermaid_as_images: bool,
+    front_matter_collapsed: bool,
 ) -> Document {
     let arena = Arena::new();
-    let options = create_options();
+    let options = create_options(delimiter_for(source));
     let root = parse_document(&arena, source, &options);
 
     let wrap_width = width.max(1) as usize;
@@ -133,11 +153,17 @@ fn parse_with_all_options<S: BuildHasher>(
         footnotes: HashMap::new(),
         code_blocks: Vec::new(),
         mermaid_sources: HashMap::new(),
+        math_sources: HashMap::new(),
         image_heights,
         wrap_width,
         mermaid_as_images,
+        front_matter: None,
+        front_matter_collapsed,
+        slugger: Slugger::default(),
+        anchors: HashMap::new(),
     };
     process_node(root, &mut ctx, 0, None);
+    offset_source_ranges(&mut ctx.lines, front_matter_line_count(root));
 
     Document::from_parsed(
         source.to_string(),
@@ -149,11 +175,14 @@ fn parse_with_all_options<S: BuildHasher>(
             footnotes: ctx.footnotes,
             code_blocks: ctx.code_blocks,
             mermaid_sources: ctx.mermaid_sources,
+            math_sources: ctx.math_sources,
+            anchors: ctx.anchors,
+            front_matter: ctx.front_matter,
         },
     )
 }
 
-fn create_options() -> Options {
+fn create_options(front_matter_delimiter: Option<&str>) -> Options {
     let mut options = Options::default();
 
     // Enable GFM extensions
@@ -164,14 +193,19 @@ fn create_options() -> Options {
     options.extension.footnotes = true;
     options.extension.superscript = true;
     options.extension.subscript = true;
+    options.extension.math_dollars = true;
 
     // Enable other useful extensions
     options.extension.header_ids = Some(String::new());
     options.extension.description_lists = true;
+    options.extension.front_matter_delimiter = front_matter_delimiter.map(str::to_string);
 
     options
 }
 
+/// Longest front matter key shown before truncating the key column.
+const FRONT_MATTER_MAX_KEY_WIDTH: usize = 20;
+
 /// Mutable context threaded through recursive node processing.
 struct ParseContext<'h, S: BuildHasher = std::collections::hash_map::RandomState> {
     lines: Vec<RenderedLine>,
@@ -181,16 +215,40 @@ struct ParseContext<'h, S: BuildHasher = std::collections::hash_map::RandomState
     footnotes: HashMap<String, usize>,
     code_blocks: Vec<CodeBlockRef>,
     mermaid_sources: HashMap<String, String>,
+    math_sources: HashMap<String, String>,
     image_heights: &'h HashMap<String, usize, S>,
     wrap_width: usize,
     mermaid_as_images: bool,
+    front_matter: Option<FrontMatter>,
+    front_matter_collapsed: bool,
+    slugger: Slugger,
+    anchors: HashMap<String, usize>,
 }
 
+/// Render `node` and tag every line it produced with its source lines.
+///
+/// Children tag their own lines first, so a block only fills in the lines
+/// it drew itself (spacers, wrapped text).
 fn process_node<'a, S: BuildHasher>(
     node: &'a AstNode<'a>,
     ctx: &mut ParseContext<'_, S>,
     depth: usize,
     list_marker: Option<String>,
+) {
+    let start = ctx.lines.len();
+    render_node(node, ctx, depth, list_marker);
+    if !matches!(node.data.borrow().value, NodeValue::Document)
+        && let Some(range) = node_source_lines(node)
+    {
+        fill_source_range(&mut ctx.lines[start..], &range);
+    }
+}
+
+fn render_node<'a, S: BuildHasher>(
+    node: &'a AstNode<'a>,
+    ctx: &mut ParseContext<'_, S>,
+    depth: usize,
+    list_marker: Option<String>,
 ) {
     match &node.data.borrow().value {
         NodeValue::Document => {
@@ -200,7 +258,15 @@ fn process_node<'a, S: BuildHasher>(
         }
 
         NodeValue::Heading(heading) => {
-            let text = extract_text(node);
+            let full_text = extract_text(node);
+            let (text, custom_id) = split_custom_id(&full_text);
+            let text = text.to_string();
+            let id = if let Some(custom_id) = custom_id {
+                ctx.slugger.reserve(custom_id);
+                custom_id.to_string()
+            } else {
+                ctx.slugger.slug(split_custom_id(&plain_text(node)).0)
+            };
 
             // Keep headings visually separated with two rows above.
             ensure_trailing_empty_lines(&mut ctx.lines, 2);
@@ -210,8 +276,9 @@ fn process_node<'a, S: BuildHasher>(
                 level: heading.level,
                 text: text.clone(),
                 line: line_num,
-                id: None, // TODO: Extract from header_ids
+                id: Some(id),
             });
+            register_html_anchors(node, line_num, &mut ctx.anchors);
 
             collect_inline_elements(node, line_num, &mut ctx.images, &mut ctx.link_refs);
 
@@ -224,10 +291,18 @@ fn process_node<'a, S: BuildHasher>(
                 .push(RenderedLine::new(String::new(), LineType::Empty));
         }
 
+        NodeValue::Paragraph if display_math_literal(node).is_some() => {
+            let tex = display_math_literal(node).unwrap_or_default();
+            render_display_math(ctx, &tex);
+            ctx.lines
+                .push(RenderedLine::new(String::new(), LineType::Empty));
+        }
+
         NodeValue::Paragraph => {
             // Check if paragraph contains only an image (common case)
             let child_images = collect_paragraph_images(node);
 
+            register_html_anchors(node, ctx.lines.len(), &mut ctx.anchors);
             if child_images.is_empty() {
                 // Regular paragraph text with inline styling and wrapping
                 let spans = collect_inline_spans(node);
@@ -375,11 +450,23 @@ fn process_node<'a, S: BuildHasher>(
                         .saturating_sub(UnicodeWidthStr::width(visible_label.as_str()))
                 )
             );
-            ctx.lines.push(RenderedLine::new(top, LineType::CodeBlock));
+            // Fenced blocks have the opening fence on the first source line.
+            let source = node_source_lines(node);
+            let body_offset = usize::from(code_block.fenced);
+            let source_line = |idx: usize| {
+                source
+                    .as_ref()
+                    .map(|range| range.start + idx..range.start + idx + 1)
+            };
+            let mut top = RenderedLine::new(top, LineType::CodeBlock);
+            if let Some(range) = source_line(0) {
+                top = top.with_source_range(range);
+            }
+            ctx.lines.push(top);
 
             let body_start = ctx.lines.len();
             let raw_lines: Vec<String> = literal.lines().map(ToString::to_string).collect();
-            for raw_line in &raw_lines {
+            for (idx, raw_line) in raw_lines.iter().enumerate() {
                 let plain_style = InlineStyle {
                     code: true,
                     ..InlineStyle::default()
@@ -398,11 +485,11 @@ fn process_node<'a, S: BuildHasher>(
                     InlineStyle::default(),
                 ));
                 let content = spans_to_string(&line_spans);
-                ctx.lines.push(RenderedLine::with_spans(
-                    content,
-                    LineType::CodeBlock,
-                    line_spans,
-                ));
+                let mut line = RenderedLine::with_spans(content, LineType::CodeBlock, line_spans);
+                if let Some(range) = source_line(body_offset + idx) {
+                    line = line.with_source_range(range);
+                }
+                ctx.lines.push(line);
             }
             let body_end = ctx.lines.len();
 
@@ -415,10 +502,14 @@ fn process_node<'a, S: BuildHasher>(
                 right_padding: CODE_RIGHT_PADDING,
             });
 
-            ctx.lines.push(RenderedLine::new(
+            let mut bottom = RenderedLine::new(
                 format!("└{}┘", "─".repeat(frame_inner_width)),
                 LineType::CodeBlock,
-            ));
+            );
+            if let Some(range) = &source {
+                bottom = bottom.with_source_range(range.end.saturating_sub(1)..range.end);
+            }
+            ctx.lines.push(bottom);
             ctx.lines
                 .push(RenderedLine::new(String::new(), LineType::Empty));
         }
@@ -520,6 +611,9 @@ fn process_node<'a, S: BuildHasher>(
                                 line_spans,
                             ));
                         }
+                        if let Some(range) = node_source_lines(child) {
+                            fill_source_range(&mut ctx.lines[base_line..], &range);
+                        }
                         let extra = fixup_link_lines(
                             &mut ctx.link_refs[link_start..],
                             &ctx.lines[base_line..],
@@ -561,14 +655,25 @@ fn process_node<'a, S: BuildHasher>(
         }
 
         NodeValue::BlockQuote => {
-            render_blockquote(
-                node,
-                &mut ctx.lines,
-                &mut ctx.link_refs,
-                &mut ctx.images,
-                ctx.wrap_width,
-                1,
-            );
+            if let Some(header) = parse_alert_header(node) {
+                render_alert(
+                    node,
+                    &header,
+                    &mut ctx.lines,
+                    &mut ctx.link_refs,
+                    &mut ctx.images,
+                    ctx.wrap_width,
+                );
+            } else {
+                render_blockquote(
+                    node,
+                    &mut ctx.lines,
+                    &mut ctx.link_refs,
+                    &mut ctx.images,
+                    ctx.wrap_width,
+                    1,
+                );
+            }
             ctx.lines
                 .push(RenderedLine::new(String::new(), LineType::Empty));
         }
@@ -662,7 +767,29 @@ fn process_node<'a, S: BuildHasher>(
             }
         }
 
+        NodeValue::FrontMatter(raw) => {
+            if let Some(front_matter) = FrontMatter::parse(raw) {
+                if let Some(title) = front_matter.title() {
+                    ctx.headings.push(HeadingRef {
+                        level: 0,
+                        text: title.to_string(),
+                        line: ctx.lines.len(),
+                        id: None,
+                    });
+                }
+                let panel =
+                    render_front_matter(&front_matter, ctx.front_matter_collapsed, ctx.wrap_width);
+                ctx.lines.extend(panel);
+                ctx.lines
+                    .push(RenderedLine::new(String::new(), LineType::Empty));
+                ctx.front_matter = Some(front_matter);
+            }
+        }
+
         NodeValue::HtmlBlock(html_block) => {
+            for name in extract_html_anchors(&html_block.literal) {
+                ctx.anchors.entry(name).or_insert(ctx.lines.len());
+            }
             for (src, alt) in extract_html_images(&html_block.literal) {
                 emit_html_image(ctx, &src, &alt);
             }
@@ -687,6 +814,128 @@ fn process_node<'a, S: BuildHasher>(
     }
 }
 
+/// Render front matter as a key/value panel.
+///
+/// Collapsed, only the header line is shown along with the field count.
+fn render_front_matter(
+    front_matter: &FrontMatter,
+    collapsed: bool,
+    wrap_width: usize,
+) -> Vec<RenderedLine> {
+    let entries = front_matter.entries();
+    let label = front_matter.format().label();
+    let header = if collapsed {
+        let noun = if entries.len() == 1 {
+            "field"
+        } else {
+            "fields"
+        };
+        format!("▸ Front matter ({label}, {} {noun})", entries.len())
+    } else {
+        format!("▾ Front matter ({label})")
+    };
+    let header_spans = vec![InlineSpan::new(
+        header.clone(),
+        InlineStyle {
+            strong: true,
+            ..InlineStyle::default()
+        },
+    )];
+    let mut lines = vec![RenderedLine::with_spans(
+        header,
+        LineType::FrontMatter,
+        header_spans,
+    )];
+    if collapsed {
+        return lines;
+    }
+
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| display_width(key))
+        .max()
+        .unwrap_or(0)
+        .min(FRONT_MATTER_MAX_KEY_WIDTH);
+    for (key, value) in entries {
+        let key_spans = truncate_spans_by_display_width(
+            &[InlineSpan::new(key.clone(), InlineStyle::default())],
+            key_width,
+        );
+        let key_text = spans_to_string(&key_spans);
+        let padding = key_width.saturating_sub(display_width(&key_text));
+        let continuation = format!("  {} │ ", " ".repeat(key_width));
+        let value_spans = [InlineSpan::new(value.clone(), InlineStyle::default())];
+        let wrapped = wrap_spans(&value_spans, wrap_width, &continuation, &continuation);
+        for (idx, mut line_spans) in wrapped.into_iter().enumerate() {
+            if idx == 0 {
+                // Replace the blank key column on the first row with the key.
+                line_spans.remove(0);
+                line_spans.splice(
+                    0..0,
+                    [
+                        InlineSpan::new("  ".to_string(), InlineStyle::default()),
+                        InlineSpan::new(
+                            key_text.clone(),
+                            InlineStyle {
+                                strong: true,
+                                ..InlineStyle::default()
+                            },
+                        ),
+                        InlineSpan::new(
+                            format!("{} │ ", " ".repeat(padding)),
+                            InlineStyle::default(),
+                        ),
+                    ],
+                );
+            }
+            let content = spans_to_string(&line_spans);
+            lines.push(RenderedLine::with_spans(
+                content,
+                LineType::FrontMatter,
+                line_spans,
+            ));
+        }
+    }
+    lines
+}
+
+/// Source lines (0-based, end exclusive) spanned by `node`, if known.
+fn node_source_lines<'a>(node: &'a AstNode<'a>) -> Option<Range<usize>> {
+    let pos = node.data.borrow().sourcepos;
+    (pos.start.line > 0).then(|| pos.start.line - 1..pos.end.line.max(pos.start.line))
+}
+
+/// Lines taken up by front matter, which comrak leaves out of sourcepos.
+fn front_matter_line_count<'a>(root: &'a AstNode<'a>) -> usize {
+    root.first_child()
+        .and_then(|child| match &child.data.borrow().value {
+            NodeValue::FrontMatter(raw) => Some(raw.matches('\n').count()),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+/// Shift source ranges past the front matter and point the front matter
+/// panel at it.
+fn offset_source_ranges(lines: &mut [RenderedLine], front_matter_lines: usize) {
+    if front_matter_lines == 0 {
+        return;
+    }
+    for line in lines {
+        let range = line.source_range().map_or(0..front_matter_lines, |range| {
+            range.start + front_matter_lines..range.end + front_matter_lines
+        });
+        *line = line.clone().with_source_range(range);
+    }
+}
+
+/// Tag lines that do not have a source range yet with `range`.
+fn fill_source_range(lines: &mut [RenderedLine], range: &Range<usize>) {
+    for line in lines {
+        line.fill_source_range(range);
+    }
+}
+
 fn ensure_trailing_empty_lines(lines: &mut Vec<RenderedLine>, count: usize) {
     let existing = lines
         .iter()
@@ -709,61 +958,239 @@ fn render_blockquote<'a>(
     let prefix = quote_prefix(quote_depth);
 
     for child in node.children() {
-        match &child.data.borrow().value {
-            NodeValue::Paragraph => {
-                let spans = collect_inline_spans(child);
-                let link_start = link_refs.len();
-                collect_inline_elements(child, 0, images, link_refs);
-                let base_line = lines.len();
-                let wrapped = wrap_spans(&spans, wrap_width, &prefix, &prefix);
+        render_quote_child(
+            child,
+            lines,
+            link_refs,
+            images,
+            wrap_width,
+            quote_depth,
+            &prefix,
+            LineType::BlockQuote,
+        );
+    }
+}
+
+/// Render one block inside a quote (or alert) with the given gutter prefix.
+#[allow(clippy::too_many_arguments)]
+fn render_quote_child<'a>(
+    child: &'a AstNode<'a>,
+    lines: &mut Vec<RenderedLine>,
+    link_refs: &mut Vec<LinkRef>,
+    images: &mut Vec<ImageRef>,
+    wrap_width: usize,
+    quote_depth: usize,
+    prefix: &str,
+    line_type: LineType,
+) {
+    let start = lines.len();
+    match &child.data.borrow().value {
+        NodeValue::Paragraph => {
+            let spans = collect_inline_spans(child);
+            push_wrapped_quote_spans(
+                child, &spans, lines, link_refs, images, wrap_width, prefix, line_type,
+            );
+        }
+        NodeValue::BlockQuote => {
+            render_blockquote(child, lines, link_refs, images, wrap_width, quote_depth + 1);
+        }
+        _ => {
+            let link_start = link_refs.len();
+            collect_inline_elements(child, 0, images, link_refs);
+            let text = extract_text(child);
+            let base_line = lines.len();
+            for raw_line in text.lines() {
+                let spans = vec![InlineSpan::new(
+                    raw_line.to_string(),
+                    InlineStyle::default(),
+                )];
+                let wrapped = wrap_spans(&spans, wrap_width, prefix, prefix);
                 for line_spans in wrapped {
                     let content = spans_to_string(&line_spans);
-                    lines.push(RenderedLine::with_spans(
-                        content,
-                        LineType::BlockQuote,
-                        line_spans,
-                    ));
+                    lines.push(RenderedLine::with_spans(content, line_type, line_spans));
                 }
-                let extra = fixup_link_lines(
-                    &mut link_refs[link_start..],
-                    &lines[base_line..],
-                    base_line,
-                    prefix.len(),
-                );
-                link_refs.extend(extra);
-            }
-            NodeValue::BlockQuote => {
-                render_blockquote(child, lines, link_refs, images, wrap_width, quote_depth + 1);
             }
-            _ => {
-                let link_start = link_refs.len();
-                collect_inline_elements(child, 0, images, link_refs);
-                let text = extract_text(child);
-                let base_line = lines.len();
-                for raw_line in text.lines() {
-                    let spans = vec![InlineSpan::new(
-                        raw_line.to_string(),
-                        InlineStyle::default(),
-                    )];
-                    let wrapped = wrap_spans(&spans, wrap_width, &prefix, &prefix);
-                    for line_spans in wrapped {
-                        let content = spans_to_string(&line_spans);
-                        lines.push(RenderedLine::with_spans(
-                            content,
-                            LineType::BlockQuote,
-                            line_spans,
-                        ));
-                    }
-                }
-                let extra = fixup_link_lines(
-                    &mut link_refs[link_start..],
-                    &lines[base_line..],
-                    base_line,
-                    prefix.len(),
-                );
-                link_refs.extend(extra);
+            let extra = fixup_link_lines(
+                &mut link_refs[link_start..],
+                &lines[base_line..],
+                base_line,
+                prefix.len(),
+            );
+            link_refs.extend(extra);
+        }
+    }
+    if let Some(range) = node_source_lines(child) {
+        fill_source_range(&mut lines[start..], &range);
+    }
+}
+
+/// Wrap `spans` (the inline content of `node`) behind a quote gutter.
+#[allow(clippy::too_many_arguments)]
+fn push_wrapped_quote_spans<'a>(
+    node: &'a AstNode<'a>,
+    spans: &[InlineSpan],
+    lines: &mut Vec<RenderedLine>,
+    link_refs: &mut Vec<LinkRef>,
+    images: &mut Vec<ImageRef>,
+    wrap_width: usize,
+    prefix: &str,
+    line_type: LineType,
+) {
+    let link_start = link_refs.len();
+    collect_inline_elements(node, 0, images, link_refs);
+    let base_line = lines.len();
+    let wrapped = wrap_spans(spans, wrap_width, prefix, prefix);
+    for line_spans in wrapped {
+        let content = spans_to_string(&line_spans);
+        lines.push(RenderedLine::with_spans(content, line_type, line_spans));
+    }
+    let extra = fixup_link_lines(
+        &mut link_refs[link_start..],
+        &lines[base_line..],
+        base_line,
+        prefix.len(),
+    );
+    link_refs.extend(extra);
+}
+
+/// Header of a GitHub alert or Obsidian callout (`[!KIND]±  Title`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AlertHeader {
+    kind: AlertKind,
+    title: String,
+    /// Obsidian fold marker: `Some('+')` expanded, `Some('-')` folded
+    fold: Option<char>,
+}
+
+/// Gutter drawn to the left of alert lines.
+const ALERT_GUTTER: &str = "  ┃ ";
+
+/// Detect an alert marker on the first line of a block quote.
+fn parse_alert_header<'a>(node: &'a AstNode<'a>) -> Option<AlertHeader> {
+    let first = node.first_child()?;
+    if !matches!(first.data.borrow().value, NodeValue::Paragraph) {
+        return None;
+    }
+    let text = extract_text(first);
+    parse_alert_marker(text.lines().next()?)
+}
+
+fn parse_alert_marker(line: &str) -> Option<AlertHeader> {
+    let rest = line.trim_start().strip_prefix("[!")?;
+    let close = rest.find(']')?;
+    let name = &rest[..close];
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+    let mut rest = &rest[close + 1..];
+    let fold = rest.chars().next().filter(|c| matches!(c, '+' | '-'));
+    if fold.is_some() {
+        rest = &rest[1..];
+    }
+    let custom = rest.trim();
+    let title = if custom.is_empty() {
+        let mut chars = name.chars();
+        chars.next().map_or_else(String::new, |c| {
+            c.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase()
+        })
+    } else {
+        custom.to_string()
+    };
+    Some(AlertHeader {
+        kind: AlertKind::from_marker(name),
+        title,
+        fold,
+    })
+}
+
+/// Render a block quote that starts with an alert marker.
+///
+/// The marker line becomes an icon + title line; the rest of the quote is
+/// rendered behind a heavy gutter. Folded Obsidian callouts (`-`) still show
+/// their body since the viewer has no per-block expand action; the fold
+/// state is only reflected in the title glyph.
+fn render_alert<'a>(
+    node: &'a AstNode<'a>,
+    header: &AlertHeader,
+    lines: &mut Vec<RenderedLine>,
+    link_refs: &mut Vec<LinkRef>,
+    images: &mut Vec<ImageRef>,
+    wrap_width: usize,
+) {
+    let line_type = LineType::Alert(header.kind);
+    let fold = match header.fold {
+        Some('-') => " ▸",
+        Some(_) => " ▾",
+        None => "",
+    };
+    let title = format!("{} {}{fold}", header.kind.icon(), header.title);
+    let title_spans = vec![
+        InlineSpan::new(ALERT_GUTTER.to_string(), InlineStyle::default()),
+        InlineSpan::new(
+            title,
+            InlineStyle {
+                strong: true,
+                ..InlineStyle::default()
+            },
+        ),
+    ];
+    let mut title_line = RenderedLine::with_spans(
+        spans_to_string(&title_spans),
+        LineType::AlertTitle(header.kind),
+        title_spans,
+    );
+    if let Some(range) = node_source_lines(node) {
+        title_line = title_line.with_source_range(range.start..range.start + 1);
+    }
+    lines.push(title_line);
+
+    let mut children = node.children();
+    if let Some(first) = children.next() {
+        let body_start = lines.len();
+        // The first paragraph's opening line is the marker; keep what follows.
+        let mut spans = Vec::new();
+        let mut past_marker = false;
+        for inline in first.children() {
+            if past_marker {
+                collect_inline_spans_recursive(inline, InlineStyle::default(), &mut spans);
+            } else if matches!(
+                inline.data.borrow().value,
+                NodeValue::SoftBreak | NodeValue::LineBreak
+            ) {
+                past_marker = true;
             }
         }
+        if !spans.is_empty() {
+            push_wrapped_quote_spans(
+                first,
+                &spans,
+                lines,
+                link_refs,
+                images,
+                wrap_width,
+                ALERT_GUTTER,
+                line_type,
+            );
+        }
+        if let Some(range) = node_source_lines(first) {
+            fill_source_range(&mut lines[body_start..], &range);
+        }
+    }
+    for child in children {
+        render_quote_child(
+            child,
+            lines,
+            link_refs,
+            images,
+            wrap_width,
+            1,
+            ALERT_GUTTER,
+            line_type,
+        );
     }
 }
 
@@ -792,6 +1219,7 @@ fn render_table<'a>(
         mut rows,
         has_header,
         row_link_indices: row_links,
+        row_sources,
     } = collect_table_rows(table_node, link_refs, images);
     if rows.is_empty() {
         return Vec::new();
@@ -823,13 +1251,23 @@ fn render_table<'a>(
     let mut lines = Vec::new();
     for (idx, row) in rows.iter().enumerate() {
         let output_line = lines.len();
-        lines.push(render_table_row(row, &col_widths, &alignments));
+        let source = row_sources.get(idx).cloned().flatten();
+        let mut line = render_table_row(row, &col_widths, &alignments);
+        if let Some(range) = &source {
+            line = line.with_source_range(range.clone());
+        }
+        lines.push(line);
         // Assign the correct output line to links collected from this row
         for link_idx in &row_links[idx] {
             link_refs[*link_idx].line = output_line;
         }
         if has_header && idx == 0 {
-            lines.push(RenderedLine::new(mid.clone(), LineType::Table));
+            // The delimiter row sits right below the header row.
+            let mut divider = RenderedLine::new(mid.clone(), LineType::Table);
+            if let Some(range) = source {
+                divider = divider.with_source_range(range.end..range.end + 1);
+            }
+            lines.push(divider);
         }
     }
     lines
@@ -841,6 +1279,8 @@ struct CollectedTableRows {
     has_header: bool,
     /// For each row, the indices into `link_refs` that belong to that row.
     row_link_indices: Vec<Vec<usize>>,
+    /// For each row, the source lines it was parsed from.
+    row_sources: Vec<Option<Range<usize>>>,
 }
 
 fn collect_table_rows<'a>(
@@ -856,6 +1296,7 @@ fn collect_table_rows<'a>(
     let mut rows = Vec::new();
     let mut has_header = false;
     let mut row_links: Vec<Vec<usize>> = Vec::new();
+    let mut row_sources = Vec::new();
     for row_node in table_node.children() {
         let is_header_row = matches!(row_node.data.borrow().value, NodeValue::TableRow(true));
         if is_header_row {
@@ -883,6 +1324,7 @@ fn collect_table_rows<'a>(
         }
         rows.push(row_cells);
         row_links.push(this_row_link_indices);
+        row_sources.push(node_source_lines(row_node));
     }
 
     CollectedTableRows {
@@ -890,6 +1332,7 @@ fn collect_table_rows<'a>(
         rows,
         has_header,
         row_link_indices: row_links,
+        row_sources,
     }
 }
 
@@ -1012,7 +1455,8 @@ fn render_csv_as_table(csv_content: &str) -> Vec<RenderedLine> {
     lines
 }
 
-fn display_width(text: &str) -> usize {
+/// Terminal display width of `text`, used to size table columns.
+pub fn display_width(text: &str) -> usize {
     UnicodeWidthStr::width(text)
 }
 
@@ -1099,7 +1543,7 @@ fn render_footnote_reference(name: &str) -> String {
     format!("[^{name}]")
 }
 
-fn map_script_chars(text: &str, superscript: bool) -> Option<String> {
+pub fn map_script_chars(text: &str, superscript: bool) -> Option<String> {
     let mut mapped = String::new();
     for ch in text.chars() {
         let mapped_char = if superscript {
@@ -1196,6 +1640,22 @@ const fn subscript_char(ch: char) -> Option<char> {
     }
 }
 
+/// Text content as GitHub sees it for slugging: code and math literals
+/// without markup, no superscript or footnote rendering.
+fn plain_text<'a>(node: &'a AstNode<'a>) -> String {
+    let mut text = String::new();
+    for child in node.descendants().skip(1) {
+        match &child.data.borrow().value {
+            NodeValue::Text(t) => text.push_str(t),
+            NodeValue::Code(code) => text.push_str(&code.literal),
+            NodeValue::Math(math) => text.push_str(&math.literal),
+            NodeValue::SoftBreak | NodeValue::LineBreak => text.push(' '),
+            _ => {}
+        }
+    }
+    text
+}
+
 fn extract_text<'a>(node: &'a AstNode<'a>) -> String {
     let mut text = String::new();
     extract_text_recursive(node, &mut text);
@@ -1269,6 +1729,9 @@ fn collect_inline_spans_recursive<'a>(
                 collect_inline_spans_recursive(child, next, spans);
             }
         }
+        NodeValue::Math(math) => {
+            spans.push(InlineSpan::new(tex_to_unicode(&math.literal), style));
+        }
         NodeValue::FootnoteReference(reference) => {
             spans.push(InlineSpan::new(
                 render_footnote_reference(&reference.name),
@@ -1326,6 +1789,9 @@ fn extract_text_recursive<'a>(node: &'a AstNode<'a>, text: &mut String) {
             }
             text.push_str(&render_subscript_text(&inner));
         }
+        NodeValue::Math(math) => {
+            text.push_str(&tex_to_unicode(&math.literal));
+        }
         NodeValue::FootnoteReference(reference) => {
             text.push_str(&render_footnote_reference(&reference.name));
         }
@@ -1498,6 +1964,66 @@ fn truncate_spans(spans: &[InlineSpan], max_len: usize) -> Vec<InlineSpan> {
     out
 }
 
+/// The TeX source of a paragraph that holds nothing but one `$$...$$` block.
+fn display_math_literal<'a>(node: &'a AstNode<'a>) -> Option<String> {
+    let mut literal = None;
+    for child in node.children() {
+        match &child.data.borrow().value {
+            NodeValue::Math(math) if math.display_math && literal.is_none() => {
+                literal = Some(math.literal.clone());
+            }
+            NodeValue::SoftBreak | NodeValue::LineBreak => {}
+            NodeValue::Text(t) if t.trim().is_empty() => {}
+            _ => return None,
+        }
+    }
+    literal
+}
+
+/// Render a display math block.
+///
+/// Display math is rasterized under the same condition as mermaid diagrams
+/// (a real graphics protocol is available); the placeholder line carries
+/// the Unicode form so it doubles as the fallback. Otherwise the Unicode
+/// rows are drawn indented like a block.
+fn render_display_math<S: BuildHasher>(ctx: &mut ParseContext<'_, S>, tex: &str) {
+    const MATH_INDENT: &str = "    ";
+    let rows = tex_to_unicode_lines(tex);
+
+    if ctx.mermaid_as_images {
+        let key = format!("math://{}", ctx.math_sources.len());
+        ctx.math_sources.insert(key.clone(), tex.trim().to_string());
+        let height_lines = ctx.image_heights.get(&key).copied().unwrap_or(1).max(1);
+        let start_line = ctx.lines.len();
+        ctx.lines.push(RenderedLine::new(
+            format!("{MATH_INDENT}{}", rows.join(" ")),
+            LineType::Image,
+        ));
+        for _ in 1..height_lines {
+            ctx.lines
+                .push(RenderedLine::new(String::new(), LineType::Image));
+        }
+        ctx.images.push(ImageRef {
+            alt: "math".to_string(),
+            src: key,
+            line_range: start_line..ctx.lines.len(),
+        });
+        return;
+    }
+
+    for row in rows {
+        let spans = vec![InlineSpan::new(row, InlineStyle::default())];
+        for line_spans in wrap_spans(&spans, ctx.wrap_width, MATH_INDENT, MATH_INDENT) {
+            let content = spans_to_string(&line_spans);
+            ctx.lines.push(RenderedLine::with_spans(
+                content,
+                LineType::Paragraph,
+                line_spans,
+            ));
+        }
+    }
+}
+
 /// Collect images from a paragraph node, returning (alt, src) pairs.
 fn collect_paragraph_images<'a>(node: &'a AstNode<'a>) -> Vec<(String, String)> {
     let mut images = Vec::new();
@@ -1727,6 +2253,45 @@ fn extract_html_images(html: &str) -> Vec<(String, String)> {
     results
 }
 
+/// Names of `<a name="...">` / `<a id="...">` anchors in an HTML fragment.
+fn extract_html_anchors(html: &str) -> Vec<String> {
+    let mut results = Vec::new();
+    let lower = html.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(pos) = lower[search..].find("<a") {
+        let abs = search + pos;
+        let Some(end) = html[abs..].find('>') else {
+            break;
+        };
+        let tag_end = abs + end + 1;
+        let tag = &html[abs..tag_end];
+        if tag[2..].starts_with(char::is_whitespace)
+            && let Some(name) =
+                extract_html_attr(tag, "name").or_else(|| extract_html_attr(tag, "id"))
+            && !name.is_empty()
+        {
+            results.push(name);
+        }
+        search = tag_end;
+    }
+    results
+}
+
+/// Record inline HTML anchors inside `node` as pointing at `line`.
+fn register_html_anchors<'a>(
+    node: &'a AstNode<'a>,
+    line: usize,
+    anchors: &mut HashMap<String, usize>,
+) {
+    for child in node.descendants() {
+        if let NodeValue::HtmlInline(html) = &child.data.borrow().value {
+            for name in extract_html_anchors(html) {
+                anchors.entry(name).or_insert(line);
+            }
+        }
+    }
+}
+
 /// Extract the value of an HTML attribute from a tag string.
 fn extract_html_attr(tag: &str, attr_name: &str) -> Option<String> {
     let lower = tag.to_ascii_lowercase();
@@ -3147,4 +3712,397 @@ mod tests {
                 .contains("Emacs-rust-eglot-markdown.png")
         );
     }
+
+    #[test]
+    fn test_yaml_front_matter_renders_panel() {
+        let md = "---\ntitle: Plan\nstatus: draft\n---\n\n# Intro\n";
+        let doc = Document::parse(md).unwrap();
+        let fm = doc.front_matter().expect("front matter should be parsed");
+        assert_eq!(fm.get("status"), Some("draft"));
+
+        let lines: Vec<_> = (0..doc.line_count())
+            .filter_map(|i| doc.line_at(i))
+            .collect();
+        assert_eq!(*lines[0].line_type(), LineType::FrontMatter);
+        assert!(lines[0].content().contains("Front matter (YAML)"));
+        assert!(
+            lines.iter().any(|l| l.content() == "  title  │ Plan"),
+            "expected aligned key/value row"
+        );
+        assert!(
+            !lines
+                .iter()
+                .any(|l| *l.line_type() == LineType::HorizontalRule),
+            "front matter should not render as a rule"
+        );
+    }
+
+    #[test]
+    fn test_toml_front_matter_is_detected() {
+        let md = "+++\ntitle = \"Hugo\"\n+++\n\nBody\n";
+        let doc = Document::parse(md).unwrap();
+        let fm = doc.front_matter().expect("front matter should be parsed");
+        assert_eq!(fm.title(), Some("Hugo"));
+        assert!(!doc.source().is_empty());
+        let text: Vec<_> = (0..doc.line_count())
+            .filter_map(|i| doc.line_at(i))
+            .map(RenderedLine::content)
+            .collect();
+        assert!(!text.iter().any(|l| l.contains("+++")));
+    }
+
+    #[test]
+    fn test_front_matter_title_is_toc_root() {
+        let md = "---\ntitle: Guide\n---\n\n# One\n\n## Two\n";
+        let doc = Document::parse(md).unwrap();
+        let headings = doc.headings();
+        assert_eq!(headings[0].level, 0);
+        assert_eq!(headings[0].text, "Guide");
+        assert_eq!(headings[0].line, 0);
+        assert_eq!(headings[1].text, "One");
+    }
+
+    #[test]
+    fn test_front_matter_without_title_adds_no_heading() {
+        let md = "---\nauthor: Jane\n---\n\n# One\n";
+        let doc = Document::parse(md).unwrap();
+        assert_eq!(doc.headings().len(), 1);
+        assert_eq!(doc.headings()[0].text, "One");
+    }
+
+    #[test]
+    fn test_collapsed_front_matter_is_single_line() {
+        let md = "---\ntitle: Plan\nstatus: draft\n---\n\nBody\n";
+        let doc = Document::parse_with_all_options(md, 80, &HashMap::new(), false, true).unwrap();
+        let front: Vec<_> = (0..doc.line_count())
+            .filter_map(|i| doc.line_at(i))
+            .filter(|l| *l.line_type() == LineType::FrontMatter)
+            .collect();
+        assert_eq!(front.len(), 1);
+        assert!(front[0].content().contains("2 fields"));
+    }
+
+    #[test]
+    fn test_front_matter_long_value_wraps_under_value_column() {
+        let md = "---\nsummary: one two three four five six seven\n---\n";
+        let doc = Document::parse_with_layout(md, 30).unwrap();
+        let rows: Vec<_> = (0..doc.line_count())
+            .filter_map(|i| doc.line_at(i))
+            .filter(|l| *l.line_type() == LineType::FrontMatter)
+            .skip(1)
+            .map(|l| l.content().to_string())
+            .collect();
+        assert!(rows.len() > 1, "value should wrap: {rows:?}");
+        assert!(rows[0].starts_with("  summary │ one"));
+        assert!(rows[1].starts_with("          │ "));
+    }
+
+    #[test]
+    fn test_leading_thematic_break_without_close_is_not_front_matter() {
+        let md = "---\n\nJust text\n";
+        let doc = Document::parse(md).unwrap();
+        assert!(doc.front_matter().is_none());
+    }
+
+    #[test]
+    fn test_leading_thematic_breaks_around_text_are_not_front_matter() {
+        let md = "---\n\nJust text\n\n---\n\nMore\n";
+        let doc = Document::parse(md).unwrap();
+        assert!(doc.front_matter().is_none());
+        let lines: Vec<_> = (0..doc.line_count())
+            .filter_map(|i| doc.line_at(i))
+            .collect();
+        assert!(lines.iter().any(|l| l.content() == "Just text"));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|l| *l.line_type() == LineType::HorizontalRule)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_github_alert_kinds() {
+        for (marker, kind, title) in [
+            ("NOTE", AlertKind::Note, "Note"),
+            ("TIP", AlertKind::Tip, "Tip"),
+            ("IMPORTANT", AlertKind::Important, "Important"),
+            ("WARNING", AlertKind::Warning, "Warning"),
+            ("CAUTION", AlertKind::Caution, "Caution"),
+        ] {
+            let md = format!("> [!{marker}]\n> Body text\n");
+            let doc = parse(&md).unwrap();
+            let lines = doc.visible_lines(0, 20);
+            let alert: Vec<_> = lines
+                .iter()
+                .filter(|l| {
+                    *l.line_type() == LineType::AlertTitle(kind)
+                        || *l.line_type() == LineType::Alert(kind)
+                })
+                .collect();
+            assert_eq!(alert.len(), 2, "{marker}: {alert:?}");
+            assert_eq!(*alert[0].line_type(), LineType::AlertTitle(kind));
+            assert_eq!(
+                alert[0].content(),
+                format!("{ALERT_GUTTER}{} {title}", kind.icon())
+            );
+            assert_eq!(alert[1].content(), format!("{ALERT_GUTTER}Body text"));
+            assert!(!lines.iter().any(|l| l.content().contains("[!")));
+        }
+    }
+
+    #[test]
+    fn test_obsidian_callout_title_and_fold() {
+        let doc = parse("> [!custom]- Why though?\n> Because.\n").unwrap();
+        let lines = doc.visible_lines(0, 20);
+        let title = lines
+            .iter()
+            .find(|l| matches!(l.line_type(), LineType::AlertTitle(_)))
+            .unwrap();
+        assert_eq!(*title.line_type(), LineType::AlertTitle(AlertKind::Note));
+        assert!(title.content().ends_with("Why though? ▸"));
+        assert!(lines.iter().any(|l| l.content().ends_with("Because.")));
+    }
+
+    #[test]
+    fn test_callout_aliases_map_to_kinds() {
+        let doc = parse("> [!danger]+\n> Careful\n").unwrap();
+        let lines = doc.visible_lines(0, 20);
+        assert!(lines.iter().any(|l| {
+            *l.line_type() == LineType::AlertTitle(AlertKind::Caution)
+                && l.content().ends_with(" ▾")
+        }));
+    }
+
+    #[test]
+    fn test_alert_keeps_links_and_later_blocks() {
+        let md = "> [!TIP]\n> See [docs](https://docs.rs)\n>\n> Second paragraph\n";
+        let doc = parse(md).unwrap();
+        let lines = doc.visible_lines(0, 20);
+        assert!(lines.iter().any(|l| {
+            *l.line_type() == LineType::Alert(AlertKind::Tip)
+                && l.content().ends_with("Second paragraph")
+        }));
+        let link = doc
+            .links()
+            .iter()
+            .find(|l| l.url == "https://docs.rs")
+            .unwrap();
+        assert!(lines[link.line].content().contains("docs"));
+    }
+
+    #[test]
+    fn test_plain_blockquote_is_not_an_alert() {
+        let doc = parse("> [not an alert]\n> text\n").unwrap();
+        let lines = doc.visible_lines(0, 20);
+        assert!(lines.iter().any(|l| *l.line_type() == LineType::BlockQuote));
+        assert!(
+            !lines
+                .iter()
+                .any(|l| matches!(l.line_type(), LineType::Alert(_) | LineType::AlertTitle(_)))
+        );
+    }
+
+    #[test]
+    fn test_inline_math_renders_as_unicode() {
+        let doc = parse("Energy $E = mc^2$ with $\\alpha \\leq \\frac{1}{2}$.").unwrap();
+        let lines = doc.visible_lines(0, 10);
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.content() == "Energy E = mc² with α ≤ 1⁄2."),
+            "{lines:?}"
+        );
+    }
+
+    #[test]
+    fn test_lone_dollar_signs_are_not_math() {
+        let doc = parse("Costs $5 and $6 today.").unwrap();
+        let lines = doc.visible_lines(0, 10);
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.content() == "Costs $5 and $6 today.")
+        );
+    }
+
+    #[test]
+    fn test_display_math_falls_back_to_indented_unicode() {
+        let doc = parse("$$\n\\sqrt{\\pi} \\approx 1.77\n$$\n").unwrap();
+        let lines = doc.visible_lines(0, 10);
+        assert!(lines.iter().any(|l| l.content() == "    √π ≈ 1.77"));
+        assert!(doc.images().is_empty());
+        assert!(doc.math_sources().is_empty());
+    }
+
+    #[test]
+    fn test_display_math_becomes_image_with_graphics() {
+        let md = "$$\n\\frac{a}{b}\n$$\n\nafter";
+        let doc = Document::parse_with_mermaid_images(md, 80).unwrap();
+        assert_eq!(doc.images().len(), 1);
+        let image = &doc.images()[0];
+        assert_eq!(image.src, "math://0");
+        assert_eq!(doc.math_sources()["math://0"], "\\frac{a}{b}");
+        // The placeholder carries the Unicode form until the image loads.
+        let placeholder = doc.line_at(image.line_range.start).unwrap();
+        assert_eq!(placeholder.content(), "    a⁄b");
+    }
+
+    #[test]
+    fn test_display_math_with_image_heights_reserves_lines() {
+        let mut heights = HashMap::new();
+        heights.insert("math://0".to_string(), 3);
+        let doc = Document::parse_with_all_options("$$x$$\n", 80, &heights, true, false).unwrap();
+        assert_eq!(doc.images()[0].line_range.len(), 3);
+    }
+
+    #[test]
+    fn test_heading_ids_are_github_slugs() {
+        let doc =
+            parse("# Getting Started\n\n## Usage\n\n## Usage\n\n## `cargo` & Café\n").unwrap();
+        let ids: Vec<_> = doc
+            .headings()
+            .iter()
+            .map(|h| h.id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["getting-started", "usage", "usage-1", "cargo--café"]);
+    }
+
+    #[test]
+    fn test_heading_custom_id_is_used_and_hidden() {
+        let doc = parse("## Installation {#setup}\n").unwrap();
+        let heading = &doc.headings()[0];
+        assert_eq!(heading.text, "Installation");
+        assert_eq!(heading.id.as_deref(), Some("setup"));
+        assert_eq!(
+            doc.line_at(heading.line).unwrap().content(),
+            "## Installation"
+        );
+        assert_eq!(doc.resolve_internal_anchor("setup"), Some(heading.line));
+    }
+
+    #[test]
+    fn test_resolve_anchor_picks_duplicate_heading() {
+        let doc = parse("## Usage\n\ntext\n\n## Usage\n\nmore\n").unwrap();
+        let second = doc.headings()[1].line;
+        assert_eq!(doc.resolve_internal_anchor("usage-1"), Some(second));
+        assert_eq!(
+            doc.resolve_internal_anchor("user-content-usage-1"),
+            Some(second)
+        );
+        assert_eq!(doc.resolve_internal_anchor("Usage-1"), Some(second));
+    }
+
+    #[test]
+    fn test_resolve_percent_encoded_unicode_anchor() {
+        let doc = parse("## Café Über\n").unwrap();
+        let line = doc.headings()[0].line;
+        assert_eq!(
+            doc.resolve_internal_anchor("caf%C3%A9-%C3%BCber"),
+            Some(line)
+        );
+    }
+
+    #[test]
+    fn test_html_name_anchors_resolve() {
+        let md = "# Top\n\n<a name=\"legacy\"></a>\n\nSee <a id=\"inline\"></a>here.\n\n## Old <a name=\"old-name\"></a>Title\n";
+        let doc = parse(md).unwrap();
+        let inline_line = doc
+            .visible_lines(0, 30)
+            .iter()
+            .position(|l| l.content().contains("here."))
+            .unwrap();
+        assert_eq!(doc.resolve_internal_anchor("inline"), Some(inline_line));
+        assert!(doc.resolve_internal_anchor("legacy").is_some());
+        let title = doc.headings()[1].line;
+        assert_eq!(doc.resolve_internal_anchor("old-name"), Some(title));
+        assert_eq!(doc.headings()[1].id.as_deref(), Some("old-title"));
+    }
+
+    fn source_ranges(doc: &Document) -> Vec<(String, Option<Range<usize>>)> {
+        doc.visible_lines(0, doc.line_count())
+            .iter()
+            .map(|l| (l.content().to_string(), l.source_range().cloned()))
+            .collect()
+    }
+
+    fn range_of(lines: &[(String, Option<Range<usize>>)], needle: &str) -> Option<Range<usize>> {
+        lines
+            .iter()
+            .find(|(content, _)| content.contains(needle))
+            .and_then(|(_, range)| range.clone())
+    }
+
+    #[test]
+    fn test_every_rendered_line_has_a_source_range() {
+        let md = "# Title\n\nSome paragraph\nspanning lines.\n\n- one\n- two\n\n> quote\n\n---\n";
+        let doc = parse(md).unwrap();
+        for (content, range) in source_ranges(&doc) {
+            assert!(range.is_some(), "no source range for {content:?}");
+        }
+    }
+
+    #[test]
+    fn test_source_ranges_follow_blocks() {
+        let md = "# Title\n\nFirst line\nsecond line.\n\n- one\n- two\n\n> quoted\n";
+        let lines = source_ranges(&parse(md).unwrap());
+        assert_eq!(range_of(&lines, "# Title"), Some(0..1));
+        assert_eq!(range_of(&lines, "First line"), Some(2..4));
+        assert_eq!(range_of(&lines, "• one"), Some(5..6));
+        assert_eq!(range_of(&lines, "• two"), Some(6..7));
+        assert_eq!(range_of(&lines, "quoted"), Some(8..9));
+    }
+
+    #[test]
+    fn test_wrapped_paragraph_lines_share_source_range() {
+        let md = "Intro\n\nalpha beta gamma delta epsilon zeta eta theta iota kappa\n";
+        let doc = parse_with_layout(md, 20, &HashMap::new()).unwrap();
+        let lines = source_ranges(&doc);
+        let wrapped: Vec<_> = lines
+            .iter()
+            .filter(|(content, _)| !content.is_empty() && content != "Intro")
+            .collect();
+        assert!(wrapped.len() > 1);
+        assert!(wrapped.iter().all(|(_, range)| *range == Some(2..3)));
+    }
+
+    #[test]
+    fn test_code_block_lines_map_to_their_source_lines() {
+        let md = "Text\n\n```rust\nfn main() {}\nlet x = 1;\n```\n";
+        let lines = source_ranges(&parse(md).unwrap());
+        assert_eq!(range_of(&lines, "┌ rust"), Some(2..3));
+        assert_eq!(range_of(&lines, "fn main()"), Some(3..4));
+        assert_eq!(range_of(&lines, "let x = 1;"), Some(4..5));
+        assert_eq!(range_of(&lines, "└"), Some(5..6));
+    }
+
+    #[test]
+    fn test_table_rows_map_to_their_source_lines() {
+        let md = "| a | b |\n|---|---|\n| 1 | 2 |\n| 3 | 4 |\n";
+        let lines = source_ranges(&parse(md).unwrap());
+        assert_eq!(range_of(&lines, " a │ b"), Some(0..1));
+        assert_eq!(range_of(&lines, "┼"), Some(1..2));
+        assert_eq!(range_of(&lines, " 1 │ 2"), Some(2..3));
+        assert_eq!(range_of(&lines, " 3 │ 4"), Some(3..4));
+    }
+
+    #[test]
+    fn test_source_ranges_skip_front_matter() {
+        let md = "---\ntitle: Doc\n---\n\n# Heading\n";
+        let lines = source_ranges(&parse(md).unwrap());
+        assert_eq!(range_of(&lines, "Front matter"), Some(0..4));
+        assert_eq!(range_of(&lines, "# Heading"), Some(4..5));
+    }
+
+    #[test]
+    fn test_rendered_line_for_source_skips_spacers() {
+        let md = "Intro\n\n\n## Next\n\nBody\n";
+        let doc = parse(md).unwrap();
+        let heading = doc.headings()[0].line;
+        assert_eq!(doc.rendered_line_for_source(3), Some(heading));
+        // A blank source line resolves to the block after it.
+        assert_eq!(doc.rendered_line_for_source(2), Some(heading));
+        assert_eq!(doc.source_line_at(heading), Some(3));
+    }
 }
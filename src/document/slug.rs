@@ -0,0 +1,128 @@
+//! GitHub-compatible heading anchors.
+//!
+//! Mirrors `github-slugger`: lowercase the heading text, drop everything
+//! that is not a letter, number, mark, `_`, `-` or space, turn spaces into
+//! dashes, and suffix repeats with `-1`, `-2`, ...
+
+use std::collections::HashMap;
+
+use unicode_properties::{GeneralCategoryGroup, UnicodeGeneralCategory};
+
+/// Slug for a single heading, without duplicate handling.
+pub fn github_slug(text: &str) -> String {
+    text.trim()
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() || is_mark(c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Marks (`\p{M}`): diacritics, viramas and vowel signs, which GitHub keeps.
+fn is_mark(c: char) -> bool {
+    c.general_category_group() == GeneralCategoryGroup::Mark
+}
+
+/// Hands out unique slugs for the headings of one document.
+#[derive(Debug, Default)]
+pub struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    /// Slug for the next heading with this text.
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = github_slug(text);
+        let mut slug = base.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self.occurrences.entry(base.clone()).or_default();
+            *count += 1;
+            slug = format!("{base}-{count}");
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+
+    /// Mark an explicit id as taken so generated slugs avoid it.
+    pub fn reserve(&mut self, id: &str) {
+        self.occurrences.entry(id.to_string()).or_default();
+    }
+}
+
+/// Split a trailing `{#custom-id}` attribute off heading text.
+///
+/// Returns the text without the attribute and the id, if one was present.
+pub fn split_custom_id(text: &str) -> (&str, Option<&str>) {
+    let trimmed = text.trim_end();
+    let Some(inner) = trimmed.strip_suffix('}') else {
+        return (text, None);
+    };
+    let Some(start) = inner.rfind("{#") else {
+        return (text, None);
+    };
+    let id = &inner[start + 2..];
+    if id.is_empty() || id.contains(char::is_whitespace) {
+        return (text, None);
+    }
+    (inner[..start].trim_end(), Some(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_slug_basics() {
+        assert_eq!(github_slug("Hello World"), "hello-world");
+        assert_eq!(github_slug("What's new in v2.0?"), "whats-new-in-v20");
+        assert_eq!(
+            github_slug("snake_case & kebab-case"),
+            "snake_case--kebab-case"
+        );
+        assert_eq!(github_slug("  Trim me  "), "trim-me");
+    }
+
+    #[test]
+    fn test_github_slug_unicode() {
+        assert_eq!(github_slug("Café Über"), "café-über");
+        assert_eq!(github_slug("日本語 見出し"), "日本語-見出し");
+        assert_eq!(github_slug("Rocket 🚀 launch"), "rocket--launch");
+    }
+
+    #[test]
+    fn test_github_slug_keeps_indic_marks() {
+        assert_eq!(github_slug("हिन्दी शीर्षक"), "हिन्दी-शीर्षक");
+        assert_eq!(github_slug("ภาษาไทย"), "ภาษาไทย");
+        assert_eq!(github_slug("বাংলা শিরোনাম"), "বাংলা-শিরোনাম");
+    }
+
+    #[test]
+    fn test_slugger_suffixes_duplicates() {
+        let mut slugger = Slugger::default();
+        assert_eq!(slugger.slug("Usage"), "usage");
+        assert_eq!(slugger.slug("Usage"), "usage-1");
+        assert_eq!(slugger.slug("Usage"), "usage-2");
+        assert_eq!(slugger.slug("Usage 1"), "usage-1-1");
+    }
+
+    #[test]
+    fn test_slugger_avoids_reserved_ids() {
+        let mut slugger = Slugger::default();
+        slugger.reserve("intro");
+        assert_eq!(slugger.slug("Intro"), "intro-1");
+    }
+
+    #[test]
+    fn test_split_custom_id() {
+        assert_eq!(
+            split_custom_id("Install {#setup}"),
+            ("Install", Some("setup"))
+        );
+        assert_eq!(split_custom_id("Set {a, b}"), ("Set {a, b}", None));
+        assert_eq!(split_custom_id("Plain"), ("Plain", None));
+    }
+}
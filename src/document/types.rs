@@ -1,7 +1,9 @@
 //! Core document types.
 
 use std::collections::HashMap;
-use std::ops::Range;
+use std::ops::{Range, RangeInclusive};
+
+use super::front_matter::FrontMatter;
 
 /// Result of parsing markdown, ready to be assembled into a `Document`.
 #[derive(Debug, Clone, Default)]
@@ -20,6 +22,12 @@ pub struct ParsedDocument {
     pub code_blocks: Vec<CodeBlockRef>,
     /// Mermaid diagram sources keyed by synthetic image src
     pub mermaid_sources: HashMap<String, String>,
+    /// Display math TeX sources keyed by synthetic image src
+    pub math_sources: HashMap<String, String>,
+    /// Explicit `<a name>` / `<a id>` anchors and the line they point at
+    pub anchors: HashMap<String, usize>,
+    /// YAML/TOML metadata from the top of the document
+    pub front_matter: Option<FrontMatter>,
 }
 
 /// Backing store for lazy hex dump rendering.
@@ -52,6 +60,12 @@ pub struct Document {
     code_blocks: Vec<CodeBlockRef>,
     /// Mermaid diagram sources keyed by synthetic image src (e.g. `mermaid://0`)
     mermaid_sources: HashMap<String, String>,
+    /// Display math TeX sources keyed by synthetic image src (e.g. `math://0`)
+    math_sources: HashMap<String, String>,
+    /// Explicit `<a name>` / `<a id>` anchors and the line they point at
+    anchors: HashMap<String, usize>,
+    /// YAML/TOML metadata from the top of the document
+    front_matter: Option<FrontMatter>,
     /// Optional hex data for lazy binary file rendering
     hex_data: Option<HexData>,
 }
@@ -68,6 +82,9 @@ impl Document {
             footnotes: HashMap::new(),
             code_blocks: Vec::new(),
             mermaid_sources: HashMap::new(),
+            math_sources: HashMap::new(),
+            anchors: HashMap::new(),
+            front_matter: None,
             hex_data: None,
         }
     }
@@ -79,7 +96,11 @@ impl Document {
     pub fn from_plain_text(source: &str) -> Self {
         let lines: Vec<RenderedLine> = source
             .lines()
-            .map(|line| RenderedLine::new(line.to_string(), LineType::Paragraph))
+            .enumerate()
+            .map(|(idx, line)| {
+                RenderedLine::new(line.to_string(), LineType::Paragraph)
+                    .with_source_range(idx..idx + 1)
+            })
             .collect();
         Self {
             source: source.to_string(),
@@ -90,6 +111,9 @@ impl Document {
             footnotes: HashMap::new(),
             code_blocks: Vec::new(),
             mermaid_sources: HashMap::new(),
+            math_sources: HashMap::new(),
+            anchors: HashMap::new(),
+            front_matter: None,
             hex_data: None,
         }
     }
@@ -105,6 +129,9 @@ impl Document {
             footnotes: result.footnotes,
             code_blocks: result.code_blocks,
             mermaid_sources: result.mermaid_sources,
+            math_sources: result.math_sources,
+            anchors: result.anchors,
+            front_matter: result.front_matter,
             hex_data: None,
         }
     }
@@ -147,6 +174,9 @@ impl Document {
             footnotes: HashMap::new(),
             code_blocks: Vec::new(),
             mermaid_sources: HashMap::new(),
+            math_sources: HashMap::new(),
+            anchors: HashMap::new(),
+            front_matter: None,
             hex_data: Some(HexData {
                 bytes,
                 header_line_count,
@@ -187,29 +217,101 @@ impl Document {
         &self.mermaid_sources
     }
 
+    /// Get display math TeX sources keyed by synthetic image src.
+    pub const fn math_sources(&self) -> &HashMap<String, String> {
+        &self.math_sources
+    }
+
+    /// Get the parsed front matter, if the document has any.
+    pub const fn front_matter(&self) -> Option<&FrontMatter> {
+        self.front_matter.as_ref()
+    }
+
     pub fn footnote_line(&self, name: &str) -> Option<usize> {
         self.footnotes.get(name).copied()
     }
 
+    /// Resolve a `#fragment` to the line it points at.
+    ///
+    /// Tries, in order: an exact heading id (GitHub slug or `{#custom-id}`),
+    /// an HTML `<a name>` anchor, a case-insensitive id match, and finally a
+    /// loose match on heading text for slugs produced by other tools. The
+    /// fragment may be percent-encoded and may carry GitHub's
+    /// `user-content-` prefix.
     pub fn resolve_internal_anchor(&self, anchor: &str) -> Option<usize> {
-        let target = anchor.trim();
+        let decoded = percent_decode(anchor.trim());
+        let target = decoded.strip_prefix("user-content-").unwrap_or(&decoded);
         if target.is_empty() {
             return None;
         }
+        let by_id = |matches: &dyn Fn(&str) -> bool| {
+            self.headings
+                .iter()
+                .find(|h| h.id.as_deref().is_some_and(matches))
+                .map(|h| h.line)
+        };
+        let lower = target.to_lowercase();
         let normalized = normalize_anchor(target);
-        self.headings.iter().find_map(|h| {
-            if h.id.as_deref().is_some_and(|id| id == target) {
-                return Some(h.line);
+        by_id(&|id| id == target)
+            .or_else(|| self.anchors.get(target).copied())
+            .or_else(|| by_id(&|id| id.to_lowercase() == lower))
+            .or_else(|| {
+                self.headings
+                    .iter()
+                    .find(|h| normalize_anchor(&h.text) == normalized)
+                    .map(|h| h.line)
+            })
+    }
+
+    /// Source line (0-based) that the rendered line at `index` came from.
+    ///
+    /// Lines without a mapping use the nearest mapped line below, then above.
+    pub fn source_line_at(&self, index: usize) -> Option<usize> {
+        let index = index.min(self.lines.len().checked_sub(1)?);
+        self.lines[index..]
+            .iter()
+            .chain(self.lines[..index].iter().rev())
+            .find_map(|line| line.source_range().map(|range| range.start))
+    }
+
+    /// First rendered line drawn from `source_line` (0-based).
+    ///
+    /// Blank spacer lines are skipped. Source lines that render to nothing
+    /// (blank lines, link definitions) resolve to the next block, and lines
+    /// past the end resolve to the last mapped line.
+    pub fn rendered_line_for_source(&self, source_line: usize) -> Option<usize> {
+        let mut next_block = None;
+        for (idx, line) in self.lines.iter().enumerate() {
+            let Some(range) = line.source_range() else {
+                continue;
+            };
+            if matches!(line.line_type(), LineType::Empty) {
+                continue;
+            }
+            if range.contains(&source_line) {
+                return Some(idx);
             }
-            let slug = normalize_anchor(&h.text);
-            if slug == normalized {
-                Some(h.line)
-            } else {
-                None
+            if next_block.is_none() && range.start >= source_line {
+                next_block = Some(idx);
             }
+        }
+        next_block.or_else(|| {
+            self.lines
+                .iter()
+                .rposition(|line| line.source_range().is_some())
         })
     }
 
+    /// Source line span covering the rendered lines in `range`.
+    pub fn source_range_for(&self, range: RangeInclusive<usize>) -> Option<Range<usize>> {
+        let (start, end) = range
+            .filter_map(|idx| self.lines.get(idx)?.source_range())
+            .fold((usize::MAX, 0), |(start, end), r| {
+                (start.min(r.start), end.max(r.end))
+            });
+        (start < end).then_some(start..end)
+    }
+
     /// Get visible lines for rendering.
     ///
     /// Returns lines from `offset` to `offset + count`.
@@ -334,8 +436,7 @@ impl Document {
                 &block.raw_lines.join("\n"),
             );
 
-            for (line_idx, spans) in
-                (block.line_range.start..block.line_range.end).zip(highlighted.into_iter())
+            for (line_idx, spans) in (block.line_range.start..block.line_range.end).zip(highlighted)
             {
                 if line_idx >= self.lines.len() {
                     break;
@@ -353,8 +454,10 @@ impl Document {
                     InlineStyle::default(),
                 ));
                 let content = spans_to_string(&line_spans);
-                self.lines[line_idx] =
-                    RenderedLine::with_spans(content, LineType::CodeBlock, line_spans);
+                let mut line = RenderedLine::with_spans(content, LineType::CodeBlock, line_spans);
+                line.source_range
+                    .clone_from(&self.lines[line_idx].source_range);
+                self.lines[line_idx] = line;
             }
 
             block.highlighted = true;
@@ -413,6 +516,18 @@ impl RenderedLine {
         self
     }
 
+    /// Source line span (0-based, end exclusive) this line was rendered from.
+    pub const fn source_range(&self) -> Option<&Range<usize>> {
+        self.source_range.as_ref()
+    }
+
+    /// Record the source line span if none has been set yet.
+    pub fn fill_source_range(&mut self, range: &Range<usize>) {
+        if self.source_range.is_none() {
+            self.source_range = Some(range.clone());
+        }
+    }
+
     /// Get the text content.
     pub fn content(&self) -> &str {
         &self.content
@@ -498,20 +613,66 @@ pub enum LineType {
     HorizontalRule,
     /// Image placeholder
     Image,
+    /// Front matter metadata panel
+    FrontMatter,
+    /// GitHub alert / Obsidian callout title line
+    AlertTitle(AlertKind),
+    /// GitHub alert / Obsidian callout body line, behind the alert's gutter
+    Alert(AlertKind),
     /// Empty line
     Empty,
 }
 
+/// Kind of a GitHub alert (`> [!NOTE]`) or Obsidian callout.
+///
+/// Obsidian's extra callout types are folded into the closest GitHub kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AlertKind {
+    /// Map a marker name (case-insensitive) to an alert kind.
+    ///
+    /// Unknown names are treated as notes, matching Obsidian.
+    pub fn from_marker(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "tip" | "hint" | "success" | "check" | "done" => Self::Tip,
+            "important" | "question" | "help" | "faq" => Self::Important,
+            "warning" | "attention" => Self::Warning,
+            "caution" | "danger" | "error" | "failure" | "fail" | "missing" | "bug" => {
+                Self::Caution
+            }
+            _ => Self::Note,
+        }
+    }
+
+    /// Single-column icon shown before the title.
+    pub const fn icon(self) -> &'static str {
+        match self {
+            Self::Note => "ℹ",
+            Self::Tip => "★",
+            Self::Important => "‼",
+            Self::Warning => "⚠",
+            Self::Caution => "✖",
+        }
+    }
+}
+
 /// Reference to a heading in the document.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HeadingRef {
-    /// Heading level (1-6)
+    /// Heading level (1-6), or 0 for the front matter title
     pub level: u8,
     /// Heading text (plain, no formatting)
     pub text: String,
     /// Line number in rendered document
     pub line: usize,
-    /// Optional heading ID (for anchors)
+    /// Anchor id: the GitHub slug, or an explicit `{#custom-id}`
     pub id: Option<String>,
 }
 
@@ -549,6 +710,27 @@ fn spans_char_len(spans: &[InlineSpan]) -> usize {
     spans.iter().map(|s| s.text().chars().count()).sum()
 }
 
+/// Decode `%XX` escapes in a URL fragment, leaving malformed ones as-is.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| s.to_string())
+}
+
 fn normalize_anchor(s: &str) -> String {
     let mut out = String::new();
     let mut last_dash = false;
@@ -744,4 +926,11 @@ mod tests {
         // line_count should be stable
         assert_eq!(doc.line_count(), 5); // 4 header + 1 hex
     }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("a%zzb"), "a%zzb");
+    }
 }
@@ -1,4 +1,6 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hasher};
+use std::ops::Range;
 
 use ropey::Rope;
 
@@ -54,15 +56,53 @@ pub enum Direction {
     Right,
 }
 
+/// Maximum number of undo steps kept per buffer.
+const UNDO_LIMIT: usize = 1000;
+
+/// One reversible change: `removed` was replaced by `inserted` at `char_idx`.
+#[derive(Debug, Clone)]
+struct Edit {
+    char_idx: usize,
+    removed: String,
+    inserted: String,
+    /// Cursor before the change, restored on undo.
+    before: Cursor,
+    /// Cursor after the change, restored on redo.
+    after: Cursor,
+}
+
+/// A cursor motion that can also extend the selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Direction(Direction),
+    Home,
+    End,
+    WordLeft,
+    WordRight,
+    BufferStart,
+    BufferEnd,
+}
+
 /// A text buffer backed by a rope data structure.
 ///
 /// Provides efficient insertion, deletion, and line-based operations
 /// for editing text files. The cursor tracks the current editing position.
+/// Every edit is recorded so it can be undone and redone.
 pub struct EditorBuffer {
     rope: Rope,
     cursor: Cursor,
     /// Hash of the text content at the last save point (or initial load).
     clean_hash: u64,
+    undo_stack: VecDeque<Edit>,
+    redo_stack: Vec<Edit>,
+    /// Whether the top undo step is a typing run that may absorb more
+    /// characters. Cleared by cursor movement and non-typing edits.
+    typing: bool,
+    /// Fixed end of the selection; the cursor is the moving end.
+    selection_anchor: Option<Cursor>,
+    /// Lowest line changed since [`EditorBuffer::take_changed_from`] was
+    /// last called, so caches of per-line state can be invalidated.
+    changed_from: Option<usize>,
 }
 
 impl EditorBuffer {
@@ -74,6 +114,11 @@ impl EditorBuffer {
             rope,
             cursor: Cursor::new(),
             clean_hash,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            typing: false,
+            selection_anchor: None,
+            changed_from: None,
         }
     }
 
@@ -123,18 +168,36 @@ impl EditorBuffer {
         self.rope.to_string()
     }
 
-    /// Insert a character at the cursor position.
+    /// Insert a character at the cursor position, replacing any selection.
+    ///
+    /// Consecutive characters typed without moving the cursor form a
+    /// single undo step.
     pub fn insert_char(&mut self, ch: char) {
+        let before = self.cursor;
+        let removed = self.take_selection();
         let char_idx = self.cursor_char_idx();
         self.rope.insert_char(char_idx, ch);
         self.cursor.set_col(self.cursor.col + ch.len_utf8());
+        let typing = removed.is_empty();
+        self.record(
+            Edit {
+                char_idx,
+                removed,
+                inserted: ch.to_string(),
+                before,
+                after: self.cursor,
+            },
+            typing,
+        );
     }
 
-    /// Insert a string at the cursor position.
+    /// Insert a string at the cursor position, replacing any selection.
     pub fn insert_str(&mut self, s: &str) {
         if s.is_empty() {
             return;
         }
+        let before = self.cursor;
+        let removed = self.take_selection();
         let char_idx = self.cursor_char_idx();
         self.rope.insert(char_idx, s);
 
@@ -146,35 +209,64 @@ impl EditorBuffer {
         } else {
             self.cursor.set_col(self.cursor.col + s.len());
         }
+        self.record(
+            Edit {
+                char_idx,
+                removed,
+                inserted: s.to_string(),
+                before,
+                after: self.cursor,
+            },
+            false,
+        );
     }
 
-    /// Split the current line at the cursor (Enter key).
+    /// Split the current line at the cursor (Enter key), replacing any
+    /// selection.
     pub fn split_line(&mut self) {
+        let before = self.cursor;
+        let removed = self.take_selection();
         let char_idx = self.cursor_char_idx();
         self.rope.insert_char(char_idx, '\n');
         self.cursor.line += 1;
         self.cursor.set_col(0);
+        self.record(
+            Edit {
+                char_idx,
+                removed,
+                inserted: "\n".to_string(),
+                before,
+                after: self.cursor,
+            },
+            false,
+        );
     }
 
-    /// Delete the character before the cursor (Backspace).
+    /// Delete the character before the cursor (Backspace), or the
+    /// selection if there is one.
     ///
-    /// Returns `true` if a character was deleted.
+    /// Returns `true` if anything was deleted.
     pub fn delete_back(&mut self) -> bool {
+        if self.selection().is_some() {
+            return self.delete_selection();
+        }
+        self.selection_anchor = None;
         if self.cursor.col == 0 && self.cursor.line == 0 {
             return false;
         }
 
+        let before = self.cursor;
+        let char_idx = self.cursor_char_idx();
+        let removed = self.rope.char(char_idx - 1).to_string();
         if self.cursor.col == 0 {
             // Join with previous line
             let prev_line_len = self.line_len(self.cursor.line - 1);
-            let char_idx = self.cursor_char_idx();
             // Delete the newline at end of previous line
             self.rope.remove(char_idx - 1..char_idx);
             self.cursor.line -= 1;
             self.cursor.set_col(prev_line_len);
         } else {
             // Delete character before cursor
-            let char_idx = self.cursor_char_idx();
             // Find the byte length of the character before cursor
             let line = self.rope.line(self.cursor.line);
             let line_str = line.to_string();
@@ -183,14 +275,29 @@ impl EditorBuffer {
             self.rope.remove(char_idx - 1..char_idx);
             self.cursor.set_col(self.cursor.col - prev_char_len);
         }
+        self.record(
+            Edit {
+                char_idx: char_idx - 1,
+                removed,
+                inserted: String::new(),
+                before,
+                after: self.cursor,
+            },
+            false,
+        );
 
         true
     }
 
-    /// Delete the character at the cursor (Delete key).
+    /// Delete the character at the cursor (Delete key), or the selection
+    /// if there is one.
     ///
-    /// Returns `true` if a character was deleted.
+    /// Returns `true` if anything was deleted.
     pub fn delete_forward(&mut self) -> bool {
+        if self.selection().is_some() {
+            return self.delete_selection();
+        }
+        self.selection_anchor = None;
         let line_len = self.line_len(self.cursor.line);
 
         if self.cursor.col >= line_len && self.cursor.line + 1 >= self.line_count() {
@@ -198,13 +305,218 @@ impl EditorBuffer {
         }
 
         let char_idx = self.cursor_char_idx();
+        let removed = self.rope.char(char_idx).to_string();
         self.rope.remove(char_idx..=char_idx);
+        self.record(
+            Edit {
+                char_idx,
+                removed,
+                inserted: String::new(),
+                before: self.cursor,
+                after: self.cursor,
+            },
+            false,
+        );
 
         true
     }
 
+    /// Delete the selected text.
+    ///
+    /// Returns `true` if there was a non-empty selection.
+    pub fn delete_selection(&mut self) -> bool {
+        let before = self.cursor;
+        let removed = self.take_selection();
+        if removed.is_empty() {
+            return false;
+        }
+        self.record(
+            Edit {
+                char_idx: self.cursor_char_idx(),
+                removed,
+                inserted: String::new(),
+                before,
+                after: self.cursor,
+            },
+            false,
+        );
+        true
+    }
+
+    /// The selected range as `(start, end)` in document order.
+    ///
+    /// Returns `None` when nothing is selected or the selection is empty.
+    pub fn selection(&self) -> Option<(Cursor, Cursor)> {
+        let anchor = self.selection_anchor?;
+        let anchor_pos = (anchor.line, anchor.col);
+        let cursor_pos = (self.cursor.line, self.cursor.col);
+        match anchor_pos.cmp(&cursor_pos) {
+            std::cmp::Ordering::Less => Some((anchor, self.cursor)),
+            std::cmp::Ordering::Greater => Some((self.cursor, anchor)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// The selected text, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        let range = self.char_idx_at(start)..self.char_idx_at(end);
+        Some(self.rope.slice(range).to_string())
+    }
+
+    /// Move the cursor while extending the selection (Shift+movement).
+    ///
+    /// The selection is anchored where the cursor was when it started.
+    pub fn select(&mut self, motion: Motion) {
+        let anchor = self.selection_anchor.unwrap_or(self.cursor);
+        match motion {
+            Motion::Direction(direction) => self.move_cursor(direction),
+            Motion::Home => self.move_home(),
+            Motion::End => self.move_end(),
+            Motion::WordLeft => self.move_word_left(),
+            Motion::WordRight => self.move_word_right(),
+            Motion::BufferStart => self.move_to_start(),
+            Motion::BufferEnd => self.move_to_end(),
+        }
+        self.selection_anchor = Some(anchor);
+    }
+
+    /// Select the whole buffer.
+    pub fn select_all(&mut self) {
+        self.move_to_start();
+        self.select(Motion::BufferEnd);
+    }
+
+    /// Drop the selection, keeping the cursor where it is.
+    pub const fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+    }
+
+    /// Byte offset of the cursor within [`Self::text`].
+    pub fn cursor_byte_offset(&self) -> usize {
+        self.rope.char_to_byte(self.cursor_char_idx())
+    }
+
+    /// Length of [`Self::text`] in bytes.
+    pub fn len_bytes(&self) -> usize {
+        self.rope.len_bytes()
+    }
+
+    /// The text in a byte range of [`Self::text`].
+    pub fn text_range(&self, range: Range<usize>) -> String {
+        self.rope.byte_slice(range).to_string()
+    }
+
+    /// Byte offset of the start of a line within [`Self::text`].
+    pub fn line_start_byte(&self, line_idx: usize) -> usize {
+        self.rope
+            .line_to_byte(line_idx.min(self.rope.len_lines().saturating_sub(1)))
+    }
+
+    /// Convert a byte offset of [`Self::text`] to a cursor position.
+    pub fn cursor_at_byte(&self, byte: usize) -> Cursor {
+        let byte = byte.min(self.rope.len_bytes());
+        let line = self.rope.byte_to_line(byte);
+        Cursor::at(line, byte - self.rope.line_to_byte(line))
+    }
+
+    /// Select a byte range of [`Self::text`], leaving the cursor at its end.
+    pub fn select_range(&mut self, range: Range<usize>) {
+        self.end_edit_run();
+        let anchor = self.cursor_at_byte(range.start);
+        self.cursor = self.cursor_at_byte(range.end);
+        self.selection_anchor = Some(anchor);
+    }
+
+    /// Replace non-overlapping byte ranges of [`Self::text`] as one undo step.
+    ///
+    /// Ranges must be sorted by start. The cursor ends up after the last
+    /// replacement.
+    pub fn replace_ranges(&mut self, replacements: &[(Range<usize>, String)]) {
+        let Some((last, _)) = replacements.last() else {
+            return;
+        };
+        let inserted: usize = replacements.iter().map(|(_, text)| text.len()).sum();
+        let removed: usize = replacements.iter().map(|(range, _)| range.len()).sum();
+        self.replace_ranges_and_move(replacements, last.end + inserted - removed);
+    }
+
+    /// Like [`Self::replace_ranges`], but leaves the cursor at byte offset
+    /// `cursor` of the resulting text.
+    pub fn replace_ranges_and_move(
+        &mut self,
+        replacements: &[(Range<usize>, String)],
+        cursor: usize,
+    ) {
+        let (Some((first, _)), Some((last, _))) = (replacements.first(), replacements.last())
+        else {
+            return;
+        };
+        let span = first.start..last.end;
+        let removed = self.rope.byte_slice(span.clone()).to_string();
+        let mut inserted = String::new();
+        let mut pos = span.start;
+        for (range, text) in replacements {
+            inserted.push_str(&removed[pos - span.start..range.start - span.start]);
+            inserted.push_str(text);
+            pos = range.end;
+        }
+
+        let before = self.cursor;
+        let char_idx = self.rope.byte_to_char(span.start);
+        self.end_edit_run();
+        self.replace(char_idx, &removed, &inserted);
+        self.cursor = self.cursor_at_byte(cursor);
+        self.record(
+            Edit {
+                char_idx,
+                removed,
+                inserted,
+                before,
+                after: self.cursor,
+            },
+            false,
+        );
+    }
+
+    /// Revert the most recent undo step and restore the cursor from before it.
+    ///
+    /// Returns `true` if there was anything to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo_stack.pop_back() else {
+            return false;
+        };
+        self.replace(edit.char_idx, &edit.inserted, &edit.removed);
+        self.mark_changed(edit.char_idx);
+        self.cursor = edit.before;
+        self.redo_stack.push(edit);
+        self.end_edit_run();
+        true
+    }
+
+    /// Reapply the most recently undone step.
+    ///
+    /// Returns `true` if there was anything to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.replace(edit.char_idx, &edit.removed, &edit.inserted);
+        self.mark_changed(edit.char_idx);
+        self.cursor = edit.after;
+        self.undo_stack.push_back(edit);
+        self.end_edit_run();
+        true
+    }
+
+    /// The lowest line changed since the last call, resetting the tracking.
+    pub const fn take_changed_from(&mut self) -> Option<usize> {
+        self.changed_from.take()
+    }
+
     /// Move the cursor in the given direction.
     pub fn move_cursor(&mut self, direction: Direction) {
+        self.end_edit_run();
         match direction {
             Direction::Left => self.move_left(),
             Direction::Right => self.move_right(),
@@ -215,17 +527,20 @@ impl EditorBuffer {
 
     /// Move cursor to the beginning of the line (Home).
     pub const fn move_home(&mut self) {
+        self.end_edit_run();
         self.cursor.set_col(0);
     }
 
     /// Move cursor to the end of the line (End).
     pub fn move_end(&mut self) {
+        self.end_edit_run();
         let len = self.line_len(self.cursor.line);
         self.cursor.set_col(len);
     }
 
     /// Move cursor one word to the left (Ctrl+Left).
     pub fn move_word_left(&mut self) {
+        self.end_edit_run();
         if self.cursor.col == 0 {
             if self.cursor.line > 0 {
                 self.cursor.line -= 1;
@@ -252,6 +567,7 @@ impl EditorBuffer {
 
     /// Move cursor one word to the right (Ctrl+Right).
     pub fn move_word_right(&mut self) {
+        self.end_edit_run();
         let line_len = self.line_len(self.cursor.line);
 
         if self.cursor.col >= line_len {
@@ -281,6 +597,7 @@ impl EditorBuffer {
 
     /// Move cursor to a specific line and column.
     pub fn move_to(&mut self, line: usize, col: usize) {
+        self.end_edit_run();
         let max_line = self.line_count().saturating_sub(1);
         self.cursor.line = line.min(max_line);
         let max_col = self.line_len(self.cursor.line);
@@ -289,35 +606,110 @@ impl EditorBuffer {
 
     /// Move cursor to the start of the buffer (Ctrl+Home).
     pub const fn move_to_start(&mut self) {
+        self.end_edit_run();
         self.cursor.line = 0;
         self.cursor.set_col(0);
     }
 
     /// Move cursor to the end of the buffer (Ctrl+End).
     pub fn move_to_end(&mut self) {
+        self.end_edit_run();
         let last_line = self.line_count().saturating_sub(1);
         self.cursor.line = last_line;
         self.cursor.set_col(self.line_len(last_line));
     }
 
+    /// Pull a cursor past the end of a non-empty line back onto its last
+    /// character, keeping the remembered column (Vim's normal mode).
+    pub fn clamp_cursor_to_last_char(&mut self) {
+        let line = self.line_at(self.cursor.line).unwrap_or_default();
+        if let Some((last, _)) = line.char_indices().next_back()
+            && self.cursor.col > last
+        {
+            self.cursor.col = last;
+        }
+    }
+
     // --- Private helpers ---
 
+    /// Movement and history steps end the typing run and drop the selection.
+    const fn end_edit_run(&mut self) {
+        self.typing = false;
+        self.selection_anchor = None;
+    }
+
+    /// Remove the selected text without recording it, leaving the cursor at
+    /// the start of the selection. Returns the removed text (empty when
+    /// nothing was selected).
+    fn take_selection(&mut self) -> String {
+        let selection = self.selection();
+        self.selection_anchor = None;
+        let Some((start, end)) = selection else {
+            return String::new();
+        };
+        let range = self.char_idx_at(start)..self.char_idx_at(end);
+        let removed = self.rope.slice(range.clone()).to_string();
+        self.rope.remove(range);
+        self.cursor = Cursor::at(start.line, start.col);
+        removed
+    }
+
+    /// Push an edit onto the undo stack, merging typing runs.
+    fn record(&mut self, edit: Edit, typing: bool) {
+        self.mark_changed(edit.char_idx);
+        self.redo_stack.clear();
+        if typing
+            && self.typing
+            && let Some(last) = self.undo_stack.back_mut()
+            && last.char_idx + last.inserted.chars().count() == edit.char_idx
+        {
+            last.inserted.push_str(&edit.inserted);
+            last.after = edit.after;
+        } else {
+            self.undo_stack.push_back(edit);
+            if self.undo_stack.len() > UNDO_LIMIT {
+                self.undo_stack.pop_front();
+            }
+        }
+        self.typing = typing;
+    }
+
+    /// Note that the text changed from the line holding `char_idx` onward.
+    fn mark_changed(&mut self, char_idx: usize) {
+        let line = self.rope.char_to_line(char_idx.min(self.rope.len_chars()));
+        self.changed_from = Some(self.changed_from.map_or(line, |from| from.min(line)));
+    }
+
+    /// Replace `old` (which must sit at `char_idx`) with `new`.
+    fn replace(&mut self, char_idx: usize, old: &str, new: &str) {
+        self.rope.remove(char_idx..char_idx + old.chars().count());
+        self.rope.insert(char_idx, new);
+    }
+
     /// Hash the rope content for dirty-checking.
+    ///
+    /// Writes the raw bytes so the hash does not depend on where the rope
+    /// happens to split its chunks.
     fn rope_hash(rope: &Rope) -> u64 {
         let mut hasher = DefaultHasher::new();
         for chunk in rope.chunks() {
-            chunk.hash(&mut hasher);
+            hasher.write(chunk.as_bytes());
         }
         hasher.finish()
     }
 
     /// Convert cursor position to a ropey char index.
     fn cursor_char_idx(&self) -> usize {
-        let line_start = self.rope.line_to_char(self.cursor.line);
-        let line = self.rope.line(self.cursor.line);
+        self.char_idx_at(self.cursor)
+    }
+
+    /// Convert a position to a ropey char index.
+    fn char_idx_at(&self, pos: Cursor) -> usize {
+        let line_start = self.rope.line_to_char(pos.line);
+        let line = self.rope.line(pos.line);
         let line_str: String = line.chars().collect();
         // Convert byte offset to char offset within the line
-        let byte_col = self.cursor.col.min(line_str.len());
+        let byte_col = pos.col.min(line_str.len());
         let char_offset = line_str
             .get(..byte_col)
             .unwrap_or(&line_str)
@@ -883,4 +1275,222 @@ mod tests {
         assert_eq!(buf.line_count(), 1);
         assert_eq!(buf.line_at(0), Some("helloworld".to_string()));
     }
+
+    // --- Undo / redo ---
+
+    #[test]
+    fn test_undo_groups_consecutive_typing() {
+        let mut buf = EditorBuffer::from_text("hi");
+        buf.move_to_end();
+        for ch in " there".chars() {
+            buf.insert_char(ch);
+        }
+        assert!(buf.undo());
+        assert_eq!(buf.text(), "hi");
+        assert_eq!(buf.cursor(), Cursor::at(0, 2));
+        assert!(!buf.undo());
+    }
+
+    #[test]
+    fn test_cursor_movement_starts_new_undo_step() {
+        let mut buf = EditorBuffer::from_text("");
+        buf.insert_char('a');
+        buf.insert_char('b');
+        buf.move_home();
+        buf.insert_char('c');
+        assert_eq!(buf.text(), "cab");
+        buf.undo();
+        assert_eq!(buf.text(), "ab");
+        buf.undo();
+        assert_eq!(buf.text(), "");
+    }
+
+    #[test]
+    fn test_undo_restores_backspace_and_cursor() {
+        let mut buf = EditorBuffer::from_text("hello\nworld");
+        buf.move_to(1, 0);
+        buf.delete_back();
+        assert_eq!(buf.text(), "helloworld");
+        buf.undo();
+        assert_eq!(buf.text(), "hello\nworld");
+        assert_eq!(buf.cursor(), Cursor::at(1, 0));
+    }
+
+    #[test]
+    fn test_undo_delete_forward_multibyte() {
+        let mut buf = EditorBuffer::from_text("café!");
+        buf.move_to(0, 3);
+        buf.delete_forward();
+        assert_eq!(buf.text(), "caf!");
+        buf.undo();
+        assert_eq!(buf.text(), "café!");
+        assert_eq!(buf.cursor(), Cursor::at(0, 3));
+    }
+
+    #[test]
+    fn test_undo_split_line_and_insert_str() {
+        let mut buf = EditorBuffer::from_text("ab");
+        buf.move_to(0, 1);
+        buf.split_line();
+        buf.insert_str("x\ny");
+        assert_eq!(buf.text(), "a\nx\nyb");
+        buf.undo();
+        assert_eq!(buf.text(), "a\nb");
+        assert_eq!(buf.cursor(), Cursor::at(1, 0));
+        buf.undo();
+        assert_eq!(buf.text(), "ab");
+        assert_eq!(buf.cursor(), Cursor::at(0, 1));
+    }
+
+    #[test]
+    fn test_redo_reapplies_and_new_edit_clears_it() {
+        let mut buf = EditorBuffer::from_text("");
+        buf.insert_char('a');
+        buf.split_line();
+        buf.undo();
+        buf.undo();
+        assert_eq!(buf.text(), "");
+        assert!(buf.redo());
+        assert_eq!(buf.text(), "a");
+        assert_eq!(buf.cursor(), Cursor::at(0, 1));
+        buf.insert_char('b');
+        assert!(!buf.redo());
+        assert_eq!(buf.text(), "ab");
+    }
+
+    #[test]
+    fn test_undo_back_to_saved_state_is_clean() {
+        let mut buf = EditorBuffer::from_text("hello");
+        buf.move_to_end();
+        buf.insert_char('!');
+        buf.mark_clean();
+        buf.split_line();
+        assert!(buf.is_dirty());
+        buf.undo();
+        assert!(!buf.is_dirty());
+        buf.undo();
+        assert!(buf.is_dirty());
+        buf.redo();
+        assert!(!buf.is_dirty());
+    }
+
+    #[test]
+    fn test_undo_to_clean_in_multi_chunk_buffer() {
+        let original = "a line of text\n".repeat(2000);
+        let mut buf = EditorBuffer::from_text(&original);
+        buf.move_to(1000, 2);
+        buf.insert_char('x');
+        buf.split_line();
+        buf.insert_str(&"y".repeat(3000));
+        assert!(buf.is_dirty());
+        while buf.undo() {}
+        assert_eq!(buf.text(), original);
+        assert!(!buf.is_dirty());
+    }
+
+    // --- Selection ---
+
+    #[test]
+    fn test_shift_selection_spans_lines() {
+        let mut buf = EditorBuffer::from_text("hello\nworld");
+        buf.move_to(0, 3);
+        buf.select(Motion::Direction(Direction::Down));
+        buf.select(Motion::End);
+        assert_eq!(buf.selected_text().as_deref(), Some("lo\nworld"));
+        assert_eq!(buf.selection(), Some((Cursor::at(0, 3), buf.cursor())));
+    }
+
+    #[test]
+    fn test_selection_backwards_is_ordered() {
+        let mut buf = EditorBuffer::from_text("hello world");
+        buf.move_to_end();
+        buf.select(Motion::WordLeft);
+        assert_eq!(buf.selected_text().as_deref(), Some("world"));
+    }
+
+    #[test]
+    fn test_plain_movement_clears_selection() {
+        let mut buf = EditorBuffer::from_text("hello");
+        buf.select(Motion::End);
+        assert!(buf.selection().is_some());
+        buf.move_home();
+        assert!(buf.selection().is_none());
+    }
+
+    #[test]
+    fn test_typing_replaces_selection_in_one_undo_step() {
+        let mut buf = EditorBuffer::from_text("hello world");
+        buf.select(Motion::WordRight);
+        buf.insert_char('J');
+        assert_eq!(buf.text(), "Jworld");
+        buf.undo();
+        assert_eq!(buf.text(), "hello world");
+    }
+
+    #[test]
+    fn test_delete_back_removes_selection() {
+        let mut buf = EditorBuffer::from_text("café!");
+        buf.move_to(0, 1);
+        buf.select(Motion::Direction(Direction::Right));
+        buf.select(Motion::Direction(Direction::Right));
+        buf.select(Motion::Direction(Direction::Right));
+        assert_eq!(buf.selected_text().as_deref(), Some("afé"));
+        assert!(buf.delete_back());
+        assert_eq!(buf.text(), "c!");
+        assert_eq!(buf.cursor(), Cursor::at(0, 1));
+        buf.undo();
+        assert_eq!(buf.text(), "café!");
+    }
+
+    #[test]
+    fn test_insert_str_replaces_selection() {
+        let mut buf = EditorBuffer::from_text("a\nb\nc");
+        buf.select_all();
+        buf.insert_str("x\ny");
+        assert_eq!(buf.text(), "x\ny");
+        assert_eq!(buf.cursor(), Cursor::at(1, 1));
+    }
+
+    // --- Range selection and replacement ---
+
+    #[test]
+    fn test_select_range_uses_byte_offsets() {
+        let mut buf = EditorBuffer::from_text("héllo\nworld");
+        buf.select_range(7..12);
+        assert_eq!(buf.selected_text().as_deref(), Some("world"));
+        assert_eq!(buf.cursor(), Cursor::at(1, 5));
+        assert_eq!(buf.cursor_byte_offset(), 12);
+        assert_eq!(buf.line_start_byte(1), 7);
+    }
+
+    #[test]
+    fn test_replace_ranges_is_one_undo_step() {
+        let mut buf = EditorBuffer::from_text("foo bar foo\nfoo");
+        buf.replace_ranges(&[
+            (0..3, "baz".to_string()),
+            (8..11, "x".to_string()),
+            (12..15, "quux".to_string()),
+        ]);
+        assert_eq!(buf.text(), "baz bar x\nquux");
+        assert_eq!(buf.cursor(), Cursor::at(1, 4));
+        assert!(buf.undo());
+        assert_eq!(buf.text(), "foo bar foo\nfoo");
+        assert!(!buf.undo());
+    }
+
+    // --- Change tracking ---
+
+    #[test]
+    fn test_take_changed_from_reports_lowest_line() {
+        let mut buf = EditorBuffer::from_text("a\nb\nc");
+        assert_eq!(buf.take_changed_from(), None);
+        buf.move_to(2, 1);
+        buf.insert_char('x');
+        buf.move_to(1, 0);
+        buf.delete_forward();
+        assert_eq!(buf.take_changed_from(), Some(1));
+        assert_eq!(buf.take_changed_from(), None);
+        buf.undo();
+        assert_eq!(buf.take_changed_from(), Some(1));
+    }
 }
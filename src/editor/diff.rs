@@ -0,0 +1,175 @@
+//! Line diffs between two versions of a text.
+
+use std::ops::Range;
+
+/// Above this many LCS table cells, a changed middle section is reported as
+/// one hunk instead of being diffed line by line.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// A changed region: lines `old` of the old text became lines `new` of the
+/// new text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub old: Range<usize>,
+    pub new: Range<usize>,
+}
+
+/// One row of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+    /// Unchanged lines left out between hunks.
+    Skipped(usize),
+}
+
+/// Changed regions between `old` and `new`, in order.
+pub fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Hunk> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    if a.is_empty() && b.is_empty() {
+        return Vec::new();
+    }
+    if a.is_empty() || b.is_empty() || a.len() * b.len() > MAX_LCS_CELLS {
+        return vec![Hunk {
+            old: prefix..prefix + a.len(),
+            new: prefix..prefix + b.len(),
+        }];
+    }
+
+    // lcs[i * width + j] is the LCS length of a[i..] and b[j..].
+    let width = b.len() + 1;
+    let mut lcs = vec![0_u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    let (mut i, mut j) = (0, 0);
+    let mut flush = |start: &mut Option<(usize, usize)>, i: usize, j: usize| {
+        if let Some((si, sj)) = start.take() {
+            hunks.push(Hunk {
+                old: prefix + si..prefix + i,
+                new: prefix + sj..prefix + j,
+            });
+        }
+    };
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            flush(&mut start, i, j);
+            i += 1;
+            j += 1;
+        } else if j < b.len()
+            && (i == a.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j])
+        {
+            start.get_or_insert((i, j));
+            j += 1;
+        } else {
+            start.get_or_insert((i, j));
+            i += 1;
+        }
+    }
+    flush(&mut start, i, j);
+    hunks
+}
+
+/// A unified diff from `old` to `new` with `context` unchanged lines
+/// around each hunk.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let hunks = diff_lines(&old, &new);
+
+    let mut out = Vec::new();
+    let mut shown = 0;
+    for (idx, hunk) in hunks.iter().enumerate() {
+        let from = hunk.old.start.saturating_sub(context).max(shown);
+        if from > shown {
+            out.push(DiffLine::Skipped(from - shown));
+        }
+        out.extend(
+            old[from..hunk.old.start]
+                .iter()
+                .map(|line| DiffLine::Context((*line).to_string())),
+        );
+        out.extend(
+            old[hunk.old.clone()]
+                .iter()
+                .map(|line| DiffLine::Removed((*line).to_string())),
+        );
+        out.extend(
+            new[hunk.new.clone()]
+                .iter()
+                .map(|line| DiffLine::Added((*line).to_string())),
+        );
+        let next = hunks.get(idx + 1).map_or(old.len(), |h| h.old.start);
+        let until = (hunk.old.end + context).min(next);
+        out.extend(
+            old[hunk.old.end..until]
+                .iter()
+                .map(|line| DiffLine::Context((*line).to_string())),
+        );
+        shown = until;
+    }
+    if !hunks.is_empty() && shown < old.len() {
+        out.push(DiffLine::Skipped(old.len() - shown));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines_finds_separate_hunks() {
+        let old = ["a", "b", "c", "d", "e"];
+        let new = ["a", "B", "c", "d", "e", "f"];
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec![
+                Hunk {
+                    old: 1..2,
+                    new: 1..2
+                },
+                Hunk {
+                    old: 5..5,
+                    new: 5..6
+                },
+            ]
+        );
+        assert!(diff_lines(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_keeps_context_and_skips_the_rest() {
+        let old = "1\n2\n3\n4\n5\n6\n7";
+        let new = "1\n2\n3\nfour\n5\n6\n7";
+        assert_eq!(
+            unified_diff(old, new, 1),
+            vec![
+                DiffLine::Skipped(2),
+                DiffLine::Context("3".to_string()),
+                DiffLine::Removed("4".to_string()),
+                DiffLine::Added("four".to_string()),
+                DiffLine::Context("5".to_string()),
+                DiffLine::Skipped(2),
+            ]
+        );
+    }
+}
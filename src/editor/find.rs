@@ -0,0 +1,153 @@
+//! Incremental find/replace state for the built-in editor.
+//!
+//! Matches are byte ranges of the editor buffer's text and are
+//! recomputed whenever the query, the options, or the text change.
+
+use std::ops::Range;
+
+use crate::search::{self, TextSearchOptions};
+
+/// Which input of the find bar receives typed characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindField {
+    Query,
+    Replacement,
+}
+
+/// State of the editor's find bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FindState {
+    pub query: String,
+    pub replacement: String,
+    pub options: TextSearchOptions,
+    /// Whether the replace row is shown.
+    pub replacing: bool,
+    pub focus: FindField,
+    matches: Vec<Range<usize>>,
+    current: Option<usize>,
+    /// Message for an invalid regex query.
+    error: Option<String>,
+    /// Byte offset the current match is searched from.
+    origin: usize,
+}
+
+impl FindState {
+    /// Create an empty find bar that searches from `origin`.
+    pub const fn new(replacing: bool, origin: usize) -> Self {
+        Self {
+            query: String::new(),
+            replacement: String::new(),
+            options: TextSearchOptions {
+                case_sensitive: false,
+                regex: false,
+                whole_word: false,
+            },
+            replacing,
+            focus: FindField::Query,
+            matches: Vec::new(),
+            current: None,
+            error: None,
+            origin,
+        }
+    }
+
+    /// The text of the focused input.
+    pub const fn active_input_mut(&mut self) -> &mut String {
+        match self.focus {
+            FindField::Query => &mut self.query,
+            FindField::Replacement => &mut self.replacement,
+        }
+    }
+
+    /// All matches in the current text.
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// The match the cursor is on.
+    pub fn current_match(&self) -> Option<&Range<usize>> {
+        self.matches.get(self.current?)
+    }
+
+    /// `(current, total)` with a 1-based current index, if there is a match.
+    pub fn counter(&self) -> Option<(usize, usize)> {
+        self.current.map(|idx| (idx + 1, self.matches.len()))
+    }
+
+    /// The regex error for the current query, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Recompute matches and pick the first one at or after the origin.
+    pub fn refresh(&mut self, text: &str) {
+        match search::find_text_matches(text, &self.query, self.options) {
+            Ok(matches) => {
+                self.matches = matches;
+                self.error = None;
+            }
+            Err(err) => {
+                self.matches.clear();
+                self.error = Some(err.to_string());
+            }
+        }
+        self.current = if self.matches.is_empty() {
+            None
+        } else {
+            Some(
+                self.matches
+                    .iter()
+                    .position(|m| m.start >= self.origin)
+                    .unwrap_or(0),
+            )
+        };
+    }
+
+    /// Move to the next (or previous) match, wrapping around.
+    pub fn step(&mut self, forward: bool) {
+        let len = self.matches.len();
+        let Some(current) = self.current else {
+            return;
+        };
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.current = Some(next);
+        self.origin = self.matches[next].start;
+    }
+
+    /// Search from `origin` on the next refresh.
+    pub const fn set_origin(&mut self, origin: usize) {
+        self.origin = origin;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_starts_at_origin_and_wraps() {
+        let mut find = FindState::new(false, 5);
+        find.query = "ab".to_string();
+        find.refresh("ab ab ab");
+        assert_eq!(find.counter(), Some((3, 3)));
+        find.step(true);
+        assert_eq!(find.current_match(), Some(&(0..2)));
+        find.step(false);
+        assert_eq!(find.counter(), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_refresh_reports_regex_errors() {
+        let mut find = FindState::new(false, 0);
+        find.options.regex = true;
+        find.query = "[".to_string();
+        find.refresh("[x]");
+        assert!(find.error().is_some());
+        assert!(find.matches().is_empty());
+        assert_eq!(find.counter(), None);
+    }
+}
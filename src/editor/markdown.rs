@@ -0,0 +1,585 @@
+//! Markdown-aware editing: list continuation, list nesting, and pipe-table
+//! alignment.
+//!
+//! Each command is a single undo step on the [`EditorBuffer`].
+
+use std::ops::Range;
+
+use super::EditorBuffer;
+use crate::document::display_width;
+
+/// The prefix of a list item line, such as `  - [ ] ` or `3. `.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListItem {
+    /// Bytes of leading whitespace.
+    indent: usize,
+    marker: ListMarker,
+    /// Bytes taken by the marker itself (`-`, `12.`).
+    marker_len: usize,
+    /// Byte offset where the item's text starts.
+    content_start: usize,
+    task: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListMarker {
+    Bullet(char),
+    Ordered { number: u64, delimiter: char },
+}
+
+impl ListMarker {
+    /// The marker for the following sibling item.
+    const fn next(self) -> Self {
+        match self {
+            Self::Bullet(_) => self,
+            Self::Ordered { number, delimiter } => Self::Ordered {
+                number: number + 1,
+                delimiter,
+            },
+        }
+    }
+
+    fn text(self) -> String {
+        match self {
+            Self::Bullet(bullet) => bullet.to_string(),
+            Self::Ordered { number, delimiter } => format!("{number}{delimiter}"),
+        }
+    }
+}
+
+/// Column alignment from a table delimiter row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl EditorBuffer {
+    /// Enter in a markdown file.
+    ///
+    /// On a list item this starts the next item (renumbering the ordered
+    /// items that follow), and on an empty item it ends the list instead.
+    /// Elsewhere the new line keeps the current line's indentation.
+    pub fn markdown_newline(&mut self) {
+        let cursor = self.cursor();
+        let text = self.line_at(cursor.line).unwrap_or_default();
+        let item = parse_list_item(&text).filter(|item| cursor.col >= item.content_start);
+        let Some(item) = item.filter(|_| self.selection().is_none()) else {
+            self.newline_with_indent();
+            return;
+        };
+
+        let line_start = self.line_start_byte(cursor.line);
+        if text[item.content_start..].trim().is_empty() {
+            // An empty item ends the list.
+            self.replace_ranges_and_move(
+                &[(line_start..line_start + text.len(), String::new())],
+                line_start,
+            );
+            return;
+        }
+
+        let next = item.marker.next();
+        let checkbox = if item.task { " [ ]" } else { "" };
+        let prefix = format!("{}{}{checkbox} ", &text[..item.indent], next.text());
+        let at = line_start + cursor.col;
+        let mut edits = vec![(at..at, format!("\n{prefix}"))];
+        if let ListMarker::Ordered { number, delimiter } = next {
+            edits.extend(self.renumber_items_after(
+                cursor.line,
+                item.indent,
+                number + 1,
+                delimiter,
+            ));
+        }
+        self.replace_ranges_and_move(&edits, at + 1 + prefix.len());
+    }
+
+    /// Enter that carries the current line's leading whitespace over.
+    pub fn newline_with_indent(&mut self) {
+        let cursor = self.cursor();
+        let text = self.line_at(cursor.line).unwrap_or_default();
+        let indent: String = text
+            .get(..cursor.col)
+            .unwrap_or(&text)
+            .chars()
+            .take_while(|c| matches!(c, ' ' | '\t'))
+            .collect();
+        if indent.is_empty() {
+            self.split_line();
+        } else {
+            self.insert_str(&format!("\n{indent}"));
+        }
+    }
+
+    /// Nest the list item under the cursor one level deeper (Tab).
+    ///
+    /// Returns `false` if the cursor is not on a list item.
+    pub fn indent_list_item(&mut self) -> bool {
+        let cursor = self.cursor();
+        let text = self.line_at(cursor.line).unwrap_or_default();
+        let Some(item) = parse_list_item(&text) else {
+            return false;
+        };
+        // Nesting under the previous sibling means lining up with its text.
+        let sibling = self
+            .previous_list_item(cursor.line, item.indent)
+            .filter(|prev| prev.indent == item.indent)
+            .unwrap_or(item);
+        let width = sibling.marker_len + 1;
+        let line_start = self.line_start_byte(cursor.line);
+        self.replace_ranges_and_move(
+            &[(line_start..line_start, " ".repeat(width))],
+            line_start + cursor.col + width,
+        );
+        true
+    }
+
+    /// Move the list item under the cursor out one level (Shift+Tab).
+    ///
+    /// Returns `false` if the cursor is not on a list item.
+    pub fn outdent_list_item(&mut self) -> bool {
+        let cursor = self.cursor();
+        let text = self.line_at(cursor.line).unwrap_or_default();
+        let Some(item) = parse_list_item(&text) else {
+            return false;
+        };
+        if item.indent == 0 {
+            return true;
+        }
+        let target = self
+            .previous_list_item(cursor.line, item.indent.saturating_sub(1))
+            .map_or(0, |parent| parent.indent);
+        let removed = item.indent - target;
+        let line_start = self.line_start_byte(cursor.line);
+        self.replace_ranges_and_move(
+            &[(line_start..line_start + removed, String::new())],
+            line_start + cursor.col.saturating_sub(removed),
+        );
+        true
+    }
+
+    /// Re-align the GFM pipe table under the cursor, padding cells to the
+    /// widest entry in each column.
+    ///
+    /// Returns `false` if the cursor is not inside a table.
+    pub fn format_table(&mut self) -> bool {
+        let cursor = self.cursor();
+        let is_row = |line: usize| {
+            self.line_at(line)
+                .is_some_and(|text| text.contains('|') && !text.trim().is_empty())
+        };
+        if !is_row(cursor.line) {
+            return false;
+        }
+        let mut first = cursor.line;
+        while first > 0 && is_row(first - 1) {
+            first -= 1;
+        }
+        let mut last = cursor.line;
+        while last + 1 < self.line_count() && is_row(last + 1) {
+            last += 1;
+        }
+
+        let lines: Vec<String> = (first..=last)
+            .map(|line| self.line_at(line).unwrap_or_default())
+            .collect();
+        // The header sits right above the delimiter row; earlier lines with
+        // pipes belong to the preceding paragraph.
+        let Some(delimiter) = lines
+            .iter()
+            .skip(1)
+            .position(|text| is_delimiter_row(text))
+            .map(|idx| idx + 1)
+        else {
+            return false;
+        };
+        let header = first + delimiter - 1;
+        if cursor.line < header {
+            return false;
+        }
+        let lines = &lines[delimiter - 1..];
+
+        let alignments: Vec<Alignment> = split_row(&lines[1])
+            .iter()
+            .map(|cell| alignment_of(cell))
+            .collect();
+        let rows: Vec<Vec<String>> = lines
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != 1)
+            .map(|(_, text)| split_row(text))
+            .collect();
+        let columns = rows
+            .iter()
+            .map(Vec::len)
+            .chain([alignments.len()])
+            .max()
+            .unwrap_or(0);
+        let mut widths = vec![3; columns];
+        for row in &rows {
+            for (idx, cell) in row.iter().enumerate() {
+                widths[idx] = widths[idx].max(display_width(cell));
+            }
+        }
+
+        let indent: String = lines[0]
+            .chars()
+            .take_while(|c| matches!(c, ' ' | '\t'))
+            .collect();
+        let mut formatted = Vec::new();
+        let mut cell_starts = Vec::new();
+        let mut rows = rows.into_iter();
+        for idx in 0..lines.len() {
+            let (text, starts) = if idx == 1 {
+                let cells: Vec<String> = widths
+                    .iter()
+                    .enumerate()
+                    .map(|(col, width)| {
+                        delimiter_cell(
+                            alignments.get(col).copied().unwrap_or(Alignment::None),
+                            *width,
+                        )
+                    })
+                    .collect();
+                format_row(&indent, &cells, &widths, &[])
+            } else {
+                let cells = rows.next().unwrap_or_default();
+                format_row(&indent, &cells, &widths, &alignments)
+            };
+            formatted.push(text);
+            cell_starts.push(starts);
+        }
+
+        // Keep the cursor in the same cell, at the start of its text.
+        let cursor_row = cursor.line - header;
+        let cell = cell_index(&lines[cursor_row], cursor.col).min(columns.saturating_sub(1));
+        let row_offset: usize = formatted[..cursor_row]
+            .iter()
+            .map(|row| row.len() + 1)
+            .sum();
+        let cell_offset = cell_starts[cursor_row].get(cell).copied().unwrap_or(0);
+
+        let start = self.line_start_byte(header);
+        let end = self.line_start_byte(last) + self.line_len(last);
+        self.replace_ranges_and_move(
+            &[(start..end, formatted.join("\n"))],
+            start + row_offset + cell_offset,
+        );
+        true
+    }
+
+    /// The closest list item above `line` indented at most `max_indent`,
+    /// stopping at the start of the list.
+    fn previous_list_item(&self, line: usize, max_indent: usize) -> Option<ListItem> {
+        for prev in (0..line).rev() {
+            let text = self.line_at(prev).unwrap_or_default();
+            if text.trim().is_empty() {
+                continue;
+            }
+            match parse_list_item(&text) {
+                Some(item) if item.indent <= max_indent => return Some(item),
+                Some(_) => {}
+                // Indented lines continue the previous item's text.
+                None if text.starts_with([' ', '\t']) => {}
+                None => return None,
+            }
+        }
+        None
+    }
+
+    /// Renumber the ordered siblings after `line` starting at `number`.
+    fn renumber_items_after(
+        &self,
+        line: usize,
+        indent: usize,
+        mut number: u64,
+        delimiter: char,
+    ) -> Vec<(Range<usize>, String)> {
+        let mut edits = Vec::new();
+        for next in line + 1..self.line_count() {
+            let text = self.line_at(next).unwrap_or_default();
+            if text.trim().is_empty() {
+                continue;
+            }
+            let Some(item) = parse_list_item(&text) else {
+                let leading = text.len() - text.trim_start().len();
+                if leading > indent {
+                    continue;
+                }
+                break;
+            };
+            if item.indent > indent {
+                continue;
+            }
+            match item.marker {
+                ListMarker::Ordered {
+                    number: current,
+                    delimiter: d,
+                } if item.indent == indent && d == delimiter => {
+                    if current != number {
+                        let start = self.line_start_byte(next) + indent;
+                        edits.push((start..start + item.marker_len - 1, number.to_string()));
+                    }
+                    number += 1;
+                }
+                _ => break,
+            }
+        }
+        edits
+    }
+}
+
+/// Parse the list marker at the start of `line`.
+fn parse_list_item(line: &str) -> Option<ListItem> {
+    let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+    let rest = &line[indent..];
+    let first = rest.chars().next()?;
+    let (marker, marker_len) = if matches!(first, '-' | '*' | '+') {
+        // `---` and `* * *` are thematic breaks, not items.
+        let marks: Vec<char> = rest.chars().filter(|c| !c.is_whitespace()).collect();
+        if marks.len() >= 3 && marks.iter().all(|c| *c == first) {
+            return None;
+        }
+        (ListMarker::Bullet(first), 1)
+    } else {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let delimiter = rest[digits..].chars().next()?;
+        if !(1..=9).contains(&digits) || !matches!(delimiter, '.' | ')') {
+            return None;
+        }
+        let number = rest[..digits].parse().ok()?;
+        (ListMarker::Ordered { number, delimiter }, digits + 1)
+    };
+    let after = &rest[marker_len..];
+    if !(after.is_empty() || after.starts_with(' ')) {
+        return None;
+    }
+
+    let mut content_start = (indent + marker_len + 1).min(line.len());
+    let body = &line[content_start..];
+    let task = ["[ ]", "[x]", "[X]"].iter().any(|box_| {
+        body.strip_prefix(box_)
+            .is_some_and(|r| r.is_empty() || r.starts_with(' '))
+    });
+    if task {
+        content_start = (content_start + 4).min(line.len());
+    }
+    Some(ListItem {
+        indent,
+        marker,
+        marker_len,
+        content_start,
+        task,
+    })
+}
+
+/// Split a table row into trimmed cells, honoring `\|` escapes.
+fn split_row(text: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut escaped = false;
+    for ch in text.trim().chars() {
+        if ch == '|' && !escaped {
+            cells.push(std::mem::take(&mut cell));
+        } else {
+            cell.push(ch);
+        }
+        escaped = ch == '\\' && !escaped;
+    }
+    cells.push(cell);
+    // Leading and trailing pipes are optional and don't open cells.
+    if text.trim().starts_with('|') {
+        cells.remove(0);
+    }
+    if text.trim().ends_with('|') && !text.trim().ends_with("\\|") {
+        cells.pop();
+    }
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+/// Index of the cell containing byte column `col` of a table row.
+fn cell_index(text: &str, col: usize) -> usize {
+    let before = text.get(..col).unwrap_or(text);
+    let mut pipes: usize = 0;
+    let mut escaped = false;
+    for ch in before.chars() {
+        if ch == '|' && !escaped {
+            pipes += 1;
+        }
+        escaped = ch == '\\' && !escaped;
+    }
+    if text.trim_start().starts_with('|') {
+        pipes = pipes.saturating_sub(1);
+    }
+    pipes
+}
+
+/// Whether `text` is a delimiter row like `| --- | :-: |`.
+fn is_delimiter_row(text: &str) -> bool {
+    let cells = split_row(text);
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+fn alignment_of(cell: &str) -> Alignment {
+    match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Alignment::Center,
+        (true, false) => Alignment::Left,
+        (false, true) => Alignment::Right,
+        (false, false) => Alignment::None,
+    }
+}
+
+fn delimiter_cell(alignment: Alignment, width: usize) -> String {
+    match alignment {
+        Alignment::None => "-".repeat(width),
+        Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+        Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+        Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+    }
+}
+
+/// Build `| a | b |` with cells padded to `widths`, returning the row and
+/// the byte offset where each cell's padding starts.
+fn format_row(
+    indent: &str,
+    cells: &[String],
+    widths: &[usize],
+    alignments: &[Alignment],
+) -> (String, Vec<usize>) {
+    let mut row = format!("{indent}|");
+    let mut starts = Vec::new();
+    for (idx, width) in widths.iter().enumerate() {
+        let cell = cells.get(idx).map_or("", String::as_str);
+        let padding = width.saturating_sub(display_width(cell));
+        let (left, right) = match alignments.get(idx).copied().unwrap_or(Alignment::None) {
+            Alignment::Right => (padding, 0),
+            Alignment::Center => (padding / 2, padding - padding / 2),
+            Alignment::None | Alignment::Left => (0, padding),
+        };
+        row.push(' ');
+        starts.push(row.len());
+        row.push_str(&" ".repeat(left));
+        row.push_str(cell);
+        row.push_str(&" ".repeat(right));
+        row.push_str(" |");
+    }
+    (row, starts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::Cursor;
+
+    fn buffer_at(text: &str, line: usize, col: usize) -> EditorBuffer {
+        let mut buf = EditorBuffer::from_text(text);
+        buf.move_to(line, col);
+        buf
+    }
+
+    #[test]
+    fn test_newline_continues_bullet_and_task_items() {
+        let mut buf = buffer_at("- one", 0, 5);
+        buf.markdown_newline();
+        assert_eq!(buf.text(), "- one\n- ");
+        assert_eq!(buf.cursor(), Cursor::at(1, 2));
+
+        let mut buf = buffer_at("  * [x] done", 0, 12);
+        buf.markdown_newline();
+        assert_eq!(buf.text(), "  * [x] done\n  * [ ] ");
+    }
+
+    #[test]
+    fn test_newline_splits_item_text() {
+        let mut buf = buffer_at("- onetwo", 0, 5);
+        buf.markdown_newline();
+        assert_eq!(buf.text(), "- one\n- two");
+        assert_eq!(buf.cursor(), Cursor::at(1, 2));
+    }
+
+    #[test]
+    fn test_newline_renumbers_ordered_items_in_one_undo_step() {
+        let text = "1. a\n2. b\n   more b\n   1. nested\n3. c\n\ntext\n1. other";
+        let mut buf = buffer_at(text, 0, 4);
+        buf.markdown_newline();
+        assert_eq!(
+            buf.text(),
+            "1. a\n2. \n3. b\n   more b\n   1. nested\n4. c\n\ntext\n1. other"
+        );
+        assert_eq!(buf.cursor(), Cursor::at(1, 3));
+        buf.undo();
+        assert_eq!(buf.text(), text);
+    }
+
+    #[test]
+    fn test_newline_on_empty_item_ends_list() {
+        let mut buf = buffer_at("- one\n- ", 1, 2);
+        buf.markdown_newline();
+        assert_eq!(buf.text(), "- one\n");
+        assert_eq!(buf.cursor(), Cursor::at(1, 0));
+    }
+
+    #[test]
+    fn test_newline_keeps_indentation_outside_lists() {
+        let mut buf = buffer_at("    code", 0, 8);
+        buf.markdown_newline();
+        assert_eq!(buf.text(), "    code\n    ");
+        assert_eq!(buf.cursor(), Cursor::at(1, 4));
+
+        let mut buf = buffer_at("---", 0, 3);
+        buf.markdown_newline();
+        assert_eq!(buf.text(), "---\n");
+    }
+
+    #[test]
+    fn test_indent_and_outdent_list_items() {
+        let mut buf = buffer_at("1. parent\n2. child", 1, 5);
+        assert!(buf.indent_list_item());
+        assert_eq!(buf.text(), "1. parent\n   2. child");
+        assert_eq!(buf.cursor(), Cursor::at(1, 8));
+
+        assert!(buf.outdent_list_item());
+        assert_eq!(buf.text(), "1. parent\n2. child");
+        assert_eq!(buf.cursor(), Cursor::at(1, 5));
+
+        let mut buf = buffer_at("plain", 0, 0);
+        assert!(!buf.indent_list_item());
+        assert!(!buf.outdent_list_item());
+    }
+
+    #[test]
+    fn test_format_table_aligns_by_display_width() {
+        let text = "intro\n|a|b|\n|:-|-:|\n|日本|x|\n\nafter";
+        let mut buf = buffer_at(text, 3, 1);
+        assert!(buf.format_table());
+        assert_eq!(
+            buf.text(),
+            "intro\n| a    |   b |\n| :--- | --: |\n| 日本 |   x |\n\nafter"
+        );
+        // The cursor stays in the first cell of its row.
+        assert_eq!(buf.cursor(), Cursor::at(3, 2));
+        buf.undo();
+        assert_eq!(buf.text(), text);
+    }
+
+    #[test]
+    fn test_format_table_requires_delimiter_row() {
+        let mut buf = buffer_at("a | b\nc | d", 0, 0);
+        assert!(!buf.format_table());
+        let mut buf = buffer_at("no pipes", 0, 0);
+        assert!(!buf.format_table());
+    }
+
+    #[test]
+    fn test_split_row_keeps_escaped_pipes() {
+        assert_eq!(split_row(r"| a \| b | c |"), vec![r"a \| b", "c"]);
+        assert_eq!(split_row("a | b"), vec!["a", "b"]);
+    }
+}
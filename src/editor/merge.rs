@@ -0,0 +1,373 @@
+//! Three-way merge of the editor buffer with a file that changed on disk.
+//!
+//! Both versions are diffed against the base text loaded when editing
+//! started. Changes that touch different lines are combined automatically;
+//! overlapping ones become conflicts the user resolves one by one.
+
+use super::diff::{DiffLine, Hunk, diff_lines, unified_diff};
+
+/// Unchanged lines shown around each hunk of the disk-vs-buffer diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Which side of a conflict ends up in the merged text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The editor buffer's lines.
+    Ours,
+    /// The lines on disk.
+    Theirs,
+    /// The buffer's lines followed by the disk's.
+    Both,
+}
+
+/// A run of lines in the merge result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeChunk {
+    /// Lines that need no decision.
+    Resolved(Vec<String>),
+    /// Overlapping edits on both sides.
+    Conflict {
+        ours: Vec<String>,
+        theirs: Vec<String>,
+    },
+}
+
+/// What a row of the merge view shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeRowKind {
+    Merged,
+    /// Start of conflict `n`.
+    ConflictStart(usize),
+    Ours(usize),
+    /// Divider between the two sides of conflict `n`.
+    ConflictSeparator(usize),
+    Theirs(usize),
+    ConflictEnd(usize),
+}
+
+/// One row of the merge view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeRow<'a> {
+    pub kind: MergeRowKind,
+    pub text: &'a str,
+}
+
+/// Merge `ours` and `theirs`, which both started out as `base`.
+///
+/// Returns the chunks and how many changed regions merged cleanly.
+pub fn merge3(base: &str, ours: &str, theirs: &str) -> (Vec<MergeChunk>, usize) {
+    let base: Vec<&str> = base.split('\n').collect();
+    let ours: Vec<&str> = ours.split('\n').collect();
+    let theirs: Vec<&str> = theirs.split('\n').collect();
+    let our_hunks = diff_lines(&base, &ours);
+    let their_hunks = diff_lines(&base, &theirs);
+
+    let mut chunks = Vec::new();
+    let mut auto_merged = 0;
+    let mut unchanged = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    // Line offsets of each side relative to base before the current group.
+    let (mut our_delta, mut their_delta) = (0_isize, 0_isize);
+    let mut pos = 0;
+    while i < our_hunks.len() || j < their_hunks.len() {
+        // Grow a group from the earliest hunk until no hunk on either side
+        // touches it.
+        let start = match (our_hunks.get(i), their_hunks.get(j)) {
+            (Some(a), Some(b)) => a.old.start.min(b.old.start),
+            (Some(a), None) => a.old.start,
+            (None, Some(b)) => b.old.start,
+            (None, None) => break,
+        };
+        let mut end = start;
+        let (first_ours, first_theirs) = (i, j);
+        loop {
+            let grown =
+                absorb(&our_hunks, &mut i, &mut end) | absorb(&their_hunks, &mut j, &mut end);
+            if !grown {
+                break;
+            }
+        }
+
+        unchanged.extend(base[pos..start].iter().map(ToString::to_string));
+        pos = end;
+        let our_lines = side_lines(&ours, start, end, our_delta, &our_hunks[first_ours..i]);
+        let their_lines = side_lines(
+            &theirs,
+            start,
+            end,
+            their_delta,
+            &their_hunks[first_theirs..j],
+        );
+        our_delta += delta(&our_hunks[first_ours..i]);
+        their_delta += delta(&their_hunks[first_theirs..j]);
+
+        // One-sided changes and identical edits on both sides merge cleanly.
+        let changed_both = first_ours != i && first_theirs != j;
+        if !changed_both || our_lines == their_lines {
+            auto_merged += 1;
+            unchanged.extend(if first_ours == i {
+                their_lines
+            } else {
+                our_lines
+            });
+        } else {
+            if !unchanged.is_empty() {
+                chunks.push(MergeChunk::Resolved(std::mem::take(&mut unchanged)));
+            }
+            chunks.push(MergeChunk::Conflict {
+                ours: our_lines,
+                theirs: their_lines,
+            });
+        }
+    }
+    unchanged.extend(base[pos..].iter().map(ToString::to_string));
+    chunks.push(MergeChunk::Resolved(unchanged));
+    (chunks, auto_merged)
+}
+
+/// Take the next hunk of one side into the group ending at `end` if it
+/// overlaps or touches it. Returns whether the group grew.
+fn absorb(hunks: &[Hunk], next: &mut usize, end: &mut usize) -> bool {
+    match hunks.get(*next) {
+        Some(hunk) if hunk.old.start <= *end => {
+            *end = (*end).max(hunk.old.end);
+            *next += 1;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Net lines added by `hunks`.
+fn delta(hunks: &[Hunk]) -> isize {
+    hunks
+        .iter()
+        .map(|hunk| hunk.new.len().cast_signed() - hunk.old.len().cast_signed())
+        .sum()
+}
+
+/// One side's lines for base lines `start..end`.
+fn side_lines(
+    lines: &[&str],
+    start: usize,
+    end: usize,
+    offset: isize,
+    hunks: &[Hunk],
+) -> Vec<String> {
+    let from = start.saturating_add_signed(offset);
+    let to = end.saturating_add_signed(offset + delta(hunks));
+    lines[from..to].iter().map(ToString::to_string).collect()
+}
+
+/// State of the merge view opened when saving over a file that changed
+/// on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeState {
+    chunks: Vec<MergeChunk>,
+    /// Choice for each conflict, in order.
+    choices: Vec<Option<Resolution>>,
+    /// Index of the selected conflict.
+    current: usize,
+    /// Changed regions that merged without a conflict.
+    pub auto_merged: usize,
+    /// The file's text on disk when the merge started.
+    pub disk_text: String,
+    /// Line diff from the disk text to the buffer.
+    pub diff: Vec<DiffLine>,
+    /// Whether the diff is shown instead of the merge.
+    pub showing_diff: bool,
+    /// First visible row.
+    pub scroll: usize,
+}
+
+impl MergeState {
+    /// Start merging the buffer text `ours` with `disk_text`, both based
+    /// on `base`.
+    pub fn new(base: &str, ours: &str, disk_text: String) -> Self {
+        let (chunks, auto_merged) = merge3(base, ours, &disk_text);
+        let conflicts = chunks
+            .iter()
+            .filter(|chunk| matches!(chunk, MergeChunk::Conflict { .. }))
+            .count();
+        let mut state = Self {
+            chunks,
+            choices: vec![None; conflicts],
+            current: 0,
+            auto_merged,
+            diff: unified_diff(&disk_text, ours, DIFF_CONTEXT),
+            disk_text,
+            showing_diff: false,
+            scroll: 0,
+        };
+        state.scroll_to_current();
+        state
+    }
+
+    /// Number of conflicts.
+    pub const fn conflict_count(&self) -> usize {
+        self.choices.len()
+    }
+
+    /// Number of conflicts without a choice yet.
+    pub fn unresolved_count(&self) -> usize {
+        self.choices
+            .iter()
+            .filter(|choice| choice.is_none())
+            .count()
+    }
+
+    /// Index of the selected conflict.
+    pub const fn current(&self) -> usize {
+        self.current
+    }
+
+    /// The choice made for conflict `idx`.
+    pub fn resolution(&self, idx: usize) -> Option<Resolution> {
+        self.choices.get(idx).copied().flatten()
+    }
+
+    /// Resolve the selected conflict and move on to the next open one.
+    pub fn resolve(&mut self, resolution: Resolution) {
+        let Some(choice) = self.choices.get_mut(self.current) else {
+            return;
+        };
+        *choice = Some(resolution);
+        let len = self.choices.len();
+        if let Some(next) = (1..len)
+            .map(|step| (self.current + step) % len)
+            .find(|idx| self.choices[*idx].is_none())
+        {
+            self.current = next;
+        }
+        self.scroll_to_current();
+    }
+
+    /// Select the next (or previous) conflict, wrapping around.
+    pub fn step(&mut self, forward: bool) {
+        let len = self.choices.len();
+        if len == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % len
+        } else {
+            (self.current + len - 1) % len
+        };
+        self.scroll_to_current();
+    }
+
+    /// The merged text, once every conflict is resolved.
+    pub fn result(&self) -> Option<String> {
+        let mut lines: Vec<&str> = Vec::new();
+        let mut conflict = 0;
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Resolved(resolved) => lines.extend(resolved.iter().map(String::as_str)),
+                MergeChunk::Conflict { ours, theirs } => {
+                    let choice = self.choices[conflict]?;
+                    conflict += 1;
+                    if matches!(choice, Resolution::Ours | Resolution::Both) {
+                        lines.extend(ours.iter().map(String::as_str));
+                    }
+                    if matches!(choice, Resolution::Theirs | Resolution::Both) {
+                        lines.extend(theirs.iter().map(String::as_str));
+                    }
+                }
+            }
+        }
+        Some(lines.join("\n"))
+    }
+
+    /// Rows of the merge view: merged text with conflicts shown as
+    /// marked blocks of both sides.
+    pub fn rows(&self) -> Vec<MergeRow<'_>> {
+        let mut rows = Vec::new();
+        let mut conflict = 0;
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Resolved(lines) => {
+                    rows.extend(lines.iter().map(|text| MergeRow {
+                        kind: MergeRowKind::Merged,
+                        text,
+                    }));
+                }
+                MergeChunk::Conflict { ours, theirs } => {
+                    let row = |kind, text| MergeRow { kind, text };
+                    rows.push(row(MergeRowKind::ConflictStart(conflict), ""));
+                    rows.extend(
+                        ours.iter()
+                            .map(|text| row(MergeRowKind::Ours(conflict), text)),
+                    );
+                    rows.push(row(MergeRowKind::ConflictSeparator(conflict), ""));
+                    rows.extend(
+                        theirs
+                            .iter()
+                            .map(|text| row(MergeRowKind::Theirs(conflict), text)),
+                    );
+                    rows.push(row(MergeRowKind::ConflictEnd(conflict), ""));
+                    conflict += 1;
+                }
+            }
+        }
+        rows
+    }
+
+    /// Scroll so the selected conflict sits near the top.
+    fn scroll_to_current(&mut self) {
+        let current = self.current;
+        if let Some(row) = self
+            .rows()
+            .iter()
+            .position(|row| row.kind == MergeRowKind::ConflictStart(current))
+        {
+            self.scroll = row.saturating_sub(3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge3_combines_separate_edits() {
+        let base = "a\nb\nc\nd\ne";
+        let ours = "A\nb\nc\nd\ne";
+        let theirs = "a\nb\nc\nd\nE\nf";
+        let (chunks, auto_merged) = merge3(base, ours, theirs);
+        assert_eq!(auto_merged, 2);
+        let state = MergeState::new(base, ours, theirs.to_string());
+        assert_eq!(state.conflict_count(), 0);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(state.result().as_deref(), Some("A\nb\nc\nd\nE\nf"));
+    }
+
+    #[test]
+    fn test_merge3_marks_overlapping_edits() {
+        let base = "title\nbody\nend";
+        let ours = "title\nmine\nend";
+        let theirs = "title\ntheirs\nend\nmore";
+        let mut state = MergeState::new(base, ours, theirs.to_string());
+        assert_eq!(state.conflict_count(), 1);
+        assert_eq!(state.auto_merged, 1);
+        assert_eq!(state.result(), None);
+
+        state.resolve(Resolution::Both);
+        assert_eq!(
+            state.result().as_deref(),
+            Some("title\nmine\ntheirs\nend\nmore")
+        );
+        state.resolve(Resolution::Theirs);
+        assert_eq!(state.result().as_deref(), Some("title\ntheirs\nend\nmore"));
+    }
+
+    #[test]
+    fn test_merge3_accepts_identical_edits() {
+        let (chunks, auto_merged) = merge3("a\nb", "a\nB", "a\nB");
+        assert_eq!(auto_merged, 1);
+        assert_eq!(
+            chunks,
+            vec![MergeChunk::Resolved(vec!["a".to_string(), "B".to_string()])]
+        );
+    }
+}
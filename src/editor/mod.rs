@@ -4,5 +4,18 @@
 //! designed for integration into the TEA architecture.
 
 mod buffer;
+mod diff;
+mod find;
+mod markdown;
+mod merge;
+mod swap;
+mod vim;
 
-pub use buffer::{Cursor, Direction, EditorBuffer};
+pub use buffer::{Cursor, Direction, EditorBuffer, Motion};
+pub use diff::{DiffLine, Hunk, diff_lines, unified_diff};
+pub use find::{FindField, FindState};
+pub use merge::{MergeRow, MergeRowKind, MergeState, Resolution};
+pub use swap::{
+    SwapRecovery, remove_swap, swap_path, take_leftover_swap, write_atomic, write_swap,
+};
+pub use vim::{ExCommand, VimKey, VimMode, VimState};
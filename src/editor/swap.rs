@@ -0,0 +1,164 @@
+//! Crash recovery for the built-in editor.
+//!
+//! While the buffer has unsaved changes it is periodically written to a
+//! hidden swap file next to the edited file (`.notes.md.markless-swp`).
+//! The swap file is removed on save or a clean exit, so one that is still
+//! around when editing starts holds changes from a session that died.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::diff::{DiffLine, unified_diff};
+
+/// Suffix appended to the hidden swap file name.
+const SWAP_SUFFIX: &str = ".markless-swp";
+
+/// Unchanged lines shown around each hunk of the recovery diff.
+const DIFF_CONTEXT: usize = 2;
+
+/// A leftover swap file waiting for the user to recover or discard it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapRecovery {
+    /// Text saved in the swap file.
+    pub text: String,
+    /// Changes from the file on disk to the swap text.
+    pub diff: Vec<DiffLine>,
+    /// Whether the diff is shown instead of the prompt.
+    pub showing_diff: bool,
+    /// First visible diff row.
+    pub scroll: usize,
+}
+
+impl SwapRecovery {
+    /// Offer `swap` as a replacement for `disk`, the text currently on disk.
+    pub fn new(disk: &str, swap: String) -> Self {
+        Self {
+            diff: unified_diff(disk, &swap, DIFF_CONTEXT),
+            text: swap,
+            showing_diff: false,
+            scroll: 0,
+        }
+    }
+}
+
+/// Path of the swap file for `file`.
+pub fn swap_path(file: &Path) -> PathBuf {
+    let name = file
+        .file_name()
+        .map_or_else(|| "untitled".into(), |name| name.to_string_lossy());
+    file.with_file_name(format!(".{name}{SWAP_SUFFIX}"))
+}
+
+/// Write `text` to the swap file for `file`.
+///
+/// # Errors
+///
+/// Returns an error if the swap file can't be written.
+pub fn write_swap(file: &Path, text: &str) -> io::Result<()> {
+    write_atomic(&swap_path(file), text.as_bytes())
+}
+
+/// Remove the swap file for `file`, if there is one.
+///
+/// # Errors
+///
+/// Returns an error if an existing swap file can't be removed.
+pub fn remove_swap(file: &Path) -> io::Result<()> {
+    match fs::remove_file(swap_path(file)) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// The text of a swap file left behind by an earlier session.
+///
+/// Only swap files at least as new as `file` count. A swap file that
+/// matches `current` holds nothing to recover and is removed.
+pub fn take_leftover_swap(file: &Path, current: &str) -> Option<String> {
+    let swap = swap_path(file);
+    let swap_modified = fs::metadata(&swap).and_then(|meta| meta.modified()).ok()?;
+    let file_modified = fs::metadata(file).and_then(|meta| meta.modified()).ok();
+    if file_modified.is_some_and(|modified| modified > swap_modified) {
+        return None;
+    }
+    let text = fs::read_to_string(&swap).ok()?;
+    if text == current {
+        let _ = fs::remove_file(&swap);
+        return None;
+    }
+    Some(text)
+}
+
+/// Replace `path` with `contents` without ever leaving it truncated.
+///
+/// The data goes to a temporary file in the same directory, which is then
+/// renamed over the target. Symlinks are written through and the
+/// target's permissions are kept.
+///
+/// # Errors
+///
+/// Returns an error if the temporary file can't be written or renamed.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = target
+        .file_name()
+        .map_or_else(|| "untitled".into(), |name| name.to_string_lossy());
+    let tmp = target.with_file_name(format!(".{name}.markless-tmp-{}", std::process::id()));
+    let result = write_then_rename(&tmp, &target, contents);
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn write_then_rename(tmp: &Path, target: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = fs::File::create(tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    if let Ok(meta) = fs::metadata(target) {
+        fs::set_permissions(tmp, meta.permissions())?;
+    }
+    fs::rename(tmp, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_path_is_hidden_sibling() {
+        assert_eq!(
+            swap_path(Path::new("docs/notes.md")),
+            PathBuf::from("docs/.notes.md.markless-swp")
+        );
+    }
+
+    #[test]
+    fn test_leftover_swap_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        write_atomic(&file, b"saved").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "saved");
+
+        write_swap(&file, "unsaved").unwrap();
+        assert_eq!(
+            take_leftover_swap(&file, "saved").as_deref(),
+            Some("unsaved")
+        );
+        // A swap matching the file has nothing to offer and is cleaned up.
+        assert_eq!(take_leftover_swap(&file, "unsaved"), None);
+        assert!(!swap_path(&file).exists());
+        remove_swap(&file).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_no_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        fs::write(&file, "old").unwrap();
+        write_atomic(&file, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,1005 @@
+//! Vim-style modal editing for the built-in editor.
+//!
+//! [`VimState`] turns the keys of normal and visual mode into motions and
+//! operators on an [`EditorBuffer`]. Insert mode types through the editor's
+//! regular keys, so only `Esc` comes back here.
+
+use std::ops::Range;
+
+use super::buffer::{Cursor, Direction, EditorBuffer};
+
+/// Largest count accepted before a command.
+const MAX_COUNT: usize = 9999;
+
+/// The current Vim mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VimMode {
+    #[default]
+    Normal,
+    Insert,
+    /// Characterwise selection.
+    Visual,
+    /// Linewise selection.
+    VisualLine,
+}
+
+impl VimMode {
+    /// Name shown in the status bar.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "NORMAL",
+            Self::Insert => "INSERT",
+            Self::Visual => "VISUAL",
+            Self::VisualLine => "V-LINE",
+        }
+    }
+}
+
+/// A key for [`VimState::handle_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimKey {
+    Char(char),
+    /// A letter pressed with Ctrl.
+    Ctrl(char),
+    Esc,
+    Backspace,
+}
+
+/// A command entered on the `:` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    /// `:w`
+    Write,
+    /// `:q`, refused while there are unsaved changes.
+    Quit,
+    /// `:q!`
+    ForceQuit,
+    /// `:wq` or `:x`
+    WriteQuit,
+    Unknown(String),
+}
+
+impl ExCommand {
+    /// Parse the text typed after `:`.
+    pub fn parse(line: &str) -> Self {
+        match line.trim() {
+            "w" | "write" => Self::Write,
+            "q" | "quit" => Self::Quit,
+            "q!" | "quit!" => Self::ForceQuit,
+            "wq" | "x" | "xit" => Self::WriteQuit,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+impl Operator {
+    const fn from_char(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Self::Delete),
+            'c' => Some(Self::Change),
+            'y' => Some(Self::Yank),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VimMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordStart,
+    WordEnd,
+    WordBack,
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    FirstLine,
+    LastLine,
+}
+
+impl VimMotion {
+    /// The motion for a single key; `gg` is handled with the `g` prefix.
+    const fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            'h' => Self::Left,
+            'l' | ' ' => Self::Right,
+            'k' => Self::Up,
+            'j' => Self::Down,
+            'w' => Self::WordStart,
+            'e' => Self::WordEnd,
+            'b' => Self::WordBack,
+            '0' => Self::LineStart,
+            '^' => Self::FirstNonBlank,
+            '$' => Self::LineEnd,
+            'G' => Self::LastLine,
+            _ => return None,
+        })
+    }
+}
+
+/// Which text between the cursor and a motion's target an operator covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reach {
+    /// Up to the target, not including it.
+    Exclusive,
+    /// Up to and including the character at the target.
+    Inclusive,
+    /// Whole lines from the cursor's to the target's.
+    Linewise,
+}
+
+/// Text last deleted or yanked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+/// Modal editing state of the built-in editor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VimState {
+    mode: VimMode,
+    /// Fixed end of the visual selection; the cursor is the moving end.
+    anchor: Cursor,
+    /// Count typed so far for the next command.
+    count: Option<usize>,
+    /// Operator waiting for a motion, with the count typed before it.
+    operator: Option<(Operator, Option<usize>)>,
+    /// Whether `g` was typed and waits for its second key.
+    g_prefix: bool,
+    /// Keys of the unfinished command, shown in the status bar.
+    pending: String,
+    register: Register,
+    /// Text of the `:` line while it is open.
+    command_line: Option<String>,
+}
+
+impl VimState {
+    /// The current mode.
+    pub const fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Keys typed so far of an unfinished command, such as `2d`.
+    pub fn pending_keys(&self) -> &str {
+        &self.pending
+    }
+
+    /// The `:` line being typed, without the colon.
+    pub fn command_line(&self) -> Option<&str> {
+        self.command_line.as_deref()
+    }
+
+    /// Close the `:` line once its command has run.
+    pub fn close_command_line(&mut self) {
+        self.command_line = None;
+    }
+
+    /// The visual selection as `(start, end)` in document order, with the
+    /// end exclusive like [`EditorBuffer::selection`].
+    pub fn visual_selection(&self, buf: &EditorBuffer) -> Option<(Cursor, Cursor)> {
+        let (start, end) = ordered(self.anchor, buf.cursor());
+        match self.mode {
+            VimMode::Visual => Some((start, Cursor::at(end.line, next_col(buf, end)))),
+            VimMode::VisualLine => {
+                let end = if end.line + 1 < buf.line_count() {
+                    Cursor::at(end.line + 1, 0)
+                } else {
+                    Cursor::at(end.line, buf.line_len(end.line))
+                };
+                Some((Cursor::at(start.line, 0), end))
+            }
+            VimMode::Normal | VimMode::Insert => None,
+        }
+    }
+
+    /// Apply a key pressed outside insert mode, or `Esc` in insert mode.
+    pub fn handle_key(&mut self, key: VimKey, buf: &mut EditorBuffer) {
+        if let Some(line) = &mut self.command_line {
+            match key {
+                VimKey::Char(c) => line.push(c),
+                VimKey::Backspace => {
+                    if line.pop().is_none() {
+                        self.command_line = None;
+                    }
+                }
+                VimKey::Esc => self.command_line = None,
+                VimKey::Ctrl(_) => {}
+            }
+            return;
+        }
+
+        match (self.mode, key) {
+            (VimMode::Insert, VimKey::Esc) => {
+                self.mode = VimMode::Normal;
+                if buf.cursor().col > 0 {
+                    buf.move_cursor(Direction::Left);
+                }
+            }
+            (VimMode::Insert, _) => {}
+            (_, VimKey::Esc) => {
+                self.reset_pending();
+                self.mode = VimMode::Normal;
+            }
+            (_, VimKey::Ctrl('r')) => {
+                for _ in 0..self.count.take().unwrap_or(1) {
+                    buf.redo();
+                }
+                self.reset_pending();
+            }
+            (_, VimKey::Ctrl(_)) => self.reset_pending(),
+            (_, VimKey::Backspace) => self.command(buf, 'h'),
+            (_, VimKey::Char(c)) => self.command(buf, c),
+        }
+        if self.mode != VimMode::Insert {
+            buf.clamp_cursor_to_last_char();
+        }
+    }
+
+    fn command(&mut self, buf: &mut EditorBuffer, c: char) {
+        if self.g_prefix {
+            self.g_prefix = false;
+            if c == 'g' {
+                self.motion(buf, VimMotion::FirstLine);
+            } else {
+                self.reset_pending();
+            }
+            return;
+        }
+        if let Some(digit) = c.to_digit(10)
+            && (digit != 0 || self.count.is_some())
+        {
+            let count = self.count.unwrap_or(0) * 10 + digit as usize;
+            self.count = Some(count.min(MAX_COUNT));
+            self.pending.push(c);
+            return;
+        }
+        if c == 'g' {
+            self.g_prefix = true;
+            self.pending.push(c);
+            return;
+        }
+        if let Some(motion) = VimMotion::from_char(c) {
+            self.motion(buf, motion);
+            return;
+        }
+        if let Some(op) = Operator::from_char(c) {
+            self.operator_key(buf, op, c);
+            return;
+        }
+        if self.operator.is_some() {
+            self.reset_pending();
+            return;
+        }
+
+        let visual = matches!(self.mode, VimMode::Visual | VimMode::VisualLine);
+        let count = self.count.take().unwrap_or(1);
+        self.reset_pending();
+        let line = buf.cursor().line;
+        match c {
+            'x' | 's' if visual => {
+                let op = if c == 'x' {
+                    Operator::Delete
+                } else {
+                    Operator::Change
+                };
+                self.apply_visual(buf, op);
+            }
+            'o' if visual => {
+                let cursor = buf.cursor();
+                buf.move_to(self.anchor.line, self.anchor.col);
+                self.anchor = cursor;
+            }
+            'i' => self.enter_insert(buf),
+            'a' => {
+                let cursor = buf.cursor();
+                buf.move_to(cursor.line, next_col(buf, cursor));
+                self.enter_insert(buf);
+            }
+            'I' => {
+                buf.move_to(line, first_non_blank(buf, line));
+                self.enter_insert(buf);
+            }
+            'A' => {
+                buf.move_end();
+                self.enter_insert(buf);
+            }
+            'o' | 'O' => self.open_line(buf, c == 'o'),
+            'x' => self.apply_motion(buf, Operator::Delete, VimMotion::Right, Some(count)),
+            'X' => self.apply_motion(buf, Operator::Delete, VimMotion::Left, Some(count)),
+            's' => self.apply_motion(buf, Operator::Change, VimMotion::Right, Some(count)),
+            'D' => self.apply_motion(buf, Operator::Delete, VimMotion::LineEnd, Some(count)),
+            'C' => self.apply_motion(buf, Operator::Change, VimMotion::LineEnd, Some(count)),
+            'S' | 'Y' => {
+                let op = if c == 'S' {
+                    Operator::Change
+                } else {
+                    Operator::Yank
+                };
+                let last = (line + count - 1).min(buf.line_count() - 1);
+                self.operate_lines(buf, op, line, last);
+            }
+            'p' | 'P' => self.paste(buf, c == 'p', count),
+            'u' => {
+                for _ in 0..count {
+                    buf.undo();
+                }
+                self.mode = VimMode::Normal;
+            }
+            'v' | 'V' => {
+                let mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+                if !visual {
+                    self.anchor = buf.cursor();
+                }
+                self.mode = if self.mode == mode {
+                    VimMode::Normal
+                } else {
+                    mode
+                };
+            }
+            ':' => self.command_line = Some(String::new()),
+            _ => {}
+        }
+    }
+
+    /// `d`, `c` or `y`: act on the visual selection, wait for a motion,
+    /// or act on whole lines when doubled (`dd`).
+    fn operator_key(&mut self, buf: &mut EditorBuffer, op: Operator, c: char) {
+        if matches!(self.mode, VimMode::Visual | VimMode::VisualLine) {
+            self.apply_visual(buf, op);
+            return;
+        }
+        match self.operator {
+            None => {
+                self.operator = Some((op, self.count.take()));
+                self.pending.push(c);
+            }
+            Some((pending, op_count)) if pending == op => {
+                let count = combine_counts(op_count, self.count.take()).unwrap_or(1);
+                let first = buf.cursor().line;
+                let last = (first + count - 1).min(buf.line_count() - 1);
+                self.reset_pending();
+                self.operate_lines(buf, op, first, last);
+            }
+            Some(_) => self.reset_pending(),
+        }
+    }
+
+    /// Move the cursor, or apply the pending operator up to the target.
+    fn motion(&mut self, buf: &mut EditorBuffer, motion: VimMotion) {
+        let count = self.count.take();
+        let operator = self.operator.take();
+        self.reset_pending();
+        if let Some((op, op_count)) = operator {
+            self.apply_motion(buf, op, motion, combine_counts(op_count, count));
+            return;
+        }
+        match motion {
+            VimMotion::Up | VimMotion::Down => {
+                let direction = if motion == VimMotion::Up {
+                    Direction::Up
+                } else {
+                    Direction::Down
+                };
+                // Stepping keeps the column remembered for vertical moves.
+                for _ in 0..count.unwrap_or(1).min(buf.line_count()) {
+                    buf.move_cursor(direction);
+                }
+            }
+            _ => {
+                let (target, _) = target(buf, buf.cursor(), motion, count, false);
+                buf.move_to(target.line, target.col);
+            }
+        }
+    }
+
+    fn apply_motion(
+        &mut self,
+        buf: &mut EditorBuffer,
+        op: Operator,
+        motion: VimMotion,
+        count: Option<usize>,
+    ) {
+        let from = buf.cursor();
+        // `cw` on a word changes only up to its end, like `ce`.
+        let (target, reach) = if op == Operator::Change
+            && motion == VimMotion::WordStart
+            && Walker::new(buf, from)
+                .ch()
+                .is_some_and(|c| !c.is_whitespace())
+        {
+            let mut pos = word_end(buf, from, true);
+            for _ in 1..count.unwrap_or(1) {
+                pos = word_end(buf, pos, false);
+            }
+            (pos, Reach::Inclusive)
+        } else {
+            target(buf, from, motion, count, true)
+        };
+
+        let (start, end) = ordered(from, target);
+        match reach {
+            Reach::Linewise => self.operate_lines(buf, op, start.line, end.line),
+            Reach::Exclusive => self.operate_chars(buf, op, byte_of(buf, start)..byte_of(buf, end)),
+            Reach::Inclusive => {
+                let end = Cursor::at(end.line, next_col(buf, end));
+                self.operate_chars(buf, op, byte_of(buf, start)..byte_of(buf, end));
+            }
+        }
+    }
+
+    fn apply_visual(&mut self, buf: &mut EditorBuffer, op: Operator) {
+        let (start, end) = ordered(self.anchor, buf.cursor());
+        let linewise = self.mode == VimMode::VisualLine;
+        self.mode = VimMode::Normal;
+        self.reset_pending();
+        if linewise {
+            self.operate_lines(buf, op, start.line, end.line);
+        } else {
+            let end = Cursor::at(end.line, next_col(buf, end));
+            self.operate_chars(buf, op, byte_of(buf, start)..byte_of(buf, end));
+        }
+    }
+
+    /// Apply `op` to a byte range of the text.
+    fn operate_chars(&mut self, buf: &mut EditorBuffer, op: Operator, range: Range<usize>) {
+        if !range.is_empty() {
+            self.register = Register {
+                text: buf.text_range(range.clone()),
+                linewise: false,
+            };
+        }
+        if op == Operator::Yank || range.is_empty() {
+            let start = buf.cursor_at_byte(range.start);
+            buf.move_to(start.line, start.col);
+        } else {
+            buf.replace_ranges_and_move(&[(range.clone(), String::new())], range.start);
+        }
+        if op == Operator::Change {
+            self.enter_insert(buf);
+        }
+    }
+
+    /// Apply `op` to lines `first..=last`.
+    fn operate_lines(&mut self, buf: &mut EditorBuffer, op: Operator, first: usize, last: usize) {
+        let last_line = buf.line_count() - 1;
+        let start = buf.line_start_byte(first);
+        let end = if last < last_line {
+            buf.line_start_byte(last + 1)
+        } else {
+            buf.len_bytes()
+        };
+        let mut text = buf.text_range(start..end);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        self.register = Register {
+            text,
+            linewise: true,
+        };
+
+        match op {
+            Operator::Yank => {
+                let cursor = buf.cursor();
+                if cursor.line != first {
+                    buf.move_to(first, cursor.col);
+                }
+            }
+            Operator::Delete => {
+                // Deleting through the last line takes the line break before it.
+                let start = if last == last_line && first > 0 {
+                    byte_of(buf, Cursor::at(first - 1, buf.line_len(first - 1)))
+                } else {
+                    start
+                };
+                if start < end {
+                    buf.replace_ranges_and_move(&[(start..end, String::new())], start);
+                }
+                let line = first.min(buf.line_count() - 1);
+                buf.move_to(line, first_non_blank(buf, line));
+            }
+            Operator::Change => {
+                // Keep the first line's indentation.
+                let start = start + first_non_blank(buf, first);
+                let end = byte_of(buf, Cursor::at(last, buf.line_len(last)));
+                if start < end {
+                    buf.replace_ranges_and_move(&[(start..end, String::new())], start);
+                } else {
+                    let cursor = buf.cursor_at_byte(start);
+                    buf.move_to(cursor.line, cursor.col);
+                }
+                self.enter_insert(buf);
+            }
+        }
+    }
+
+    /// `p` / `P`: put the register after or before the cursor.
+    fn paste(&self, buf: &mut EditorBuffer, after: bool, count: usize) {
+        if self.register.text.is_empty() {
+            return;
+        }
+        let text = self.register.text.repeat(count);
+        let cursor = buf.cursor();
+        if self.register.linewise {
+            let (at, text, line) = if !after {
+                (buf.line_start_byte(cursor.line), text, cursor.line)
+            } else if cursor.line + 1 < buf.line_count() {
+                (buf.line_start_byte(cursor.line + 1), text, cursor.line + 1)
+            } else {
+                // Below the last line, which has no line break to insert after.
+                let text = format!("\n{}", text.strip_suffix('\n').unwrap_or(&text));
+                (buf.len_bytes(), text, cursor.line + 1)
+            };
+            buf.replace_ranges_and_move(&[(at..at, text)], at);
+            buf.move_to(line, first_non_blank(buf, line));
+        } else {
+            let at = if after {
+                byte_of(buf, Cursor::at(cursor.line, next_col(buf, cursor)))
+            } else {
+                byte_of(buf, cursor)
+            };
+            // Leave the cursor on the last pasted character.
+            let last = text.chars().next_back().map_or(0, char::len_utf8);
+            let end = at + text.len() - last;
+            buf.replace_ranges_and_move(&[(at..at, text)], end);
+        }
+    }
+
+    /// `o` / `O`: open an indented line below or above and start inserting.
+    fn open_line(&mut self, buf: &mut EditorBuffer, below: bool) {
+        let line = buf.cursor().line;
+        let text = buf.line_at(line).unwrap_or_default();
+        let indent: String = text
+            .chars()
+            .take_while(|c| matches!(c, ' ' | '\t'))
+            .collect();
+        if below {
+            let at = byte_of(buf, Cursor::at(line, text.len()));
+            let cursor = at + 1 + indent.len();
+            buf.replace_ranges_and_move(&[(at..at, format!("\n{indent}"))], cursor);
+        } else {
+            let at = buf.line_start_byte(line);
+            let cursor = at + indent.len();
+            buf.replace_ranges_and_move(&[(at..at, format!("{indent}\n"))], cursor);
+        }
+        self.enter_insert(buf);
+    }
+
+    /// Switch to insert mode; what follows is typed as a new undo step.
+    fn enter_insert(&mut self, buf: &mut EditorBuffer) {
+        let cursor = buf.cursor();
+        buf.move_to(cursor.line, cursor.col);
+        self.mode = VimMode::Insert;
+    }
+
+    fn reset_pending(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.g_prefix = false;
+        self.pending.clear();
+    }
+}
+
+/// The count for an operator typed with counts before and after it
+/// (`2d3w` deletes six words).
+fn combine_counts(before: Option<usize>, after: Option<usize>) -> Option<usize> {
+    match (before, after) {
+        (None, None) => None,
+        _ => Some((before.unwrap_or(1) * after.unwrap_or(1)).min(MAX_COUNT)),
+    }
+}
+
+/// Where `motion` repeated `count` times leads from `from`, and how an
+/// operator would cover the text up to it.
+fn target(
+    buf: &EditorBuffer,
+    from: Cursor,
+    motion: VimMotion,
+    count: Option<usize>,
+    for_operator: bool,
+) -> (Cursor, Reach) {
+    let n = count.unwrap_or(1);
+    let last_line = buf.line_count() - 1;
+    match motion {
+        VimMotion::Left => {
+            let text = buf.line_at(from.line).unwrap_or_default();
+            let col = text[..from.col.min(text.len())]
+                .char_indices()
+                .rev()
+                .nth(n - 1)
+                .map_or(0, |(col, _)| col);
+            (Cursor::at(from.line, col), Reach::Exclusive)
+        }
+        VimMotion::Right => {
+            let text = buf.line_at(from.line).unwrap_or_default();
+            let rest = &text[from.col.min(text.len())..];
+            let col = from.col + rest.char_indices().nth(n).map_or(rest.len(), |(i, _)| i);
+            (Cursor::at(from.line, col), Reach::Exclusive)
+        }
+        VimMotion::Up => (
+            Cursor::at(from.line.saturating_sub(n), from.col),
+            Reach::Linewise,
+        ),
+        VimMotion::Down => (
+            Cursor::at((from.line + n).min(last_line), from.col),
+            Reach::Linewise,
+        ),
+        VimMotion::WordStart => {
+            let mut pos = from;
+            for _ in 0..n {
+                pos = word_start(buf, pos);
+            }
+            // An operator stops at the end of the line the last word was
+            // on instead of reaching into the next one.
+            if for_operator && pos.line > from.line {
+                let line = pos.line - 1;
+                pos = Cursor::at(line, buf.line_len(line));
+            }
+            (pos, Reach::Exclusive)
+        }
+        VimMotion::WordEnd => {
+            let mut pos = from;
+            for _ in 0..n {
+                pos = word_end(buf, pos, false);
+            }
+            (pos, Reach::Inclusive)
+        }
+        VimMotion::WordBack => {
+            let mut pos = from;
+            for _ in 0..n {
+                pos = word_back(buf, pos);
+            }
+            (pos, Reach::Exclusive)
+        }
+        VimMotion::LineStart => (Cursor::at(from.line, 0), Reach::Exclusive),
+        VimMotion::FirstNonBlank => (
+            Cursor::at(from.line, first_non_blank(buf, from.line)),
+            Reach::Exclusive,
+        ),
+        VimMotion::LineEnd => {
+            let line = (from.line + n - 1).min(last_line);
+            let text = buf.line_at(line).unwrap_or_default();
+            let col = text.char_indices().next_back().map_or(0, |(col, _)| col);
+            (Cursor::at(line, col), Reach::Inclusive)
+        }
+        VimMotion::FirstLine | VimMotion::LastLine => {
+            let default = if motion == VimMotion::FirstLine {
+                0
+            } else {
+                last_line
+            };
+            let line = count.map_or(default, |n| n - 1).min(last_line);
+            (
+                Cursor::at(line, first_non_blank(buf, line)),
+                Reach::Linewise,
+            )
+        }
+    }
+}
+
+/// Walks the text a character at a time. Each line break counts as one
+/// `'\n'` at the end of its line.
+struct Walker<'a> {
+    buf: &'a EditorBuffer,
+    line: usize,
+    col: usize,
+    text: String,
+}
+
+impl<'a> Walker<'a> {
+    fn new(buf: &'a EditorBuffer, pos: Cursor) -> Self {
+        let text = buf.line_at(pos.line).unwrap_or_default();
+        Self {
+            buf,
+            line: pos.line,
+            col: pos.col.min(text.len()),
+            text,
+        }
+    }
+
+    const fn pos(&self) -> Cursor {
+        Cursor::at(self.line, self.col)
+    }
+
+    /// The character under the walker; `None` at the end of the text.
+    fn ch(&self) -> Option<char> {
+        self.text[self.col..]
+            .chars()
+            .next()
+            .or_else(|| (self.line + 1 < self.buf.line_count()).then_some('\n'))
+    }
+
+    fn forward(&mut self) -> bool {
+        if let Some(ch) = self.text[self.col..].chars().next() {
+            self.col += ch.len_utf8();
+        } else if self.line + 1 < self.buf.line_count() {
+            self.line += 1;
+            self.col = 0;
+            self.text = self.buf.line_at(self.line).unwrap_or_default();
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn backward(&mut self) -> bool {
+        if let Some(ch) = self.text[..self.col].chars().next_back() {
+            self.col -= ch.len_utf8();
+        } else if self.line > 0 {
+            self.line -= 1;
+            self.text = self.buf.line_at(self.line).unwrap_or_default();
+            self.col = self.text.len();
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+/// Word characters, punctuation and blanks form separate classes; a
+/// word is a run of one non-blank class.
+fn char_class(ch: char) -> u8 {
+    if ch.is_whitespace() {
+        0
+    } else if ch.is_alphanumeric() || ch == '_' {
+        2
+    } else {
+        1
+    }
+}
+
+/// `w`: the start of the next word. Empty lines count as words.
+fn word_start(buf: &EditorBuffer, pos: Cursor) -> Cursor {
+    let mut walker = Walker::new(buf, pos);
+    let Some(ch) = walker.ch() else {
+        return pos;
+    };
+    let class = char_class(ch);
+    if class != 0 {
+        while walker.forward() && walker.ch().is_some_and(|c| char_class(c) == class) {}
+    }
+    while walker.ch().is_some_and(char::is_whitespace) {
+        if walker.line != pos.line && walker.text.is_empty() {
+            break;
+        }
+        if !walker.forward() {
+            break;
+        }
+    }
+    walker.pos()
+}
+
+/// `e`: the end of the next word, or of the current one when `stay` is
+/// set and the cursor is already on it.
+fn word_end(buf: &EditorBuffer, pos: Cursor, stay: bool) -> Cursor {
+    let mut walker = Walker::new(buf, pos);
+    if !stay && !walker.forward() {
+        return pos;
+    }
+    while walker.ch().is_some_and(char::is_whitespace) {
+        if !walker.forward() {
+            break;
+        }
+    }
+    let Some(ch) = walker.ch() else {
+        return walker.pos();
+    };
+    let class = char_class(ch);
+    while walker.forward() {
+        if walker.ch().is_none_or(|c| char_class(c) != class) {
+            walker.backward();
+            break;
+        }
+    }
+    walker.pos()
+}
+
+/// `b`: the start of the previous word. Empty lines count as words.
+fn word_back(buf: &EditorBuffer, pos: Cursor) -> Cursor {
+    let mut walker = Walker::new(buf, pos);
+    if !walker.backward() {
+        return pos;
+    }
+    while walker.ch().is_some_and(char::is_whitespace) {
+        if walker.text.is_empty() || !walker.backward() {
+            return walker.pos();
+        }
+    }
+    let Some(ch) = walker.ch() else {
+        return walker.pos();
+    };
+    let class = char_class(ch);
+    while walker.backward() {
+        if walker.ch().is_none_or(|c| char_class(c) != class) {
+            walker.forward();
+            break;
+        }
+    }
+    walker.pos()
+}
+
+const fn ordered(a: Cursor, b: Cursor) -> (Cursor, Cursor) {
+    if a.line < b.line || (a.line == b.line && a.col <= b.col) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn byte_of(buf: &EditorBuffer, pos: Cursor) -> usize {
+    buf.line_start_byte(pos.line) + pos.col
+}
+
+/// The column just past the character at `pos`.
+fn next_col(buf: &EditorBuffer, pos: Cursor) -> usize {
+    let text = buf.line_at(pos.line).unwrap_or_default();
+    text.get(pos.col..)
+        .and_then(|rest| rest.chars().next())
+        .map_or(pos.col, |ch| pos.col + ch.len_utf8())
+}
+
+/// Column of the first non-blank character of `line`.
+fn first_non_blank(buf: &EditorBuffer, line: usize) -> usize {
+    let text = buf.line_at(line).unwrap_or_default();
+    text.find(|c: char| !matches!(c, ' ' | '\t'))
+        .unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `keys` to a fresh normal-mode state; `<` stands for Esc.
+    fn run(text: &str, keys: &str) -> (VimState, EditorBuffer) {
+        let mut buf = EditorBuffer::from_text(text);
+        let mut vim = VimState::default();
+        for c in keys.chars() {
+            let key = if c == '<' {
+                VimKey::Esc
+            } else {
+                VimKey::Char(c)
+            };
+            if vim.mode() == VimMode::Insert && key != VimKey::Esc {
+                buf.insert_char(c);
+            } else {
+                vim.handle_key(key, &mut buf);
+            }
+        }
+        (vim, buf)
+    }
+
+    fn cursor(buf: &EditorBuffer) -> (usize, usize) {
+        let c = buf.cursor();
+        (c.line, c.col)
+    }
+
+    #[test]
+    fn test_word_motions_cross_lines_and_punctuation() {
+        let text = "foo.bar baz\n\n  qux";
+        assert_eq!(cursor(&run(text, "w").1), (0, 3));
+        assert_eq!(cursor(&run(text, "3w").1), (0, 8));
+        assert_eq!(cursor(&run(text, "4w").1), (1, 0));
+        assert_eq!(cursor(&run(text, "5w").1), (2, 2));
+        assert_eq!(cursor(&run(text, "e").1), (0, 2));
+        assert_eq!(cursor(&run(text, "Gb").1), (1, 0));
+        assert_eq!(cursor(&run(text, "$b").1), (0, 8));
+    }
+
+    #[test]
+    fn test_line_motions_and_counts() {
+        let text = "one\n  two\nthree\nfour";
+        assert_eq!(cursor(&run(text, "$").1), (0, 2));
+        assert_eq!(cursor(&run(text, "j^").1), (1, 2));
+        assert_eq!(cursor(&run(text, "G").1), (3, 0));
+        assert_eq!(cursor(&run(text, "G2gg").1), (1, 2));
+        assert_eq!(cursor(&run(text, "Ggg").1), (0, 0));
+        assert_eq!(cursor(&run(text, "jj$0").1), (2, 0));
+        // The normal-mode cursor stays on the last character.
+        assert_eq!(cursor(&run(text, "10l").1), (0, 2));
+    }
+
+    #[test]
+    fn test_delete_with_motions() {
+        assert_eq!(run("one two three", "dw").1.text(), "two three");
+        assert_eq!(run("one two three", "2dw").1.text(), "three");
+        assert_eq!(run("one two three", "wde").1.text(), "one  three");
+        assert_eq!(run("one two three", "$db").1.text(), "one two e");
+        assert_eq!(run("one two three", "wD").1.text(), "one ");
+        assert_eq!(run("one two", "3x").1.text(), " two");
+        // `dw` on the last word of a line keeps the line break.
+        assert_eq!(run("one two\nthree", "wdw").1.text(), "one \nthree");
+    }
+
+    #[test]
+    fn test_linewise_delete_yank_and_paste() {
+        let (vim, buf) = run("a\nb\nc\nd", "jdd");
+        assert_eq!(buf.text(), "a\nc\nd");
+        assert_eq!(vim.register.text, "b\n");
+        assert_eq!(run("a\nb\nc\nd", "j2dd").1.text(), "a\nd");
+        assert_eq!(run("a\nb\nc\nd", "Gdd").1.text(), "a\nb\nc");
+        assert_eq!(run("a\nb\nc\nd", "dj").1.text(), "c\nd");
+        assert_eq!(run("a\nb\nc", "yyjp").1.text(), "a\nb\na\nc");
+        assert_eq!(run("a\nb\nc", "yyGp").1.text(), "a\nb\nc\na");
+        assert_eq!(run("a\nb\nc", "jyyP").1.text(), "a\nb\nb\nc");
+        let (_, buf) = run("a\nb", "ddp");
+        assert_eq!(buf.text(), "b\na");
+        assert_eq!(cursor(&buf), (1, 0));
+    }
+
+    #[test]
+    fn test_charwise_yank_and_paste() {
+        let (_, buf) = run("one two", "ywP");
+        assert_eq!(buf.text(), "one one two");
+        assert_eq!(cursor(&buf), (0, 3));
+        assert_eq!(run("ab", "xp").1.text(), "ba");
+        assert_eq!(run("one two", "yw$p").1.text(), "one twoone ");
+    }
+
+    #[test]
+    fn test_change_and_insert_commands() {
+        assert_eq!(run("one two", "cwXY<").1.text(), "XY two");
+        assert_eq!(run("one two", "c2wX<").1.text(), "X");
+        assert_eq!(run("  one\ntwo", "ccX<").1.text(), "  X\ntwo");
+        assert_eq!(run("one", "AX<").1.text(), "oneX");
+        assert_eq!(run("one", "$aX<").1.text(), "oneX");
+        assert_eq!(run("  one", "$IX<").1.text(), "  Xone");
+        assert_eq!(run("  one", "oX<").1.text(), "  one\n  X");
+        assert_eq!(run("one", "OX<").1.text(), "X\none");
+        let (vim, buf) = run("one", "A<");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert_eq!(cursor(&buf), (0, 2));
+    }
+
+    #[test]
+    fn test_visual_modes_operate_on_selection() {
+        assert_eq!(run("one two", "vlld").1.text(), " two");
+        assert_eq!(run("one two", "wvhhy$p").1.text(), "one twoe t");
+        assert_eq!(run("a\nb\nc", "Vjd").1.text(), "c");
+        let (vim, buf) = run("one two", "wvl");
+        assert_eq!(
+            vim.visual_selection(&buf),
+            Some((Cursor::at(0, 4), Cursor::at(0, 6)))
+        );
+        let (vim, buf) = run("one\ntwo", "Vj");
+        assert_eq!(
+            vim.visual_selection(&buf),
+            Some((Cursor::at(0, 0), Cursor::at(1, 3)))
+        );
+        assert_eq!(run("one", "v<").0.mode(), VimMode::Normal);
+    }
+
+    #[test]
+    fn test_undo_and_pending_keys() {
+        assert_eq!(run("one two", "dwu").1.text(), "one two");
+        let (vim, _) = run("one", "2d");
+        assert_eq!(vim.pending_keys(), "2d");
+        let (vim, _) = run("one", "2d<");
+        assert_eq!(vim.pending_keys(), "");
+    }
+
+    #[test]
+    fn test_command_line_collects_text() {
+        let (mut vim, mut buf) = run("one", ":wq");
+        assert_eq!(vim.command_line(), Some("wq"));
+        assert_eq!(ExCommand::parse("wq"), ExCommand::WriteQuit);
+        assert_eq!(ExCommand::parse(" q! "), ExCommand::ForceQuit);
+        assert_eq!(ExCommand::parse("e"), ExCommand::Unknown("e".to_string()));
+        for _ in 0..3 {
+            vim.handle_key(VimKey::Backspace, &mut buf);
+        }
+        assert_eq!(vim.command_line(), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! User grammars and code themes, and the on-disk cache of compiled sets.
+//!
+//! `.sublime-syntax` files in the syntaxes directory are added to syntect's
+//! built-in grammars and `.tmTheme` files in the themes directory to its
+//! built-in themes. Compiling grammars is slow, so the resulting sets are
+//! dumped to the cache directory under a name derived from the source
+//! files' names, sizes and modification times; any change to the files
+//! builds and caches a fresh set on the next start.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::{SyntaxDefinition, SyntaxSet};
+
+/// Where extra grammars and themes are read from, and the code theme to use.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighlightConfig {
+    /// Directory of `.sublime-syntax` grammars.
+    pub syntax_dir: Option<PathBuf>,
+    /// Directory of `.tmTheme` code themes.
+    pub theme_dir: Option<PathBuf>,
+    /// Directory for the compiled-set cache; `None` disables caching.
+    pub cache_dir: Option<PathBuf>,
+    /// Code theme by name (a built-in syntect theme or a `.tmTheme` file
+    /// stem); `None` picks one to suit the background.
+    pub code_theme: Option<String>,
+}
+
+/// Built-in grammars plus those in `config.syntax_dir`, and a description
+/// of each grammar file that failed to load.
+pub fn load_syntax_set(config: &HighlightConfig) -> (SyntaxSet, Vec<String>) {
+    let files = asset_files(config.syntax_dir.as_deref(), "sublime-syntax");
+    if files.is_empty() {
+        return (SyntaxSet::load_defaults_newlines(), Vec::new());
+    }
+    let cache = cache_path(config, "syntaxes", &files);
+    if let Some(set) = cache.as_deref().and_then(read_cache) {
+        return (set, Vec::new());
+    }
+
+    let _scope = crate::perf::scope("highlight.syntax_set.build");
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    let mut problems = Vec::new();
+    for path in &files {
+        let name = path.file_stem().map(|stem| stem.to_string_lossy());
+        let loaded = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| {
+                SyntaxDefinition::load_from_str(&content, true, name.as_deref())
+                    .map_err(|err| err.to_string())
+            });
+        match loaded {
+            Ok(syntax) => builder.add(syntax),
+            Err(err) => problems.push(format!("{}: {err}", path.display())),
+        }
+    }
+    let set = builder.build();
+    if problems.is_empty()
+        && let Some(path) = cache
+    {
+        write_cache(&path, &set);
+    }
+    (set, problems)
+}
+
+/// Built-in themes plus those in `config.theme_dir`, and a description of
+/// each theme file that failed to load.
+pub fn load_theme_set(config: &HighlightConfig) -> (ThemeSet, Vec<String>) {
+    let files = asset_files(config.theme_dir.as_deref(), "tmTheme");
+    if files.is_empty() {
+        return (ThemeSet::load_defaults(), Vec::new());
+    }
+    let cache = cache_path(config, "themes", &files);
+    if let Some(set) = cache.as_deref().and_then(read_cache) {
+        return (set, Vec::new());
+    }
+
+    let mut set = ThemeSet::load_defaults();
+    let mut problems = Vec::new();
+    for path in &files {
+        let Some(name) = path.file_stem().map(|stem| stem.to_string_lossy()) else {
+            continue;
+        };
+        match ThemeSet::get_theme(path) {
+            Ok(theme) => {
+                set.themes.insert(name.into_owned(), theme);
+            }
+            Err(err) => problems.push(format!("{}: {err}", path.display())),
+        }
+    }
+    if problems.is_empty()
+        && let Some(path) = cache
+    {
+        write_cache(&path, &set);
+    }
+    (set, problems)
+}
+
+/// Files in `dir` with extension `ext`, sorted by path.
+fn asset_files(dir: Option<&Path>, ext: &str) -> Vec<PathBuf> {
+    let Some(entries) = dir.and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == ext))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Cache file for a set built from `files`, named `<kind>-<fingerprint>.bin`.
+fn cache_path(config: &HighlightConfig, kind: &str, files: &[PathBuf]) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    for path in files {
+        path.hash(&mut hasher);
+        if let Ok(meta) = fs::metadata(path) {
+            meta.len().hash(&mut hasher);
+            meta.modified().ok().hash(&mut hasher);
+        }
+    }
+    let dir = config.cache_dir.as_deref()?;
+    Some(dir.join(format!("{kind}-{:016x}.bin", hasher.finish())))
+}
+
+fn read_cache<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let _scope = crate::perf::scope("highlight.cache.load");
+    syntect::dumps::from_uncompressed_dump_file(path).ok()
+}
+
+/// Write a set to the cache and remove older caches of the same kind.
+/// Failures only cost the next start a rebuild, so they are ignored.
+fn write_cache<T: Serialize>(path: &Path, set: &T) {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let tmp = path.with_extension("tmp");
+    if syntect::dumps::dump_to_uncompressed_file(set, &tmp).is_err()
+        || fs::rename(&tmp, path).is_err()
+    {
+        let _ = fs::remove_file(&tmp);
+        return;
+    }
+    let name = name.to_string_lossy();
+    let Some((kind, _)) = name.split_once('-') else {
+        return;
+    };
+    let stale_prefix = format!("{kind}-");
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let entry_name = entry.file_name().to_string_lossy().into_owned();
+        if entry_name.starts_with(&stale_prefix) && entry_name != name {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const TOML_SYNTAX: &str = r#"%YAML 1.2
+---
+name: Test TOML
+file_extensions: [tomltest]
+scope: source.tomltest
+contexts:
+  main:
+    - match: '#.*$'
+      scope: comment.line.tomltest
+    - match: '"[^"]*"'
+      scope: string.quoted.tomltest
+"#;
+
+    fn config(root: &Path) -> HighlightConfig {
+        HighlightConfig {
+            syntax_dir: Some(root.join("syntaxes")),
+            theme_dir: Some(root.join("themes")),
+            cache_dir: Some(root.join("cache")),
+            code_theme: None,
+        }
+    }
+
+    #[test]
+    fn test_user_syntax_is_added_and_cached() {
+        let root = tempdir().unwrap();
+        let config = config(root.path());
+        fs::create_dir_all(root.path().join("syntaxes")).unwrap();
+        fs::write(
+            root.path().join("syntaxes/test.sublime-syntax"),
+            TOML_SYNTAX,
+        )
+        .unwrap();
+
+        let (set, problems) = load_syntax_set(&config);
+        assert!(problems.is_empty(), "{problems:?}");
+        assert!(set.find_syntax_by_extension("tomltest").is_some());
+        assert!(set.find_syntax_by_token("rust").is_some());
+
+        let cached: Vec<_> = fs::read_dir(root.path().join("cache"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(cached.len(), 1, "{cached:?}");
+        assert!(cached[0].starts_with("syntaxes-"));
+
+        // A second load comes from the cache.
+        let (set, _) = load_syntax_set(&config);
+        assert!(set.find_syntax_by_extension("tomltest").is_some());
+    }
+
+    #[test]
+    fn test_changed_sources_replace_the_cache() {
+        let root = tempdir().unwrap();
+        let config = config(root.path());
+        let dir = root.path().join("syntaxes");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.sublime-syntax"), TOML_SYNTAX).unwrap();
+        load_syntax_set(&config);
+        let first = fs::read_dir(root.path().join("cache")).unwrap().count();
+
+        let renamed = TOML_SYNTAX
+            .replace("Test TOML", "Other")
+            .replace("tomltest", "othertest");
+        fs::write(dir.join("b.sublime-syntax"), renamed).unwrap();
+        let (set, _) = load_syntax_set(&config);
+        assert!(set.find_syntax_by_extension("othertest").is_some());
+        assert_eq!(first, 1);
+        assert_eq!(fs::read_dir(root.path().join("cache")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_bad_grammar_is_reported_and_not_cached() {
+        let root = tempdir().unwrap();
+        let config = config(root.path());
+        let dir = root.path().join("syntaxes");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.sublime-syntax"), "not: [valid").unwrap();
+
+        let (set, problems) = load_syntax_set(&config);
+        assert_eq!(problems.len(), 1);
+        assert!(
+            problems[0].contains("broken.sublime-syntax"),
+            "{problems:?}"
+        );
+        assert!(set.find_syntax_by_token("rust").is_some());
+        assert!(!root.path().join("cache").exists());
+    }
+
+    #[test]
+    fn test_user_theme_is_added_by_file_stem() {
+        let root = tempdir().unwrap();
+        let config = config(root.path());
+        let dir = root.path().join("themes");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("mine.tmTheme"), MINIMAL_TMTHEME).unwrap();
+
+        let (set, problems) = load_theme_set(&config);
+        assert!(problems.is_empty(), "{problems:?}");
+        assert!(set.themes.contains_key("mine"));
+        assert!(set.themes.contains_key("base16-ocean.dark"));
+    }
+
+    const MINIMAL_TMTHEME: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>name</key>
+  <string>Mine</string>
+  <key>settings</key>
+  <array>
+    <dict>
+      <key>settings</key>
+      <dict>
+        <key>foreground</key>
+        <string>#ABCDEF</string>
+      </dict>
+    </dict>
+    <dict>
+      <key>scope</key>
+      <string>comment</string>
+      <key>settings</key>
+      <dict>
+        <key>foreground</key>
+        <string>#808080</string>
+      </dict>
+    </dict>
+  </array>
+</dict>
+</plist>
+"#;
+}
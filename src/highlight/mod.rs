@@ -1,15 +1,21 @@
 //! Syntax highlighting for code blocks.
 //!
-//! Uses syntect for highlighting with Sublime Text syntax definitions.
+//! Uses syntect for highlighting with Sublime Text syntax definitions,
+//! plus any user grammars and themes (see [`HighlightConfig`]).
 
-use std::sync::{Mutex, OnceLock};
+mod assets;
 
-use syntect::easy::HighlightLines;
-use syntect::highlighting::{Theme, ThemeSet};
-use syntect::parsing::SyntaxSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::Result;
+
+use syntect::highlighting::{HighlightIterator, HighlightState, Highlighter, Theme, ThemeSet};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
 
 use crate::document::{InlineColor, InlineSpan, InlineStyle};
 
+pub use assets::{HighlightConfig, load_syntax_set, load_theme_set};
+
 // TODO: Implement syntax highlighting
 // - Load syntax set
 // - Load theme
@@ -266,37 +272,69 @@ fn leak_str(s: &str) -> &'static str {
 }
 
 pub fn highlight_code(language: Option<&str>, code: &str) -> Vec<Vec<InlineSpan>> {
-    let mut lines = Vec::new();
-    let syntax_set = syntax_set();
-    let mode = background_mode();
-    let syntax = language
-        .and_then(|lang| syntax_set.find_syntax_by_token(lang))
-        .or_else(|| language.and_then(|lang| syntax_set.find_syntax_by_name(lang)));
-
-    let Some(syntax) = syntax else {
-        for line in code.lines() {
-            let style = InlineStyle {
-                code: true,
-                ..InlineStyle::default()
-            };
-            lines.push(vec![InlineSpan::new(line.to_string(), style)]);
-        }
-        return lines;
+    let Some(mut highlighter) = language.and_then(LineHighlighter::new) else {
+        return code
+            .lines()
+            .map(|line| {
+                let style = InlineStyle {
+                    code: true,
+                    ..InlineStyle::default()
+                };
+                vec![InlineSpan::new(line.to_string(), style)]
+            })
+            .collect();
     };
 
-    let mut highlighter = HighlightLines::new(syntax, theme());
-    for line in code.lines() {
+    code.lines()
+        .map(|line| highlighter.highlight_line(line))
+        .collect()
+}
+
+/// Highlights code one line at a time, carrying syntax state between lines.
+///
+/// Cloning the highlighter snapshots that state, so callers can resume
+/// highlighting from any line they have already passed.
+#[derive(Debug, Clone)]
+pub struct LineHighlighter {
+    parse: ParseState,
+    highlight: HighlightState,
+    /// The theme's compiled selectors, shared by clones.
+    highlighter: Arc<Highlighter<'static>>,
+}
+
+impl LineHighlighter {
+    /// Start highlighting a language given by token (`rs`) or name (`Rust`).
+    pub fn new(language: &str) -> Option<Self> {
+        let syntax_set = syntax_set();
+        let syntax = syntax_set
+            .find_syntax_by_token(language)
+            .or_else(|| syntax_set.find_syntax_by_name(language))?;
+        let highlighter = Highlighter::new(theme());
+        Some(Self {
+            parse: ParseState::new(syntax),
+            highlight: HighlightState::new(&highlighter, ScopeStack::new()),
+            highlighter: Arc::new(highlighter),
+        })
+    }
+
+    /// Highlight the next line (without its line break).
+    pub fn highlight_line(&mut self, line: &str) -> Vec<InlineSpan> {
+        let syntax_set = syntax_set();
+        let mode = background_mode();
         // SyntaxSet::load_defaults_newlines() expects each line to end
         // with '\n'.  str::lines() strips newlines, so we must re-add it;
         // otherwise the parser never closes scopes that terminate at EOL
         // (e.g. `//` comments), and the unclosed scope bleeds into every
         // subsequent line.
         let line_nl = format!("{line}\n");
-        let ranges = highlighter
-            .highlight_line(&line_nl, syntax_set)
+        let ops = self
+            .parse
+            .parse_line(&line_nl, syntax_set)
             .unwrap_or_default();
         let mut spans = Vec::new();
-        for (style, text) in ranges {
+        for (style, text) in
+            HighlightIterator::new(&mut self.highlight, &ops, &line_nl, &self.highlighter)
+        {
             // Strip the trailing '\n' we added — it must not appear in the
             // rendered output.
             let text = text.trim_end_matches('\n');
@@ -315,25 +353,78 @@ pub fn highlight_code(language: Option<&str>, code: &str) -> Vec<Vec<InlineSpan>
             inline_style.fg = Some(adjust_fg_for_background(fg, mode));
             spans.push(InlineSpan::new(text.to_string(), inline_style));
         }
-        lines.push(spans);
+        spans
     }
+}
+
+static CONFIG: OnceLock<HighlightConfig> = OnceLock::new();
+
+/// Sets where user grammars and themes are loaded from and the code theme.
+///
+/// Takes effect only before the first code block is highlighted.
+pub fn set_highlight_config(config: HighlightConfig) {
+    let _ = CONFIG.set(config);
+}
 
-    lines
+fn config() -> &'static HighlightConfig {
+    CONFIG.get_or_init(HighlightConfig::default)
+}
+
+/// Check that the configured code theme, if any, names a known theme.
+///
+/// Loads the same theme set highlighting uses, so call it after
+/// [`set_highlight_config`].
+///
+/// # Errors
+/// Returns an error listing the available themes when it does not.
+pub fn check_code_theme() -> Result<()> {
+    let Some(name) = config().code_theme.as_deref() else {
+        return Ok(());
+    };
+    let (theme_set, _) = theme_set();
+    if theme_set.themes.contains_key(name) {
+        return Ok(());
+    }
+    let names: Vec<&str> = theme_set.themes.keys().map(String::as_str).collect();
+    anyhow::bail!(
+        "Unknown code theme \"{name}\" (available: {})",
+        names.join(", ")
+    )
 }
 
 fn syntax_set() -> &'static SyntaxSet {
     static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
     SYNTAX_SET.get_or_init(|| {
-        let _scope = crate::perf::scope("highlight.syntax_set.load_defaults");
-        SyntaxSet::load_defaults_newlines()
+        let _scope = crate::perf::scope("highlight.syntax_set.load");
+        load_syntax_set(config()).0
+    })
+}
+
+/// Theme files that failed to load into the theme set highlighting uses.
+pub fn theme_set_problems() -> &'static [String] {
+    &theme_set().1
+}
+
+fn theme_set() -> &'static (ThemeSet, Vec<String>) {
+    static THEME_SET: OnceLock<(ThemeSet, Vec<String>)> = OnceLock::new();
+    THEME_SET.get_or_init(|| {
+        let _scope = crate::perf::scope("highlight.theme_set.load");
+        load_theme_set(config())
     })
 }
 
 fn theme() -> &'static Theme {
     static THEME: OnceLock<Theme> = OnceLock::new();
     THEME.get_or_init(|| {
-        let _scope = crate::perf::scope("highlight.theme.load_defaults");
-        let theme_set = ThemeSet::load_defaults();
+        let _scope = crate::perf::scope("highlight.theme.load");
+        let (theme_set, _) = theme_set();
+        if let Some(theme) = config()
+            .code_theme
+            .as_deref()
+            .and_then(|name| theme_set.themes.get(name))
+        {
+            return theme.clone();
+        }
         let mode = background_mode();
         let preferred = match mode {
             BackgroundMode::Dark => [
@@ -89,6 +89,15 @@ pub fn create_picker(image_mode: Option<ImageMode>) -> Option<Picker> {
     // Try to create a picker, which will detect the terminal's capabilities
     #[cfg(unix)]
     {
+        // The capability query reads the reply from stdin. When the document
+        // itself was piped in there is no terminal on stdin to answer.
+        if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            crate::perf::log_event(
+                "image.create_picker",
+                "stdin not a terminal protocol=Halfblocks",
+            );
+            return Some(Picker::halfblocks());
+        }
         let picker = Picker::from_query_stdio_with_options(query_options()).ok()?;
         crate::perf::log_event(
             "image.create_picker",
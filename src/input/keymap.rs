@@ -0,0 +1,740 @@
+//! Configurable view-mode keybindings.
+//!
+//! A [`Keymap`] maps key sequences (one or more [`KeyChord`]s, e.g. `gg`)
+//! to named [`Action`]s, separately for the document view and the focused
+//! TOC sidebar. The defaults reproduce markless' built-in keys; the
+//! `[keys]` sections of the config file override them.
+
+use std::fmt;
+
+use anyhow::{Result, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeyBinding;
+
+/// A single key press: a key code plus the Ctrl/Alt/Shift state.
+///
+/// Shift is folded into the character for printable keys, so `G` and
+/// `Shift-g` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// A chord for `code` with no modifiers.
+    pub const fn plain(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// Parse a key sequence such as `gg`, `Ctrl-d`, `Space` or `Ctrl-w j`.
+    ///
+    /// Whitespace separates chords. A token is either a named key
+    /// (`Enter`, `PageDown`, `F1`, …), a modifier-prefixed key
+    /// (`Ctrl-d`, `Alt-Left`, `Shift-Tab`), or a run of plain characters
+    /// where every character is its own chord. Returns `None` for an
+    /// empty sequence or an unrecognised modified key.
+    pub fn parse_sequence(text: &str) -> Option<Vec<Self>> {
+        let mut chords = Vec::new();
+        for token in text.split_whitespace() {
+            chords.extend(parse_token(token)?);
+        }
+        (!chords.is_empty()).then_some(chords)
+    }
+
+    const fn is_plain_char(self) -> bool {
+        self.modifiers.is_empty() && matches!(self.code, KeyCode::Char(c) if c != ' ')
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if !matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= key.modifiers & KeyModifiers::SHIFT;
+        }
+        Self {
+            code: key.code,
+            modifiers,
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, named)| *named == code)
+                    .map_or("?", |(name, _)| name);
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+/// Display a key sequence the way it is written in the config:
+/// runs of plain characters are joined (`gg`), anything else is
+/// separated by spaces (`Ctrl-w j`).
+pub fn format_sequence(chords: &[KeyChord]) -> String {
+    if chords.iter().all(|chord| chord.is_plain_char()) {
+        return chords.iter().map(ToString::to_string).collect();
+    }
+    chords
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Key names accepted in bindings, in their canonical spelling.
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("Space", KeyCode::Char(' ')),
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Tab", KeyCode::Tab),
+    ("Shift-Tab", KeyCode::BackTab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+];
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    if let Some(n) = name
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|n| (1..=12).contains(n))
+    {
+        return Some(KeyCode::F(n));
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "escape" => Some(KeyCode::Esc),
+        "return" => Some(KeyCode::Enter),
+        "backtab" => Some(KeyCode::BackTab),
+        "pgup" => Some(KeyCode::PageUp),
+        "pgdn" => Some(KeyCode::PageDown),
+        "del" => Some(KeyCode::Delete),
+        lower => NAMED_KEYS
+            .iter()
+            .find(|(named, _)| named.eq_ignore_ascii_case(lower))
+            .map(|(_, code)| *code),
+    }
+}
+
+fn parse_token(token: &str) -> Option<Vec<KeyChord>> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = token;
+    while let Some((head, tail)) = rest.split_once('-')
+        && !tail.is_empty()
+    {
+        modifiers |= match head.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        rest = tail;
+    }
+
+    if modifiers.is_empty() {
+        if let Some(code) = named_key(rest) {
+            return Some(vec![KeyChord::plain(code)]);
+        }
+        return Some(
+            rest.chars()
+                .map(|c| KeyChord::plain(KeyCode::Char(c)))
+                .collect(),
+        );
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => named_key(rest)?,
+    };
+    let code = match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            KeyCode::Char(c.to_ascii_uppercase())
+        }
+        KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            KeyCode::BackTab
+        }
+        code => code,
+    };
+    Some(vec![KeyChord { code, modifiers }])
+}
+
+/// Where a binding applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    /// The document view (the default focus).
+    View,
+    /// The TOC or file-browser sidebar while it has focus.
+    Toc,
+}
+
+impl KeyContext {
+    /// Context for a config section name (`[keys.toc]` → `toc`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "view" => Some(Self::View),
+            "toc" => Some(Self::Toc),
+            _ => None,
+        }
+    }
+}
+
+/// A view-mode command that keys can be bound to.
+///
+/// Each action corresponds to an application `Message`, and is named after
+/// it in the config file (`go_to_top` for `GoToTop`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    GoToTop,
+    GoToBottom,
+    StartSearch,
+    ClearSearch,
+    ProjectSearch,
+    ToggleToc,
+    ToggleTocFocus,
+    SwitchFocus,
+    TocDown,
+    TocUp,
+    TocSelect,
+    TocCollapse,
+    TocExpand,
+    HeadingPalette,
+    EnterBrowseMode,
+    EnterFileMode,
+    TocParent,
+    FileFinder,
+    EnterEditMode,
+    ToggleWatch,
+    ForceReload,
+    OpenVisibleLinks,
+    ToggleFrontMatter,
+    ToggleHelp,
+    Quit,
+}
+
+impl Action {
+    /// Every action, grouped by help section.
+    pub const ALL: [Self; 31] = [
+        Self::ScrollDown,
+        Self::ScrollUp,
+        Self::PageDown,
+        Self::PageUp,
+        Self::HalfPageDown,
+        Self::HalfPageUp,
+        Self::GoToTop,
+        Self::GoToBottom,
+        Self::StartSearch,
+        Self::ClearSearch,
+        Self::ProjectSearch,
+        Self::ToggleToc,
+        Self::ToggleTocFocus,
+        Self::SwitchFocus,
+        Self::TocDown,
+        Self::TocUp,
+        Self::TocSelect,
+        Self::TocCollapse,
+        Self::TocExpand,
+        Self::HeadingPalette,
+        Self::EnterBrowseMode,
+        Self::EnterFileMode,
+        Self::TocParent,
+        Self::FileFinder,
+        Self::EnterEditMode,
+        Self::ToggleWatch,
+        Self::ForceReload,
+        Self::OpenVisibleLinks,
+        Self::ToggleFrontMatter,
+        Self::ToggleHelp,
+        Self::Quit,
+    ];
+
+    /// Name used in the config file.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ScrollDown => "scroll_down",
+            Self::ScrollUp => "scroll_up",
+            Self::PageDown => "page_down",
+            Self::PageUp => "page_up",
+            Self::HalfPageDown => "half_page_down",
+            Self::HalfPageUp => "half_page_up",
+            Self::GoToTop => "go_to_top",
+            Self::GoToBottom => "go_to_bottom",
+            Self::StartSearch => "start_search",
+            Self::ClearSearch => "clear_search",
+            Self::ProjectSearch => "project_search",
+            Self::ToggleToc => "toggle_toc",
+            Self::ToggleTocFocus => "toggle_toc_focus",
+            Self::SwitchFocus => "switch_focus",
+            Self::TocDown => "toc_down",
+            Self::TocUp => "toc_up",
+            Self::TocSelect => "toc_select",
+            Self::TocCollapse => "toc_collapse",
+            Self::TocExpand => "toc_expand",
+            Self::HeadingPalette => "heading_palette",
+            Self::EnterBrowseMode => "enter_browse_mode",
+            Self::EnterFileMode => "enter_file_mode",
+            Self::TocParent => "toc_parent",
+            Self::FileFinder => "file_finder",
+            Self::EnterEditMode => "enter_edit_mode",
+            Self::ToggleWatch => "toggle_watch",
+            Self::ForceReload => "force_reload",
+            Self::OpenVisibleLinks => "open_visible_links",
+            Self::ToggleFrontMatter => "toggle_front_matter",
+            Self::ToggleHelp => "toggle_help",
+            Self::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// One-line description shown in the help overlay.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::ScrollDown => "Scroll down",
+            Self::ScrollUp => "Scroll up",
+            Self::PageDown => "Page down",
+            Self::PageUp => "Page up",
+            Self::HalfPageDown => "Half page down",
+            Self::HalfPageUp => "Half page up",
+            Self::GoToTop => "Top",
+            Self::GoToBottom => "Bottom",
+            Self::StartSearch => "Start search",
+            Self::ClearSearch => "Clear search",
+            Self::ProjectSearch => "Search all files (browse mode)",
+            Self::ToggleToc => "Toggle TOC",
+            Self::ToggleTocFocus => "Toggle + focus TOC",
+            Self::SwitchFocus => "Switch focus",
+            Self::TocDown => "Next entry",
+            Self::TocUp => "Previous entry",
+            Self::TocSelect => "Jump to entry",
+            Self::TocCollapse => "Collapse / parent dir",
+            Self::TocExpand => "Expand / enter dir",
+            Self::HeadingPalette => "Jump to heading (fuzzy)",
+            Self::EnterBrowseMode => "Browse directory",
+            Self::EnterFileMode => "Focus on file only",
+            Self::TocParent => "Parent directory (in TOC)",
+            Self::FileFinder => "Find file (fuzzy)",
+            Self::EnterEditMode => "Enter edit mode",
+            Self::ToggleWatch => "Toggle watch",
+            Self::ForceReload => "Reload file",
+            Self::OpenVisibleLinks => "Open visible links (1-9)",
+            Self::ToggleFrontMatter => "Collapse / expand front matter",
+            Self::ToggleHelp => "Toggle help",
+            Self::Quit => "Quit",
+        }
+    }
+
+    /// Help overlay section the action is listed under.
+    pub const fn section(self) -> &'static str {
+        match self {
+            Self::ScrollDown
+            | Self::ScrollUp
+            | Self::PageDown
+            | Self::PageUp
+            | Self::HalfPageDown
+            | Self::HalfPageUp
+            | Self::GoToTop
+            | Self::GoToBottom => "Navigation",
+            Self::StartSearch | Self::ClearSearch | Self::ProjectSearch => "Search",
+            Self::ToggleToc
+            | Self::ToggleTocFocus
+            | Self::SwitchFocus
+            | Self::TocDown
+            | Self::TocUp
+            | Self::TocSelect
+            | Self::TocCollapse
+            | Self::TocExpand
+            | Self::HeadingPalette => "TOC",
+            Self::EnterBrowseMode | Self::EnterFileMode | Self::TocParent | Self::FileFinder => {
+                "Browse"
+            }
+            Self::EnterEditMode => "Editor",
+            Self::ToggleWatch
+            | Self::ForceReload
+            | Self::OpenVisibleLinks
+            | Self::ToggleFrontMatter
+            | Self::ToggleHelp
+            | Self::Quit => "Other",
+        }
+    }
+}
+
+/// Action name that removes a binding instead of setting one.
+pub const UNBIND_ACTION: &str = "none";
+
+/// Bindings active in both contexts.
+const DEFAULT_SHARED: &[(&str, Action)] = &[
+    ("Space", Action::PageDown),
+    ("PageDown", Action::PageDown),
+    ("b", Action::PageUp),
+    ("PageUp", Action::PageUp),
+    ("Ctrl-d", Action::HalfPageDown),
+    ("Ctrl-u", Action::HalfPageUp),
+    ("g", Action::GoToTop),
+    ("Home", Action::GoToTop),
+    ("G", Action::GoToBottom),
+    ("End", Action::GoToBottom),
+    ("/", Action::StartSearch),
+    ("S", Action::ProjectSearch),
+    ("w", Action::ToggleWatch),
+    ("r", Action::ForceReload),
+    ("R", Action::ForceReload),
+    ("o", Action::OpenVisibleLinks),
+    ("t", Action::ToggleToc),
+    ("H", Action::HeadingPalette),
+    ("B", Action::EnterBrowseMode),
+    ("F", Action::EnterFileMode),
+    ("Ctrl-p", Action::FileFinder),
+    ("e", Action::EnterEditMode),
+    ("?", Action::ToggleHelp),
+    ("F1", Action::ToggleHelp),
+    ("q", Action::Quit),
+    ("Ctrl-c", Action::Quit),
+    ("Ctrl-q", Action::Quit),
+];
+
+const DEFAULT_VIEW: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("Down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("Up", Action::ScrollUp),
+    ("T", Action::ToggleTocFocus),
+    ("Tab", Action::SwitchFocus),
+    ("m", Action::ToggleFrontMatter),
+    ("Esc", Action::ClearSearch),
+];
+
+const DEFAULT_TOC: &[(&str, Action)] = &[
+    ("j", Action::TocDown),
+    ("Down", Action::TocDown),
+    ("k", Action::TocUp),
+    ("Up", Action::TocUp),
+    ("Enter", Action::TocSelect),
+    ("h", Action::TocCollapse),
+    ("Left", Action::TocCollapse),
+    ("l", Action::TocExpand),
+    ("Right", Action::TocExpand),
+    ("Backspace", Action::TocParent),
+    ("Tab", Action::SwitchFocus),
+    ("Esc", Action::SwitchFocus),
+];
+
+/// Result of looking up a (possibly partial) key sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLookup {
+    /// The sequence is bound to an action.
+    Action(Action),
+    /// The sequence is the start of a longer binding; wait for more keys.
+    Prefix,
+    /// Nothing is bound to the sequence.
+    Unbound,
+}
+
+/// Key sequence to action tables for each [`KeyContext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    view: Vec<(Vec<KeyChord>, Action)>,
+    toc: Vec<(Vec<KeyChord>, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Self {
+            view: Vec::new(),
+            toc: Vec::new(),
+        };
+        let tables = [
+            (KeyContext::View, DEFAULT_SHARED),
+            (KeyContext::View, DEFAULT_VIEW),
+            (KeyContext::Toc, DEFAULT_SHARED),
+            (KeyContext::Toc, DEFAULT_TOC),
+        ];
+        for (context, table) in tables {
+            for &(keys, action) in table {
+                let chords = KeyChord::parse_sequence(keys).expect("valid default key");
+                keymap.bind(context, chords, Some(action));
+            }
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// The default keymap with the configured `bindings` applied in order.
+    ///
+    /// # Errors
+    /// Returns an error listing every binding with an unknown context,
+    /// an unparseable key, or an unknown action name.
+    pub fn with_bindings(bindings: &[KeyBinding]) -> Result<Self> {
+        let mut keymap = Self::default();
+        let mut problems = Vec::new();
+        for binding in bindings {
+            let Some(context) = KeyContext::from_name(&binding.context) else {
+                problems.push(format!(
+                    "[keys.{}]: unknown key context (expected \"view\" or \"toc\")",
+                    binding.context
+                ));
+                continue;
+            };
+            let Some(chords) = KeyChord::parse_sequence(&binding.keys) else {
+                problems.push(format!("\"{}\": invalid key", binding.keys));
+                continue;
+            };
+            let action = if binding.action == UNBIND_ACTION {
+                None
+            } else if let Some(action) = Action::from_name(&binding.action) {
+                Some(action)
+            } else {
+                problems.push(format!(
+                    "\"{}\": unknown action \"{}\"",
+                    binding.keys, binding.action
+                ));
+                continue;
+            };
+            keymap.bind(context, chords, action);
+        }
+        if !problems.is_empty() {
+            bail!(
+                "Invalid key bindings in config:\n  {}",
+                problems.join("\n  ")
+            );
+        }
+        Ok(keymap)
+    }
+
+    const fn table(&self, context: KeyContext) -> &Vec<(Vec<KeyChord>, Action)> {
+        match context {
+            KeyContext::View => &self.view,
+            KeyContext::Toc => &self.toc,
+        }
+    }
+
+    /// Bind `keys` to `action` in `context`, or unbind them for `None`.
+    ///
+    /// Bindings that `keys` extends or that extend `keys` are dropped, so
+    /// binding `gg` replaces a single-key `g` binding and no sequence is
+    /// ever ambiguous.
+    pub fn bind(&mut self, context: KeyContext, keys: Vec<KeyChord>, action: Option<Action>) {
+        let table = match context {
+            KeyContext::View => &mut self.view,
+            KeyContext::Toc => &mut self.toc,
+        };
+        table.retain(|(bound, _)| !bound.starts_with(&keys) && !keys.starts_with(bound));
+        if let Some(action) = action {
+            table.push((keys, action));
+        }
+    }
+
+    /// Look up the key sequence typed so far.
+    pub fn lookup(&self, context: KeyContext, keys: &[KeyChord]) -> KeyLookup {
+        let mut result = KeyLookup::Unbound;
+        for (bound, action) in self.table(context) {
+            if bound.as_slice() == keys {
+                return KeyLookup::Action(*action);
+            }
+            if bound.starts_with(keys) {
+                result = KeyLookup::Prefix;
+            }
+        }
+        result
+    }
+
+    /// Every key sequence bound to `action` in any context, formatted for
+    /// display, without duplicates.
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        for (bound, bound_action) in self.view.iter().chain(&self.toc) {
+            let text = format_sequence(bound);
+            if *bound_action == action && !keys.contains(&text) {
+                keys.push(text);
+            }
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(context: &str, keys: &str, action: &str) -> KeyBinding {
+        KeyBinding {
+            context: context.to_string(),
+            keys: keys.to_string(),
+            action: action.to_string(),
+        }
+    }
+
+    fn chords(text: &str) -> Vec<KeyChord> {
+        KeyChord::parse_sequence(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_sequence_named_modified_and_plain_keys() {
+        assert_eq!(chords("Space"), vec![KeyChord::plain(KeyCode::Char(' '))]);
+        assert_eq!(chords("pagedown"), vec![KeyChord::plain(KeyCode::PageDown)]);
+        assert_eq!(chords("F1"), vec![KeyChord::plain(KeyCode::F(1))]);
+        assert_eq!(
+            chords("Ctrl-d"),
+            vec![KeyChord {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::CONTROL,
+            }]
+        );
+        assert_eq!(chords("Shift-Tab"), vec![KeyChord::plain(KeyCode::BackTab)]);
+        assert_eq!(chords("Shift-g"), vec![KeyChord::plain(KeyCode::Char('G'))]);
+        assert_eq!(
+            chords("gg"),
+            vec![
+                KeyChord::plain(KeyCode::Char('g')),
+                KeyChord::plain(KeyCode::Char('g'))
+            ]
+        );
+        assert_eq!(chords("-"), vec![KeyChord::plain(KeyCode::Char('-'))]);
+        assert_eq!(KeyChord::parse_sequence("Ctrl-Nope"), None);
+        assert_eq!(KeyChord::parse_sequence("  "), None);
+    }
+
+    #[test]
+    fn test_format_sequence_round_trips() {
+        for text in [
+            "gg",
+            "Ctrl-d",
+            "Space",
+            "Ctrl-w j",
+            "Shift-Tab",
+            "F12",
+            "Alt-Left",
+        ] {
+            assert_eq!(format_sequence(&chords(text)), text);
+        }
+    }
+
+    #[test]
+    fn test_chord_from_event_folds_shift_into_characters() {
+        let event = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from(event), KeyChord::plain(KeyCode::Char('G')));
+        let event = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from(event), KeyChord::plain(KeyCode::BackTab));
+    }
+
+    #[test]
+    fn test_default_keymap_lookup() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.lookup(KeyContext::View, &chords("j")),
+            KeyLookup::Action(Action::ScrollDown)
+        );
+        assert_eq!(
+            keymap.lookup(KeyContext::Toc, &chords("j")),
+            KeyLookup::Action(Action::TocDown)
+        );
+        assert_eq!(
+            keymap.lookup(KeyContext::Toc, &chords("Ctrl-d")),
+            KeyLookup::Action(Action::HalfPageDown)
+        );
+        assert_eq!(
+            keymap.lookup(KeyContext::View, &chords("x")),
+            KeyLookup::Unbound
+        );
+    }
+
+    #[test]
+    fn test_sequence_binding_replaces_its_prefix() {
+        let keymap = Keymap::with_bindings(&[binding("view", "gg", "go_to_top")]).unwrap();
+        assert_eq!(
+            keymap.lookup(KeyContext::View, &chords("g")),
+            KeyLookup::Prefix
+        );
+        assert_eq!(
+            keymap.lookup(KeyContext::View, &chords("gg")),
+            KeyLookup::Action(Action::GoToTop)
+        );
+        // The TOC context keeps its own single-key binding.
+        assert_eq!(
+            keymap.lookup(KeyContext::Toc, &chords("g")),
+            KeyLookup::Action(Action::GoToTop)
+        );
+        assert_eq!(
+            keymap.keys_for(Action::GoToTop),
+            vec!["Home".to_string(), "gg".to_string(), "g".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_none_action_unbinds_key() {
+        let keymap = Keymap::with_bindings(&[binding("view", "q", "none")]).unwrap();
+        assert_eq!(
+            keymap.lookup(KeyContext::View, &chords("q")),
+            KeyLookup::Unbound
+        );
+        assert_eq!(
+            keymap.keys_for(Action::Quit),
+            vec!["Ctrl-c".to_string(), "Ctrl-q".to_string(), "q".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_with_bindings_reports_every_problem() {
+        let err = Keymap::with_bindings(&[
+            binding("view", "x", "explode"),
+            binding("view", "Ctrl-Nope", "quit"),
+            binding("sidebar", "j", "toc_down"),
+            binding("toc", "J", "scroll_down"),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("\"x\": unknown action \"explode\""), "{err}");
+        assert!(err.contains("\"Ctrl-Nope\": invalid key"), "{err}");
+        assert!(err.contains("[keys.sidebar]"), "{err}");
+        assert!(!err.contains("\"J\""), "{err}");
+    }
+
+    #[test]
+    fn test_action_names_round_trip() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(action));
+        }
+        assert_eq!(Action::from_name("none"), None);
+    }
+}
@@ -2,7 +2,6 @@
 //!
 //! Maps terminal events to application messages.
 
-// TODO: Implement configurable keybindings
-// - Default keybinding set
-// - Vim-style navigation
-// - Configurable overrides
+mod keymap;
+
+pub use keymap::{Action, KeyChord, KeyContext, KeyLookup, Keymap, UNBIND_ACTION, format_sequence};
@@ -8,31 +8,48 @@
 //! markless README.md
 //! markless --watch README.md
 //! markless --no-toc README.md
+//! markless --print README.md | less -R
+//! curl -s https://example.com/README.md | markless
 //! ```
 
-use std::path::PathBuf;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 
-use markless::app::App;
+use markless::app::{App, STDIN_FILE_NAME};
 use markless::config::{
-    ConfigFlags, ImageMode, ThemeMode, clear_config_flags, global_config_path, load_config_flags,
-    local_override_path, parse_flag_tokens, save_config_flags,
+    ConfigFlags, ConfigLayer, ConfigSource, ImageMode, LayeredConfig, ThemeMode,
+    active_global_config_path, cache_dir, clear_config_flags, config_file_paths,
+    global_config_path, legacy_global_config_path, load_config_flags, parse_flag_tokens,
+    save_config_flags, syntaxes_dir, themes_dir,
 };
-use markless::highlight::{HighlightBackground, set_background_mode};
+use markless::document::prepare_document_from_bytes;
+use markless::highlight::{
+    HighlightBackground, HighlightConfig, check_code_theme, load_syntax_set, set_background_mode,
+    set_highlight_config, theme_set_problems,
+};
+use markless::input::Keymap;
 use markless::perf;
+use markless::ui::print::write_document;
+use markless::ui::style::{ColorOverrides, Theme, set_color_overrides, set_theme};
 
 /// A terminal markdown viewer with image support
 #[derive(Parser, Debug)]
 #[command(name = "markless", version, about, long_about = None)]
 struct Cli {
-    /// Markdown file or directory to view
-    #[arg(value_name = "PATH", default_value = ".")]
-    path: PathBuf,
+    /// Markdown file or directory to view (`-` reads from stdin; defaults to
+    /// stdin when input is piped, otherwise the current directory)
+    #[arg(value_name = "PATH")]
+    path: Option<PathBuf>,
+
+    /// `check [PATH]` when PATH is the `config` subcommand
+    #[arg(hide = true)]
+    subcommand_args: Vec<String>,
 
     /// Watch file for changes and auto-reload
     #[arg(short, long)]
@@ -54,6 +71,16 @@ struct Cli {
     #[arg(long, value_enum, default_value = "auto")]
     theme: ThemeMode,
 
+    /// Color theme: dark, light, solarized, gruvbox, nord, high-contrast,
+    /// a theme in the config's themes directory, or a theme file path
+    #[arg(long, value_name = "NAME")]
+    theme_name: Option<String>,
+
+    /// Syntax highlighting theme for code blocks (a built-in syntect theme
+    /// or a .tmTheme file in the config's themes directory)
+    #[arg(long, value_name = "NAME")]
+    code_theme: Option<String>,
+
     /// Enable startup performance logging
     #[arg(long)]
     perf: bool,
@@ -82,6 +109,14 @@ struct Cli {
     #[arg(long, conflicts_with = "editor")]
     no_editor: bool,
 
+    /// Use Vim-style modal keybindings in the built-in editor
+    #[arg(long)]
+    vim: bool,
+
+    /// Turn off Vim-style keybindings set in a config file
+    #[arg(long, conflicts_with = "vim")]
+    no_vim: bool,
+
     /// Save current command-line flags as defaults in .marklessrc
     #[arg(long)]
     save: bool,
@@ -89,6 +124,11 @@ struct Cli {
     /// Clear saved defaults in .marklessrc
     #[arg(long)]
     clear: bool,
+
+    /// Print the rendered document to stdout instead of opening the viewer
+    /// (implied when stdout is not a terminal)
+    #[arg(long)]
+    print: bool,
 }
 
 // Query the terminal background using OSC 11.
@@ -258,6 +298,130 @@ fn parse_osc_component(s: &str) -> Option<u8> {
     }
 }
 
+fn read_stdin() -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut bytes)
+        .context("Failed to read stdin")?;
+    Ok(bytes)
+}
+
+/// Render `bytes` (the contents of `path`) to stdout with ANSI styling.
+///
+/// Width comes from `--wrap-width`, then the terminal (if any), then 80
+/// columns. Colors are dropped when `NO_COLOR` is set.
+fn print_document(path: &Path, bytes: Vec<u8>, wrap_width: Option<u16>) -> Result<()> {
+    let width = wrap_width
+        .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w))
+        .filter(|w| *w > 0)
+        .unwrap_or(80);
+    let mut document = prepare_document_from_bytes(path, bytes, width);
+    let color = std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty());
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    match write_document(&mut document, &mut out, color) {
+        // The reader (e.g. `head`) went away; that's not an error for us.
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        other => other.context("Failed to write output"),
+    }
+}
+
+/// Directory whose `.marklessrc.toml` files (and its ancestors') apply to
+/// the document at `path`.
+fn config_dir_for(path: Option<&Path>) -> PathBuf {
+    match path {
+        Some(path) if path != Path::new("-") && !path.is_dir() => path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf),
+        Some(path) if path.is_dir() => path.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// User grammar and theme directories and the chosen code theme.
+fn highlight_config(flags: &ConfigFlags) -> HighlightConfig {
+    HighlightConfig {
+        syntax_dir: syntaxes_dir(),
+        theme_dir: themes_dir(),
+        cache_dir: cache_dir(),
+        code_theme: flags.code_theme.clone(),
+    }
+}
+
+/// `markless config check [PATH]`: list the config files that apply to
+/// `path`, every value they set and where it came from, and any problems.
+fn config_check(path: Option<&Path>) -> Result<()> {
+    let mut config = LayeredConfig::default();
+    let mut problems = Vec::new();
+
+    println!("Config files, lowest precedence first:");
+    for file in config_file_paths(&config_dir_for(path)) {
+        match ConfigLayer::load(&file) {
+            Ok(Some(layer)) => {
+                println!("  {}", file.display());
+                config.push(layer);
+            }
+            Ok(None) => println!("  {} (not found)", file.display()),
+            Err(err) => {
+                println!("  {} (invalid)", file.display());
+                problems.push(format!("{err:#}"));
+            }
+        }
+    }
+
+    println!();
+    let values = config.values();
+    if values.is_empty() {
+        println!("No settings; everything uses the built-in defaults.");
+    } else {
+        println!("Effective values:");
+        let width = values
+            .iter()
+            .map(|(key, value, _)| key.len() + value.len() + 3);
+        let width = width.max().unwrap_or(0);
+        for (key, value, source) in &values {
+            let setting = format!("{key} = {value}");
+            println!("  {setting:<width$}  {source}");
+        }
+        println!("Everything else uses the built-in defaults.");
+    }
+
+    match config.flags() {
+        Ok(flags) => {
+            if let Err(err) = Keymap::with_bindings(&flags.keys) {
+                problems.push(err.to_string());
+            }
+            if let Err(err) = ColorOverrides::from_config(&flags.colors) {
+                problems.push(err.to_string());
+            }
+            if let Some(name) = flags.theme_name.as_deref()
+                && let Err(err) = Theme::named(name, themes_dir().as_deref())
+            {
+                problems.push(format!("{err:#}"));
+            }
+            let highlight = highlight_config(&flags);
+            problems.extend(load_syntax_set(&highlight).1);
+            set_highlight_config(highlight);
+            problems.extend(theme_set_problems().iter().cloned());
+            if let Err(err) = check_code_theme() {
+                problems.push(err.to_string());
+            }
+        }
+        Err(err) => problems.push(format!("{err:#}")),
+    }
+
+    println!();
+    if problems.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{problem}");
+    }
+    anyhow::bail!("config check found {} problem(s)", problems.len())
+}
+
 fn main() -> Result<()> {
     // Restore terminal state on panic so the shell isn't left in raw mode
     let default_hook = std::panic::take_hook();
@@ -277,25 +441,72 @@ fn main() -> Result<()> {
 
     let raw_args = std::env::args().collect::<Vec<_>>();
     let cli = Cli::parse();
+    // `markless config` still opens a file or directory named `config`
+    // unless `check` follows it. Flags may come before or after.
+    let subcommand = cli.subcommand_args.first().map(String::as_str);
+    if cli.path.as_deref() == Some(Path::new("config"))
+        && (subcommand == Some("check") || !Path::new("config").exists())
+    {
+        return match cli.subcommand_args.as_slice() {
+            [check] | [check, _] if check == "check" => {
+                config_check(cli.subcommand_args.get(1).map(Path::new))
+            }
+            _ => anyhow::bail!("Usage: markless config check [PATH]"),
+        };
+    }
+    if let Some(extra) = cli.subcommand_args.first() {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::UnknownArgument,
+                format!("unexpected argument '{extra}' found"),
+            )
+            .exit();
+    }
     let global_path = global_config_path();
-    let local_path = local_override_path();
     let cli_flags = parse_flag_tokens(&raw_args);
 
     if cli.clear {
         clear_config_flags(&global_path)?;
+        clear_config_flags(&legacy_global_config_path())?;
     }
     if cli.save {
-        save_config_flags(&global_path, &cli_flags)?;
+        // Only flags can be given on the command line; keep the saved sections.
+        let existing = load_config_flags(&active_global_config_path())?;
+        let saved = ConfigFlags {
+            keys: existing.keys,
+            colors: existing.colors,
+            images: existing.images,
+            mermaid: existing.mermaid,
+            browse: existing.browse,
+            ..cli_flags.clone()
+        };
+        save_config_flags(&global_path, &saved)?;
     }
 
-    let file_flags = if cli.clear {
-        ConfigFlags::default()
+    let config_files = if cli.clear {
+        Vec::new()
     } else {
-        let global_flags = load_config_flags(&global_path)?;
-        let local_flags = load_config_flags(&local_path)?;
-        global_flags.union(&local_flags)
+        config_file_paths(&config_dir_for(cli.path.as_deref()))
     };
-    let effective = file_flags.union(&cli_flags);
+    let mut config = LayeredConfig::default();
+    for path in &config_files {
+        if let Some(layer) = ConfigLayer::load(path)? {
+            config.push(layer);
+        }
+    }
+    config.push(ConfigLayer::from_flags(
+        ConfigSource::CommandLine,
+        &cli_flags,
+    ));
+    let effective = config.flags()?;
+    let keymap = Keymap::with_bindings(&effective.keys)?;
+    set_color_overrides(ColorOverrides::from_config(&effective.colors)?);
+    if let Some(name) = effective.theme_name.as_deref() {
+        set_theme(Theme::named(name, themes_dir().as_deref())?);
+    }
+    let highlight = highlight_config(&effective);
+    set_highlight_config(highlight);
+    check_code_theme()?;
 
     perf::set_enabled(effective.perf);
     let render_debug_log_path = effective
@@ -312,9 +523,11 @@ fn main() -> Result<()> {
         );
     }
 
+    let print_mode = cli.print || !std::io::stdout().is_terminal();
+
     match effective.theme.unwrap_or(ThemeMode::Auto) {
         ThemeMode::Auto => {
-            if let Some(mode) = detect_theme() {
+            if !print_mode && let Some(mode) = detect_theme() {
                 return relaunch_with_theme(mode, &raw_args);
             }
             set_background_mode(None);
@@ -323,32 +536,60 @@ fn main() -> Result<()> {
         ThemeMode::Dark => set_background_mode(Some(HighlightBackground::Dark)),
     }
 
+    let stdin_content = match cli.path.as_deref() {
+        Some(p) if p == Path::new("-") => Some(read_stdin()?),
+        None if !std::io::stdin().is_terminal() => Some(read_stdin()?),
+        _ => None,
+    };
+    let path = if stdin_content.is_some() {
+        // Relative images and links in piped documents resolve against the CWD.
+        std::env::current_dir()
+            .context("Failed to determine current directory")?
+            .join(STDIN_FILE_NAME)
+    } else {
+        cli.path.unwrap_or_else(|| PathBuf::from("."))
+    };
+
     // Verify path exists
-    if !cli.path.exists() {
-        anyhow::bail!("Path not found: {}", cli.path.display());
+    if stdin_content.is_none() && !path.exists() {
+        anyhow::bail!("Path not found: {}", path.display());
     }
 
-    let is_directory = cli.path.is_dir();
+    let is_directory = stdin_content.is_none() && path.is_dir();
+
+    if print_mode {
+        if is_directory {
+            anyhow::bail!("Cannot print a directory: {}", path.display());
+        }
+        let bytes = match stdin_content {
+            Some(bytes) => bytes,
+            None => std::fs::read(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+        };
+        return print_document(&path, bytes, effective.wrap_width);
+    }
 
     // Run the application
     // Normalize editor: empty string from --no-editor becomes None
     let editor = effective.editor.filter(|e| !e.is_empty());
 
-    let mut app = App::new(cli.path)
+    let mut app = App::new(path)
         .with_watch(effective.watch)
         .with_toc_visible(effective.toc && !effective.no_toc)
         .with_image_mode(effective.image_mode)
         .with_images_enabled(!effective.no_images)
         .with_browse_mode(is_directory)
         .with_wrap_width(effective.wrap_width)
+        .with_image_limits(effective.images)
+        .with_mermaid_options(effective.mermaid)
+        .with_browse_filters(effective.browse)
         .with_editor(editor)
+        .with_vim_keys(effective.vim.unwrap_or(false))
+        .with_keymap(keymap)
+        .with_stdin_content(stdin_content)
         .with_config_paths(
-            Some(global_path),
-            if local_path.exists() {
-                Some(local_path)
-            } else {
-                None
-            },
+            Some(active_global_config_path()),
+            config_files.get(1..).and_then(<[PathBuf]>::last).cloned(),
         );
 
     app.run().context("Application error")
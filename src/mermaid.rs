@@ -87,8 +87,13 @@ fn fix_svg_font_families(svg: &str) -> String {
 ///
 /// Scales the SVG so its width matches `target_width_px`, preserving aspect
 /// ratio. This avoids lossy upscaling since the vector is rasterized directly
-/// at the final display resolution.
-fn rasterize_svg(svg: &str, target_width_px: u32) -> Result<DynamicImage> {
+/// at the final display resolution. Display math goes through here too.
+///
+/// # Errors
+///
+/// Returns an error if the SVG cannot be parsed or the pixmap cannot be
+/// allocated.
+pub fn rasterize_svg(svg: &str, target_width_px: u32) -> Result<DynamicImage> {
     let mut db = fontdb::Database::new();
     db.load_system_fonts();
 
@@ -0,0 +1,114 @@
+//! Background index of the files under a directory, for the file finder.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use super::walk_files;
+use crate::config::BrowseFilters;
+
+/// Files sent together while indexing.
+const BATCH: usize = 256;
+
+/// Progress reported while indexing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileIndexEvent {
+    /// More files, relative to the root, in path order.
+    Files(Vec<PathBuf>),
+    /// Every file has been listed.
+    Done,
+}
+
+/// Files under a directory, listed on a background thread. Dropping the
+/// index stops the walk.
+#[derive(Debug)]
+pub struct FileIndex {
+    rx: Receiver<FileIndexEvent>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl FileIndex {
+    /// Start listing the files under `root` that browse mode would show
+    /// (see [`walk_files`]).
+    pub fn start(root: &Path, filters: &BrowseFilters) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&cancel);
+        let root = root.to_path_buf();
+        let filters = filters.clone();
+        thread::spawn(move || {
+            let _scope = crate::perf::scope("search.file_index");
+            let mut batch = Vec::with_capacity(BATCH);
+            for path in walk_files(&root, &filters) {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                let relative = path
+                    .strip_prefix(&root)
+                    .map_or_else(|_| path.clone(), Path::to_path_buf);
+                batch.push(relative);
+                if batch.len() == BATCH
+                    && tx
+                        .send(FileIndexEvent::Files(std::mem::take(&mut batch)))
+                        .is_err()
+                {
+                    return;
+                }
+            }
+            if !batch.is_empty() {
+                let _ = tx.send(FileIndexEvent::Files(batch));
+            }
+            let _ = tx.send(FileIndexEvent::Done);
+        });
+        Self { rx, cancel }
+    }
+
+    /// Events sent since the last call.
+    pub fn poll(&self) -> Vec<FileIndexEvent> {
+        self.rx.try_iter().collect()
+    }
+}
+
+impl Drop for FileIndex {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_index_lists_relative_paths() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        for path in ["README.md", "docs/guide.md", "target/out.md", ".cache/x.md"] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x").unwrap();
+        }
+
+        let index = FileIndex::start(dir.path(), &BrowseFilters::default());
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut files = Vec::new();
+        'wait: while Instant::now() < deadline {
+            for event in index.poll() {
+                match event {
+                    FileIndexEvent::Files(batch) => files.extend(batch),
+                    FileIndexEvent::Done => break 'wait,
+                }
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(
+            files,
+            [PathBuf::from("README.md"), PathBuf::from("docs/guide.md")]
+        );
+    }
+}
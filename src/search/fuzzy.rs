@@ -0,0 +1,179 @@
+//! Fuzzy matching for the heading palette and file finder.
+//!
+//! A query matches a candidate when its characters appear in order. The
+//! best alignment is found by dynamic programming: matches score more at
+//! the start of a word or right after the previous match, and every
+//! character skipped between matches costs a little.
+
+/// Score for each matched character.
+const MATCH: i32 = 16;
+/// Extra score when a match follows the previous one directly.
+const CONSECUTIVE: i32 = 5;
+/// Extra score for matching the first character of the candidate.
+const START: i32 = 10;
+/// Extra score for matching just after a path separator.
+const AFTER_SLASH: i32 = 9;
+/// Extra score for matching the first character of a word.
+const WORD_START: i32 = 8;
+/// Extra score for matching an uppercase letter after a lowercase one.
+const CAMEL: i32 = 7;
+/// Cost of each candidate character skipped after the first match.
+const GAP: i32 = 1;
+
+/// How well a query matched a candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i32,
+    /// Byte offsets of the matched characters in the candidate.
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` against `candidate`, ignoring case unless the query has an
+/// uppercase letter. Spaces in the query are ignored. An empty query
+/// matches everything with score 0.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let chars: Vec<(usize, char)> = candidate.char_indices().collect();
+    let folded: Vec<char> = chars.iter().map(|&(_, c)| fold(c)).collect();
+    let (n, m) = (query.len(), chars.len());
+    if n > m || !is_subsequence(&query, &folded) {
+        return None;
+    }
+
+    let bonus: Vec<i32> = (0..m)
+        .map(|j| {
+            let c = chars[j].1;
+            match j.checked_sub(1).map(|p| chars[p].1) {
+                None => START,
+                Some('/' | '\\') => AFTER_SLASH,
+                Some(prev) if !prev.is_alphanumeric() => WORD_START,
+                Some(prev) if prev.is_lowercase() && c.is_uppercase() => CAMEL,
+                Some(_) => 0,
+            }
+        })
+        .collect();
+
+    // `ends[i][j]`: best score for query[..=i] with query[i] matched at j.
+    // `best[i][j]`: best score for query[..=i] within candidate[..=j],
+    // less the gap since the last match.
+    let none = i32::MIN / 2;
+    let mut ends = vec![vec![none; m]; n];
+    let mut best = vec![vec![none; m]; n];
+    for i in 0..n {
+        for j in i..m {
+            if folded[j] == query[i] {
+                let score = MATCH + bonus[j];
+                ends[i][j] = if i == 0 {
+                    score
+                } else if j > 0 {
+                    let after_gap = best[i - 1][j - 1] + score;
+                    let consecutive = ends[i - 1][j - 1] + score + CONSECUTIVE;
+                    after_gap.max(consecutive)
+                } else {
+                    none
+                };
+            }
+            let carried = if j > 0 { best[i][j - 1] - GAP } else { none };
+            best[i][j] = ends[i][j].max(carried);
+        }
+    }
+
+    // Walk back from the best final match to recover the positions.
+    let mut positions = vec![0; n];
+    let mut j = (n - 1..m)
+        .max_by_key(|&j| (ends[n - 1][j], std::cmp::Reverse(j)))
+        .unwrap_or(n - 1);
+    let score = ends[n - 1][j];
+    for i in (0..n).rev() {
+        positions[i] = chars[j].0;
+        if i == 0 {
+            break;
+        }
+        let step = MATCH + bonus[j];
+        j = if ends[i][j] == ends[i - 1][j - 1] + step + CONSECUTIVE {
+            j - 1
+        } else {
+            let limit = j - 1;
+            (i - 1..=limit)
+                .max_by_key(|&k| {
+                    let gap = i32::try_from(limit - k).unwrap_or(i32::MAX);
+                    (ends[i - 1][k].saturating_sub(gap * GAP), k)
+                })
+                .unwrap_or(i - 1)
+        };
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+fn is_subsequence(query: &[char], candidate: &[char]) -> bool {
+    let mut rest = candidate.iter();
+    query.iter().all(|q| rest.any(|c| c == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(query: &str, candidate: &str) -> i32 {
+        fuzzy_match(query, candidate).unwrap().score
+    }
+
+    #[test]
+    fn test_matches_in_order_only() {
+        assert!(fuzzy_match("ist", "Installation").is_some());
+        assert!(fuzzy_match("tsi", "Installation").is_none());
+        assert!(fuzzy_match("", "anything").is_some());
+        assert!(fuzzy_match("longer", "long").is_none());
+    }
+
+    #[test]
+    fn test_positions_are_byte_offsets() {
+        let m = fuzzy_match("ca", "Über café").unwrap();
+        let matched: String = m
+            .positions
+            .iter()
+            .map(|&pos| "Über café"[pos..].chars().next().unwrap())
+            .collect();
+        assert_eq!(matched, "ca");
+        assert_eq!(m.positions, [6, 7]);
+    }
+
+    #[test]
+    fn test_prefers_word_starts_and_runs() {
+        assert!(score("gs", "Getting Started") > score("gs", "Settings"));
+        assert!(score("conf", "Configuration") > score("conf", "Icon font"));
+        assert!(score("api", "API Reference") > score("api", "Rapid iteration"));
+        let m = fuzzy_match("ref", "Prefer the API reference").unwrap();
+        assert_eq!(m.positions, [15, 16, 17]);
+    }
+
+    #[test]
+    fn test_prefers_file_names_after_slash() {
+        assert!(score("read", "docs/readme.md") > score("read", "src/thread_pool.md"));
+    }
+
+    #[test]
+    fn test_smartcase() {
+        assert!(fuzzy_match("Api", "api notes").is_none());
+        assert!(fuzzy_match("api", "API notes").is_some());
+    }
+}
@@ -2,56 +2,361 @@
 //!
 //! Provides text search within documents with:
 //! - Forward and backward search
-//! - Case-insensitive option
-//! - Match highlighting
+//! - Smartcase matching with `\c` / `\C` overrides
+//! - Regex and whole-word modes
+//! - Match byte ranges for highlighting
+//! - Case-sensitive and regex search over raw text (editor find/replace)
+//! - Searching every text file under a directory in the background
+//! - Fuzzy matching for the heading palette and file finder
+//! - A background index of files for the file finder
+
+mod files;
+mod fuzzy;
+mod project;
+
+pub use files::{FileIndex, FileIndexEvent};
+pub use fuzzy::{FuzzyMatch, fuzzy_match};
+pub use project::{
+    FileHits, MAX_PROJECT_HITS, ProjectHit, ProjectSearch, ProjectSearchEvent, walk_files,
+};
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
 
 use crate::document::Document;
 
-/// Find matching rendered line indices for a query (case-insensitive).
+/// How to interpret a raw-text search query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextSearchOptions {
+    pub case_sensitive: bool,
+    pub regex: bool,
+    /// Only match whole words.
+    pub whole_word: bool,
+}
+
+/// Search modes toggled from the view's search bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchMode {
+    pub regex: bool,
+    pub whole_word: bool,
+}
+
+/// Matches on one rendered line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// Rendered line index.
+    pub line: usize,
+    /// Byte ranges of the matches within the line's content.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Compile a query into a regex, escaping it unless regex mode is on.
+///
+/// # Errors
+/// Returns an error if regex mode is on and the query is not a valid regex.
+pub fn text_pattern(query: &str, options: TextSearchOptions) -> Result<Regex, regex::Error> {
+    let mut pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    if options.whole_word {
+        pattern = format!(r"\b(?:{pattern})\b");
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+}
+
+/// Byte ranges of every non-empty match of `query` in `text`.
+///
+/// # Errors
+/// Returns an error if regex mode is on and the query is not a valid regex.
+pub fn find_text_matches(
+    text: &str,
+    query: &str,
+    options: TextSearchOptions,
+) -> Result<Vec<Range<usize>>, regex::Error> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = text_pattern(query, options)?;
+    Ok(pattern
+        .find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.range())
+        .collect())
+}
+
+/// Text that replaces the match at `range`, where `pattern` is the query
+/// compiled with [`text_pattern`].
+///
+/// In regex mode `$1` / `${name}` refer to capture groups of that match;
+/// otherwise the replacement is used literally.
+pub fn expand_replacement(
+    text: &str,
+    range: &Range<usize>,
+    pattern: &Regex,
+    replacement: &str,
+    options: TextSearchOptions,
+) -> String {
+    if !options.regex {
+        return replacement.to_string();
+    }
+    let mut expanded = String::new();
+    if let Some(caps) = pattern.captures_at(text, range.start) {
+        caps.expand(replacement, &mut expanded);
+    }
+    expanded
+}
+
+/// Split a view search query into the text to search for and its options.
+///
+/// The query is trimmed. Matching is case-insensitive unless the query
+/// contains an uppercase letter (smartcase); `\c` anywhere forces
+/// case-insensitive and `\C` case-sensitive matching, and both are removed
+/// from the text. In regex mode, letters in escapes such as `\S` don't
+/// count as uppercase.
+pub fn parse_query(query: &str, mode: SearchMode) -> (String, TextSearchOptions) {
+    let mut text = String::new();
+    let mut force_case = None;
+    let mut has_upper = false;
+    let mut chars = query.trim().chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            has_upper |= c.is_uppercase();
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('c') => force_case = Some(false),
+            Some('C') => force_case = Some(true),
+            Some(next) => {
+                has_upper |= !mode.regex && next.is_uppercase();
+                text.push(c);
+                text.push(next);
+            }
+            None => text.push(c),
+        }
+    }
+    let options = TextSearchOptions {
+        case_sensitive: force_case.unwrap_or(has_upper),
+        regex: mode.regex,
+        whole_word: mode.whole_word,
+    };
+    (text.trim().to_string(), options)
+}
+
+/// Find the rendered lines matching a view search query, with the byte
+/// range of every match in each line (see [`parse_query`]).
 ///
 /// For hex-mode documents, generates each hex line on the fly and matches
 /// against offsets, hex bytes, and the ASCII column.
-pub fn find_matches(document: &Document, query: &str) -> Vec<usize> {
-    let trimmed = query.trim();
-    if trimmed.is_empty() {
-        return Vec::new();
+///
+/// # Errors
+/// Returns an error if regex mode is on and the query is not a valid regex.
+pub fn find_matches(
+    document: &Document,
+    query: &str,
+    mode: SearchMode,
+) -> Result<Vec<LineMatch>, regex::Error> {
+    let (text, options) = parse_query(query, mode);
+    if text.is_empty() {
+        return Ok(Vec::new());
     }
-    let needle = trimmed.to_lowercase();
+    let pattern = text_pattern(&text, options)?;
+    let line_match = |line: usize, content: &str| {
+        let ranges: Vec<Range<usize>> = pattern
+            .find_iter(content)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect();
+        (!ranges.is_empty()).then_some(LineMatch { line, ranges })
+    };
     if document.is_hex_mode() {
-        return (0..document.line_count())
-            .filter(|&idx| {
+        return Ok((0..document.line_count())
+            .filter_map(|idx| {
                 document
                     .hex_line_content(idx)
-                    .is_some_and(|text| text.to_lowercase().contains(&needle))
+                    .and_then(|content| line_match(idx, &content))
             })
-            .collect();
+            .collect());
     }
-    document
+    Ok(document
         .visible_lines(0, document.line_count())
         .iter()
         .enumerate()
-        .filter_map(|(idx, line)| {
-            let haystack = line.content().to_lowercase();
-            haystack.contains(&needle).then_some(idx)
-        })
-        .collect()
+        .filter_map(|(idx, line)| line_match(idx, line.content()))
+        .collect())
+}
+
+/// A one-line description of a regex error, for the status area.
+pub fn regex_error_summary(err: &regex::Error) -> String {
+    let message = err.to_string();
+    let last = message
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default();
+    last.trim().trim_start_matches("error: ").to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn lines(matches: &[LineMatch]) -> Vec<usize> {
+        matches.iter().map(|m| m.line).collect()
+    }
+
     #[test]
     fn test_find_matches_case_insensitive() {
         let doc = Document::parse("Alpha\n\nbeta\n\nALPHA alpha").unwrap();
-        let matches = find_matches(&doc, "alpha");
+        let matches = find_matches(&doc, "alpha", SearchMode::default()).unwrap();
         assert!(matches.len() >= 2);
     }
 
+    #[test]
+    fn test_find_matches_returns_byte_ranges() {
+        let doc = Document::parse("Alpha and alpha").unwrap();
+        let matches = find_matches(&doc, "alpha", SearchMode::default()).unwrap();
+        assert_eq!(
+            matches,
+            vec![LineMatch {
+                line: 0,
+                ranges: vec![0..5, 10..15]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_smartcase_and_overrides() {
+        let doc = Document::parse("Alpha\n\nalpha").unwrap();
+        let mode = SearchMode::default();
+        assert_eq!(
+            lines(&find_matches(&doc, "alpha", mode).unwrap()),
+            vec![0, 2]
+        );
+        assert_eq!(lines(&find_matches(&doc, "Alpha", mode).unwrap()), vec![0]);
+        assert_eq!(
+            lines(&find_matches(&doc, "Alpha\\c", mode).unwrap()),
+            vec![0, 2]
+        );
+        assert_eq!(
+            lines(&find_matches(&doc, "\\Calpha", mode).unwrap()),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_ignores_regex_escapes_for_smartcase() {
+        let regex = SearchMode {
+            regex: true,
+            ..SearchMode::default()
+        };
+        let (text, options) = parse_query(r"\Sfoo\s", regex);
+        assert_eq!(text, r"\Sfoo\s");
+        assert!(!options.case_sensitive);
+        let (_, options) = parse_query(r"\Sfoo", SearchMode::default());
+        assert!(options.case_sensitive);
+    }
+
+    #[test]
+    fn test_find_matches_regex_and_whole_word() {
+        let doc = Document::parse("v1 v22\n\nevaluate val").unwrap();
+        let regex = SearchMode {
+            regex: true,
+            ..SearchMode::default()
+        };
+        let matches = find_matches(&doc, r"v\d+", regex).unwrap();
+        assert_eq!(matches[0].ranges, vec![0..2, 3..6]);
+        assert!(find_matches(&doc, "(", regex).is_err());
+
+        let word = SearchMode {
+            whole_word: true,
+            ..SearchMode::default()
+        };
+        let matches = find_matches(&doc, "val", word).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].ranges, std::slice::from_ref(&(9..12)));
+    }
+
+    #[test]
+    fn test_regex_error_summary_is_one_line() {
+        let err = find_matches(
+            &Document::parse("text").unwrap(),
+            "(",
+            SearchMode {
+                regex: true,
+                whole_word: false,
+            },
+        )
+        .unwrap_err();
+        let summary = regex_error_summary(&err);
+        assert!(!summary.contains('\n'));
+        assert!(summary.contains("unclosed group"), "{summary}");
+    }
+
+    #[test]
+    fn test_find_text_matches_respects_case() {
+        let text = "Foo foo FOO";
+        let insensitive = find_text_matches(text, "foo", TextSearchOptions::default()).unwrap();
+        assert_eq!(insensitive, vec![0..3, 4..7, 8..11]);
+        let sensitive = TextSearchOptions {
+            case_sensitive: true,
+            ..TextSearchOptions::default()
+        };
+        assert_eq!(
+            find_text_matches(text, "foo", sensitive).unwrap(),
+            vec![4..7]
+        );
+    }
+
+    #[test]
+    fn test_find_text_matches_literal_escapes_regex() {
+        let matches = find_text_matches("a.b axb", "a.b", TextSearchOptions::default()).unwrap();
+        assert_eq!(matches, vec![0..3]);
+    }
+
+    #[test]
+    fn test_find_text_matches_regex_and_errors() {
+        let options = TextSearchOptions {
+            regex: true,
+            ..TextSearchOptions::default()
+        };
+        let matches = find_text_matches("v1 v22 x", r"v\d+", options).unwrap();
+        assert_eq!(matches, vec![0..2, 3..6]);
+        assert!(find_text_matches("x", "(", options).is_err());
+        // Empty matches are skipped.
+        assert!(find_text_matches("abc", "x*", options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expand_replacement_uses_capture_groups() {
+        let options = TextSearchOptions {
+            regex: true,
+            ..TextSearchOptions::default()
+        };
+        let text = "name: value";
+        let pattern = text_pattern(r"(\w+): (\w+)", options).unwrap();
+        let replaced = expand_replacement(text, &(0..11), &pattern, "$2=$1", options);
+        assert_eq!(replaced, "value=name");
+        let literal_options = TextSearchOptions::default();
+        let pattern = text_pattern("name", literal_options).unwrap();
+        let literal = expand_replacement(text, &(0..4), &pattern, "$1", literal_options);
+        assert_eq!(literal, "$1");
+    }
+
     #[test]
     fn test_find_matches_empty_query() {
         let doc = Document::parse("Alpha").unwrap();
-        assert!(find_matches(&doc, "").is_empty());
+        let matches = find_matches(&doc, "", SearchMode::default()).unwrap();
+        assert!(matches.is_empty());
+        let matches = find_matches(&doc, "\\c", SearchMode::default()).unwrap();
+        assert!(matches.is_empty());
     }
 
     #[test]
@@ -59,17 +364,17 @@ mod tests {
         // "Hello" in bytes followed by nulls
         let bytes = b"Hello\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
         let doc = Document::from_hex("test.bin", bytes);
-        let matches = find_matches(&doc, "Hello");
+        let matches = find_matches(&doc, "Hello", SearchMode::default()).unwrap();
         assert!(!matches.is_empty(), "should find ASCII text in hex dump");
         // Match should be on a hex line (after 4 header lines)
-        assert!(matches[0] >= 4);
+        assert!(matches[0].line >= 4);
     }
 
     #[test]
     fn test_find_matches_hex_by_hex_bytes() {
         let bytes = vec![0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x00, 0x00, 0x00];
         let doc = Document::from_hex("test.bin", bytes);
-        let matches = find_matches(&doc, "de ad be ef");
+        let matches = find_matches(&doc, "de ad be ef", SearchMode::default()).unwrap();
         assert!(!matches.is_empty(), "should find hex byte patterns");
     }
 
@@ -77,8 +382,11 @@ mod tests {
     fn test_find_matches_hex_header() {
         let bytes = vec![0x00; 32];
         let doc = Document::from_hex("special.bin", bytes);
-        let matches = find_matches(&doc, "special.bin");
+        let matches = find_matches(&doc, "special.bin", SearchMode::default()).unwrap();
         assert!(!matches.is_empty(), "should find filename in header");
-        assert_eq!(matches[0], 0, "filename match should be on heading line");
+        assert_eq!(
+            matches[0].line, 0,
+            "filename match should be on heading line"
+        );
     }
 }
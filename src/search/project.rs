@@ -0,0 +1,261 @@
+//! Searching every markdown and text file under a directory.
+//!
+//! The files searched are the ones the editor can open, as decided by
+//! [`is_editable_file`].
+//!
+//! [`ProjectSearch::start`] walks the tree on a background thread, leaving
+//! out whatever `.gitignore` and the browse filters hide, and sends the hits
+//! for each file as soon as that file has been searched.
+
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use regex::Regex;
+
+use super::{SearchMode, parse_query, text_pattern};
+use crate::config::BrowseFilters;
+use crate::document::is_editable_file;
+
+/// Hits kept per search; the walk stops once this many are found.
+pub const MAX_PROJECT_HITS: usize = 5000;
+
+/// A matching line in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectHit {
+    /// Line in the file (0-based).
+    pub line: usize,
+    /// The line's text.
+    pub text: String,
+    /// Byte ranges of the matches within `text`.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Every hit in one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHits {
+    pub path: PathBuf,
+    pub hits: Vec<ProjectHit>,
+}
+
+/// Progress reported by a running project search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectSearchEvent {
+    /// A file with at least one hit.
+    File(FileHits),
+    /// The walk is over.
+    Done {
+        /// Files searched.
+        files: usize,
+        /// Whether the walk stopped early at [`MAX_PROJECT_HITS`].
+        truncated: bool,
+    },
+}
+
+/// A search running on a background thread. Dropping it stops the search.
+#[derive(Debug)]
+pub struct ProjectSearch {
+    rx: Receiver<ProjectSearchEvent>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ProjectSearch {
+    /// Start searching the files under `root` for a view search query (see
+    /// [`parse_query`]).
+    ///
+    /// # Errors
+    /// Returns an error if regex mode is on and the query is not a valid regex.
+    pub fn start(
+        root: &Path,
+        query: &str,
+        mode: SearchMode,
+        filters: &BrowseFilters,
+    ) -> Result<Self, regex::Error> {
+        let (text, options) = parse_query(query, mode);
+        let pattern = text_pattern(&text, options)?;
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&cancel);
+        let root = root.to_path_buf();
+        let filters = filters.clone();
+        thread::spawn(move || {
+            let _scope = crate::perf::scope("search.project");
+            let mut files = 0;
+            let mut total = 0;
+            let mut truncated = false;
+            for path in walk_files(&root, &filters) {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                if !is_editable_file(&path) {
+                    continue;
+                }
+                files += 1;
+                let mut hits = search_file(&path, &pattern);
+                if hits.is_empty() {
+                    continue;
+                }
+                if total + hits.len() > MAX_PROJECT_HITS {
+                    hits.truncate(MAX_PROJECT_HITS - total);
+                    truncated = true;
+                }
+                total += hits.len();
+                if tx
+                    .send(ProjectSearchEvent::File(FileHits { path, hits }))
+                    .is_err()
+                {
+                    return;
+                }
+                if truncated {
+                    break;
+                }
+            }
+            let _ = tx.send(ProjectSearchEvent::Done { files, truncated });
+        });
+        Ok(Self { rx, cancel })
+    }
+
+    /// Events sent since the last call.
+    pub fn poll(&self) -> Vec<ProjectSearchEvent> {
+        self.rx.try_iter().collect()
+    }
+}
+
+impl Drop for ProjectSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Files under `root` in path order, leaving out what `.gitignore` files
+/// ignore and what `filters` hide from the browse listing.
+pub fn walk_files(root: &Path, filters: &BrowseFilters) -> impl Iterator<Item = PathBuf> {
+    let filters = filters.clone();
+    ignore::WalkBuilder::new(root)
+        // Dot files follow the browse filters instead.
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(std::cmp::Ord::cmp)
+        .filter_entry(move |entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || (name != ".git" && !filters.hides(&name))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .map(ignore::DirEntry::into_path)
+}
+
+/// Lines of the file at `path` that `pattern` matches. Unreadable and
+/// non-UTF-8 files have none.
+fn search_file(path: &Path, pattern: &Regex) -> Vec<ProjectHit> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let ranges: Vec<Range<usize>> = pattern
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect();
+            (!ranges.is_empty()).then(|| ProjectHit {
+                line,
+                text: text.to_string(),
+                ranges,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    fn collect(search: &ProjectSearch) -> (Vec<FileHits>, usize) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut found = Vec::new();
+        while Instant::now() < deadline {
+            for event in search.poll() {
+                match event {
+                    ProjectSearchEvent::File(file) => found.push(file),
+                    ProjectSearchEvent::Done { files, .. } => return (found, files),
+                }
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        panic!("project search did not finish");
+    }
+
+    #[test]
+    fn test_project_search_finds_hits_in_text_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("guide")).unwrap();
+        fs::write(
+            dir.path().join("a.md"),
+            "# Alpha\n\nno match\nalpha again\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("guide/b.txt"), "the alpha notes\n").unwrap();
+        fs::write(dir.path().join("guide/c.mdown"), "alpha\n").unwrap();
+        fs::write(dir.path().join("photo.png"), "alpha in binary\n").unwrap();
+
+        let search = ProjectSearch::start(
+            dir.path(),
+            "alpha",
+            SearchMode::default(),
+            &BrowseFilters::default(),
+        )
+        .unwrap();
+        let (found, files) = collect(&search);
+        assert_eq!(files, 3);
+        assert_eq!(found.len(), 3);
+        assert!(found[0].path.ends_with("a.md"));
+        let lines: Vec<usize> = found[0].hits.iter().map(|hit| hit.line).collect();
+        assert_eq!(lines, [0, 3]);
+        assert_eq!(found[0].hits[1].ranges, std::slice::from_ref(&(0..5)));
+        assert!(found[1].path.ends_with("guide/b.txt"));
+        assert!(found[2].path.ends_with("guide/c.mdown"));
+    }
+
+    #[test]
+    fn test_project_search_respects_gitignore_and_filters() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+        for sub in ["build", "drafts", ".hidden", "docs"] {
+            fs::create_dir_all(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("page.md"), "needle\n").unwrap();
+        }
+        let filters = BrowseFilters {
+            exclude: vec!["draft*".to_string()],
+            ..BrowseFilters::default()
+        };
+
+        let search =
+            ProjectSearch::start(dir.path(), "needle", SearchMode::default(), &filters).unwrap();
+        let (found, _) = collect(&search);
+        let paths: Vec<_> = found
+            .iter()
+            .map(|file| file.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(paths, [PathBuf::from("docs/page.md")]);
+    }
+
+    #[test]
+    fn test_project_search_rejects_invalid_regex() {
+        let mode = SearchMode {
+            regex: true,
+            whole_word: false,
+        };
+        let dir = tempdir().unwrap();
+        assert!(ProjectSearch::start(dir.path(), "(", mode, &BrowseFilters::default()).is_err());
+    }
+}
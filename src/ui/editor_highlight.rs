@@ -0,0 +1,409 @@
+//! Incremental syntax highlighting for the built-in editor.
+//!
+//! Markdown structure is styled with [`Theme`] colors and fenced code
+//! blocks with their language's syntax; other files are highlighted with
+//! the syntax for their file type. Block state is checkpointed every
+//! [`CHECKPOINT_INTERVAL`] lines and dropped from the first edited line
+//! onward, so a frame only highlights the visible lines plus at most one
+//! interval above them.
+
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use ratatui::style::Style;
+use regex::Regex;
+
+use crate::editor::EditorBuffer;
+use crate::highlight::LineHighlighter;
+use crate::ui::style::{Theme, active_theme, style_for_inline};
+
+/// Lines between cached block-state checkpoints.
+const CHECKPOINT_INTERVAL: usize = 32;
+
+/// A byte range of a line and the style it is drawn with.
+pub type StyledRange = (Range<usize>, Style);
+
+/// Block context at the start of a line.
+#[derive(Debug, Clone)]
+enum BlockState {
+    /// The first line of a markdown file, where front matter may open.
+    DocumentStart,
+    /// Markdown outside of code blocks.
+    Markdown,
+    /// YAML front matter.
+    FrontMatter(Option<LineHighlighter>),
+    /// Inside a fenced code block opened by `len` repetitions of `marker`.
+    Fence {
+        marker: char,
+        len: usize,
+        code: Option<LineHighlighter>,
+    },
+    /// A non-markdown file, highlighted as a whole.
+    Code(Option<LineHighlighter>),
+}
+
+/// Cached highlighting state for one editor buffer.
+#[derive(Debug, Clone)]
+pub struct EditorHighlighter {
+    theme: Theme,
+    /// Block state at the start of line `i * CHECKPOINT_INTERVAL`.
+    checkpoints: Vec<BlockState>,
+}
+
+impl EditorHighlighter {
+    /// Create a highlighter for the file being edited.
+    pub fn new(file_path: &Path) -> Self {
+        let initial = if crate::document::is_fence_wrapped(file_path) {
+            BlockState::Code(
+                crate::highlight::language_for_file(file_path).and_then(LineHighlighter::new),
+            )
+        } else {
+            BlockState::DocumentStart
+        };
+        let theme = active_theme().unwrap_or_else(|| {
+            if crate::highlight::is_light_background() {
+                Theme::light()
+            } else {
+                Theme::dark()
+            }
+        });
+        Self {
+            theme,
+            checkpoints: vec![initial],
+        }
+    }
+
+    /// Drop cached state for lines after `line`, which was edited.
+    pub fn invalidate_from(&mut self, line: usize) {
+        // The checkpoint at or before `line` only depends on earlier lines.
+        self.checkpoints.truncate(line / CHECKPOINT_INTERVAL + 1);
+    }
+
+    /// Styled byte ranges for each line of `lines`.
+    pub fn highlight(&mut self, buf: &EditorBuffer, lines: Range<usize>) -> Vec<Vec<StyledRange>> {
+        let checkpoint = lines.start / CHECKPOINT_INTERVAL;
+        while self.checkpoints.len() <= checkpoint {
+            let idx = self.checkpoints.len() - 1;
+            let mut state = self.checkpoints[idx].clone();
+            for line in idx * CHECKPOINT_INTERVAL..(idx + 1) * CHECKPOINT_INTERVAL {
+                self.style_line(&mut state, &buf.line_at(line).unwrap_or_default());
+            }
+            self.checkpoints.push(state);
+        }
+
+        let mut state = self.checkpoints[checkpoint].clone();
+        for line in checkpoint * CHECKPOINT_INTERVAL..lines.start {
+            self.style_line(&mut state, &buf.line_at(line).unwrap_or_default());
+        }
+        lines
+            .map(|line| self.style_line(&mut state, &buf.line_at(line).unwrap_or_default()))
+            .collect()
+    }
+
+    /// Style one line and advance `state` past it.
+    fn style_line(&self, state: &mut BlockState, text: &str) -> Vec<StyledRange> {
+        match state {
+            BlockState::Code(code) => code_ranges(code.as_mut(), text, Style::default()),
+            BlockState::FrontMatter(code) => {
+                if matches!(text.trim_end(), "---" | "...") {
+                    *state = BlockState::Markdown;
+                    vec![(0..text.len(), self.theme.hr)]
+                } else {
+                    code_ranges(code.as_mut(), text, self.theme.code)
+                }
+            }
+            BlockState::Fence { marker, len, code } => {
+                if closes_fence(text, *marker, *len) {
+                    *state = BlockState::Markdown;
+                    vec![(0..text.len(), self.theme.code)]
+                } else {
+                    code_ranges(code.as_mut(), text, self.theme.code)
+                }
+            }
+            BlockState::DocumentStart | BlockState::Markdown => {
+                let at_start = matches!(state, BlockState::DocumentStart);
+                *state = BlockState::Markdown;
+                if at_start && text.trim_end() == "---" {
+                    *state = BlockState::FrontMatter(LineHighlighter::new("yaml"));
+                    return vec![(0..text.len(), self.theme.hr)];
+                }
+                if let Some((marker, len, language)) = opens_fence(text) {
+                    *state = BlockState::Fence {
+                        marker,
+                        len,
+                        code: language.and_then(LineHighlighter::new),
+                    };
+                    return vec![(0..text.len(), self.theme.code)];
+                }
+                self.markdown_line(text)
+            }
+        }
+    }
+
+    /// Style a markdown line outside of code blocks.
+    fn markdown_line(&self, text: &str) -> Vec<StyledRange> {
+        let theme = &self.theme;
+        let indent = text.len() - text.trim_start().len();
+        let rest = &text[indent..];
+
+        if indent <= 3 {
+            let hashes = rest.bytes().take_while(|b| *b == b'#').count();
+            if (1..=6).contains(&hashes) && matches!(rest.as_bytes().get(hashes), None | Some(b' '))
+            {
+                let style = match hashes {
+                    1 => theme.h1,
+                    2 => theme.h2,
+                    3 => theme.h3,
+                    _ => theme.h4,
+                };
+                return vec![(0..text.len(), style)];
+            }
+            if is_thematic_break(rest) {
+                return vec![(0..text.len(), theme.hr)];
+            }
+        }
+
+        let mut styles = vec![Style::default(); text.len()];
+        if rest.starts_with('>') {
+            let marker_end = indent
+                + rest
+                    .find(|c: char| c != '>' && c != ' ')
+                    .unwrap_or(rest.len());
+            styles.fill(theme.quote);
+            self.style_inline(text, marker_end, &mut styles);
+        } else if rest.starts_with('|') {
+            self.style_inline(text, indent, &mut styles);
+            let divider = is_table_divider(rest);
+            for (idx, ch) in text.char_indices() {
+                if ch == '|' || divider && matches!(ch, '-' | ':') {
+                    styles[idx] = theme.table_border;
+                }
+            }
+        } else if let Some(marker_len) = list_marker_len(rest) {
+            styles[indent..indent + marker_len].fill(theme.list_marker);
+            self.style_inline(text, indent + marker_len, &mut styles);
+        } else {
+            self.style_inline(text, indent, &mut styles);
+        }
+        coalesce(&styles)
+    }
+
+    /// Layer emphasis, links, and code spans from `start` onward.
+    fn style_inline(&self, text: &str, start: usize, styles: &mut [Style]) {
+        let theme = &self.theme;
+        let patterns = inline_patterns();
+        let rest = &text[start..];
+        let mut apply = |pattern: &Regex, style: Style, replace: bool| {
+            for m in pattern.find_iter(rest) {
+                for byte_style in &mut styles[start + m.start()..start + m.end()] {
+                    *byte_style = if replace {
+                        style
+                    } else {
+                        byte_style.patch(style)
+                    };
+                }
+            }
+        };
+        apply(&patterns.emphasis, theme.emphasis, false);
+        apply(&patterns.strong, theme.strong, false);
+        apply(&patterns.strikethrough, theme.strikethrough, false);
+        apply(&patterns.link, theme.link, false);
+        apply(&patterns.image, theme.image, true);
+        apply(&patterns.code, theme.inline_code, true);
+    }
+}
+
+/// Inline markdown patterns, compiled once.
+struct InlinePatterns {
+    code: Regex,
+    strong: Regex,
+    emphasis: Regex,
+    strikethrough: Regex,
+    link: Regex,
+    image: Regex,
+}
+
+fn inline_patterns() -> &'static InlinePatterns {
+    static PATTERNS: OnceLock<InlinePatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let compile = |pattern: &str| Regex::new(pattern).expect("valid inline pattern");
+        InlinePatterns {
+            code: compile(r"`[^`]+`"),
+            strong: compile(r"\*\*[^*\s](?:[^*]*[^*\s])?\*\*|__[^_\s](?:[^_]*[^_\s])?__"),
+            emphasis: compile(r"\*[^*\s](?:[^*]*[^*\s])?\*|\b_[^_\s](?:[^_]*[^_\s])?_\b"),
+            strikethrough: compile(r"~~[^~]+~~"),
+            link: compile(
+                r"\[[^\]]*\](?:\([^)]*\)|\[[^\]]*\])|<[a-zA-Z][a-zA-Z0-9+.-]*:[^>\s]*>|https?://[^\s)>]+",
+            ),
+            image: compile(r"!\[[^\]]*\]\([^)]*\)"),
+        }
+    })
+}
+
+/// Syntax-highlighted ranges for a code line, or `base` for the whole line
+/// when the language is unknown.
+fn code_ranges(code: Option<&mut LineHighlighter>, text: &str, base: Style) -> Vec<StyledRange> {
+    let Some(code) = code else {
+        return vec![(0..text.len(), base)];
+    };
+    let mut pos = 0;
+    code.highlight_line(text)
+        .into_iter()
+        .map(|span| {
+            let range = pos..pos + span.text().len();
+            pos = range.end;
+            (range, style_for_inline(base, span.style()))
+        })
+        .collect()
+}
+
+/// Merge runs of equal per-byte styles into ranges.
+fn coalesce(styles: &[Style]) -> Vec<StyledRange> {
+    let mut ranges: Vec<StyledRange> = Vec::new();
+    for (byte, style) in styles.iter().enumerate() {
+        match ranges.last_mut() {
+            Some((range, last)) if last == style => range.end = byte + 1,
+            _ => ranges.push((byte..byte + 1, *style)),
+        }
+    }
+    ranges
+}
+
+/// An opening code fence: marker, marker count, and info-string language.
+fn opens_fence(text: &str) -> Option<(char, usize, Option<&str>)> {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+    let rest = &text[indent..];
+    let marker = rest.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = rest.chars().take_while(|c| *c == marker).count();
+    if indent > 3 || len < 3 {
+        return None;
+    }
+    let info = rest[len..].trim();
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    let language = info
+        .split(|c: char| c.is_whitespace() || c == '{' || c == ',')
+        .next()
+        .filter(|lang| !lang.is_empty());
+    Some((marker, len, language))
+}
+
+/// Whether `text` closes a fence opened by `len` repetitions of `marker`.
+fn closes_fence(text: &str, marker: char, len: usize) -> bool {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+    let rest = text[indent..].trim_end();
+    indent <= 3 && rest.len() >= len && rest.chars().all(|c| c == marker)
+}
+
+/// `---`, `***`, or `___` (three or more, spaces allowed between).
+fn is_thematic_break(rest: &str) -> bool {
+    let marks: Vec<char> = rest.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// A table divider row such as `| --- | :-: |`.
+fn is_table_divider(rest: &str) -> bool {
+    rest.contains('-')
+        && rest
+            .chars()
+            .all(|c| matches!(c, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+/// Length of a list marker (`- `, `1. `, `- [x] `) at the start of `rest`.
+fn list_marker_len(rest: &str) -> Option<usize> {
+    let bullet = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if !(1..=9).contains(&digits) || !matches!(rest.as_bytes().get(digits), Some(b'.' | b')')) {
+            return None;
+        }
+        digits + 1
+    };
+    let after = &rest[bullet..];
+    if !(after.is_empty() || after.starts_with(' ')) {
+        return None;
+    }
+    let task = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find(|task| after[1.min(after.len())..].starts_with(**task))
+        .map_or(0, |task| task.len());
+    Some((bullet + 1 + task).min(rest.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style_at(ranges: &[StyledRange], byte: usize) -> Style {
+        ranges
+            .iter()
+            .find(|(range, _)| range.contains(&byte))
+            .map_or_else(Style::default, |(_, style)| *style)
+    }
+
+    #[test]
+    fn test_markdown_structure_uses_theme_styles() {
+        let buf = EditorBuffer::from_text("# Title\n- item with `code`\n> quote\n---");
+        let mut highlighter = EditorHighlighter::new(Path::new("notes.md"));
+        let theme = highlighter.theme.clone();
+        let lines = highlighter.highlight(&buf, 0..4);
+
+        assert_eq!(style_at(&lines[0], 0), theme.h1);
+        assert_eq!(style_at(&lines[1], 0), theme.list_marker);
+        assert_eq!(style_at(&lines[1], 2), Style::default());
+        assert_eq!(style_at(&lines[1], 13), theme.inline_code);
+        assert_eq!(style_at(&lines[2], 3), theme.quote);
+        assert_eq!(style_at(&lines[3], 0), theme.hr);
+    }
+
+    #[test]
+    fn test_fenced_code_is_syntax_highlighted() {
+        let buf = EditorBuffer::from_text("```rust\nfn main() {}\n```\n# After");
+        let mut highlighter = EditorHighlighter::new(Path::new("notes.md"));
+        let theme = highlighter.theme.clone();
+        let lines = highlighter.highlight(&buf, 0..4);
+
+        assert_eq!(style_at(&lines[0], 0), theme.code);
+        assert!(lines[1].len() > 1, "expected several syntax colors");
+        assert_eq!(style_at(&lines[2], 0), theme.code);
+        assert_eq!(style_at(&lines[3], 0), theme.h1);
+    }
+
+    #[test]
+    fn test_checkpoints_are_invalidated_after_edits() {
+        let mut text = "```\n".to_string();
+        text.push_str(&"code\n".repeat(2 * CHECKPOINT_INTERVAL));
+        let mut buf = EditorBuffer::from_text(&text);
+        let mut highlighter = EditorHighlighter::new(Path::new("notes.md"));
+        let theme = highlighter.theme.clone();
+        let last = 2 * CHECKPOINT_INTERVAL;
+        assert_eq!(
+            style_at(&highlighter.highlight(&buf, last..last + 1)[0], 0),
+            theme.code
+        );
+
+        // Removing the opening fence turns the rest into plain markdown.
+        buf.move_to(0, 3);
+        buf.delete_back();
+        buf.delete_back();
+        buf.delete_back();
+        highlighter.invalidate_from(buf.take_changed_from().unwrap());
+        assert_eq!(
+            style_at(&highlighter.highlight(&buf, last..last + 1)[0], 0),
+            Style::default()
+        );
+    }
+
+    #[test]
+    fn test_non_markdown_files_use_their_syntax() {
+        let buf = EditorBuffer::from_text("# comment\nx = 1");
+        let mut highlighter = EditorHighlighter::new(Path::new("script.py"));
+        let theme = highlighter.theme.clone();
+        let lines = highlighter.highlight(&buf, 0..2);
+        assert_ne!(style_at(&lines[0], 0), theme.h1);
+        assert!(lines[0].iter().all(|(_, style)| style.fg.is_some()));
+    }
+}
@@ -4,23 +4,30 @@
 //! - [`viewport`]: Scroll position and visible range management
 //! - [`widgets`]: Ratatui widgets for rendering
 //! - [`style`]: Theming and colors
+//! - [`print`]: ANSI output for non-interactive use
 
+pub mod print;
 pub mod style;
 pub mod viewport;
 pub mod widgets;
 
+mod editor_highlight;
 mod images;
 mod overlays;
 mod render;
 mod status;
 
+pub use editor_highlight::EditorHighlighter;
 pub use overlays::{link_picker_content_top, link_picker_rect};
 pub use render::line_number_width;
-pub use render::{document_content_width, render, split_main_columns};
+pub use render::{
+    document_content_width, preview_content_width, render, split_editor_columns, split_main_columns,
+};
 
 pub const DOCUMENT_LEFT_PADDING: u16 = 2;
 pub const TOC_WIDTH_PERCENT: u16 = 30;
 pub const DOC_WIDTH_PERCENT: u16 = 70;
+pub const EDITOR_WIDTH_PERCENT: u16 = 50;
 
 #[cfg(test)]
 mod tests;
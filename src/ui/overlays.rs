@@ -1,7 +1,11 @@
+use std::path::Path;
+
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
 
 use crate::app::Model;
+use crate::editor::{DiffLine, MergeRowKind, MergeState, Resolution, SwapRecovery};
+use crate::input::Action;
 
 pub fn link_picker_rect(area: Rect, items_len: usize) -> Rect {
     let popup_width = area.width.saturating_sub(16).max(44);
@@ -70,6 +74,354 @@ pub fn render_link_picker_overlay(model: &Model, frame: &mut Frame, area: Rect)
     frame.render_widget(Paragraph::new(lines).block(block), popup);
 }
 
+pub fn render_search_results_overlay(model: &Model, frame: &mut Frame, area: Rect) {
+    let Some(results) = model.search_results.as_ref() else {
+        return;
+    };
+    let items = model.search_result_items();
+    let popup_width = area.width.saturating_sub(16).max(44);
+    let popup_height = area.height.saturating_sub(4).max(8);
+    let popup = centered_popup_rect(popup_width, popup_height, area);
+    let dim_style = Style::default().fg(Color::Indexed(245));
+
+    let total = model.search_match_count();
+    let query = model.search_query.as_deref().unwrap_or_default().trim();
+    let count = if items.len() == total {
+        format!("{total} matches for /{query}")
+    } else {
+        format!("{} of {total} matches for /{query}", items.len())
+    };
+    let filter = if results.filter.is_empty() {
+        Span::styled("type to filter", dim_style)
+    } else {
+        Span::raw(results.filter.clone())
+    };
+    let mut lines = vec![
+        Line::from(vec![Span::styled("Filter: ", dim_style), filter]),
+        Line::styled(count, Style::default().add_modifier(Modifier::BOLD)),
+        Line::raw(""),
+    ];
+
+    // Borders, filter, count and blank rows; two rows per item.
+    let visible = (popup.height.saturating_sub(5) as usize / 2).max(1);
+    let selected = results.selected.min(items.len().saturating_sub(1));
+    let start = selected.saturating_sub(visible - 1);
+    let match_style = super::style::search_match_style();
+    let selection = super::style::selection_style();
+    if items.is_empty() {
+        lines.push(Line::styled("No matches", dim_style));
+    }
+    for (idx, item) in items.iter().enumerate().skip(start).take(visible) {
+        let heading = item.heading.as_deref().unwrap_or("(no heading)");
+        let mut header = Line::from(vec![
+            Span::raw(if idx == selected { "> " } else { "  " }),
+            Span::styled(
+                format!("L{}", item.line + 1),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                heading.to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]);
+        let mut snippet = vec![Span::raw("    ")];
+        let mut cursor = 0;
+        for range in &item.ranges {
+            snippet.push(Span::raw(item.snippet[cursor..range.start].to_string()));
+            snippet.push(Span::styled(
+                item.snippet[range.clone()].to_string(),
+                match_style,
+            ));
+            cursor = range.end;
+        }
+        snippet.push(Span::raw(item.snippet[cursor..].to_string()));
+        let mut snippet = Line::from(snippet);
+        if idx == selected {
+            header = header.patch_style(selection);
+            snippet = snippet.patch_style(selection);
+        }
+        lines.push(header);
+        lines.push(snippet);
+    }
+
+    let block = Block::default()
+        .title("Search Results")
+        .title_bottom(Line::styled(
+            " ↑/↓ move · Enter jump · type to filter · Esc back ",
+            dim_style,
+        ))
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_project_search_overlay(model: &Model, frame: &mut Frame, area: Rect) {
+    let Some(state) = model.project_search.as_ref() else {
+        return;
+    };
+    let popup_width = area.width.saturating_sub(8).max(44);
+    let popup_height = area.height.saturating_sub(4).max(8);
+    let popup = centered_popup_rect(popup_width, popup_height, area);
+    let dim_style = Style::default().fg(Color::Indexed(245));
+
+    let hits = state.hit_count();
+    let mut status = if state.searched.is_none() {
+        "Enter to search".to_string()
+    } else if state.is_running() {
+        format!("Searching… {hits} matches in {} files", state.files.len())
+    } else {
+        format!(
+            "{hits} matches in {} of {} files",
+            state.files.len(),
+            state.files_searched.unwrap_or_default()
+        )
+    };
+    if state.truncated {
+        status.push_str(" (stopped at the match limit)");
+    }
+    if state.searched.as_ref().is_some_and(|q| *q != state.query) {
+        status.push_str(" · Enter to search again");
+    }
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Search: ", dim_style),
+            Span::raw(state.query.clone()),
+        ]),
+        Line::styled(status, Style::default().add_modifier(Modifier::BOLD)),
+        Line::raw(""),
+    ];
+
+    // One header row per file, then its hits; `None` marks a header.
+    let mut rows: Vec<(Option<usize>, Line)> = Vec::new();
+    let match_style = super::style::search_match_style();
+    let mut index = 0;
+    for file in &state.files {
+        let path = file
+            .path
+            .strip_prefix(&model.browse_dir)
+            .unwrap_or(&file.path);
+        rows.push((
+            None,
+            Line::from(vec![
+                Span::styled(
+                    path.display().to_string(),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("  {}", file.hits.len()), dim_style),
+            ]),
+        ));
+        for hit in &file.hits {
+            let mut spans = vec![Span::styled(
+                format!("  {:>5}: ", hit.line + 1),
+                Style::default().fg(Color::Yellow),
+            )];
+            let text = hit.text.trim_end();
+            let mut cursor = 0;
+            for range in hit.ranges.iter().filter(|range| range.end <= text.len()) {
+                spans.push(Span::raw(text[cursor..range.start].to_string()));
+                spans.push(Span::styled(text[range.clone()].to_string(), match_style));
+                cursor = range.end;
+            }
+            spans.push(Span::raw(text[cursor..].to_string()));
+            let mut line = Line::from(spans);
+            if index == state.selected {
+                line = line.patch_style(super::style::selection_style());
+            }
+            rows.push((Some(index), line));
+            index += 1;
+        }
+    }
+
+    // Borders plus the query, status and blank rows.
+    let visible = popup.height.saturating_sub(5).max(1) as usize;
+    let selected_row = rows
+        .iter()
+        .position(|(hit, _)| *hit == Some(state.selected))
+        .unwrap_or(0);
+    let start = selected_row.saturating_sub(visible - 1);
+    lines.extend(
+        rows.into_iter()
+            .skip(start)
+            .take(visible)
+            .map(|(_, line)| line),
+    );
+
+    let block = Block::default()
+        .title("Search Files")
+        .title_bottom(Line::styled(
+            " Enter search/open · ↑/↓ move · Alt-r regex · Alt-w word · Esc close ",
+            dim_style,
+        ))
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_heading_palette_overlay(model: &Model, frame: &mut Frame, area: Rect) {
+    let Some(palette) = model.heading_palette.as_ref() else {
+        return;
+    };
+    let items = model.heading_palette_items();
+    let popup_width = area.width.saturating_sub(16).max(44);
+    let popup_height = area.height.saturating_sub(4).max(8);
+    let popup = centered_popup_rect(popup_width, popup_height, area);
+    let dim_style = Style::default().fg(Color::Indexed(245));
+    let match_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Heading: ", dim_style),
+            Span::raw(palette.query.clone()),
+        ]),
+        Line::raw(""),
+    ];
+    if items.is_empty() {
+        lines.push(Line::styled("No matching headings", dim_style));
+    }
+
+    // Borders plus the query and blank rows.
+    let visible = (popup.height.saturating_sub(4) as usize).max(1);
+    let selected = palette.selected.min(items.len().saturating_sub(1));
+    let start = selected.saturating_sub(visible - 1);
+    for (idx, item) in items.iter().enumerate().skip(start).take(visible) {
+        let level = if item.level == 0 {
+            "Title".to_string()
+        } else {
+            format!("H{}", item.level)
+        };
+        let mut spans = vec![
+            Span::raw(if idx == selected { "> " } else { "  " }),
+            Span::styled(format!("{level:<6}"), dim_style),
+        ];
+        for parent in &item.breadcrumb {
+            spans.push(Span::styled(format!("{parent} › "), dim_style));
+        }
+        let mut cursor = 0;
+        for &pos in &item.positions {
+            let end = pos + item.text[pos..].chars().next().map_or(0, char::len_utf8);
+            spans.push(Span::raw(item.text[cursor..pos].to_string()));
+            spans.push(Span::styled(item.text[pos..end].to_string(), match_style));
+            cursor = end;
+        }
+        spans.push(Span::raw(item.text[cursor..].to_string()));
+        let mut line = Line::from(spans);
+        if idx == selected {
+            line = line.patch_style(super::style::selection_style());
+        }
+        lines.push(line);
+    }
+
+    let block = Block::default()
+        .title("Go to Heading")
+        .title_bottom(Line::styled(
+            " type to filter · ↑/↓ move · Enter jump · Esc close ",
+            dim_style,
+        ))
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// The file finder sits in the lower part of the screen so the file it
+/// previews stays visible above it.
+pub fn render_file_finder_overlay(model: &Model, frame: &mut Frame, area: Rect) {
+    let Some(finder) = model.file_finder.as_ref() else {
+        return;
+    };
+    let items = finder.items();
+    let popup_height = (area.height * 2 / 5)
+        .max(8)
+        .min(area.height.saturating_sub(1));
+    let popup = Rect {
+        x: area.x,
+        y: area.bottom().saturating_sub(popup_height + 1).max(area.y),
+        width: area.width,
+        height: popup_height,
+    };
+    let dim_style = Style::default().fg(Color::Indexed(245));
+    let match_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let status = if finder.is_indexing() {
+        format!("indexing… {} files", finder.file_count())
+    } else {
+        format!("{} of {} files", items.len(), finder.file_count())
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::styled("File: ", dim_style),
+        Span::raw(finder.query.clone()),
+        Span::styled(format!("  ({status})"), dim_style),
+    ])];
+    if items.is_empty() && !finder.is_indexing() {
+        lines.push(Line::styled("No matching files", dim_style));
+    }
+
+    // Borders plus the query row.
+    let visible = (popup.height.saturating_sub(3) as usize).max(1);
+    let selected = finder.selected.min(items.len().saturating_sub(1));
+    let start = selected.saturating_sub(visible - 1);
+    for (idx, item) in items.iter().enumerate().skip(start).take(visible) {
+        let mut spans = vec![Span::raw(if idx == selected { "> " } else { "  " })];
+        let mut cursor = 0;
+        for &pos in &item.positions {
+            let end = pos + item.path[pos..].chars().next().map_or(0, char::len_utf8);
+            spans.push(Span::raw(item.path[cursor..pos].to_string()));
+            spans.push(Span::styled(item.path[pos..end].to_string(), match_style));
+            cursor = end;
+        }
+        spans.push(Span::raw(item.path[cursor..].to_string()));
+        let mut line = Line::from(spans);
+        if idx == selected {
+            line = line.patch_style(super::style::selection_style());
+        }
+        lines.push(line);
+    }
+
+    let block = Block::default()
+        .title(format!("Find File in {}", finder.root.display()))
+        .title_bottom(Line::styled(
+            " type to filter · ↑/↓ preview · Enter open · Esc cancel ",
+            dim_style,
+        ))
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// Help rows for the view-mode actions listed under `section`, showing
+/// the keys bound to each in the active keymap. Unbound actions are
+/// left out.
+fn keymap_help_lines(model: &Model, section: &str) -> Vec<Line<'static>> {
+    Action::ALL
+        .into_iter()
+        .filter(|action| action.section() == section)
+        .filter_map(|action| {
+            let keys = model.keymap.keys_for(action);
+            (!keys.is_empty()).then(|| help_row(&keys.join(" / "), action.description()))
+        })
+        .collect()
+}
+
+fn help_row(keys: &str, description: &str) -> Line<'static> {
+    Line::raw(format!("  {keys:<19} {description}"))
+}
+
 pub fn render_help_overlay(model: &Model, frame: &mut Frame, area: Rect) {
     let popup_width = area.width.saturating_sub(12).max(48);
     let popup_height = area.height.saturating_sub(6).max(12);
@@ -89,68 +441,71 @@ pub fn render_help_overlay(model: &Model, frame: &mut Frame, area: Rect) {
         .add_modifier(Modifier::BOLD);
     let dim_style = Style::default().fg(Color::Indexed(245));
 
+    // Rows for configurable keys come from the active keymap; the rest
+    // (editor, mouse) have fixed bindings.
+    let sections: [(&str, &[(&str, &str)]); 6] = [
+        ("Navigation", &[]),
+        ("Search", &[]),
+        ("TOC", &[("Mouse click", "Jump to entry")]),
+        ("Browse", &[]),
+        (
+            "Editor",
+            &[
+                ("Ctrl-e", "Toggle edit mode"),
+                ("Esc", "Return to view mode"),
+                ("Ctrl-s", "Save (merge if changed on disk)"),
+                ("Ctrl-p", "Toggle live preview"),
+                ("Ctrl-z / Ctrl-y", "Undo / redo"),
+                ("Shift+movement", "Select text"),
+                ("Ctrl-a", "Select all"),
+                ("Ctrl-c/x/v", "Copy / cut / paste"),
+                ("Ctrl-f / Ctrl-r", "Find / replace"),
+                ("Alt-c / Alt-r", "Find: case / regex"),
+                ("Alt-a", "Replace all"),
+                ("Tab / Shift-Tab", "Indent / outdent list item"),
+                ("Ctrl-t", "Align table"),
+                ("Arrows, Home/End", "Navigate"),
+                ("Ctrl+Left/Right", "Word movement"),
+                ("Ctrl+Home/End", "Buffer start / end"),
+                ("PageUp/PageDown", "Scroll editor"),
+            ],
+        ),
+        (
+            "Other",
+            &[
+                ("Mouse drag", "Select lines + copy"),
+                ("Alt + mouse drag", "Select lines + copy markdown"),
+            ],
+        ),
+    ];
     let mut all_lines: Vec<Line> = Vec::new();
-
-    // Navigation
-    all_lines.push(Line::styled("Navigation", section_style));
-    all_lines.push(Line::raw("  j/k or Up/Down      Scroll"));
-    all_lines.push(Line::raw("  Space/PageDown      Page down"));
-    all_lines.push(Line::raw("  b/PageUp            Page up"));
-    all_lines.push(Line::raw("  Ctrl-d / Ctrl-u     Half page"));
-    all_lines.push(Line::raw("  g / G               Top / bottom"));
-    all_lines.push(Line::raw(""));
-
-    // Search
-    all_lines.push(Line::styled("Search", section_style));
-    all_lines.push(Line::raw("  /                   Start search"));
-    all_lines.push(Line::raw("  Enter               Next match"));
-    all_lines.push(Line::raw("  Esc                 Clear search"));
-    all_lines.push(Line::raw(""));
-
-    // TOC
-    all_lines.push(Line::styled("TOC", section_style));
-    all_lines.push(Line::raw("  t                   Toggle TOC"));
-    all_lines.push(Line::raw("  T                   Toggle + focus TOC"));
-    all_lines.push(Line::raw("  Tab                 Switch focus"));
-    all_lines.push(Line::raw("  j/k, arrows, Enter/Space, mouse, click"));
-    all_lines.push(Line::raw("  h / Left            Collapse / parent dir"));
-    all_lines.push(Line::raw("  l / Right           Expand / enter dir"));
-    all_lines.push(Line::raw(""));
-
-    // Browse
-    all_lines.push(Line::styled("Browse", section_style));
-    all_lines.push(Line::raw("  B                   Browse directory"));
-    all_lines.push(Line::raw("  F                   Focus on file only"));
-    all_lines.push(Line::raw("  Backspace           Parent directory (in TOC)"));
-    all_lines.push(Line::raw(""));
-
-    // Editor
-    all_lines.push(Line::styled("Editor", section_style));
-    all_lines.push(Line::raw("  e                   Enter edit mode"));
-    all_lines.push(Line::raw("  Ctrl-e              Toggle edit mode"));
-    all_lines.push(Line::raw("  Esc                 Return to view mode"));
-    all_lines.push(Line::raw("  Ctrl-s              Save file"));
-    all_lines.push(Line::raw("  Arrows, Home/End    Navigate"));
-    all_lines.push(Line::raw("  Ctrl+Left/Right     Word movement"));
-    all_lines.push(Line::raw("  Ctrl+Home/End       Buffer start / end"));
-    all_lines.push(Line::raw("  PageUp/PageDown     Scroll editor"));
-    all_lines.push(Line::raw(""));
-
-    // Other
-    all_lines.push(Line::styled("Other", section_style));
-    all_lines.push(Line::raw("  w                   Toggle watch"));
-    all_lines.push(Line::raw("  r / R               Reload file"));
-    all_lines.push(Line::raw("  o                   Open visible links (1-9)"));
-    all_lines.push(Line::raw("  q / Ctrl-c / Ctrl-q Quit"));
-    all_lines.push(Line::raw("  ? / F1              Toggle help"));
-    all_lines.push(Line::raw("  Mouse drag          Select lines + copy"));
-    all_lines.push(Line::raw(""));
+    for (section, fixed) in sections {
+        all_lines.push(Line::styled(section, section_style));
+        all_lines.extend(keymap_help_lines(model, section));
+        all_lines.extend(fixed.iter().map(|(keys, desc)| help_row(keys, desc)));
+        all_lines.push(Line::raw(""));
+    }
 
     // Config
     all_lines.push(Line::styled("Config", section_style));
     all_lines.push(Line::raw(format!("  Global: {global_cfg}")));
     all_lines.push(Line::raw(format!("  Local override: {local_cfg}")));
 
+    if model.editor_vim_keys {
+        all_lines.push(Line::raw(""));
+        all_lines.push(Line::styled("Editor (Vim keys)", section_style));
+        all_lines.push(Line::raw("  i a I A o O         Insert mode; Esc: normal"));
+        all_lines.push(Line::raw("  v / V               Visual / visual line"));
+        all_lines.push(Line::raw("  h j k l w b e       Move (with count)"));
+        all_lines.push(Line::raw("  0 ^ $ gg G          Line / buffer motions"));
+        all_lines.push(Line::raw("  d c y + motion      Delete / change / yank"));
+        all_lines.push(Line::raw("  dd cc yy            Whole lines"));
+        all_lines.push(Line::raw("  x D C s S Y         Shortcuts"));
+        all_lines.push(Line::raw("  p / P               Put after / before"));
+        all_lines.push(Line::raw("  u / Ctrl-r          Undo / redo"));
+        all_lines.push(Line::raw("  :w :q :wq :q!       Save / quit editor"));
+    }
+
     let block = Block::default()
         .title("Help")
         .borders(Borders::ALL)
@@ -186,6 +541,212 @@ pub fn render_help_overlay(model: &Model, frame: &mut Frame, area: Rect) {
     frame.render_widget(Paragraph::new(footer), footer_area);
 }
 
+/// Prompt for a leftover swap file, or the diff it would apply.
+pub fn render_swap_recovery_overlay(
+    recovery: &SwapRecovery,
+    file_path: &Path,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let dim_style = Style::default().fg(Color::Indexed(245));
+    let key_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let name = file_path.file_name().map_or_else(
+        || file_path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+
+    let (popup, lines, footer) = if recovery.showing_diff {
+        let popup = centered_popup_rect(
+            area.width.saturating_sub(8).max(48),
+            area.height.saturating_sub(4).max(12),
+            area,
+        );
+        // Border(1) + padding(1) on each side, plus the footer row.
+        let rows = popup.height.saturating_sub(5) as usize;
+        let scroll = recovery
+            .scroll
+            .min(recovery.diff.len().saturating_sub(rows));
+        let lines: Vec<Line> = recovery.diff[scroll..]
+            .iter()
+            .take(rows)
+            .map(diff_line)
+            .collect();
+        let footer = "j/k scroll \u{2502} r recover \u{2502} x discard \u{2502} d/Esc back";
+        (popup, lines, footer)
+    } else {
+        let popup = centered_popup_rect(area.width.saturating_sub(16).max(52), 11, area);
+        let lines = vec![
+            Line::raw(format!("{name} has unsaved changes from a session")),
+            Line::raw("that did not exit cleanly."),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("r", key_style),
+                Span::raw(" recover   "),
+                Span::styled("d", key_style),
+                Span::raw(" show diff   "),
+                Span::styled("x", key_style),
+                Span::raw(" discard"),
+            ]),
+        ];
+        (
+            popup,
+            lines,
+            "Esc leaves the swap file and exits the editor",
+        )
+    };
+
+    let block = Block::default()
+        .title("Recover Unsaved Changes")
+        .borders(Borders::ALL)
+        .padding(Padding::uniform(1))
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+    let content_area = Rect {
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+    frame.render_widget(Paragraph::new(lines), content_area);
+    let footer_area = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: 1,
+        ..inner
+    };
+    frame.render_widget(Paragraph::new(Line::styled(footer, dim_style)), footer_area);
+}
+
+/// Merge view for a file that changed on disk while editing.
+pub fn render_merge_overlay(merge: &MergeState, frame: &mut Frame, area: Rect) {
+    let dim_style = Style::default().fg(Color::Indexed(245));
+    let popup = centered_popup_rect(
+        area.width.saturating_sub(8).max(48),
+        area.height.saturating_sub(4).max(12),
+        area,
+    );
+    let block = Block::default()
+        .title("Merge Changes From Disk")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+
+    let conflicts = merge.conflict_count();
+    let summary = if conflicts > 0 {
+        format!(
+            "{} change(s) merged automatically \u{2502} conflict {}/{conflicts} \u{2502} {} unresolved",
+            merge.auto_merged,
+            merge.current() + 1,
+            merge.unresolved_count()
+        )
+    } else {
+        format!("{} change(s) merged automatically", merge.auto_merged)
+    };
+    let header = if merge.showing_diff {
+        Line::styled(
+            "Disk \u{2192} buffer",
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Line::styled(summary, Style::default().add_modifier(Modifier::BOLD))
+    };
+
+    // Summary row and footer row around the scrolling content.
+    let rows = inner.height.saturating_sub(3) as usize;
+    let lines: Vec<Line> = if merge.showing_diff {
+        let scroll = merge.scroll.min(merge.diff.len().saturating_sub(rows));
+        merge.diff[scroll..]
+            .iter()
+            .take(rows)
+            .map(diff_line)
+            .collect()
+    } else {
+        let all = merge.rows();
+        let scroll = merge.scroll.min(all.len().saturating_sub(rows));
+        all[scroll..]
+            .iter()
+            .take(rows)
+            .map(|row| merge_row_line(merge, row.kind, row.text))
+            .collect()
+    };
+
+    let header_area = Rect { height: 1, ..inner };
+    frame.render_widget(Paragraph::new(header), header_area);
+    let content_area = Rect {
+        y: inner.y + 2,
+        height: inner.height.saturating_sub(3),
+        ..inner
+    };
+    frame.render_widget(Paragraph::new(lines), content_area);
+    let footer_area = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: 1,
+        ..inner
+    };
+    let footer = "n/p conflict \u{2502} o mine \u{2502} t disk \u{2502} b both \u{2502} Enter apply \u{2502} d diff \u{2502} Esc cancel";
+    frame.render_widget(Paragraph::new(Line::styled(footer, dim_style)), footer_area);
+}
+
+fn merge_row_line(merge: &MergeState, kind: MergeRowKind, text: &str) -> Line<'static> {
+    let marker = |label: &str, idx: usize| {
+        let style = if idx == merge.current() {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Indexed(245))
+        };
+        Line::styled(label.to_string(), style)
+    };
+    // The side that was not chosen is dimmed and struck through.
+    let side = |idx: usize, color: Color, dropped: Resolution| {
+        let style = if merge.resolution(idx) == Some(dropped) {
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            Style::default().fg(color)
+        };
+        Line::styled(format!("  {text}"), style)
+    };
+    match kind {
+        MergeRowKind::Merged => Line::raw(format!("  {text}")),
+        MergeRowKind::ConflictStart(idx) => {
+            let choice = match merge.resolution(idx) {
+                None => "unresolved",
+                Some(Resolution::Ours) => "keeping yours",
+                Some(Resolution::Theirs) => "keeping disk",
+                Some(Resolution::Both) => "keeping both",
+            };
+            marker(&format!("<<<<<<< yours ({choice})"), idx)
+        }
+        MergeRowKind::Ours(idx) => side(idx, Color::Green, Resolution::Theirs),
+        MergeRowKind::ConflictSeparator(idx) => marker("=======", idx),
+        MergeRowKind::Theirs(idx) => side(idx, Color::Cyan, Resolution::Ours),
+        MergeRowKind::ConflictEnd(idx) => marker(">>>>>>> disk", idx),
+    }
+}
+
+fn diff_line(line: &DiffLine) -> Line<'static> {
+    match line {
+        DiffLine::Context(text) => Line::raw(format!("  {text}")),
+        DiffLine::Removed(text) => {
+            Line::styled(format!("- {text}"), Style::default().fg(Color::Red))
+        }
+        DiffLine::Added(text) => {
+            Line::styled(format!("+ {text}"), Style::default().fg(Color::Green))
+        }
+        DiffLine::Skipped(count) => Line::styled(
+            format!("  \u{22ef} {count} unchanged line(s)"),
+            Style::default().fg(Color::Indexed(245)),
+        ),
+    }
+}
+
 fn centered_popup_rect(width: u16, height: u16, area: Rect) -> Rect {
     let w = width.min(area.width);
     let h = height.min(area.height);
@@ -0,0 +1,196 @@
+//! Non-interactive output.
+//!
+//! Writes a rendered [`Document`] as plain lines with ANSI SGR escapes,
+//! using the same line and inline styles as the interactive view. This is
+//! what `markless --print` (or piping stdout) uses instead of the TUI.
+
+use std::io::{self, Write};
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::document::{Document, LineType};
+
+use super::style::{line_spans, style_for_line_type};
+
+/// Write every line of `document` to `out`.
+///
+/// Code blocks are highlighted up front so the whole document is styled,
+/// not just what would fit in a viewport. When `color` is false the text is
+/// written without any escape sequences.
+///
+/// # Errors
+/// Returns any error produced by the underlying writer.
+pub fn write_document<W: Write>(
+    document: &mut Document,
+    out: &mut W,
+    color: bool,
+) -> io::Result<()> {
+    let total = document.line_count();
+    if document.is_hex_mode() {
+        let style = style_for_line_type(&LineType::CodeBlock);
+        for idx in 0..total {
+            let text = document.hex_line_content(idx).unwrap_or_default();
+            write_styled(out, &text, style, color)?;
+            out.write_all(b"\n")?;
+        }
+        return out.flush();
+    }
+
+    document.ensure_highlight_for_range(0..total);
+    for line in document.visible_lines(0, total) {
+        for span in line_spans(line) {
+            write_styled(out, &span.content, span.style, color)?;
+        }
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
+fn write_styled<W: Write>(out: &mut W, text: &str, style: Style, color: bool) -> io::Result<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    let sgr = if color { sgr_for_style(style) } else { None };
+    match sgr {
+        Some(sgr) => write!(out, "\x1b[{sgr}m{text}\x1b[0m"),
+        None => out.write_all(text.as_bytes()),
+    }
+}
+
+/// Build the SGR parameter list (without `ESC [` and `m`) for a style.
+///
+/// Returns `None` when the style has no visible attributes.
+fn sgr_for_style(style: Style) -> Option<String> {
+    let mut params: Vec<String> = Vec::new();
+    let modifiers = style.add_modifier - style.sub_modifier;
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifiers.contains(modifier) {
+            params.push(code.to_string());
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|c| color_param(c, false)) {
+        params.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_param(c, true)) {
+        params.push(bg);
+    }
+    if params.is_empty() {
+        None
+    } else {
+        Some(params.join(";"))
+    }
+}
+
+fn color_param(color: Color, background: bool) -> Option<String> {
+    let base: u8 = if background { 40 } else { 30 };
+    let bright: u8 = if background { 100 } else { 90 };
+    let extended = if background { "48" } else { "38" };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => bright,
+        Color::LightRed => bright + 1,
+        Color::LightGreen => bright + 2,
+        Color::LightYellow => bright + 3,
+        Color::LightBlue => bright + 4,
+        Color::LightMagenta => bright + 5,
+        Color::LightCyan => bright + 6,
+        Color::White => bright + 7,
+        Color::Indexed(idx) => return Some(format!("{extended};5;{idx}")),
+        Color::Rgb(r, g, b) => return Some(format!("{extended};2;{r};{g};{b}")),
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(md: &str, color: bool) -> String {
+        let mut doc = Document::parse_with_layout(md, 60).unwrap();
+        let mut out = Vec::new();
+        write_document(&mut doc, &mut out, color).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_plain_output_has_no_escapes() {
+        let out = render("# Title\n\nSome *text* here.\n", false);
+        assert!(!out.contains('\x1b'));
+        assert!(out.contains("Title"));
+        assert!(out.contains("Some text here."));
+    }
+
+    #[test]
+    fn test_color_output_styles_heading() {
+        let out = render("# Title\n", true);
+        assert!(
+            out.contains("\x1b[1;4;"),
+            "heading should be bold+underlined: {out:?}"
+        );
+        assert!(out.contains("\x1b[0m"));
+    }
+
+    #[test]
+    fn test_code_block_is_boxed_and_highlighted() {
+        let out = render("```rust\nfn main() {}\n```\n", true);
+        assert!(out.contains("┌"));
+        assert!(out.contains("└"));
+        assert!(out.contains("fn"));
+        // Highlighted spans carry explicit foreground colors.
+        assert!(out.contains("38;"), "expected highlighted code: {out:?}");
+    }
+
+    #[test]
+    fn test_table_rows_are_written() {
+        let out = render("| a | b |\n|---|---|\n| 1 | 2 |\n", false);
+        assert!(out.lines().any(|l| l.contains('a') && l.contains('b')));
+        assert!(out.lines().any(|l| l.contains('1') && l.contains('2')));
+    }
+
+    #[test]
+    fn test_every_document_line_is_written() {
+        let md = "# A\n\npara\n\n- item\n";
+        let doc = Document::parse_with_layout(md, 60).unwrap();
+        let out = render(md, false);
+        assert_eq!(out.lines().count(), doc.line_count());
+    }
+
+    #[test]
+    fn test_sgr_for_plain_style_is_none() {
+        assert_eq!(sgr_for_style(Style::default()), None);
+    }
+
+    #[test]
+    fn test_sgr_for_colors() {
+        let style = Style::default()
+            .fg(Color::Rgb(1, 2, 3))
+            .bg(Color::Indexed(17));
+        assert_eq!(sgr_for_style(style).as_deref(), Some("38;2;1;2;3;48;5;17"));
+        let style = Style::default().fg(Color::LightBlue).bg(Color::Black);
+        assert_eq!(sgr_for_style(style).as_deref(), Some("94;40"));
+    }
+
+    #[test]
+    fn test_hex_document_is_written() {
+        let mut doc = Document::from_hex("blob.bin", vec![0u8, 1, 2, 3]);
+        let mut out = Vec::new();
+        write_document(&mut doc, &mut out, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("00000000"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
 
@@ -5,7 +7,8 @@ use crate::app::Model;
 use crate::document::LineType;
 
 use super::{
-    DOC_WIDTH_PERCENT, DOCUMENT_LEFT_PADDING, TOC_WIDTH_PERCENT, images, overlays, status,
+    DOC_WIDTH_PERCENT, DOCUMENT_LEFT_PADDING, EDITOR_WIDTH_PERCENT, TOC_WIDTH_PERCENT, images,
+    overlays, status,
 };
 
 pub fn split_main_columns(area: Rect) -> std::rc::Rc<[Rect]> {
@@ -18,6 +21,27 @@ pub fn split_main_columns(area: Rect) -> std::rc::Rc<[Rect]> {
         .split(area)
 }
 
+/// Split the editor area into the source pane and the live preview pane.
+pub fn split_editor_columns(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(EDITOR_WIDTH_PERCENT),
+            Constraint::Percentage(100 - EDITOR_WIDTH_PERCENT),
+        ])
+        .split(area)
+}
+
+/// Width available to the rendered document in the live preview pane.
+pub fn preview_content_width(total_width: u16) -> u16 {
+    let area = Rect::new(0, 0, total_width, 1);
+    split_editor_columns(area)
+        .get(1)
+        .map_or(total_width, |r| r.width)
+        .saturating_sub(DOCUMENT_LEFT_PADDING)
+        .max(1)
+}
+
 pub fn document_content_width(total_width: u16, toc_visible: bool) -> u16 {
     let area = Rect::new(0, 0, total_width, 1);
     let doc_width = if toc_visible {
@@ -56,6 +80,14 @@ pub fn render(model: &mut Model, frame: &mut Frame) {
         overlays::render_help_overlay(model, frame, area);
     } else if model.link_picker_active() {
         overlays::render_link_picker_overlay(model, frame, area);
+    } else if model.search_results.is_some() {
+        overlays::render_search_results_overlay(model, frame, area);
+    } else if model.project_search.is_some() {
+        overlays::render_project_search_overlay(model, frame, area);
+    } else if model.heading_palette.is_some() {
+        overlays::render_heading_palette_overlay(model, frame, area);
+    } else if model.file_finder.is_some() {
+        overlays::render_file_finder_overlay(model, frame, area);
     }
 }
 
@@ -69,6 +101,8 @@ fn render_toc(model: &Model, frame: &mut Frame, area: Rect) {
 
 fn render_heading_toc(model: &Model, frame: &mut Frame, area: Rect) {
     let headings = model.document.headings();
+    // A front matter title (level 0) is the TOC root; nest real headings under it.
+    let root_depth = usize::from(headings.first().is_some_and(|h| h.level == 0));
     let visible_rows = area.height.saturating_sub(2) as usize;
     let max_start = headings.len().saturating_sub(visible_rows);
     let start = model.toc_scroll_offset.min(max_start);
@@ -80,7 +114,12 @@ fn render_heading_toc(model: &Model, frame: &mut Frame, area: Rect) {
         .skip(start)
         .take(end.saturating_sub(start))
         .map(|(i, h)| {
-            let indent = "  ".repeat(h.level.saturating_sub(1) as usize);
+            let depth = if h.level == 0 {
+                0
+            } else {
+                root_depth + h.level.saturating_sub(1) as usize
+            };
+            let indent = "  ".repeat(depth);
             let marker = if model.toc_selected == Some(i) {
                 ">"
             } else {
@@ -204,6 +243,23 @@ fn render_document(model: &mut Model, frame: &mut Frame, area: Rect) {
         ..area
     };
 
+    render_document_content(model, frame, doc_outer_area);
+
+    // Render status bar
+    if hover_active {
+        status::render_hover_link_bar(model, frame, hover_area);
+    }
+    if toast_active {
+        status::render_toast_bar(model, frame, toast_area);
+    }
+    if search_active {
+        status::render_search_bar(model, frame, search_area);
+    }
+    status::render_status_bar(model, frame, status_area);
+}
+
+/// Render the visible document lines and images into `doc_outer_area`.
+fn render_document_content(model: &mut Model, frame: &mut Frame, doc_outer_area: Rect) {
     // Render document content with styling
     let visible_lines = model
         .document
@@ -217,42 +273,15 @@ fn render_document(model: &mut Model, frame: &mut Frame, area: Rect) {
         let selected = selection
             .as_ref()
             .is_some_and(|range| range.contains(&line_idx));
-        let line_style = super::style::style_for_line_type(line.line_type());
-        if let Some(spans) = line.spans() {
-            let mut styled_spans = spans
-                .iter()
-                .map(|span| {
-                    Span::styled(
-                        span.text().to_string(),
-                        super::style::style_for_inline(line_style, span.style()),
-                    )
-                })
-                .collect::<Vec<_>>();
-            if let Some(query) = model
-                .search_query
-                .as_deref()
-                .filter(|q| q.chars().count() >= 3)
-            {
-                styled_spans = highlight_spans(&styled_spans, query);
-            }
-            if selected {
-                styled_spans = apply_selection_bg(styled_spans, Color::DarkGray);
-            }
-            content.push(Line::from(styled_spans));
-        } else {
-            let mut styled_spans = vec![Span::styled(line.content().to_string(), line_style)];
-            if let Some(query) = model
-                .search_query
-                .as_deref()
-                .filter(|q| q.chars().count() >= 3)
-            {
-                styled_spans = highlight_spans(&styled_spans, query);
-            }
-            if selected {
-                styled_spans = apply_selection_bg(styled_spans, Color::DarkGray);
-            }
-            content.push(Line::from(styled_spans));
+        let mut styled_spans = super::style::line_spans(line);
+        let ranges = model.search_ranges_for_line(line_idx);
+        if !ranges.is_empty() {
+            styled_spans = highlight_spans(styled_spans, ranges);
+        }
+        if selected {
+            styled_spans = apply_selection(styled_spans, super::style::selection_style());
         }
+        content.push(Line::from(styled_spans));
     }
 
     let doc_block = Block::default()
@@ -267,31 +296,31 @@ fn render_document(model: &mut Model, frame: &mut Frame, area: Rect) {
     if model.images_enabled {
         images::render_images(model, frame, doc_area);
     }
-
-    // Render status bar
-    if hover_active {
-        status::render_hover_link_bar(model, frame, hover_area);
-    }
-    if toast_active {
-        status::render_toast_bar(model, frame, toast_area);
-    }
-    if search_active {
-        status::render_search_bar(model, frame, search_area);
-    }
-    status::render_status_bar(model, frame, status_area);
 }
 
-fn render_editor(model: &Model, frame: &mut Frame, area: Rect) {
-    let Some(buf) = &model.editor_buffer else {
-        return;
-    };
-
+fn render_editor(model: &mut Model, frame: &mut Frame, area: Rect) {
     let toast_active = model.active_toast().is_some();
-    let footer_rows = 1 + u16::from(toast_active);
-    let editor_area = Rect {
+    let find_rows = model.editor_find_rows();
+    let footer_rows = 1 + u16::from(toast_active) + find_rows;
+    let text_area = Rect {
         height: area.height.saturating_sub(footer_rows),
         ..area
     };
+    // With the live preview on, the rendered document takes the right column.
+    let (editor_area, preview_area) = if model.editor_preview {
+        let columns = split_editor_columns(text_area);
+        match (columns.first(), columns.get(1)) {
+            (Some(&left), Some(&right)) => (left, Some(right)),
+            _ => (text_area, None),
+        }
+    } else {
+        (text_area, None)
+    };
+    let find_area = Rect {
+        y: area.y + area.height.saturating_sub(footer_rows),
+        height: find_rows,
+        ..area
+    };
     let toast_area = Rect {
         y: area.y + area.height.saturating_sub(1 + u16::from(toast_active)),
         height: 1,
@@ -303,14 +332,45 @@ fn render_editor(model: &Model, frame: &mut Frame, area: Rect) {
         ..area
     };
 
+    let visible_height = editor_area.height as usize;
+    let start = model.editor_scroll_offset;
+
+    // Re-highlight only the visible lines, dropping cached state below edits.
+    let syntax = match (&mut model.editor_buffer, &mut model.editor_highlight) {
+        (Some(buf), Some(highlighter)) => {
+            if let Some(line) = buf.take_changed_from() {
+                highlighter.invalidate_from(line);
+            }
+            let end = (start + visible_height).min(buf.line_count());
+            highlighter.highlight(buf, start..end.max(start))
+        }
+        _ => Vec::new(),
+    };
+
+    if let Some(preview_area) = preview_area {
+        render_document_content(model, frame, preview_area);
+    }
+
+    let model = &*model;
+    let Some(buf) = &model.editor_buffer else {
+        return;
+    };
+
     // Line number gutter width
     let total_lines = buf.line_count();
     let gutter_width = line_number_width(total_lines);
 
-    let visible_height = editor_area.height as usize;
-    let start = model.editor_scroll_offset;
     let end = (start + visible_height).min(total_lines);
     let cursor = buf.cursor();
+    let selection = model
+        .editor_vim
+        .as_ref()
+        .and_then(|vim| vim.visual_selection(buf))
+        .or_else(|| buf.selection());
+    let find_matches = model
+        .editor_find
+        .as_ref()
+        .map_or(&[][..], crate::editor::FindState::matches);
 
     let mut content: Vec<Line> = Vec::new();
     for line_idx in start..end {
@@ -319,30 +379,40 @@ fn render_editor(model: &Model, frame: &mut Frame, area: Rect) {
 
         let mut spans = vec![Span::styled(line_num, Style::default().fg(Color::DarkGray))];
 
-        if line_idx == cursor.line {
-            // Split line at cursor position for cursor rendering
-            let col = cursor.col.min(line_text.len());
-            let before = &line_text[..col];
-            let cursor_char = line_text.get(col..col + 1).unwrap_or(" ");
-            let after = if col < line_text.len() {
-                &line_text[col + 1..]
+        let selected = selection.and_then(|(sel_start, sel_end)| {
+            if line_idx < sel_start.line || line_idx > sel_end.line {
+                return None;
+            }
+            let from = if line_idx == sel_start.line {
+                sel_start.col
+            } else {
+                0
+            };
+            let to = if line_idx == sel_end.line {
+                sel_end.col
             } else {
-                ""
+                usize::MAX
             };
+            Some(from..to)
+        });
+        // Find matches overlapping this line, as line-relative byte ranges.
+        let line_start = buf.line_start_byte(line_idx);
+        let line_end = line_start + line_text.len();
+        let first = find_matches.partition_point(|m| m.end <= line_start);
+        let matched: Vec<std::ops::Range<usize>> = find_matches[first..]
+            .iter()
+            .take_while(|m| m.start < line_end)
+            .map(|m| m.start.saturating_sub(line_start)..m.end.min(line_end) - line_start)
+            .collect();
 
-            if !before.is_empty() {
-                spans.push(Span::raw(before.to_string()));
-            }
-            spans.push(Span::styled(
-                cursor_char.to_string(),
-                Style::default().bg(Color::White).fg(Color::Black),
-            ));
-            if !after.is_empty() {
-                spans.push(Span::raw(after.to_string()));
-            }
-        } else {
-            spans.push(Span::raw(line_text));
-        }
+        let cursor_col = (line_idx == cursor.line).then_some(cursor.col);
+        spans.extend(editor_line_spans(
+            &line_text,
+            syntax.get(line_idx - start).map_or(&[][..], Vec::as_slice),
+            cursor_col,
+            selected.as_ref(),
+            &matched,
+        ));
 
         content.push(Line::from(spans));
     }
@@ -351,6 +421,10 @@ fn render_editor(model: &Model, frame: &mut Frame, area: Rect) {
     frame.render_widget(Clear, editor_area);
     frame.render_widget(doc, editor_area);
 
+    if let Some(find) = &model.editor_find {
+        render_editor_find_bar(find, frame, find_area);
+    }
+
     // Render toast if active
     if toast_active {
         status::render_toast_bar(model, frame, toast_area);
@@ -358,6 +432,70 @@ fn render_editor(model: &Model, frame: &mut Frame, area: Rect) {
 
     // Render editor status bar
     render_editor_status_bar(model, frame, status_area);
+
+    if let Some(recovery) = &model.editor_swap_recovery {
+        overlays::render_swap_recovery_overlay(recovery, &model.file_path, frame, area);
+    } else if let Some(merge) = &model.editor_merge {
+        overlays::render_merge_overlay(merge, frame, area);
+    }
+}
+
+/// Style one editor line over its `syntax` colors: the cursor cell is
+/// inverted, `selected` (byte columns; an end past the text includes the
+/// line break) gets a selection background, and find `matches` are
+/// highlighted.
+fn editor_line_spans(
+    text: &str,
+    syntax: &[super::editor_highlight::StyledRange],
+    cursor_col: Option<usize>,
+    selected: Option<&std::ops::Range<usize>>,
+    matches: &[std::ops::Range<usize>],
+) -> Vec<Span<'static>> {
+    let cursor_style = Style::default().bg(Color::White).fg(Color::Black);
+    let selected_style = super::style::selection_style();
+    let match_style = super::style::search_match_style();
+    let cursor_col = cursor_col.map(|col| col.min(text.len()));
+    let style_at = |byte: usize| {
+        if cursor_col == Some(byte) {
+            cursor_style
+        } else if matches.iter().any(|range| range.contains(&byte))
+            && !selected.is_some_and(|range| range.contains(&byte))
+        {
+            match_style
+        } else {
+            let base = syntax
+                .iter()
+                .find(|(range, _)| range.contains(&byte))
+                .map_or_else(Style::default, |(_, style)| *style);
+            if selected.is_some_and(|range| range.contains(&byte)) {
+                base.patch(selected_style)
+            } else {
+                base
+            }
+        }
+    };
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_style = Style::default();
+    for (byte, ch) in text.char_indices() {
+        let style = style_at(byte);
+        if style != run_style && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = style;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, run_style));
+    }
+
+    // A cell past the end shows the cursor or a selected line break.
+    let end_style = style_at(text.len());
+    if end_style != Style::default() {
+        spans.push(Span::styled(" ", end_style));
+    }
+    spans
 }
 
 fn render_editor_status_bar(model: &Model, frame: &mut Frame, area: Rect) {
@@ -377,14 +515,101 @@ fn render_editor_status_bar(model: &Model, frame: &mut Frame, area: Rect) {
         format!("  Ln {}, Col {}", c.line + 1, c.col + 1)
     });
 
-    let status = format!(" EDIT  {filename}{dirty_indicator}{cursor_info}  Esc:view  Ctrl+S:save");
+    let match_info = model
+        .editor_find
+        .as_ref()
+        .filter(|find| !find.query.is_empty())
+        .map_or_else(String::new, |find| {
+            if find.error().is_some() {
+                "  [invalid regex]".to_string()
+            } else if let Some((current, total)) = find.counter() {
+                format!("  [{current}/{total}]")
+            } else {
+                "  [no matches]".to_string()
+            }
+        });
+
+    let style = Style::default().bg(Color::Magenta).fg(Color::White);
+    let status = match &model.editor_vim {
+        Some(vim) => {
+            // The `:` line takes over the whole bar while it is open.
+            if let Some(command) = vim.command_line() {
+                let cursor_style = Style::default().bg(Color::White).fg(Color::Black);
+                let line = Line::from(vec![
+                    Span::raw(format!(":{command}")),
+                    Span::styled(" ", cursor_style),
+                ]);
+                frame.render_widget(Paragraph::new(line).style(style), area);
+                return;
+            }
+            let mode = vim.mode().label();
+            let pending = vim.pending_keys();
+            let pending = if pending.is_empty() {
+                String::new()
+            } else {
+                format!("  {pending}")
+            };
+            format!(
+                " {mode}  {filename}{dirty_indicator}{cursor_info}{match_info}{pending}  :w:save  :q:view"
+            )
+        }
+        None => format!(
+            " EDIT  {filename}{dirty_indicator}{cursor_info}{match_info}  Esc:view  Ctrl+S:save"
+        ),
+    };
 
-    let status_bar =
-        Paragraph::new(status).style(Style::default().bg(Color::Magenta).fg(Color::White));
+    let status_bar = Paragraph::new(status).style(style);
 
     frame.render_widget(status_bar, area);
 }
 
+/// Find row, plus the replace row when replacing. The focused field shows
+/// a cursor cell and enabled options are inverted.
+fn render_editor_find_bar(find: &crate::editor::FindState, frame: &mut Frame, area: Rect) {
+    use crate::editor::FindField;
+
+    let bar_style = Style::default().bg(Color::Blue).fg(Color::White);
+    let on_style = Style::default().bg(Color::White).fg(Color::Blue);
+    let cursor_style = Style::default().bg(Color::White).fg(Color::Black);
+    let toggle =
+        |label: &'static str, on: bool| Span::styled(label, if on { on_style } else { bar_style });
+    let field = |label: &'static str, text: &str, focused: bool| {
+        let mut spans = vec![Span::raw(label), Span::raw(text.to_string())];
+        if focused {
+            spans.push(Span::styled(" ", cursor_style));
+        }
+        spans
+    };
+
+    let mut find_line = field(" Find:    ", &find.query, find.focus == FindField::Query);
+    find_line.push(Span::raw("  "));
+    find_line.push(toggle("Aa", find.options.case_sensitive));
+    find_line.push(Span::raw(" "));
+    find_line.push(toggle(".*", find.options.regex));
+    match find.error() {
+        Some(_) => find_line.push(Span::styled(
+            "  invalid regex",
+            Style::default().bg(Color::Blue).fg(Color::LightRed),
+        )),
+        None => find_line.push(Span::raw(
+            "  Enter/↑↓:next/prev  Alt+C:case  Alt+R:regex  Esc:close",
+        )),
+    }
+    let mut lines = vec![Line::from(find_line)];
+
+    if find.replacing {
+        let mut replace_line = field(
+            " Replace: ",
+            &find.replacement,
+            find.focus == FindField::Replacement,
+        );
+        replace_line.push(Span::raw("  Enter:replace  Alt+A:all  Tab:switch field"));
+        lines.push(Line::from(replace_line));
+    }
+
+    frame.render_widget(Paragraph::new(lines).style(bar_style), area);
+}
+
 /// Calculate the width needed for line numbers.
 pub const fn line_number_width(total_lines: usize) -> u16 {
     if total_lines < 10 {
@@ -402,51 +627,51 @@ pub const fn line_number_width(total_lines: usize) -> u16 {
     }
 }
 
-fn highlight_spans(spans: &[Span<'_>], query: &str) -> Vec<Span<'static>> {
-    let needle = query.trim();
-    if needle.is_empty() {
-        return spans
-            .iter()
-            .map(|s| Span::styled(s.content.to_string(), s.style))
-            .collect();
-    }
-    let needle_lower = needle.to_ascii_lowercase();
+/// Patch the search match style onto the byte `ranges` of a line, splitting
+/// spans where a match starts or ends.
+fn highlight_spans(spans: Vec<Span<'static>>, ranges: &[Range<usize>]) -> Vec<Span<'static>> {
+    let match_style = super::style::search_match_style();
     let mut out = Vec::new();
+    let mut offset = 0usize;
 
     for span in spans {
-        let text = span.content.to_string();
-        let text_lower = text.to_ascii_lowercase();
+        let text = span.content.as_ref();
+        let span_end = offset + text.len();
         let mut cursor = 0usize;
-
-        while let Some(rel_idx) = text_lower[cursor..].find(&needle_lower) {
-            let start = cursor + rel_idx;
-            let end = start + needle_lower.len();
-
+        for range in ranges {
+            let start = range.start.clamp(offset, span_end) - offset;
+            let end = range.end.clamp(offset, span_end) - offset;
+            if start >= end || start < cursor {
+                continue;
+            }
+            if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                continue;
+            }
             if start > cursor {
                 out.push(Span::styled(text[cursor..start].to_string(), span.style));
             }
             out.push(Span::styled(
                 text[start..end].to_string(),
-                span.style.bg(Color::Yellow).fg(Color::Black),
+                span.style.patch(match_style),
             ));
             cursor = end;
         }
-
         if cursor < text.len() {
             out.push(Span::styled(text[cursor..].to_string(), span.style));
         }
+        offset = span_end;
     }
 
     out
 }
 
-fn apply_selection_bg(spans: Vec<Span<'static>>, bg: Color) -> Vec<Span<'static>> {
+fn apply_selection(spans: Vec<Span<'static>>, selection: Style) -> Vec<Span<'static>> {
     spans
         .into_iter()
         .map(|span| {
             let mut style = span.style;
             if style.bg.is_none() || style.bg == Some(Color::Reset) {
-                style = style.bg(bg);
+                style = style.patch(selection);
             }
             Span::styled(span.content.to_string(), style)
         })
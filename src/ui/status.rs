@@ -2,6 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
 use crate::app::Model;
+use crate::input::{Action, format_sequence};
 
 pub fn render_hover_link_bar(model: &Model, frame: &mut Frame, area: Rect) {
     let Some(url) = model.hovered_link_url.as_deref() else {
@@ -25,7 +26,20 @@ pub fn render_search_bar(model: &Model, frame: &mut Frame, area: Rect) {
     } else {
         String::new()
     };
-    let text = format!("/{query}{match_info}  Enter: next  Esc: clear");
+    let (_, options) = crate::search::parse_query(query, model.search_mode);
+    let mut mode_tags = String::new();
+    for (on, tag) in [
+        (options.regex, " [regex]"),
+        (options.whole_word, " [word]"),
+        (options.case_sensitive, " [case]"),
+    ] {
+        if on {
+            mode_tags.push_str(tag);
+        }
+    }
+    let text = format!(
+        "/{query}{match_info}{mode_tags}  Enter: next  Tab: list  Alt-r: regex  Alt-w: word  Esc: clear"
+    );
     let bar = Paragraph::new(text).style(Style::default().bg(Color::Blue).fg(Color::White));
     frame.render_widget(bar, area);
 }
@@ -50,11 +64,22 @@ pub fn render_status_bar(model: &Model, frame: &mut Frame, area: Rect) {
     };
     let toc_indicator = if model.toc_visible { " [TOC]" } else { "" };
 
-    let status =
-        format!(" {filename}  [{percent}%]  {line_info}{watch_indicator}{toc_indicator}  ?:help");
+    let pending = if model.pending_keys.is_empty() {
+        String::new()
+    } else {
+        format!("  {}", format_sequence(&model.pending_keys))
+    };
+    let help_hint = model
+        .keymap
+        .keys_for(Action::ToggleHelp)
+        .first()
+        .map_or_else(String::new, |key| format!("  {key}:help"));
+
+    let status = format!(
+        " {filename}  [{percent}%]  {line_info}{watch_indicator}{toc_indicator}{pending}{help_hint}"
+    );
 
-    let status_bar =
-        Paragraph::new(status).style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    let status_bar = Paragraph::new(status).style(super::style::status_bar_style());
 
     frame.render_widget(status_bar, area);
 }
@@ -3,14 +3,174 @@
 //! This module defines the visual styling for rendered markdown elements.
 //! Uses ANSI colors that adapt to the terminal's color palette.
 
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+
+use crate::document::{AlertKind, InlineStyle, LineType, RenderedLine};
+
+/// Element names accepted in the config's `[colors]` section.
+pub const COLOR_ELEMENTS: &[&str] = &[
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "code",
+    "inline_code",
+    "quote",
+    "link",
+    "rule",
+    "image",
+    "front_matter",
+];
 
-use crate::document::{InlineStyle, LineType};
+/// Foreground colors from the config's `[colors]` section, by element.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColorOverrides(HashMap<&'static str, Color>);
+
+impl ColorOverrides {
+    /// Parse `[colors]` entries. Values are color names (`cyan`,
+    /// `light-red`), `#rrggbb`, or a 256-color index.
+    ///
+    /// # Errors
+    /// Returns an error listing every unknown element or unparseable color.
+    pub fn from_config(colors: &BTreeMap<String, String>) -> Result<Self> {
+        let mut overrides = HashMap::new();
+        let mut problems = Vec::new();
+        for (element, value) in colors {
+            let Some(&name) = COLOR_ELEMENTS.iter().find(|name| **name == element) else {
+                problems.push(format!(
+                    "{element}: unknown element (expected one of {})",
+                    COLOR_ELEMENTS.join(", ")
+                ));
+                continue;
+            };
+            match value.parse::<Color>() {
+                Ok(color) => {
+                    overrides.insert(name, color);
+                }
+                Err(_) => problems.push(format!("{element}: invalid color \"{value}\"")),
+            }
+        }
+        if !problems.is_empty() {
+            anyhow::bail!("Invalid [colors] in config:\n  {}", problems.join("\n  "));
+        }
+        Ok(Self(overrides))
+    }
+
+    pub fn get(&self, element: &str) -> Option<Color> {
+        self.0.get(element).copied()
+    }
+}
+
+static COLOR_OVERRIDES: OnceLock<Mutex<ColorOverrides>> = OnceLock::new();
+
+/// Sets the configured element colors used by all document styles.
+pub fn set_color_overrides(overrides: ColorOverrides) {
+    let lock = COLOR_OVERRIDES.get_or_init(|| Mutex::new(ColorOverrides::default()));
+    let mut guard = lock
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *guard = overrides;
+}
+
+fn color_override(element: &str) -> Option<Color> {
+    let lock = COLOR_OVERRIDES.get()?;
+    lock.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(element)
+}
+
+static THEME: OnceLock<Mutex<Option<Theme>>> = OnceLock::new();
+
+/// Sets the theme used by all styles; `None` keeps the default colors,
+/// which follow the terminal's light or dark background.
+pub fn set_theme(theme: Option<Theme>) {
+    let lock = THEME.get_or_init(|| Mutex::new(None));
+    let mut guard = lock
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *guard = theme;
+}
+
+/// The theme set with [`set_theme`], if any.
+pub fn active_theme() -> Option<Theme> {
+    let lock = THEME.get()?;
+    lock.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+}
+
+/// One style from the active theme, if a theme is set.
+fn theme_style(pick: impl FnOnce(&Theme) -> Style) -> Option<Style> {
+    let lock = THEME.get()?;
+    lock.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .as_ref()
+        .map(pick)
+}
+
+/// Style of the status bar.
+pub fn status_bar_style() -> Style {
+    theme_style(|theme| theme.status_bar)
+        .unwrap_or_else(|| Style::default().bg(Color::DarkGray).fg(Color::White))
+}
+
+/// Style of search matches, patched over the matched text's style.
+pub fn search_match_style() -> Style {
+    theme_style(|theme| theme.search_match)
+        .unwrap_or_else(|| Style::default().bg(Color::Yellow).fg(Color::Black))
+}
+
+/// Style of selected lines, applied to text without its own background.
+pub fn selection_style() -> Style {
+    theme_style(|theme| theme.selection).unwrap_or_else(|| Style::default().bg(Color::DarkGray))
+}
+
+/// The `[colors]` element that sets a line type's foreground.
+const fn color_element(line_type: &LineType) -> Option<&'static str> {
+    match line_type {
+        LineType::Heading(1) => Some("h1"),
+        LineType::Heading(2) => Some("h2"),
+        LineType::Heading(3) => Some("h3"),
+        LineType::Heading(4) => Some("h4"),
+        LineType::Heading(5) => Some("h5"),
+        LineType::Heading(_) => Some("h6"),
+        LineType::CodeBlock => Some("code"),
+        LineType::BlockQuote => Some("quote"),
+        LineType::HorizontalRule => Some("rule"),
+        LineType::Image => Some("image"),
+        LineType::FrontMatter => Some("front_matter"),
+        _ => None,
+    }
+}
 
 /// Get the style for a given line type.
 ///
-/// Uses semantic ANSI colors that respect the terminal's theme.
+/// Uses semantic ANSI colors that respect the terminal's theme, unless the
+/// config sets a color for the element.
 pub fn style_for_line_type(line_type: &LineType) -> Style {
+    let style = THEME
+        .get()
+        .and_then(|lock| {
+            lock.lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .as_ref()
+                .map(|theme| theme.line_style(line_type))
+        })
+        .unwrap_or_else(|| default_style_for_line_type(line_type));
+    color_element(line_type)
+        .and_then(color_override)
+        .map_or(style, |color| style.fg(color))
+}
+
+fn default_style_for_line_type(line_type: &LineType) -> Style {
     let light_bg = crate::highlight::is_light_background();
     match line_type {
         // Headings - bold with distinct colors per level
@@ -73,6 +233,10 @@ pub fn style_for_line_type(line_type: &LineType) -> Style {
             })
             .add_modifier(Modifier::ITALIC),
 
+        // Alert titles - colored per kind; the body keeps the paragraph
+        // style and only its gutter takes the color (see `line_spans`)
+        LineType::AlertTitle(kind) => Style::default().fg(alert_color(*kind, light_bg)),
+
         // Horizontal rule - dim
         LineType::HorizontalRule => Style::default()
             .fg(if light_bg {
@@ -91,10 +255,35 @@ pub fn style_for_line_type(line_type: &LineType) -> Style {
             })
             .add_modifier(Modifier::ITALIC),
 
+        // Front matter panel - muted so it doesn't compete with content
+        LineType::FrontMatter => Style::default().fg(if light_bg {
+            Color::Indexed(242)
+        } else {
+            Color::Indexed(248)
+        }),
+
         // List items, tables, paragraphs, empty lines - normal style
-        LineType::ListItem(_) | LineType::Table | LineType::Paragraph | LineType::Empty => {
-            Style::default()
-        }
+        LineType::ListItem(_)
+        | LineType::Table
+        | LineType::Paragraph
+        | LineType::Alert(_)
+        | LineType::Empty => Style::default(),
+    }
+}
+
+/// Accent color for an alert's title and gutter.
+const fn alert_color(kind: AlertKind, light_bg: bool) -> Color {
+    match (kind, light_bg) {
+        (AlertKind::Note, true) => Color::Indexed(24),
+        (AlertKind::Note, false) => Color::Blue,
+        (AlertKind::Tip, true) => Color::Indexed(22),
+        (AlertKind::Tip, false) => Color::Green,
+        (AlertKind::Important, true) => Color::Indexed(54),
+        (AlertKind::Important, false) => Color::Magenta,
+        (AlertKind::Warning, true) => Color::Indexed(94),
+        (AlertKind::Warning, false) => Color::Yellow,
+        (AlertKind::Caution, true) => Color::Indexed(88),
+        (AlertKind::Caution, false) => Color::Red,
     }
 }
 
@@ -113,38 +302,158 @@ pub fn style_for_inline(base: Style, inline: InlineStyle) -> Style {
 
     if inline.emphasis {
         style = style.add_modifier(Modifier::ITALIC);
+        style = theme_style(|theme| theme.emphasis).map_or(style, |themed| style.patch(themed));
     }
     if inline.strong {
         style = style.add_modifier(Modifier::BOLD);
+        style = theme_style(|theme| theme.strong).map_or(style, |themed| style.patch(themed));
     }
     if inline.strikethrough {
         style = style.add_modifier(Modifier::CROSSED_OUT);
+        style =
+            theme_style(|theme| theme.strikethrough).map_or(style, |themed| style.patch(themed));
     }
     if inline.link {
         style = style.add_modifier(Modifier::UNDERLINED);
         if inline.fg.is_none() {
-            let light_bg = crate::highlight::is_light_background();
-            style = style.fg(if light_bg {
-                Color::Blue
-            } else {
-                Color::LightBlue
-            });
+            style = match theme_style(|theme| theme.link) {
+                Some(themed) => style.patch(themed),
+                None if crate::highlight::is_light_background() => style.fg(Color::Blue),
+                None => style.fg(Color::LightBlue),
+            };
+            if let Some(color) = color_override("link") {
+                style = style.fg(color);
+            }
         }
     }
     if inline.code && inline.fg.is_none() {
-        let light_bg = crate::highlight::is_light_background();
-        style = style
-            .fg(if light_bg {
-                Color::Indexed(88)
-            } else {
-                Color::Red
-            })
-            .add_modifier(Modifier::BOLD);
+        style = match theme_style(|theme| theme.inline_code) {
+            Some(themed) => style.patch(themed),
+            None if crate::highlight::is_light_background() => {
+                style.fg(Color::Indexed(88)).add_modifier(Modifier::BOLD)
+            }
+            None => style.fg(Color::Red).add_modifier(Modifier::BOLD),
+        };
+        if let Some(color) = color_override("inline_code") {
+            style = style.fg(color);
+        }
     }
 
     style
 }
 
+/// Styled spans for a rendered line: its line and inline styles, plus the
+/// active theme's list marker and table border styles and the alert gutter
+/// color.
+pub fn line_spans(line: &RenderedLine) -> Vec<Span<'static>> {
+    let line_style = style_for_line_type(line.line_type());
+    let spans = line.spans().map_or_else(
+        || vec![Span::styled(line.content().to_string(), line_style)],
+        |spans| {
+            spans
+                .iter()
+                .map(|span| {
+                    Span::styled(
+                        span.text().to_string(),
+                        style_for_inline(line_style, span.style()),
+                    )
+                })
+                .collect()
+        },
+    );
+    match line.line_type() {
+        LineType::ListItem(_) => match theme_style(|theme| theme.list_marker) {
+            Some(marker) => style_list_marker(spans, marker),
+            None => spans,
+        },
+        LineType::Table => match theme_style(|theme| theme.table_border) {
+            Some(border) => style_table_borders(spans, border),
+            None => spans,
+        },
+        LineType::Alert(kind) => {
+            style_alert_gutter(spans, style_for_line_type(&LineType::AlertTitle(*kind)))
+        }
+        _ => spans,
+    }
+}
+
+/// Color the gutter of an alert body line. The parser puts the gutter in a
+/// span of its own.
+fn style_alert_gutter(mut spans: Vec<Span<'static>>, accent: Style) -> Vec<Span<'static>> {
+    if let Some(gutter) = spans.first_mut() {
+        gutter.style = gutter.style.patch(accent);
+    }
+    spans
+}
+
+/// Style the bullet or number of a list item's first line. The parser puts
+/// the indent and marker in a span of their own; continuation lines have
+/// only indent there.
+fn style_list_marker(mut spans: Vec<Span<'static>>, marker: Style) -> Vec<Span<'static>> {
+    let Some(first) = spans.first() else {
+        return spans;
+    };
+    let text = first.content.to_string();
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+    if start >= end {
+        return spans;
+    }
+    let base = first.style;
+    let mut styled = Vec::with_capacity(spans.len() + 2);
+    if start > 0 {
+        styled.push(Span::styled(text[..start].to_string(), base));
+    }
+    styled.push(Span::styled(
+        text[start..end].to_string(),
+        base.patch(marker),
+    ));
+    if end < text.len() {
+        styled.push(Span::styled(text[end..].to_string(), base));
+    }
+    styled.extend(spans.drain(1..));
+    styled
+}
+
+const fn is_table_border(c: char) -> bool {
+    matches!(
+        c,
+        '│' | '─' | '┼' | '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴'
+    )
+}
+
+/// Style the box-drawing characters of a table line.
+fn style_table_borders(spans: Vec<Span<'static>>, border: Style) -> Vec<Span<'static>> {
+    let mut styled = Vec::new();
+    for span in spans {
+        let text = span.content.as_ref();
+        let mut run_start = 0;
+        let mut run_is_border = None;
+        for (idx, c) in text.char_indices() {
+            let is_border = is_table_border(c);
+            if run_is_border.is_some_and(|prev| prev != is_border) {
+                let style = if is_border {
+                    span.style
+                } else {
+                    span.style.patch(border)
+                };
+                styled.push(Span::styled(text[run_start..idx].to_string(), style));
+                run_start = idx;
+            }
+            run_is_border = Some(is_border);
+        }
+        if let Some(is_border) = run_is_border {
+            let style = if is_border {
+                span.style.patch(border)
+            } else {
+                span.style
+            };
+            styled.push(Span::styled(text[run_start..].to_string(), style));
+        }
+    }
+    styled
+}
+
 fn fg_color_for_terminal(fg: crate::document::InlineColor) -> Color {
     if supports_truecolor() {
         Color::Rgb(fg.r, fg.g, fg.b)
@@ -191,7 +500,7 @@ fn rgb_to_xterm_256(r: u8, g: u8, b: u8) -> u8 {
 }
 
 /// Theme configuration for the entire application.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Theme {
     /// Heading level 1 style
     pub h1: Style,
@@ -199,8 +508,12 @@ pub struct Theme {
     pub h2: Style,
     /// Heading level 3 style
     pub h3: Style,
-    /// Heading level 4+ style
+    /// Heading level 4 style
     pub h4: Style,
+    /// Heading level 5 style
+    pub h5: Style,
+    /// Heading level 6 style
+    pub h6: Style,
     /// Code block style
     pub code: Style,
     /// Inline code style
@@ -223,10 +536,12 @@ pub struct Theme {
     pub image: Style,
     /// Horizontal rule style
     pub hr: Style,
-    /// Status bar background
-    pub status_bg: Color,
-    /// Status bar foreground
-    pub status_fg: Color,
+    /// Status bar style
+    pub status_bar: Style,
+    /// Search match highlight
+    pub search_match: Style,
+    /// Selected lines
+    pub selection: Style,
 }
 
 impl Default for Theme {
@@ -244,6 +559,12 @@ impl Default for Theme {
             h4: Style::default()
                 .fg(Color::Blue)
                 .add_modifier(Modifier::BOLD),
+            h5: Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+            h6: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
             code: Style::default().fg(Color::Indexed(250)),
             inline_code: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             quote: Style::default()
@@ -261,12 +582,58 @@ impl Default for Theme {
                 .fg(Color::Magenta)
                 .add_modifier(Modifier::ITALIC),
             hr: Style::default().fg(Color::Indexed(240)),
-            status_bg: Color::Indexed(236), // Dark gray that works on both
-            status_fg: Color::Indexed(252), // Light gray
+            // Dark gray bar that works on both backgrounds
+            status_bar: Style::default()
+                .bg(Color::Indexed(236))
+                .fg(Color::Indexed(252)),
+            search_match: Style::default().bg(Color::Yellow).fg(Color::Black),
+            selection: Style::default().bg(Color::DarkGray),
         }
     }
 }
 
+/// Names of the built-in themes.
+pub const BUILTIN_THEMES: &[&str] = &[
+    "dark",
+    "light",
+    "solarized",
+    "gruvbox",
+    "nord",
+    "high-contrast",
+];
+
+/// Theme files shipped with markless, written in the user theme format.
+const THEME_FILES: &[(&str, &str)] = &[
+    ("solarized", include_str!("themes/solarized.toml")),
+    ("gruvbox", include_str!("themes/gruvbox.toml")),
+    ("nord", include_str!("themes/nord.toml")),
+    ("high-contrast", include_str!("themes/high-contrast.toml")),
+];
+
+/// Keys a theme file may set, one per [`Theme`] field.
+pub const THEME_ELEMENTS: &[&str] = &[
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "code",
+    "inline_code",
+    "quote",
+    "link",
+    "emphasis",
+    "strong",
+    "strikethrough",
+    "list_marker",
+    "table_border",
+    "image",
+    "hr",
+    "status_bar",
+    "search_match",
+    "selection",
+];
+
 impl Theme {
     /// Create a theme optimized for dark terminals.
     pub fn dark() -> Self {
@@ -288,6 +655,12 @@ impl Theme {
             h4: Style::default()
                 .fg(Color::Indexed(25)) // Darker blue
                 .add_modifier(Modifier::BOLD),
+            h5: Style::default()
+                .fg(Color::Indexed(91)) // Darker magenta
+                .add_modifier(Modifier::BOLD),
+            h6: Style::default()
+                .fg(Color::Indexed(31))
+                .add_modifier(Modifier::BOLD),
             code: Style::default().fg(Color::Indexed(240)),
             inline_code: Style::default()
                 .fg(Color::Indexed(124)) // Darker red
@@ -307,10 +680,207 @@ impl Theme {
                 .fg(Color::Indexed(133))
                 .add_modifier(Modifier::ITALIC),
             hr: Style::default().fg(Color::Indexed(245)),
-            status_bg: Color::Indexed(252),
-            status_fg: Color::Indexed(235),
+            status_bar: Style::default()
+                .bg(Color::Indexed(252))
+                .fg(Color::Indexed(235)),
+            search_match: Style::default().bg(Color::Indexed(228)).fg(Color::Black),
+            selection: Style::default().bg(Color::Indexed(252)),
+        }
+    }
+
+    /// The built-in theme called `name`.
+    ///
+    /// # Panics
+    /// Never in practice: the built-in theme files are checked by tests.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => THEME_FILES
+                .iter()
+                .find(|(file_name, _)| *file_name == name)
+                .map(|(_, content)| {
+                    parse_theme(content, supports_truecolor()).expect("built-in themes are valid")
+                }),
         }
     }
+
+    /// Resolve a `--theme-name` value: `auto` (the default terminal-adaptive
+    /// colors, `None`), a path to a theme file, `<name>.toml` in
+    /// `themes_dir`, or a built-in theme.
+    ///
+    /// # Errors
+    /// Returns an error if the theme file is invalid or no theme has that name.
+    pub fn named(name: &str, themes_dir: Option<&Path>) -> Result<Option<Self>> {
+        if name == "auto" {
+            return Ok(None);
+        }
+        let is_path = Path::new(name)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+            || name.contains(std::path::MAIN_SEPARATOR);
+        let file = if is_path {
+            Some(PathBuf::from(name))
+        } else {
+            themes_dir
+                .map(|dir| dir.join(format!("{name}.toml")))
+                .filter(|path| path.is_file())
+        };
+        if let Some(path) = file {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read theme {}", path.display()))?;
+            return Self::from_toml(&content)
+                .map(Some)
+                .with_context(|| format!("Invalid theme {}", path.display()));
+        }
+        Self::builtin(name).map(Some).ok_or_else(|| {
+            let user_theme = themes_dir.map_or_else(String::new, |dir| {
+                format!("; or add {}", dir.join(format!("{name}.toml")).display())
+            });
+            anyhow::anyhow!(
+                "Unknown theme \"{name}\" (built-in themes: {}{user_theme})",
+                BUILTIN_THEMES.join(", ")
+            )
+        })
+    }
+
+    /// Parse a theme file.
+    ///
+    /// `base` picks the theme to start from, `dark` (the default) or
+    /// `light`. Every other key is one of [`THEME_ELEMENTS`], set either to a
+    /// foreground color or to a table with `fg`, `bg`, `bold`, `italic`,
+    /// `underline`, `dim` and `strikethrough`; unset attributes keep the base
+    /// theme's. `#rrggbb` colors fall back to the nearest 256-color index on
+    /// terminals without truecolor.
+    ///
+    /// # Errors
+    /// Returns an error listing every unknown key or invalid value.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        parse_theme(content, supports_truecolor())
+    }
+
+    /// Style for a line type, before any `[colors]` overrides.
+    fn line_style(&self, line_type: &LineType) -> Style {
+        match line_type {
+            LineType::Heading(1) => self.h1,
+            LineType::Heading(2) => self.h2,
+            LineType::Heading(3) => self.h3,
+            LineType::Heading(4) => self.h4,
+            LineType::Heading(5) => self.h5,
+            LineType::Heading(_) => self.h6,
+            LineType::CodeBlock => self.code,
+            LineType::BlockQuote => self.quote,
+            LineType::HorizontalRule => self.hr,
+            LineType::Image => self.image,
+            _ => default_style_for_line_type(line_type),
+        }
+    }
+
+    fn element_mut(&mut self, element: &str) -> Option<&mut Style> {
+        Some(match element {
+            "h1" => &mut self.h1,
+            "h2" => &mut self.h2,
+            "h3" => &mut self.h3,
+            "h4" => &mut self.h4,
+            "h5" => &mut self.h5,
+            "h6" => &mut self.h6,
+            "code" => &mut self.code,
+            "inline_code" => &mut self.inline_code,
+            "quote" => &mut self.quote,
+            "link" => &mut self.link,
+            "emphasis" => &mut self.emphasis,
+            "strong" => &mut self.strong,
+            "strikethrough" => &mut self.strikethrough,
+            "list_marker" => &mut self.list_marker,
+            "table_border" => &mut self.table_border,
+            "image" => &mut self.image,
+            "hr" => &mut self.hr,
+            "status_bar" => &mut self.status_bar,
+            "search_match" => &mut self.search_match,
+            "selection" => &mut self.selection,
+            _ => return None,
+        })
+    }
+}
+
+fn parse_theme(content: &str, truecolor: bool) -> Result<Theme> {
+    let table: toml::Table = content.parse()?;
+    let mut theme = match table.get("base") {
+        None => Theme::dark(),
+        Some(toml::Value::String(base)) if base == "dark" => Theme::dark(),
+        Some(toml::Value::String(base)) if base == "light" => Theme::light(),
+        Some(_) => anyhow::bail!("`base` must be \"dark\" or \"light\""),
+    };
+    let mut problems = Vec::new();
+    for (key, value) in &table {
+        if key == "base" {
+            continue;
+        }
+        let Some(style) = theme.element_mut(key) else {
+            problems.push(format!(
+                "{key}: unknown element (expected one of {})",
+                THEME_ELEMENTS.join(", ")
+            ));
+            continue;
+        };
+        if let Err(problem) = patch_style(style, value, truecolor) {
+            problems.push(format!("{key}: {problem}"));
+        }
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("{}", problems.join("\n"));
+    }
+    Ok(theme)
+}
+
+/// Apply a theme file entry to `style`.
+fn patch_style(style: &mut Style, value: &toml::Value, truecolor: bool) -> Result<(), String> {
+    let table = match value {
+        toml::Value::String(color) => {
+            *style = style.fg(parse_theme_color(color, truecolor)?);
+            return Ok(());
+        }
+        toml::Value::Table(table) => table,
+        _ => return Err("expected a color or a table".to_string()),
+    };
+    for (attr, value) in table {
+        let modifier = match attr.as_str() {
+            "fg" | "bg" => {
+                let color = value
+                    .as_str()
+                    .ok_or_else(|| format!("`{attr}` must be a color string"))
+                    .and_then(|color| parse_theme_color(color, truecolor))?;
+                *style = if attr == "fg" {
+                    style.fg(color)
+                } else {
+                    style.bg(color)
+                };
+                continue;
+            }
+            "bold" => Modifier::BOLD,
+            "italic" => Modifier::ITALIC,
+            "underline" => Modifier::UNDERLINED,
+            "dim" => Modifier::DIM,
+            "strikethrough" => Modifier::CROSSED_OUT,
+            _ => return Err(format!("unknown attribute `{attr}`")),
+        };
+        match value.as_bool() {
+            Some(true) => *style = style.add_modifier(modifier),
+            Some(false) => *style = style.remove_modifier(modifier),
+            None => return Err(format!("`{attr}` must be true or false")),
+        }
+    }
+    Ok(())
+}
+
+/// Parse a theme color, mapping `#rrggbb` to the 256-color palette when the
+/// terminal lacks truecolor.
+fn parse_theme_color(value: &str, truecolor: bool) -> Result<Color, String> {
+    match value.parse::<Color>() {
+        Ok(Color::Rgb(r, g, b)) if !truecolor => Ok(Color::Indexed(rgb_to_xterm_256(r, g, b))),
+        Ok(color) => Ok(color),
+        Err(_) => Err(format!("invalid color \"{value}\"")),
+    }
 }
 
 #[cfg(test)]
@@ -318,6 +888,66 @@ mod tests {
     use super::*;
     use crate::document::InlineColor;
 
+    #[test]
+    fn test_color_overrides_from_config() {
+        let colors = BTreeMap::from([
+            ("h1".to_string(), "#ff8800".to_string()),
+            ("code".to_string(), "light-green".to_string()),
+            ("quote".to_string(), "244".to_string()),
+        ]);
+        let overrides = ColorOverrides::from_config(&colors).unwrap();
+        assert_eq!(overrides.get("h1"), Some(Color::Rgb(0xff, 0x88, 0x00)));
+        assert_eq!(overrides.get("code"), Some(Color::LightGreen));
+        assert_eq!(overrides.get("quote"), Some(Color::Indexed(244)));
+        assert_eq!(overrides.get("link"), None);
+    }
+
+    #[test]
+    fn test_color_overrides_report_bad_entries() {
+        let colors = BTreeMap::from([
+            ("h7".to_string(), "red".to_string()),
+            ("link".to_string(), "not-a-color".to_string()),
+        ]);
+        let err = ColorOverrides::from_config(&colors)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("h7: unknown element"), "{err}");
+        assert!(err.contains("link: invalid color \"not-a-color\""), "{err}");
+    }
+
+    #[test]
+    fn test_color_element_for_deep_headings() {
+        assert_eq!(color_element(&LineType::Heading(6)), Some("h6"));
+        assert_eq!(color_element(&LineType::Paragraph), None);
+    }
+
+    #[test]
+    fn test_alert_color_is_on_title_and_gutter_only() {
+        let doc = crate::document::Document::parse("> [!WARNING]\n> Body text\n").unwrap();
+        let lines = doc.visible_lines(0, 10);
+        let accent = style_for_line_type(&LineType::AlertTitle(AlertKind::Warning)).fg;
+        assert!(accent.is_some());
+
+        let title = lines
+            .iter()
+            .find(|l| *l.line_type() == LineType::AlertTitle(AlertKind::Warning))
+            .unwrap();
+        assert!(line_spans(title).iter().all(|span| span.style.fg == accent));
+
+        let body = lines
+            .iter()
+            .find(|l| *l.line_type() == LineType::Alert(AlertKind::Warning))
+            .unwrap();
+        let spans = line_spans(body);
+        assert_eq!(spans[0].style.fg, accent);
+        let text: Vec<_> = spans[1..]
+            .iter()
+            .filter(|span| !span.content.trim().is_empty())
+            .collect();
+        assert!(!text.is_empty());
+        assert!(text.iter().all(|span| span.style.fg.is_none()));
+    }
+
     #[test]
     fn test_heading_styles_are_bold() {
         for level in 1..=6 {
@@ -390,6 +1020,136 @@ mod tests {
         assert!(theme.h1.add_modifier.contains(Modifier::BOLD));
     }
 
+    #[test]
+    fn test_builtin_themes_load() {
+        for name in BUILTIN_THEMES {
+            assert!(Theme::builtin(name).is_some(), "{name}");
+        }
+        for (name, content) in THEME_FILES {
+            let theme = parse_theme(content, false).unwrap();
+            assert!(
+                !matches!(theme.h1.fg, Some(Color::Rgb(..))),
+                "{name} should fall back to indexed colors"
+            );
+        }
+        assert!(Theme::builtin("missing").is_none());
+    }
+
+    #[test]
+    fn test_theme_file_patches_base_theme() {
+        let theme = parse_theme(
+            "base = \"light\"\nh1 = \"#ff0000\"\nlink = { bold = true, underline = false }\n",
+            true,
+        )
+        .unwrap();
+        let light = Theme::light();
+        assert_eq!(theme.h1.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(theme.h1.add_modifier, light.h1.add_modifier);
+        assert_eq!(theme.link.fg, light.link.fg);
+        assert!(theme.link.add_modifier.contains(Modifier::BOLD));
+        assert!(theme.link.sub_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(theme.code, light.code);
+    }
+
+    #[test]
+    fn test_theme_rgb_falls_back_to_256_colors() {
+        let theme = parse_theme("code = { fg = \"#ff0000\", bg = \"blue\" }\n", false).unwrap();
+        assert_eq!(theme.code.fg, Some(Color::Indexed(196)));
+        assert_eq!(theme.code.bg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_theme_file_reports_bad_entries() {
+        let err = parse_theme(
+            "h7 = \"red\"\ncode = { fg = \"nope\" }\nquote = 3\nhr = { blink = true }\n",
+            true,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("h7: unknown element"), "{err}");
+        assert!(err.contains("code: invalid color \"nope\""), "{err}");
+        assert!(err.contains("quote: expected a color or a table"), "{err}");
+        assert!(err.contains("hr: unknown attribute `blink`"), "{err}");
+        assert!(parse_theme("base = \"nord\"\n", true).is_err());
+    }
+
+    #[test]
+    fn test_theme_named_resolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("mine.toml");
+        std::fs::write(&file, "h2 = \"red\"\n").unwrap();
+
+        let mine = Theme::named("mine", Some(dir.path())).unwrap().unwrap();
+        assert_eq!(mine.h2.fg, Some(Color::Red));
+        let by_path = Theme::named(file.to_str().unwrap(), None).unwrap().unwrap();
+        assert_eq!(by_path, mine);
+        assert_eq!(
+            Theme::named("nord", Some(dir.path())).unwrap(),
+            Theme::builtin("nord")
+        );
+        assert!(Theme::named("auto", None).unwrap().is_none());
+
+        let err = Theme::named("missing", Some(dir.path()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("solarized"), "{err}");
+        assert!(err.contains("missing.toml"), "{err}");
+    }
+
+    #[test]
+    fn test_theme_line_style_covers_all_headings() {
+        let theme = Theme::dark();
+        assert_eq!(theme.line_style(&LineType::Heading(5)), theme.h5);
+        assert_eq!(theme.line_style(&LineType::Heading(6)), theme.h6);
+        assert_eq!(theme.line_style(&LineType::HorizontalRule), theme.hr);
+    }
+
+    #[test]
+    fn test_style_list_marker_only_on_first_line() {
+        let marker = Style::default().fg(Color::Yellow);
+        let spans = vec![Span::raw("  • "), Span::raw("item")];
+        let styled = style_list_marker(spans, marker);
+        let parts: Vec<_> = styled
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.fg))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("  ", None),
+                ("•", Some(Color::Yellow)),
+                (" ", None),
+                ("item", None)
+            ]
+        );
+
+        let continuation = vec![Span::raw("    "), Span::raw("more")];
+        assert_eq!(
+            style_list_marker(continuation.clone(), marker),
+            continuation
+        );
+    }
+
+    #[test]
+    fn test_style_table_borders() {
+        let border = Style::default().fg(Color::Indexed(240));
+        let spans = vec![Span::raw(" a │ b"), Span::raw("──┼──")];
+        let styled = style_table_borders(spans, border);
+        let parts: Vec<_> = styled
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.fg))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                (" a ", None),
+                ("│", Some(Color::Indexed(240))),
+                (" b", None),
+                ("──┼──", Some(Color::Indexed(240)))
+            ]
+        );
+    }
+
     #[test]
     fn test_inline_color_removes_dim_modifier() {
         let base = Style::default().add_modifier(Modifier::DIM);
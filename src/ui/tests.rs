@@ -1,7 +1,9 @@
 use super::*;
 use crate::app::Model;
+use crate::config::KeyBinding;
 use crate::document::Document;
 use crate::document::LineType;
+use crate::input::Keymap;
 use ratatui::Terminal;
 use ratatui::backend::TestBackend;
 use ratatui_image::picker::Picker;
@@ -149,13 +151,13 @@ fn test_help_overlay_shows_config_paths_full_width() {
     let mut model = Model::new(
         PathBuf::from("test.md"),
         Document::parse("# Title").unwrap(),
-        (80, 60),
+        (80, 100),
     );
     model.help_visible = true;
     model.config_global_path = Some(PathBuf::from("/path/that/should/be/visible/in/help/config"));
     model.config_local_path = Some(PathBuf::from("/local/override/path/visible/in/help/config"));
 
-    let backend = TestBackend::new(80, 60);
+    let backend = TestBackend::new(80, 100);
     let mut terminal = Terminal::new(backend).unwrap();
     terminal.draw(|frame| render(&mut model, frame)).unwrap();
 
@@ -1055,6 +1057,217 @@ fn test_editor_status_bar_shows_modified_after_edit() {
     );
 }
 
+#[test]
+fn test_editor_renders_selection_and_multibyte_cursor() {
+    let md = "café au lait";
+    let doc = Document::parse(md).unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model = crate::app::update(model, crate::app::Message::EnterEditMode);
+    let mut watcher = None;
+    crate::app::App::handle_message_side_effects(
+        &mut model,
+        &mut watcher,
+        &crate::app::Message::EnterEditMode,
+    );
+    for _ in 0..4 {
+        model = crate::app::update(
+            model,
+            crate::app::Message::EditorSelect(crate::editor::Motion::Direction(
+                crate::editor::Direction::Right,
+            )),
+        );
+    }
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    // Gutter is "1 ", so text starts at column 2.
+    let selected: String = (2..6)
+        .map(|x| buffer.cell((x, 0)).unwrap().symbol().to_string())
+        .collect();
+    assert_eq!(selected, "café");
+    for x in 2..6 {
+        assert_eq!(
+            buffer.cell((x, 0)).unwrap().bg,
+            ratatui::style::Color::DarkGray
+        );
+    }
+    // The cursor sits on the space after the selection.
+    assert_eq!(
+        buffer.cell((6, 0)).unwrap().bg,
+        ratatui::style::Color::White
+    );
+}
+
+#[test]
+fn test_editor_find_bar_highlights_matches_and_counts() {
+    let md = "ab ab ab";
+    let doc = Document::parse(md).unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model = crate::app::update(model, crate::app::Message::EnterEditMode);
+    let mut watcher = None;
+    crate::app::App::handle_message_side_effects(
+        &mut model,
+        &mut watcher,
+        &crate::app::Message::EnterEditMode,
+    );
+    model = crate::app::update(model, crate::app::Message::EditorFindOpen);
+    model = crate::app::update(
+        model,
+        crate::app::Message::EditorFindInput("ab".to_string()),
+    );
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| -> String {
+        (0..buffer.area.width)
+            .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+            .collect()
+    };
+    let height = buffer.area.height;
+    assert!(
+        row(height - 1).contains("[1/3]"),
+        "got '{}'",
+        row(height - 1)
+    );
+    assert!(
+        row(height - 2).contains("Find:    ab"),
+        "got '{}'",
+        row(height - 2)
+    );
+    // The current match is selected; the others are highlighted.
+    assert_eq!(
+        buffer.cell((2, 0)).unwrap().bg,
+        ratatui::style::Color::DarkGray
+    );
+    assert_eq!(
+        buffer.cell((5, 0)).unwrap().bg,
+        ratatui::style::Color::Yellow
+    );
+}
+
+#[test]
+fn test_editor_preview_splits_source_and_rendered_document() {
+    let doc = Document::parse("# Title\n\nSome **bold** text").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model = crate::app::update(model, crate::app::Message::EnterEditMode);
+    let mut watcher = None;
+    crate::app::App::handle_message_side_effects(
+        &mut model,
+        &mut watcher,
+        &crate::app::Message::EnterEditMode,
+    );
+    model = crate::app::update(model, crate::app::Message::EditorTogglePreview);
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let text = |y: u16, xs: std::ops::Range<u16>| -> String {
+        xs.map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+            .collect()
+    };
+    let split = crate::ui::split_editor_columns(buffer.area)[1].x;
+    assert!(text(2, 0..split).contains("Some **bold** text"));
+    let preview: Vec<String> = (0..buffer.area.height)
+        .map(|y| text(y, split..buffer.area.width))
+        .collect();
+    assert!(
+        preview.iter().any(|row| row.contains("Some bold text")),
+        "got {preview:?}"
+    );
+}
+
+#[test]
+fn test_editor_merge_overlay_marks_conflicts() {
+    let doc = Document::parse("base").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model = crate::app::update(model, crate::app::Message::EnterEditMode);
+    model.editor_buffer = Some(crate::editor::EditorBuffer::from_text("mine"));
+    model.editor_merge = Some(crate::editor::MergeState::new(
+        "base",
+        "mine",
+        "theirs".to_string(),
+    ));
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let screen: String = (0..buffer.area.height)
+        .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+        .map(|pos| buffer.cell(pos).unwrap().symbol().to_string())
+        .collect();
+    assert!(screen.contains("conflict 1/1"));
+    assert!(screen.contains("<<<<<<< yours (unresolved)"));
+    assert!(screen.contains(">>>>>>> disk"));
+}
+
+#[test]
+fn test_editor_status_bar_shows_vim_mode_and_command_line() {
+    use crate::app::Message;
+    use crate::editor::VimKey;
+
+    let doc = Document::parse("one two").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model = crate::app::update(model, Message::EnterEditMode);
+    model.editor_buffer = Some(crate::editor::EditorBuffer::from_text("one two"));
+    model.editor_vim = Some(crate::editor::VimState::default());
+
+    let status_row = |model: &mut Model| -> String {
+        let mut terminal = create_test_terminal();
+        terminal.draw(|frame| render(model, frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let y = buffer.area.height - 1;
+        (0..buffer.area.width)
+            .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+            .collect()
+    };
+
+    assert!(status_row(&mut model).starts_with(" NORMAL  test.md"));
+    model = crate::app::update(model, Message::EditorVimKey(VimKey::Char('2')));
+    model = crate::app::update(model, Message::EditorVimKey(VimKey::Char('d')));
+    assert!(status_row(&mut model).contains("Col 1  2d  :w:save"));
+    model = crate::app::update(model, Message::EditorVimKey(VimKey::Esc));
+    model = crate::app::update(model, Message::EditorVimKey(VimKey::Char('v')));
+    assert!(status_row(&mut model).starts_with(" VISUAL"));
+    model = crate::app::update(model, Message::EditorVimKey(VimKey::Char(':')));
+    model = crate::app::update(model, Message::EditorVimKey(VimKey::Char('w')));
+    assert!(status_row(&mut model).starts_with(":w "));
+}
+
+#[test]
+fn test_editor_swap_recovery_overlay_shows_prompt_and_diff() {
+    let doc = Document::parse("one\ntwo").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 24));
+    model = crate::app::update(model, crate::app::Message::EnterEditMode);
+    model.editor_buffer = Some(crate::editor::EditorBuffer::from_text("one\ntwo"));
+    model.editor_swap_recovery = Some(crate::editor::SwapRecovery::new(
+        "one\ntwo",
+        "one\n2".to_string(),
+    ));
+
+    let screen = |model: &mut Model| -> String {
+        let mut terminal = create_test_terminal();
+        terminal.draw(|frame| render(model, frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+            .map(|pos| buffer.cell(pos).unwrap().symbol().to_string())
+            .collect()
+    };
+    let prompt = screen(&mut model);
+    assert!(prompt.contains("Recover Unsaved Changes"));
+    assert!(prompt.contains("r recover"));
+
+    model = crate::app::update(model, crate::app::Message::EditorSwapToggleDiff);
+    let diff = screen(&mut model);
+    assert!(diff.contains("- two"));
+    assert!(diff.contains("+ 2"));
+}
+
 #[test]
 fn test_help_overlay_single_column() {
     let doc = Document::parse("# Test\n\nHello world").unwrap();
@@ -1086,6 +1299,84 @@ fn test_help_overlay_single_column() {
     );
 }
 
+#[test]
+fn test_help_overlay_lists_configured_keys() {
+    let doc = Document::parse("# Test\n\nHello world").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 40));
+    model.keymap = Keymap::with_bindings(&[
+        KeyBinding {
+            context: "view".to_string(),
+            keys: "Ctrl-f".to_string(),
+            action: "half_page_down".to_string(),
+        },
+        KeyBinding {
+            context: "view".to_string(),
+            keys: "Ctrl-d".to_string(),
+            action: "none".to_string(),
+        },
+        KeyBinding {
+            context: "toc".to_string(),
+            keys: "Ctrl-d".to_string(),
+            action: "none".to_string(),
+        },
+    ])
+    .unwrap();
+    model.help_visible = true;
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect::<String>()
+        })
+        .find(|row| row.contains("Half page down"))
+        .expect("half page row not found");
+    assert!(row.contains("Ctrl-f "), "{row}");
+    assert!(!row.contains("Ctrl-d"), "{row}");
+}
+
+#[test]
+fn test_help_overlay_quit_row_comes_from_keymap() {
+    let doc = Document::parse("# Test\n\nHello world").unwrap();
+    let mut model = Model::new(PathBuf::from("test.md"), doc, (80, 40));
+    model.keymap = Keymap::with_bindings(&[
+        KeyBinding {
+            context: "view".to_string(),
+            keys: "Ctrl-q".to_string(),
+            action: "none".to_string(),
+        },
+        KeyBinding {
+            context: "toc".to_string(),
+            keys: "Ctrl-q".to_string(),
+            action: "none".to_string(),
+        },
+    ])
+    .unwrap();
+    model.help_visible = true;
+    // The "Other" section is near the end.
+    model.help_scroll_offset = usize::MAX;
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect()
+        })
+        .collect();
+    let quit_rows: Vec<&String> = rows.iter().filter(|row| row.contains(" Quit")).collect();
+    assert_eq!(quit_rows.len(), 1, "{quit_rows:?}");
+    assert!(quit_rows[0].contains("q / Ctrl-c "), "{}", quit_rows[0]);
+    assert!(!quit_rows[0].contains("Ctrl-q"), "{}", quit_rows[0]);
+}
+
 #[test]
 fn test_help_overlay_scroll_clamps() {
     let doc = Document::parse("# Test\n\nHello world").unwrap();
@@ -1109,3 +1400,171 @@ fn test_help_overlay_scroll_clamps() {
         "Config section should be visible at max scroll"
     );
 }
+
+#[test]
+fn test_search_highlights_regex_match_ranges() {
+    let mut model = Model::new(
+        PathBuf::from("test.md"),
+        Document::parse("order 1234 shipped").unwrap(),
+        (80, 24),
+    );
+    model.search_mode.regex = true;
+    model = crate::app::update(model, crate::app::Message::StartSearch);
+    model = crate::app::update(model, crate::app::Message::SearchInput(r"\d+".to_string()));
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let match_bg = style::search_match_style().bg;
+    let (x, y) = (0..buffer.area.height)
+        .find_map(|y| {
+            let row: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            row.find("order 1234")
+                .map(|col| (u16::try_from(col).unwrap(), y))
+        })
+        .expect("document line missing");
+
+    let highlighted: Vec<bool> = (x..x + 18)
+        .map(|col| buffer[(col, y)].bg == match_bg.unwrap())
+        .collect();
+    let expected: Vec<bool> = (0..18).map(|i| (6..10).contains(&i)).collect();
+    assert_eq!(highlighted, expected);
+}
+
+#[test]
+fn test_search_results_overlay_lists_matches() {
+    let mut model = Model::new(
+        PathBuf::from("test.md"),
+        Document::parse("# Intro\n\nalpha one\n\n## Details\n\nmore alpha here").unwrap(),
+        (80, 24),
+    );
+    model = crate::app::update(model, crate::app::Message::StartSearch);
+    model = crate::app::update(model, crate::app::Message::SearchInput("alpha".to_string()));
+    model = crate::app::update(model, crate::app::Message::OpenSearchResults);
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let content: String = buffer
+        .content()
+        .iter()
+        .map(ratatui::buffer::Cell::symbol)
+        .collect();
+    assert!(content.contains("Search Results"));
+    assert!(content.contains("2 matches for /alpha"));
+    assert!(content.contains("> L5  Intro"));
+    assert!(content.contains("Details"));
+    assert!(content.contains("more alpha here"));
+}
+
+#[test]
+fn test_project_search_overlay_groups_hits_by_file() {
+    let mut model = Model::new(
+        PathBuf::from("/docs/index.md"),
+        Document::parse("# Index").unwrap(),
+        (80, 24),
+    );
+    model.browse_dir = PathBuf::from("/docs");
+    let mut state = crate::app::ProjectSearchState::new("needle".to_string());
+    state.searched = Some("needle".to_string());
+    state.files_searched = Some(7);
+    state.files = vec![crate::search::FileHits {
+        path: PathBuf::from("/docs/guide/setup.md"),
+        hits: vec![crate::search::ProjectHit {
+            line: 11,
+            text: "Thread the needle.".to_string(),
+            ranges: std::slice::from_ref(&(11..17)).to_vec(),
+        }],
+    }];
+    model.project_search = Some(state);
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let content: String = buffer
+        .content()
+        .iter()
+        .map(ratatui::buffer::Cell::symbol)
+        .collect();
+    assert!(content.contains("Search Files"));
+    assert!(content.contains("1 matches in 1 of 7 files"));
+    assert!(content.contains("guide/setup.md"));
+    assert!(content.contains("12: Thread the needle."));
+}
+
+#[test]
+fn test_heading_palette_overlay_shows_level_and_breadcrumb() {
+    let mut model = Model::new(
+        PathBuf::from("test.md"),
+        Document::parse("# Guide\n\n## Setup\n\n### Install steps\n\n## Usage").unwrap(),
+        (80, 24),
+    );
+    model = crate::app::update(model, crate::app::Message::OpenHeadingPalette);
+    model = crate::app::update(
+        model,
+        crate::app::Message::HeadingPaletteInput("inst".to_string()),
+    );
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let content: String = buffer
+        .content()
+        .iter()
+        .map(ratatui::buffer::Cell::symbol)
+        .collect();
+    assert!(content.contains("Go to Heading"));
+    assert!(content.contains("Heading: inst"));
+    assert!(content.contains("> H3    Guide › Setup › Install steps"));
+    assert!(!content.contains("Usage"));
+}
+
+#[test]
+fn test_file_finder_overlay_leaves_document_visible() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("docs")).unwrap();
+    std::fs::write(dir.path().join("docs/guide.md"), "x").unwrap();
+    std::fs::write(dir.path().join("notes.md"), "x").unwrap();
+    let mut model = Model::new(
+        dir.path().join("notes.md"),
+        Document::parse("# Preview Title").unwrap(),
+        (80, 24),
+    );
+    model.browse_mode = true;
+    model.load_directory(dir.path()).unwrap();
+    model = crate::app::update(model, crate::app::Message::OpenFileFinder);
+    crate::app::App::handle_message_side_effects(
+        &mut model,
+        &mut None,
+        &crate::app::Message::OpenFileFinder,
+    );
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while model
+        .file_finder
+        .as_ref()
+        .is_some_and(crate::app::FileFinder::is_indexing)
+    {
+        assert!(std::time::Instant::now() < deadline);
+        model.poll_file_finder();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    model = crate::app::update(
+        model,
+        crate::app::Message::FileFinderInput("guide".to_string()),
+    );
+
+    let mut terminal = create_test_terminal();
+    terminal.draw(|frame| render(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let content: String = buffer
+        .content()
+        .iter()
+        .map(ratatui::buffer::Cell::symbol)
+        .collect();
+    assert!(content.contains("Find File in"));
+    assert!(content.contains("File: guide  (1 of 2 files)"));
+    assert!(content.contains("> docs/guide.md"));
+    assert!(content.contains("Preview Title"));
+}
@@ -1,6 +1,18 @@
 use std::path::PathBuf;
 
-use markless::config::{ConfigFlags, ImageMode, ThemeMode, load_config_flags, parse_flag_tokens};
+use markless::config::{
+    ConfigFlags, ConfigLayer, ConfigSource, ImageMode, LayeredConfig, ThemeMode, load_config_flags,
+    parse_flag_tokens,
+};
+
+/// Merge `layers` the way startup does, later layers winning.
+fn merge(layers: &[&ConfigFlags]) -> ConfigFlags {
+    let mut config = LayeredConfig::default();
+    for flags in layers {
+        config.push(ConfigLayer::from_flags(ConfigSource::CommandLine, flags));
+    }
+    config.flags().unwrap()
+}
 
 #[test]
 fn test_config_file_parsing_ignores_comments_and_blank_lines() {
@@ -38,7 +50,7 @@ fn test_cli_flags_override_file_flags() {
     ];
     let cli_flags = parse_flag_tokens(&cli_args);
 
-    let effective = file_flags.union(&cli_flags);
+    let effective = merge(&[&file_flags, &cli_flags]);
     assert!(effective.watch, "file flags should remain enabled");
     assert!(effective.force_half_cell, "cli flags should be applied");
     assert_eq!(
@@ -71,7 +83,7 @@ fn test_parse_flag_tokens_handles_equals_syntax() {
 }
 
 #[test]
-fn test_config_union_merges_booleans() {
+fn test_config_merge_keeps_booleans() {
     let file = ConfigFlags {
         watch: true,
         no_toc: true,
@@ -82,7 +94,7 @@ fn test_config_union_merges_booleans() {
         perf: true,
         ..ConfigFlags::default()
     };
-    let merged = file.union(&cli);
+    let merged = merge(&[&file, &cli]);
     assert!(merged.watch);
     assert!(merged.no_toc);
     assert!(merged.toc);